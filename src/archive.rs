@@ -0,0 +1,121 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::serde::Serialize;
+use near_sdk::AccountId;
+
+use crate::difficulty::Difficulty;
+use crate::{Sudoku, SudokuTwoDimensionalArray};
+
+/// Storage of the marker recording that an account solved an archived puzzle and of the record of
+/// its date under the index of the completion, see [`ArchiveDates`]
+pub const ARCHIVE_COMPLETION_SIZE: u128 = 240;
+/// Storage of an archived daily puzzle, paid by the transaction archiving it
+pub const ARCHIVED_PUZZLE_SIZE: u128 = 100;
+
+/// The daily puzzle of a UTC day, see [`crate::reward::day`]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ArchivedPuzzle {
+    pub sudoku: Sudoku,
+    pub difficulty: Difficulty,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ArchivedPuzzleRequest {
+    pub date: u64,
+    pub sudoku: SudokuTwoDimensionalArray,
+    pub difficulty: Difficulty,
+}
+
+/// Daily puzzles cycle through the difficulties from easy to expert
+pub fn daily_difficulty(date: u64) -> Difficulty {
+    Difficulty::ALL[(date % Difficulty::ALL.len() as u64) as usize]
+}
+
+/// Dates of the archived puzzles a player solved, each stored under the account and the index of
+/// the completion in [`crate::Contract::archive_dates`], so the completion records can be removed
+/// with the player. Players read from layouts before version 5 keep the dates inline until
+/// [`crate::Contract::migrate_players`] moves them.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum ArchiveDates {
+    Inline(Vec<u64>),
+    /// Number of stored dates
+    Indexed(u32),
+}
+
+impl ArchiveDates {
+    pub fn len(&self) -> u32 {
+        match self {
+            Self::Inline(dates) => dates.len() as u32,
+            Self::Indexed(count) => *count,
+        }
+    }
+
+    pub fn get(
+        &self,
+        dates: &LookupMap<(AccountId, u32), u64>,
+        account_id: &AccountId,
+    ) -> Vec<u64> {
+        match self {
+            Self::Inline(inline) => inline.clone(),
+            Self::Indexed(count) => (0..*count)
+                .map(|index| dates.get(&(account_id.clone(), index)).unwrap())
+                .collect(),
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        dates: &mut LookupMap<(AccountId, u32), u64>,
+        account_id: &AccountId,
+        date: u64,
+    ) {
+        match self {
+            Self::Inline(inline) => inline.push(date),
+            Self::Indexed(count) => {
+                dates.insert(&(account_id.clone(), *count), &date);
+                *count += 1;
+            }
+        }
+    }
+
+    /// Removes the stored dates and returns all of them
+    pub fn remove(
+        &mut self,
+        dates: &mut LookupMap<(AccountId, u32), u64>,
+        account_id: &AccountId,
+    ) -> Vec<u64> {
+        let removed = self.get(dates, account_id);
+        if let Self::Indexed(count) = self {
+            for index in 0..*count {
+                dates.remove(&(account_id.clone(), index));
+            }
+        }
+        *self = Self::Indexed(0);
+        removed
+    }
+
+    /// Stores inline dates under their indexes
+    pub fn migrate(
+        &mut self,
+        dates: &mut LookupMap<(AccountId, u32), u64>,
+        account_id: &AccountId,
+    ) {
+        if let Self::Inline(inline) = self {
+            for (index, date) in (0..).zip(inline.iter()) {
+                dates.insert(&(account_id.clone(), index), date);
+            }
+            *self = Self::Indexed(inline.len() as u32);
+        }
+    }
+}
+
+impl ArchivedPuzzle {
+    pub fn get(&self, date: u64) -> ArchivedPuzzleRequest {
+        ArchivedPuzzleRequest {
+            date,
+            sudoku: self.sudoku.to_two_dimensional_array(),
+            difficulty: self.difficulty,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, BlockHeight, Timestamp};
+
+use crate::novelty;
+use crate::Sudoku;
+
+/// Storage of an attestation, paid from the prepaid balance of the solver
+pub const ATTESTATION_SIZE: u128 = 200;
+/// Storage of the per account count of attestations, paid with the first attestation
+pub const ATTESTATION_COUNT_SIZE: u128 = 120;
+
+/// Record of a ranked solve, see [`crate::Contract::verify_attestation`]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Attestation {
+    /// sha256 of the canonical form of the puzzle, shared by all its transformed copies
+    puzzle_hash: [u8; 32],
+    /// Solve time in milliseconds, including the penalty of wrong submissions
+    elapsed: Timestamp,
+    block_height: BlockHeight,
+    timestamp: Timestamp,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestationRequest {
+    pub account_id: AccountId,
+    /// Attested solves of the account counted from 0
+    pub index: u64,
+    /// Hex encoded, see [`Attestation`]
+    pub puzzle_hash: String,
+    pub elapsed: Timestamp,
+    pub block_height: BlockHeight,
+    pub timestamp: Timestamp,
+}
+
+impl Attestation {
+    /// Attests the solve of `puzzle` in the current block
+    pub fn new(puzzle: &Sudoku, elapsed: Timestamp) -> Self {
+        Self {
+            puzzle_hash: novelty::canonical_hash(puzzle),
+            elapsed,
+            block_height: env::block_height(),
+            timestamp: env::block_timestamp_ms(),
+        }
+    }
+
+    pub fn get(&self, account_id: AccountId, index: u64) -> AttestationRequest {
+        AttestationRequest {
+            account_id,
+            index,
+            puzzle_hash: self
+                .puzzle_hash
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            elapsed: self.elapsed,
+            block_height: self.block_height,
+            timestamp: self.timestamp,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Solving many sudokus at once, e.g. to validate puzzle pools off-chain
+//!
+//! With the `parallel` feature, the work is spread over all cores with rayon.
+//! The feature has no effect on wasm32, where the sudokus are always solved one after another.
+use alloc::vec::Vec;
+use crate::Sudoku;
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+use rayon::prelude::*;
+
+/// Solves all `sudokus`. Each entry is the unique solution of the sudoku at the same index
+/// or `None`, if it has no or multiple solutions. See [`Sudoku::solution`].
+pub fn solve_all(sudokus: &[Sudoku]) -> Vec<Option<Sudoku>> {
+    map_all(sudokus, |sudoku| sudoku.solution())
+}
+
+/// Checks for all `sudokus`, whether they have exactly one solution. See [`Sudoku::is_uniquely_solvable`].
+pub fn are_uniquely_solvable(sudokus: &[Sudoku]) -> Vec<bool> {
+    map_all(sudokus, |sudoku| sudoku.is_uniquely_solvable())
+}
+
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn map_all<T: Send>(sudokus: &[Sudoku], f: impl Fn(Sudoku) -> T + Sync + Send) -> Vec<T> {
+    sudokus.par_iter().map(|&sudoku| f(sudoku)).collect()
+}
+
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn map_all<T>(sudokus: &[Sudoku], f: impl Fn(Sudoku) -> T) -> Vec<T> {
+    sudokus.iter().map(|&sudoku| f(sudoku)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solve_all() {
+        let unique =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let empty = Sudoku::from_bytes([0; 81]).unwrap();
+        let mut contradicting = unique;
+        contradicting.0[2] = 5;
+
+        let sudokus = [unique, empty, contradicting];
+        assert_eq!(super::solve_all(&sudokus), [unique.solution(), None, None]);
+        assert_eq!(are_uniquely_solvable(&sudokus), [true, false, false]);
+        assert!(super::solve_all(&[]).is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+//! Generates pools of graded puzzles off-chain
+//!
+//! Usage: `poolgen <difficulty> <count> <output file> [seed]`
+//!
+//! Writes one puzzle per line in the line format, followed by its difficulty and
+//! [`Sudoku::difficulty_score`], e.g.
+//!
+//! ```text
+//! 971.3...8.5.....31.....2..9..32.8...1...9...3...3.61..5..9.....61.....5.8...5.294 medium 900
+//! ```
+//!
+//! Puzzles that are equivalent up to relabeling, rotation and the like are only written once.
+//! The lines can be uploaded as they are with the contract's `add_puzzles`.
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+    use std::io::{BufWriter, Write};
+    use sudoku::{Difficulty, Sudoku};
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if !(4..=5).contains(&args.len()) {
+        exit("usage: poolgen <easy|medium|hard|expert> <count> <output file> [seed]");
+    }
+    let difficulty = Difficulty::from_name(&args[1])
+        .unwrap_or_else(|| exit("difficulty should be one of easy, medium, hard or expert"));
+    let count: usize = args[2].parse().unwrap_or_else(|_| exit("count should be a number"));
+    let seed: u64 = match args.get(4) {
+        Some(seed) => seed.parse().unwrap_or_else(|_| exit("seed should be a number")),
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64),
+    };
+    let file =
+        std::fs::File::create(&args[3]).unwrap_or_else(|err| exit(&format!("can't create {}: {}", args[3], err)));
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut out = BufWriter::new(file);
+    let mut seen = HashSet::new();
+    let (mut n_off_target, mut n_duplicates) = (0, 0);
+    while seen.len() < count {
+        let sudoku = match Sudoku::generate_graded(&mut rng, difficulty) {
+            Ok(generation) => generation.sudoku,
+            Err(_) => {
+                n_off_target += 1;
+                continue;
+            }
+        };
+        let (canonical, _) = sudoku.canonicalized().expect("generated sudokus are uniquely solvable");
+        if !seen.insert(canonical) {
+            n_duplicates += 1;
+            continue;
+        }
+        writeln!(out, "{} {} {}", sudoku.to_str_line(), difficulty.name(), sudoku.difficulty_score())
+            .unwrap_or_else(|err| exit(&format!("can't write {}: {}", args[3], err)));
+    }
+    out.flush().unwrap_or_else(|err| exit(&format!("can't write {}: {}", args[3], err)));
+
+    eprintln!(
+        "wrote {} puzzles with seed {}, skipped {} duplicates and {} failed generations",
+        count, seed, n_duplicates, n_off_target
+    );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1)
+}
+
+// the contract build compiles all targets for wasm, where there is no file system to write to
+#[cfg(target_arch = "wasm32")]
+fn main() {}
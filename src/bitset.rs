@@ -4,10 +4,28 @@
 //! Efficient storage is important for maximal performance, but it should not be possible
 //! to confuse bitmasks for different things. This module contains type-safe, space-efficient
 //! fixed-length bitsets for digits and various sudoku positions.
+//!
+//! Sets combine with the usual operators: `|` is the union, `&` the intersection, `-` the difference
+//! and `^` the symmetric difference. Either side may also be a single element.
+//!
+//! ```
+//! use sudoku::bitset::Set;
+//! use sudoku::board::Digit;
+//!
+//! let odd: Set<Digit> = [1, 3, 5, 7, 9].iter().map(|&digit| Digit::new(digit)).collect();
+//! let small = Set::from([Digit::new(1), Digit::new(2), Digit::new(3)]);
+//! assert_eq!(odd - small, Set::from([Digit::new(5), Digit::new(7), Digit::new(9)]));
+//! assert_eq!((odd ^ small).len(), 4);
+//! assert_eq!(u128::from(small), 0b111);
+//! ```
 
 use crate::board::{Cell, Col, Digit, House, Line, Position, Row};
 use crate::helper::Unsolvable;
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::convert::TryFrom;
+use core::iter::FromIterator;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign};
+#[cfg(feature = "std")]
+use near_sdk::serde::{Serialize, Serializer};
 
 /// Generic, fixed-size bitset
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -17,6 +35,17 @@ pub struct Set<T: SetElement>(pub(crate) T::Storage);
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Iter<T: SetElement>(T::Storage);
 
+/// Serializes as the sequence of contained elements.
+#[cfg(feature = "std")]
+impl<T: SetElement + Serialize> Serialize for Set<T>
+where
+    Iter<T>: Iterator<Item = T>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(Iter::<T>(self.0))
+    }
+}
+
 impl<T: SetElement> IntoIterator for Set<T>
 where
     Iter<T>: Iterator,
@@ -101,6 +130,113 @@ where
     }
 }
 
+impl<T: SetElement> Sub for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.without(other)
+    }
+}
+
+impl<T: SetElement> Sub<T> for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    type Output = Self;
+    fn sub(self, other: T) -> Self {
+        self.without(other.as_set())
+    }
+}
+
+impl<T: SetElement> SubAssign for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.remove(other)
+    }
+}
+
+impl<T: SetElement> SubAssign<T> for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    fn sub_assign(&mut self, other: T) {
+        self.remove(other)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+//                                  Conversions
+///////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T: SetElement> FromIterator<T> for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Set::NONE;
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: SetElement> Extend<T> for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<T: SetElement, const N: usize> From<[T; N]> for Set<T>
+where
+    Self: PartialEq + Copy,
+{
+    fn from(elements: [T; N]) -> Self {
+        IntoIterator::into_iter(elements).collect()
+    }
+}
+
+impl<T: SetElement> From<Set<T>> for u128
+where
+    T::Storage: Into<u128>,
+{
+    fn from(set: Set<T>) -> u128 {
+        set.0.into()
+    }
+}
+
+/// Error for [`Set::try_from`] with bits set above [`Set::ALL`]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct InvalidBits;
+
+impl fmt::Display for InvalidBits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bits set outside of the set's elements")
+    }
+}
+
+impl<T: SetElement> TryFrom<u128> for Set<T>
+where
+    Self: PartialEq + Copy,
+    T::Storage: TryFrom<u128>,
+{
+    type Error = InvalidBits;
+
+    fn try_from(bits: u128) -> Result<Self, InvalidBits> {
+        match T::Storage::try_from(bits) {
+            Ok(mask) if mask <= <T as SetElement>::ALL => Ok(Set(mask)),
+            _ => Err(InvalidBits),
+        }
+    }
+}
+
 /// Potential return value for [`Set::unique`]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub struct Empty;
@@ -142,9 +278,14 @@ where
         Set(self.0 & !other.0)
     }
 
-    /// Deletes all elements from this set that are present in `other`.
-    pub fn remove(&mut self, other: Self) {
-        self.0 &= !other.0;
+    /// Adds all elements of `other`, which may be a single element, to this set.
+    pub fn insert(&mut self, other: impl Into<Self>) {
+        self.0 |= other.into().0;
+    }
+
+    /// Deletes all elements from this set that are present in `other`, which may be a single element.
+    pub fn remove(&mut self, other: impl Into<Self>) {
+        self.0 &= !other.into().0;
     }
 
     /// Checks if `self` and `other` contain any common element.
@@ -221,10 +362,11 @@ pub trait SetElement: Sized + set_element::Sealed {
         + BitXorAssign
         + Not<Output = Self::Storage>
         + PartialOrd
-        + std::fmt::Binary
+        + core::fmt::Binary
         + Copy;
 
     fn count_possibilities(set: Self::Storage) -> u32;
+    #[allow(clippy::wrong_self_convention)]
     fn as_set(self) -> Set<Self>;
 }
 mod set_element {
@@ -339,9 +481,46 @@ impl_iter_for_setiter!(
     //Position<Chute> => Position::new,
 );
 
-use std::fmt;
+use core::fmt;
 impl<T: SetElement> fmt::Binary for Set<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:b}", self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_ops() {
+        let digits = |digits: &[u8]| digits.iter().map(|&digit| Digit::new(digit)).collect::<Set<Digit>>();
+        let mut set = digits(&[1, 2, 3]);
+        set.insert(Digit::new(9));
+        set.remove(Digit::new(2));
+        assert_eq!(set, digits(&[1, 3, 9]));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(Digit::new(9)) && !set.contains(Digit::new(2)));
+
+        assert_eq!(set - digits(&[3, 4]), digits(&[1, 9]));
+        assert_eq!(set - Digit::new(1), digits(&[3, 9]));
+        assert_eq!(set ^ digits(&[3, 4]), digits(&[1, 4, 9]));
+        let mut difference = set;
+        difference -= Digit::new(3);
+        assert_eq!(difference, digits(&[1, 9]));
+
+        assert_eq!(Set::from([Cell::new(0), Cell::new(80)]).len(), 2);
+        assert_eq!(Set::<Digit>::from([]), Set::NONE);
+    }
+
+    #[test]
+    fn bits_conversion() {
+        let cells = Set::from([Cell::new(0), Cell::new(80)]);
+        assert_eq!(u128::from(cells), 1 | 1 << 80);
+        assert_eq!(Set::try_from(1 | 1 << 80), Ok(cells));
+        assert_eq!(Set::<Cell>::try_from(1 << 81), Err(InvalidBits));
+        assert_eq!(Set::<Digit>::try_from(0b1_1111_1111), Ok(Set::ALL));
+        assert_eq!(Set::<Digit>::try_from(1 << 9), Err(InvalidBits));
+        assert_eq!(Set::<Digit>::try_from(1 << 20), Err(InvalidBits));
+    }
+}
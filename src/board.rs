@@ -1,5 +1,7 @@
 //! Types for cells, digits and other things on a sudoku board
+mod builder;
 mod candidate;
+mod candidates;
 mod canonicalization;
 mod cell_state;
 mod digit;
@@ -13,9 +15,15 @@ pub(crate) use self::positions::*;
 pub use self::{
     sudoku::Sudoku,
     sudoku::Symmetry,
+    sudoku::Blank,
+    sudoku::RenderOptions,
+    sudoku::SudokuDisplay,
+    sudoku::Conflict,
+    builder::SudokuBuilder,
     digit::Digit,
     positions::Cell,
     candidate::Candidate,
+    candidates::Candidates,
     cell_state::CellState,
-    // grid_state::GridState,
+    grid_state::GridState,
 };
@@ -0,0 +1,97 @@
+use super::{Cell, Digit, Sudoku};
+use crate::consts::*;
+use crate::errors::BuildError;
+
+/// Incrementally constructs a sudoku from single clues, e.g. in a puzzle editor.
+///
+/// Every clue is checked against the clues of its row, column and block as it's added,
+/// so the finished sudoku never contains two equal digits in a house.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SudokuBuilder {
+    cells: [u8; N_CELLS],
+}
+
+impl Default for SudokuBuilder {
+    fn default() -> Self {
+        SudokuBuilder::new()
+    }
+}
+
+impl SudokuBuilder {
+    /// Starts from an empty grid.
+    pub fn new() -> Self {
+        SudokuBuilder { cells: [0; N_CELLS] }
+    }
+
+    /// Enters `digit` in `cell`, replacing any clue the cell had before.
+    ///
+    /// Fails without changing anything, if another cell in the same row, column or block already holds `digit`.
+    pub fn clue(&mut self, cell: Cell, digit: Digit) -> Result<&mut Self, BuildError> {
+        let peer = cell
+            .neighbors()
+            .into_iter()
+            .find(|peer| self.cells[peer.as_index()] == digit.get());
+        if let Some(peer) = peer {
+            return Err(BuildError::Conflict {
+                cell,
+                digit: digit.get(),
+                peer,
+            });
+        }
+        self.cells[cell.as_index()] = digit.get();
+        Ok(self)
+    }
+
+    /// Removes the clue of `cell`, if it has one.
+    pub fn clear(&mut self, cell: Cell) -> &mut Self {
+        self.cells[cell.as_index()] = 0;
+        self
+    }
+
+    /// Returns the sudoku with the clues entered so far. It may have any number of solutions.
+    pub fn build(&self) -> Sudoku {
+        Sudoku(self.cells)
+    }
+
+    /// Returns the sudoku with the clues entered so far, if it has exactly one solution.
+    pub fn build_unique(&self) -> Result<Sudoku, BuildError> {
+        let sudoku = self.build();
+        match sudoku.solutions_count_up_to(2) {
+            1 => Ok(sudoku),
+            n_solutions => Err(BuildError::NotUnique { n_solutions }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builder() {
+        let mut builder = SudokuBuilder::new();
+        builder.clue(Cell::new(0), Digit::new(5)).unwrap().clue(Cell::new(40), Digit::new(5)).unwrap();
+        assert_eq!(
+            builder.clue(Cell::new(8), Digit::new(5)).unwrap_err(),
+            BuildError::Conflict {
+                cell: Cell::new(8),
+                digit: 5,
+                peer: Cell::new(0),
+            }
+        );
+        assert_eq!(builder.build().n_clues(), 2);
+
+        // replacing a clue doesn't conflict with the clue itself
+        builder.clue(Cell::new(0), Digit::new(5)).unwrap().clue(Cell::new(0), Digit::new(6)).unwrap();
+        builder.clear(Cell::new(40));
+        assert_eq!(builder.build().to_bytes()[..2], [6, 0]);
+        assert_eq!(builder.build_unique(), Err(BuildError::NotUnique { n_solutions: 2 }));
+
+        let solution = Sudoku::generate_solved(&mut rand::SeedableRng::seed_from_u64(0));
+        let mut builder = SudokuBuilder::new();
+        for (cell, digit) in solution.entries() {
+            builder.clue(cell, digit.unwrap()).unwrap();
+        }
+        assert_eq!(builder.build_unique(), Ok(solution));
+    }
+}
@@ -1,8 +1,12 @@
 use crate::bitset::Set;
 use crate::board::{Block, Cell, Col, Digit, Row};
+#[cfg(feature = "std")]
+use near_sdk::serde::Serialize;
 
 /// Represents a digit in a specific cell
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 #[allow(missing_docs)]
 pub struct Candidate {
     pub cell: Cell,
@@ -0,0 +1,101 @@
+use super::{Cell, CellState, Digit, GridState, Sudoku};
+use crate::bitset::Set;
+
+/// The remaining candidates of all cells of a (possibly partial) sudoku, i.e. the automatic pencil marks.
+///
+/// A digit is a candidate of an empty cell, if it isn't entered in any cell of the same row, column or block.
+/// Filled cells have no candidates. Created by [`Sudoku::candidates`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Candidates {
+    sudoku: Sudoku,
+    cell_candidates: [Set<Digit>; 81],
+}
+
+impl Candidates {
+    /// Computes the candidates of all empty cells of `sudoku`.
+    pub fn new(sudoku: Sudoku) -> Self {
+        let mut cell_candidates = [Set::NONE; 81];
+        for cell in Cell::all().filter(|cell| sudoku.0[cell.as_index()] == 0) {
+            let entered = cell
+                .neighbors_set()
+                .into_iter()
+                .filter_map(|neighbor| Digit::new_checked(sudoku.0[neighbor.as_index()]))
+                .fold(Set::NONE, |digits, digit| digits | digit);
+            cell_candidates[cell.as_index()] = !entered;
+        }
+        Candidates {
+            sudoku,
+            cell_candidates,
+        }
+    }
+
+    /// The candidates of `cell`. Empty for filled cells.
+    pub fn candidates_of(&self, cell: Cell) -> Set<Digit> {
+        self.cell_candidates[cell.as_index()]
+    }
+
+    /// The empty cells that `digit` can still be entered in.
+    pub fn cells_with_candidate(&self, digit: Digit) -> Set<Cell> {
+        Cell::all()
+            .filter(|&cell| self.candidates_of(cell).contains(digit))
+            .fold(Set::NONE, |cells, cell| cells | cell)
+    }
+
+    /// The empty cells without any candidate. If there are any, the sudoku is unsolvable.
+    pub fn cells_without_candidates(&self) -> Set<Cell> {
+        Cell::all()
+            .filter(|&cell| self.sudoku.0[cell.as_index()] == 0 && self.candidates_of(cell).is_empty())
+            .fold(Set::NONE, |cells, cell| cells | cell)
+    }
+}
+
+impl From<Candidates> for GridState {
+    fn from(candidates: Candidates) -> Self {
+        let mut cell_states = [CellState::Candidates(Set::NONE); 81];
+        for (cell, cell_state) in Cell::all().zip(cell_states.iter_mut()) {
+            *cell_state = match Digit::new_checked(candidates.sudoku.0[cell.as_index()]) {
+                Some(digit) => CellState::Digit(digit),
+                None => CellState::Candidates(candidates.candidates_of(cell)),
+            };
+        }
+        GridState::from(cell_states)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn candidates() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let candidates = sudoku.candidates();
+        let digits = |digits: &[u8]| digits.iter().fold(Set::NONE, |set, &digit| set | Digit::new(digit));
+
+        assert_eq!(candidates.candidates_of(Cell::new(0)), Set::NONE);
+        assert_eq!(candidates.candidates_of(Cell::new(2)), digits(&[1, 2, 4]));
+        assert_eq!(candidates.candidates_of(Cell::new(3)), digits(&[2, 6]));
+
+        let cells_with_5 = [24, 28, 29, 30, 33, 34, 37, 38, 40, 42, 43, 46, 47, 48, 51, 52, 56, 57, 58, 73, 74, 75]
+            .iter()
+            .fold(Set::NONE, |set, &cell| set | Cell::new(cell));
+        assert_eq!(candidates.cells_with_candidate(Digit::new(5)), cells_with_5);
+        assert!(candidates.cells_without_candidates().is_empty());
+
+        let grid_state = GridState::from(candidates);
+        assert_eq!(grid_state.cell_states()[0], CellState::Digit(Digit::new(5)));
+        assert_eq!(grid_state.cell_states()[3], CellState::Candidates(digits(&[2, 6])));
+    }
+
+    #[test]
+    fn cells_without_candidates() {
+        // the first cell sees 1-8 in its row and 9 in its column
+        let mut bytes = [0; 81];
+        bytes[1..10].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let sudoku = Sudoku::from_bytes(bytes).unwrap();
+        let candidates = sudoku.candidates();
+        assert_eq!(candidates.cells_without_candidates(), Cell::new(0).as_set());
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::{vec, vec::Vec};
 use rand::rngs::StdRng;
 
 use crate::Sudoku;
@@ -58,7 +59,7 @@ impl Transformation {
         apply_digit_mapping(self.digit_remapping, sudoku);
     }
 
-    pub(crate) fn random(mut rng: &mut StdRng) -> Self {
+    pub(crate) fn random(rng: &mut StdRng) -> Self {
         use rand::{distributions::Distribution, Rng};
         // SmallRng is a good 10% faster, but it uses XorShiftRng which can fail some statistical tests
         // There are some adaptions that fix this, but I don't know if Rust implements them.
@@ -121,7 +122,7 @@ impl Permutation3 {
     }
 }
 
-fn apply_digit_mapping(digit_remapping: [u8; 9], sudoku: &mut [u8]) {
+pub(crate) fn apply_digit_mapping(digit_remapping: [u8; 9], sudoku: &mut [u8]) {
     for cell_digit in sudoku {
         if *cell_digit == 0 {
             continue;
@@ -153,13 +154,13 @@ pub(crate) fn find_canonical_sudoku_and_transformation(sudoku: Sudoku) -> (Sudok
         }
     }
 
-    use std::cmp::Ordering::*;
+    use core::cmp::Ordering::*;
     let mut count = 1;
     min_transformations
         .into_iter()
         .map(|trans| find_minimal_transformation_for_band(sudoku, trans))
         .min_by(|(sudoku1, _), (sudoku2, _)| {
-            let c = sudoku1.cmp(&sudoku2);
+            let c = sudoku1.cmp(sudoku2);
             match c {
                 Greater => count = 1,
                 Less => {}
@@ -306,7 +307,7 @@ fn find_minimal_transformation_for_band(
         let needs_band_switch = min_sudoku[27..54] > min_sudoku[54..];
         let band_choice2 = needs_band_switch as u8;
         if needs_band_switch {
-            std::mem::swap(&mut row_perm2, &mut row_perm3);
+            core::mem::swap(&mut row_perm2, &mut row_perm3);
         }
         swap_bands(min_sudoku, 1, 1 + band_choice2);
 
@@ -325,14 +326,14 @@ fn find_minimal_transformation_for_band(
 
 /// Find minimal row order and the permutation to get there again.
 fn sort_rows_in_band_and_find_permutation(sudoku: &mut [u8], band: u8) -> Permutation3 {
-    let mut band = &mut sudoku[band as usize * 27..][..27];
+    let band = &mut sudoku[band as usize * 27..][..27];
     let first_choice = (0..3).min_by_key(|&row| &band[9 * row as usize..][..9]).unwrap();
-    swap_rows_in_band(&mut band, 0, first_choice);
+    swap_rows_in_band(band, 0, first_choice);
 
     let second_choice = (0..2)
         .min_by_key(|&row| &band[9 * (row + 1) as usize..][..9])
         .unwrap();
-    swap_rows_in_band(&mut band, 1, 1 + second_choice);
+    swap_rows_in_band(band, 1, 1 + second_choice);
     Permutation3::from_choices(first_choice, second_choice)
 }
 
@@ -346,8 +347,8 @@ fn permute<T: ?Sized>(
     swapper(sudoku, offset + 1, offset + 1 + permutation.choice2());
 }
 
-fn transpose(sudoku: &mut [u8]) {
-    use std::iter::repeat;
+pub(crate) fn transpose(sudoku: &mut [u8]) {
+    use core::iter::repeat;
     swap_cells(
         sudoku,
         (0..9)
@@ -357,7 +358,7 @@ fn transpose(sudoku: &mut [u8]) {
 }
 
 #[rustfmt::skip]
-fn swap_rows(sudoku: &mut [u8], row1: u8, row2: u8) {
+pub(crate) fn swap_rows(sudoku: &mut [u8], row1: u8, row2: u8) {
     if row1 == row2 {
         return;
     }
@@ -371,7 +372,7 @@ fn swap_rows(sudoku: &mut [u8], row1: u8, row2: u8) {
     )
 }
 
-fn swap_cols(sudoku: &mut [u8], col1: u8, col2: u8) {
+pub(crate) fn swap_cols(sudoku: &mut [u8], col1: u8, col2: u8) {
     if col1 == col2 {
         return;
     }
@@ -383,7 +384,7 @@ fn swap_cols(sudoku: &mut [u8], col1: u8, col2: u8) {
     )
 }
 
-fn swap_stacks(sudoku: &mut [u8], stack1: u8, stack2: u8) {
+pub(crate) fn swap_stacks(sudoku: &mut [u8], stack1: u8, stack2: u8) {
     if stack1 == stack2 {
         return;
     }
@@ -394,7 +395,7 @@ fn swap_stacks(sudoku: &mut [u8], stack1: u8, stack2: u8) {
     }
 }
 
-fn swap_bands(sudoku: &mut [u8], band1: u8, band2: u8) {
+pub(crate) fn swap_bands(sudoku: &mut [u8], band1: u8, band2: u8) {
     if band1 == band2 {
         return;
     }
@@ -9,8 +9,8 @@ pub enum CellState {
     Candidates(Set<Digit>),
 }
 
-impl std::fmt::Display for CellState {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl core::fmt::Display for CellState {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         let requested_width = f.width().unwrap_or(0);
         match self {
             Self::Digit(digit) => write!(f, "{:<width$}", digit.get(), width = requested_width),
@@ -23,7 +23,7 @@ impl std::fmt::Display for CellState {
                     }
                 }
 
-                let required_padding = requested_width.saturating_sub(std::cmp::max(cands.len(), 1) as usize);
+                let required_padding = requested_width.saturating_sub(core::cmp::max(cands.len(), 1) as usize);
                 if required_padding > 0 {
                     write!(f, "{:<width$}", " ", width = required_padding)?;
                 }
@@ -1,8 +1,15 @@
-use std::num::NonZeroU8;
+use crate::errors::InvalidDigit;
+use core::convert::TryFrom;
+use core::fmt;
+use core::num::NonZeroU8;
+#[cfg(feature = "std")]
+use near_sdk::serde::Serialize;
 
 // define digit separately because it has an offset
 /// A digit that can be entered in a cell of a sudoku.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Digit(NonZeroU8);
 
 impl Digit {
@@ -22,6 +29,16 @@ impl Digit {
         NonZeroU8::new(digit).map(Digit)
     }
 
+    /// Constructs a new `Digit` from one of the chars `'1'..='9'`. Returns `None` for any other char.
+    pub fn from_char(ch: char) -> Option<Self> {
+        ch.to_digit(10).and_then(|digit| Self::new_checked(digit as u8))
+    }
+
+    /// Returns the digit as one of the chars `'1'..='9'`.
+    pub fn to_char(self) -> char {
+        (b'0' + self.get()) as char
+    }
+
     /// Constructs a new `Digit` from an index, i.e. `digit - 1`.
     ///
     /// # Panic
@@ -45,3 +62,68 @@ impl Digit {
         self.get() as usize - 1
     }
 }
+
+impl TryFrom<u8> for Digit {
+    type Error = InvalidDigit;
+
+    fn try_from(digit: u8) -> Result<Self, InvalidDigit> {
+        Digit::new_checked(digit).ok_or(InvalidDigit::Number(digit))
+    }
+}
+
+impl TryFrom<char> for Digit {
+    type Error = InvalidDigit;
+
+    fn try_from(ch: char) -> Result<Self, InvalidDigit> {
+        Digit::from_char(ch).ok_or(InvalidDigit::Char(ch))
+    }
+}
+
+impl From<Digit> for u8 {
+    fn from(digit: Digit) -> u8 {
+        digit.get()
+    }
+}
+
+impl From<Digit> for char {
+    fn from(digit: Digit) -> char {
+        digit.to_char()
+    }
+}
+
+impl fmt::Display for Digit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bitset::Set;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Digit::try_from(7), Ok(Digit::new(7)));
+        assert_eq!(Digit::try_from(0), Err(InvalidDigit::Number(0)));
+        assert_eq!(Digit::try_from(10), Err(InvalidDigit::Number(10)));
+        assert_eq!(Digit::try_from('3'), Ok(Digit::new(3)));
+        assert_eq!(Digit::try_from('0'), Err(InvalidDigit::Char('0')));
+        assert_eq!(Digit::from_char('x'), None);
+        for digit in Digit::all() {
+            assert_eq!(Digit::from_char(digit.to_char()), Some(digit));
+            assert_eq!(u8::from(digit), digit.get());
+            assert_eq!(digit.to_string(), char::from(digit).to_string());
+        }
+    }
+
+    #[test]
+    fn digit_sets() {
+        let digits = |line: &str| line.chars().filter_map(Digit::from_char).collect::<Set<Digit>>();
+        let candidates = digits("1357");
+        assert_eq!(candidates - digits("37"), digits("15"));
+        assert_eq!(candidates & Digit::new(3), digits("3"));
+        assert_eq!(!candidates, digits("24689"));
+        assert_eq!(candidates.into_iter().map(Digit::to_char).collect::<String>(), "1357");
+    }
+}
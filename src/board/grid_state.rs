@@ -1,7 +1,64 @@
-use super::CellState;
-use std::fmt::Formatter;
+use alloc::borrow::ToOwned;
+use super::{CellState, Digit};
+use crate::bitset::Set;
+use crate::errors::GridStateParseError;
+use core::fmt::Formatter;
 
-pub struct GridState([CellState; 81]);
+/// The state of all cells of a sudoku, either a digit or the remaining candidates.
+///
+/// It is printed in the pencil-marked block format shown below and can be parsed from it with
+/// [`GridState::from_str_block`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GridState(pub(crate) [CellState; 81]);
+
+impl GridState {
+    /// Reads a grid in the pencil-marked block format, e.g. as printed by [`GridState`] or by the
+    /// [`StrategySolver`](crate::strategy::StrategySolver) or as exported by Hodoku.
+    ///
+    /// Each cell is given by its candidates as one group of digits, separated by whitespace.
+    /// `'_'` denotes a cell without candidates. A single candidate is read as a solved cell.
+    /// Frame characters such as `│ ─ ┼ | - + . :` are ignored.
+    pub fn from_str_block(s: &str) -> Result<GridState, GridStateParseError> {
+        let is_frame = |ch: char| "│─┌┐└┘├┤┬┴┼|-+.:=*".contains(ch);
+
+        let mut grid = [CellState::Candidates(Set::NONE); 81];
+        let mut n_cells = 0;
+        for entry in s.split_whitespace().filter(|entry| !entry.chars().all(is_frame)) {
+            let candidates = match entry {
+                "_" => Set::NONE,
+                _ => entry.chars().try_fold(Set::NONE, |candidates, ch| {
+                    match ch.to_digit(10).and_then(|digit| Digit::new_checked(digit as u8)) {
+                        Some(digit) => Ok(candidates | digit),
+                        None => Err(GridStateParseError::InvalidEntry(entry.to_owned())),
+                    }
+                })?,
+            };
+            if let Some(cell_state) = grid.get_mut(n_cells) {
+                *cell_state = match candidates.unique().unwrap_or(None) {
+                    Some(digit) => CellState::Digit(digit),
+                    None => CellState::Candidates(candidates),
+                };
+            }
+            n_cells += 1;
+        }
+
+        match n_cells {
+            81 => Ok(GridState(grid)),
+            _ => Err(GridStateParseError::WrongCellCount(n_cells)),
+        }
+    }
+
+    /// Returns the state of every cell, left-to-right, top-to-bottom.
+    pub fn cell_states(&self) -> [CellState; 81] {
+        self.0
+    }
+}
+
+impl From<[CellState; 81]> for GridState {
+    fn from(cell_states: [CellState; 81]) -> Self {
+        GridState(cell_states)
+    }
+}
 
 /* Example output
 ┌──────────────────────────────┬──────────────────────────────┬──────────────────────────────┐
@@ -19,19 +76,19 @@ pub struct GridState([CellState; 81]);
 └──────────────────────────────┴──────────────────────────────┴──────────────────────────────┘
 */
 
-impl std::fmt::Display for GridState {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+impl core::fmt::Display for GridState {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), core::fmt::Error> {
         let mut column_widths = [0; 9];
-        for col in 0..9 {
+        for (col, column_width) in column_widths.iter_mut().enumerate() {
             let max_width = (0..9)
                 .map(|row| match self.0[row * 9 + col] {
                     CellState::Digit(_) => 1,
-                    CellState::Candidates(cands) => std::cmp::max(1, cands.len()),
+                    CellState::Candidates(cands) => core::cmp::max(1, cands.len()),
                 })
                 .max()
                 .unwrap();
-            debug_assert!(1 <= max_width && max_width <= 9);
-            column_widths[col] = max_width;
+            debug_assert!((1..=9).contains(&max_width));
+            *column_width = max_width;
         }
 
         let stack_width = |stack_nr: usize| {
@@ -109,8 +166,57 @@ fn grid_state_roundtrip() {
 │ 23456789  13456789  12456789 │ 12356789  12346789  12345789 │ 12345689  12345679  12345678 │
 │ 23456789  13456789  12456789 │ 12356789  12346789  12345789 │ 12345689  12345679  12345678 │
 └──────────────────────────────┴──────────────────────────────┴──────────────────────────────┘";
-    let solver = StrategySolver::from_grid_state_str(s);
+    let solver = StrategySolver::from_grid_state_str(s).unwrap();
     let grid_state = GridState(solver.grid_state());
     let new_str = format!("{}", grid_state);
     assert_eq!(s, &new_str[..]);
+    assert_eq!(GridState::from_str_block(s).unwrap(), grid_state);
+}
+
+#[test]
+fn strategy_solver_printout_roundtrip() {
+    use crate::strategy::StrategySolver;
+    use crate::Sudoku;
+    let sudoku =
+        Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+    let solver = StrategySolver::from_sudoku(sudoku);
+    let printout = solver.to_string();
+    let reparsed = StrategySolver::from_grid_state_str(&printout).unwrap();
+    assert_eq!(reparsed.to_string(), printout);
+}
+
+#[test]
+fn grid_state_ascii_frame() {
+    let s = ".----------------.----------------.----------------.
+| 1    2    3    | 4    5    6    | 7    8    _    |
+| 45   45   45   | 78   78   78   | 16   16   9    |
+| 469  469  469  | 12   12   12   | 15   15   15   |
+:----------------+----------------+----------------:
+| 9    19   19   | 3    3    3    | 4    4    4    |
+| 12   12   12   | 3    3    3    | 4    4    4    |
+| 12   12   12   | 3    3    3    | 4    4    4    |
+:----------------+----------------+----------------:
+| 12   12   12   | 3    3    3    | 4    4    4    |
+| 12   12   12   | 3    3    3    | 4    4    4    |
+| 12   12   12   | 3    3    3    | 4    4    4    |
+'----------------'----------------'----------------'";
+    let s = s.replace('\'', ".");
+    let grid_state = GridState::from_str_block(&s).unwrap();
+    let cells = grid_state.cell_states();
+    assert_eq!(cells[0], CellState::Digit(Digit::new(1)));
+    assert_eq!(cells[8], CellState::Candidates(Set::NONE));
+    assert_eq!(cells[9], CellState::Candidates(Digit::new(4).as_set() | Digit::new(5)));
+}
+
+#[test]
+fn grid_state_parse_errors() {
+    let row = "1 2 3 4 5 6 7 8 9\n";
+    assert_eq!(
+        GridState::from_str_block(&row.repeat(8)),
+        Err(GridStateParseError::WrongCellCount(72))
+    );
+    assert_eq!(
+        GridState::from_str_block(&format!("{}1x {}", row.repeat(8), "2 3 4 5 6 7 8 9")),
+        Err(GridStateParseError::InvalidEntry("1x".to_owned()))
+    );
 }
@@ -2,12 +2,18 @@
 //!
 //! A sudoku consists of 81 cells, arranged into 9 rows, 9 columns and 9 blocks.
 #![allow(unused, missing_docs)]
+// the octal cell masks are grouped by row and band
+#![allow(clippy::unusual_byte_groupings)]
 
 use crate::bitset::{Set, SetElement};
 use crate::board::Digit;
 use crate::consts::*;
+use crate::errors::OutOfRange;
 use crate::helper::Unsolvable;
-use std::num::NonZeroU8;
+use core::convert::TryFrom;
+use core::num::NonZeroU8;
+#[cfg(feature = "std")]
+use near_sdk::serde::{Serialize, Serializer};
 
 #[inline(always)]
 pub(crate) fn row(cell: u8) -> u8 {
@@ -179,7 +185,7 @@ macro_rules! define_types(
             /// # Panics
             /// Panics, if the argument is outside the allowed range.
             pub fn new(num: u8) -> Self {
-                debug_assert!(num < $limit);
+                assert!(num < $limit, concat!(stringify!($name), " out of range: {}"), num);
                 $name(num)
             }
 
@@ -205,7 +211,15 @@ macro_rules! define_types(
 
             /// Iterator over all positions of this type.
             pub fn all() -> impl Iterator<Item = Self> {
-                (0..$limit).map(Self::new)
+                (0..$limit).map($name)
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = OutOfRange;
+
+            fn try_from(num: u8) -> Result<Self, OutOfRange> {
+                $name::new_checked(num).ok_or(OutOfRange { value: num, limit: $limit })
             }
         }
     };
@@ -213,50 +227,74 @@ macro_rules! define_types(
 
 /// One of the 81 cells of the sudoku
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Cell(u8);
 
 /// Set of 9 cells in a horizontal line
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Row(u8);
 
 /// Set of 9 cells in a vertical line
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Col(u8);
 
 /// Set of 9 cells in a 3x3 box shape
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Block(u8);
 
 /// A [`Row`] or [`Col`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Line(u8);
 
 /// A [`Row`], [`Col`] or [`Block`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct House(u8);
 
 /// Intersection of a [`Block`] and a [`Row`], 3 cells in a row.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct MiniRow(u8);
 
 /// Intersection of a [`Block`] and a [`Col`], 3 cells in a column.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct MiniCol(u8);
 
 /// A [`MiniRow`] or [`MiniCol`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct MiniLine(u8);
 
 /// Set of 3 [`Row`]s and 3 [`Block`]s where each [`Row`] intersects each [`Block`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Band(u8);
 
 /// Set of 3 [`Col`]s and 3 [`Block`]s where each [`Col`] intersects each [`Block`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Stack(u8);
 
 /// A [`Band`] or [`Stack`]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub struct Chute(u8);
 
 define_types!(
@@ -276,6 +314,8 @@ define_types!(
 
 /// A [`Row`] or [`Col`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub enum LineType {
     Row(Row),
     Col(Col),
@@ -294,6 +334,8 @@ impl Line {
 
 /// A [`Row`], [`Col`] or [`Block`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub enum HouseType {
     Row(Row),
     Col(Col),
@@ -301,9 +343,12 @@ pub enum HouseType {
 }
 
 impl House {
-    pub(crate) const ALL_ROWS: Set<House> = Set(0o000_000_777);
-    pub(crate) const ALL_COLS: Set<House> = Set(0o000_777_000);
-    pub(crate) const ALL_BLOCKS: Set<House> = Set(0o777_000_000);
+    /// Set of all rows
+    pub const ALL_ROWS: Set<House> = Set(0o000_000_777);
+    /// Set of all columns
+    pub const ALL_COLS: Set<House> = Set(0o000_777_000);
+    /// Set of all blocks
+    pub const ALL_BLOCKS: Set<House> = Set(0o777_000_000);
 
     /// Determine whether this house is a [`Row`], [`Col`] or [`Block`]
     #[rustfmt::skip]
@@ -317,8 +362,21 @@ impl House {
     }
 }
 
+/// Formats the house with its number counted from 1, e.g. `row 3`.
+impl core::fmt::Display for House {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.categorize() {
+            HouseType::Row(row) => write!(f, "row {}", row.get() + 1),
+            HouseType::Col(col) => write!(f, "column {}", col.get() + 1),
+            HouseType::Block(block) => write!(f, "block {}", block.get() + 1),
+        }
+    }
+}
+
 /// A [`Band`] or [`Stack`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub enum ChuteType {
     Band(Band),
     Stack(Stack),
@@ -337,6 +395,8 @@ impl Chute {
 
 /// A [`MiniRow`] or [`MiniCol`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 pub enum MiniLineType {
     MiniRow(MiniRow),
     MiniCol(MiniCol),
@@ -355,14 +415,77 @@ impl MiniLine {
 
 /// Generic struct for a cell inside a given set of cells, like e.g. a [`House`]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
-pub struct Position<IN>(pub(crate) u8, std::marker::PhantomData<IN>);
+pub struct Position<IN>(pub(crate) u8, core::marker::PhantomData<IN>);
 
-impl<IN> Position<IN> {
-    /// Construct a new instance of this type.
+/// Serializes as the index of the position inside its container.
+#[cfg(feature = "std")]
+impl<IN> Serialize for Position<IN> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+/// Trait for cell groupings that a [`Position`] can point into
+pub trait PositionContainer: position_container::Sealed {
+    /// Number of cells in the grouping, the exclusive upper bound of its positions
+    const N_POSITIONS: u8;
+}
+
+mod position_container {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_position_container {
+    ( $( $type:ty => $n_positions:expr ),* $(,)* ) => {
+        $(
+            impl position_container::Sealed for $type {}
+            impl PositionContainer for $type {
+                const N_POSITIONS: u8 = $n_positions;
+            }
+        )*
+    };
+}
+
+impl_position_container!(
+    Row => 9,
+    Col => 9,
+    Block => 9,
+    Line => 9,
+    House => 9,
+    Band => 27,
+    Stack => 27,
+    Chute => 27,
+);
+
+impl<IN: PositionContainer> Position<IN> {
+    /// Construct a new instance of this type. `pos` needs to be inside `0..IN::N_POSITIONS`.
+    ///
+    /// # Panics
+    /// Panics, if the argument is outside the allowed range.
     pub fn new(pos: u8) -> Self {
-        // TODO: make panic on invalid positions
-        Position(pos, std::marker::PhantomData)
+        assert!(pos < IN::N_POSITIONS, "position out of range: {}", pos);
+        Position(pos, core::marker::PhantomData)
+    }
+
+    /// Construct a new instance of this type. If `pos` is outside the allowed range, then `None` is returned.
+    pub fn new_checked(pos: u8) -> Option<Self> {
+        if pos < IN::N_POSITIONS {
+            Some(Position(pos, core::marker::PhantomData))
+        } else {
+            None
+        }
+    }
+}
+
+impl<IN: PositionContainer> TryFrom<u8> for Position<IN> {
+    type Error = OutOfRange;
+
+    fn try_from(pos: u8) -> Result<Self, OutOfRange> {
+        Position::new_checked(pos).ok_or(OutOfRange { value: pos, limit: IN::N_POSITIONS })
     }
+}
+
+impl<IN> Position<IN> {
 
     /// Returns the number contained within.
     pub fn get(self) -> u8 {
@@ -416,12 +539,12 @@ into_cells!(
             Block(block) => block.cells().0,
         }
     };
-    MiniRow => |mr| { 0o7 << 3 * mr.0 };
+    MiniRow => |mr| { 0o7 << (3 * mr.0) };
     //MiniCol => |mc| { 0o001_001_001 << mc / 9 * 27 + mc % 9 }; // old, different counting system
     MiniCol => |mc| {
         let band = mc.0 % 3;
         let col = mc.0 / 3;
-        0o001_001_001 << band * 27 + col
+        0o001_001_001 << (band * 27 + col)
     };
     MiniLine => |ml| {
         use self::MiniLineType::*;
@@ -430,8 +553,8 @@ into_cells!(
             MiniCol(mc) => mc.cells().0,
         }
     };
-    Band => |band| { 0o777_777_777 << 27 * band.0 };
-    Stack => |stack| { 0o_007_007_007___007_007_007___007_007_007 << 3 * stack.0 };
+    Band => |band| { 0o777_777_777 << (27 * band.0) };
+    Stack => |stack| { 0o_007_007_007___007_007_007___007_007_007 << (3 * stack.0) };
     Chute => |chute| {
         use self::ChuteType::*;
         match chute.categorize() {
@@ -456,6 +579,7 @@ macro_rules! define_conversion_shortcuts {
         $(
             impl $type {
                 $(
+                    #[doc = concat!("Returns the [`", stringify!($target_type), "`] corresponding to this value.")]
                     #[inline(always)]
                     pub fn $method_name(self) -> $target_type {
                         <$target_type>::from(self)
@@ -471,11 +595,12 @@ define_conversion_shortcuts!(
         Row, row;
         Col, col;
         Block, block;
+        Band, band;
+        Stack, stack;
         //Position<Row>, row_pos;
         //Position<Col>, col_pos;
         //Position<Block>, block_pos;
     }
-    /*
     Row : {
         Line, line;
         House, house;
@@ -484,9 +609,18 @@ define_conversion_shortcuts!(
         Line, line;
         House, house;
     }
+    Line : {
+        House, house;
+    }
     Block : {
         House, house;
     }
+    Band : {
+        Chute, chute;
+    }
+    Stack : {
+        Chute, chute;
+    }
     Position<Row> : {
         Position<Line>, line_pos;
         Position<House>, house_pos;
@@ -495,22 +629,34 @@ define_conversion_shortcuts!(
         Position<Line>, line_pos;
         Position<House>, house_pos;
     }
+    Position<Line> : {
+        Position<House>, house_pos;
+    }
     Position<Block> : {
         Position<House>, house_pos;
     }
-    */
 );
 
+/// Formats the cell by row and column counted from 1, e.g. `r4c7`.
+impl core::fmt::Display for Cell {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "r{}c{}", row(self.0) + 1, col(self.0) + 1)
+    }
+}
+
 impl Cell {
-    pub(crate) fn row_pos(self) -> Position<House> {
+    /// Returns the position of this cell in its row, i.e. its column index.
+    pub fn row_pos(self) -> Position<House> {
         Position::<Row>::from(self).into()
     }
 
-    pub(crate) fn col_pos(self) -> Position<House> {
+    /// Returns the position of this cell in its column, i.e. its row index.
+    pub fn col_pos(self) -> Position<House> {
         Position::<Col>::from(self).into()
     }
 
-    pub(crate) fn block_pos(self) -> Position<House> {
+    /// Returns the position of this cell in its block, counted left to right, then top to bottom.
+    pub fn block_pos(self) -> Position<House> {
         Position::<Block>::from(self).into()
     }
 }
@@ -519,14 +665,14 @@ impl Cell {
 
 impl Cell {
     /// Returns an array of the row, column and block this cell belongs to, in that order.
-    pub(crate) fn houses(self) -> [House; 3] {
+    pub fn houses(self) -> [House; 3] {
         [self.row().house(), self.col().house(), self.block().house()]
     }
 
     /// Returns an iterator over the 20 cells that share a house with this one. The iteration
     /// order is unspecified.
     #[inline(always)]
-    pub(crate) fn neighbors(self) -> impl IntoIterator<Item = Cell> {
+    pub fn neighbors(self) -> impl IntoIterator<Item = Cell> {
         HOUSE_NEIGHBORS_OF_CELL[self.as_index()]
             .iter()
             .cloned()
@@ -536,7 +682,7 @@ impl Cell {
     /// Returns a set of the 20 cells that share a house with this one.
     #[inline(always)]
     #[rustfmt::skip]
-    pub(crate) fn neighbors_set(self) -> Set<Cell> {
+    pub fn neighbors_set(self) -> Set<Cell> {
         (self.row().cells() | self.col().cells() | self.block().cells())
             ^ self
     }
@@ -546,7 +692,7 @@ impl Chute {
     /// Returns an array of all minilines in this chute, ordered first by lines, then by block.
     /// This means that minirows are given from left to right, then top to bottom and minicols
     /// the other way around.
-    pub(crate) fn minilines(self) -> [MiniLine; 9] {
+    pub fn minilines(self) -> [MiniLine; 9] {
         let mut slices = [MiniLine(0); 9];
         for (i, slice) in (0..9).zip(slices.iter_mut()) {
             *slice = MiniLine(self.0 * 9 + i);
@@ -556,14 +702,16 @@ impl Chute {
 }
 
 impl Line {
-    pub(crate) const ALL_ROWS: Set<Line> = Set(0o000_777);
-    pub(crate) const ALL_COLS: Set<Line> = Set(0o777_000);
+    /// Set of all rows
+    pub const ALL_ROWS: Set<Line> = Set(0o000_777);
+    /// Set of all columns
+    pub const ALL_COLS: Set<Line> = Set(0o777_000);
 }
 
 impl MiniLine {
     /// Returns the line and block neighbors of this miniline. The neighbors within the same
     /// line are given in the first array.
-    pub(crate) fn neighbors(self) -> ([MiniLine; 2], [MiniLine; 2]) {
+    pub fn neighbors(self) -> ([MiniLine; 2], [MiniLine; 2]) {
         // line neighbor, block neighbor
         let (ln, bn) = MINILINE_NEIGHBORS[self.as_index()];
         (
@@ -572,8 +720,9 @@ impl MiniLine {
         )
     }
 
+    /// Returns the chute this miniline lies in.
     // TODO: refactor to be part of the define_conversion_shortcuts macro
-    pub(crate) fn chute(self) -> Chute {
+    pub fn chute(self) -> Chute {
         Chute::new(self.0 / 9)
     }
 }
@@ -585,6 +734,14 @@ pub trait CellAt: Sized {
     /// Return the cell at the given position in this cell grouping
     fn cell_at(self, pos: Position<Self>) -> Cell;
 
+    /// Return the cell at position `pos` in this cell grouping or `None`, if `pos` is out of range.
+    fn cell_at_checked(self, pos: u8) -> Option<Cell>
+    where
+        Self: PositionContainer,
+    {
+        Position::new_checked(pos).map(|pos| self.cell_at(pos))
+    }
+
     /// Return the set of cells at the given positions in this cell grouping
     fn cells_at(self, positions: Set<Position<Self>>) -> Set<Cell>
     where
@@ -895,4 +1052,48 @@ mod test {
             assert!(iter1.eq(iter2));
         }
     }
+
+    #[test]
+    fn relationships() {
+        let cell = Cell::new(40);
+        assert_eq!(cell.houses(), [Row::new(4).house(), Col::new(4).house(), Block::new(4).house()]);
+        assert_eq!(cell.neighbors().into_iter().count(), 20);
+        assert!(cell.neighbors().into_iter().eq(cell.neighbors_set()));
+        assert_eq!((cell.band().chute(), cell.stack().chute()), (Chute::new(1), Chute::new(4)));
+
+        assert_eq!(Col::new(2).line(), Line::new(11));
+        assert_eq!(Line::new(11).house(), House::new(11));
+        assert_eq!(Block::new(0).house(), House::new(18));
+        assert_eq!(Position::<Col>::new(3).house_pos(), Position::<House>::new(3));
+        assert_eq!(cell.block_pos(), Position::new(4));
+
+        let minilines = Chute::new(4).minilines();
+        assert!(minilines.iter().all(|miniline| miniline.chute() == Chute::new(4)));
+    }
+
+    #[test]
+    fn checked_construction() {
+        assert_eq!(Cell::try_from(80), Ok(Cell::new(80)));
+        assert_eq!(Cell::try_from(81), Err(OutOfRange { value: 81, limit: 81 }));
+        assert_eq!(Chute::try_from(6), Err(OutOfRange { value: 6, limit: 6 }));
+        assert_eq!(Position::<House>::try_from(8), Ok(Position::new(8)));
+        assert_eq!(Position::<House>::try_from(9), Err(OutOfRange { value: 9, limit: 9 }));
+        assert_eq!(Position::<Band>::new_checked(26), Some(Position::new(26)));
+
+        assert_eq!(Row::new(1).cell_at_checked(8), Some(Cell::new(17)));
+        assert_eq!(Col::new(1).cell_at_checked(8), Some(Cell::new(73)));
+        assert_eq!(Row::new(1).cell_at_checked(9), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cell_out_of_range() {
+        Cell::new(81);
+    }
+
+    #[test]
+    #[should_panic]
+    fn position_out_of_range() {
+        Position::<Row>::new(9);
+    }
 }
@@ -1,17 +1,22 @@
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{self, *};
+#[cfg(feature = "std")]
+use std::io;
 use rand::rngs::StdRng;
-use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
+use crate::bitset::Set;
 use crate::consts::*;
-use crate::errors::{BlockParseError, InvalidEntry, LineParseError, NotEnoughRows};
-use crate::generator::SudokuGenerator;
-use crate::solver::SudokuSolver;
+use crate::errors::{BlockParseError, Contradiction, Exceeded, InvalidEntry, LineParseError, NotEnoughRows};
+use crate::generator::{EntropyPool, SudokuGenerator};
+use crate::solver::{Placement, SolverStats, SudokuSolver};
+use crate::strategy::{Deductions, Grade, GradedGeneration, Hint, Strategy, StrategySolver};
 use crate::*;
 
 #[cfg(feature = "serde")]
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
-use std::{
+use near_sdk::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use core::{
     convert::{From, TryFrom},
     fmt, iter, ops, slice, str,
 };
@@ -24,18 +29,16 @@ type SudokuArray = [u8; N_CELLS];
 /// `Sudoku`s can generated, constructed from arrays or parsed from `&str`s
 /// in either the line or block format.
 
-#[derive(
-    BorshDeserialize, BorshSerialize, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Hash,
-)]
+#[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Hash)]
 pub struct Sudoku(pub(crate) SudokuArray);
 
 impl Sudoku {
     pub fn from_two_dimensional_array(array: &SudokuTwoDimensionalArray) -> Sudoku {
         let mut sudoku = Sudoku([0; 81]);
 
-        for x in 0..9 {
-            for y in 0..9 {
-                sudoku.0[x * 9 + y] = array[x][y];
+        for (x, row) in array.iter().enumerate() {
+            for (y, &num) in row.iter().enumerate() {
+                sudoku.0[x * 9 + y] = num;
             }
         }
 
@@ -45,9 +48,9 @@ impl Sudoku {
     pub fn to_two_dimensional_array(&self) -> SudokuTwoDimensionalArray {
         let mut multi_array: [[u8; 9]; 9] = [[0; 9]; 9];
 
-        for x in 0..9 {
-            for y in 0..9 {
-                multi_array[x][y] = self.0[x * 9 + y];
+        for (x, row) in multi_array.iter_mut().enumerate() {
+            for (y, num) in row.iter_mut().enumerate() {
+                *num = self.0[x * 9 + y];
             }
         }
 
@@ -122,8 +125,59 @@ impl<'de> Deserialize<'de> for Sudoku {
     }
 }
 
+/// First byte of the packed borsh encoding. Never a valid cell of the legacy encoding.
+#[cfg(feature = "std")]
+const PACKED_TAG: u8 = 0xff;
+/// Length of the packed borsh encoding without the tag, two cells per byte
+const PACKED_LEN: usize = N_CELLS.div_ceil(2);
+
+// Borsh stores sudokus packed as `PACKED_TAG` followed by one nibble per cell, low nibble first.
+// Sudokus stored in the legacy byte per cell layout are still read and get packed on the next write.
+#[cfg(feature = "std")]
+impl BorshSerialize for Sudoku {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[PACKED_TAG])?;
+        writer.write_all(&self.to_packed_bytes())
+    }
+}
+
+#[cfg(feature = "std")]
+impl BorshDeserialize for Sudoku {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        use core::convert::TryInto;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid sudoku");
+
+        if buf.first() != Some(&PACKED_TAG) {
+            let cells = <SudokuArray as BorshDeserialize>::deserialize(buf)?;
+            return Sudoku::from_bytes(cells).map_err(|_| invalid());
+        }
+        if buf.len() < 1 + PACKED_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of packed sudoku",
+            ));
+        }
+
+        let (packed, rest) = buf[1..].split_at(PACKED_LEN);
+        let sudoku = Sudoku::from_packed_bytes(packed.try_into().unwrap()).map_err(|_| invalid())?;
+        *buf = rest;
+        Ok(sudoku)
+    }
+}
+
 pub type Iter<'a> = iter::Map<slice::Iter<'a, u8>, fn(&u8) -> Option<u8>>; // Iter over Sudoku cells
 
+/// Two cells of a house holding the same digit, see [`Sudoku::find_conflicts`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// The row, column or block containing both cells
+    pub house: super::House,
+    /// The digit entered in both cells
+    pub digit: super::Digit,
+    /// The two cells, the lower one first
+    pub cells: (super::Cell, super::Cell),
+}
+
 /// Position symmetries for clues of generated sudokus
 ///
 /// For use with functions like [`Sudoku::generate_with_symmetry`].
@@ -153,9 +207,45 @@ pub enum Symmetry {
     None,
 }
 
+/// Character printed for empty cells, see [`RenderOptions`]
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Blank {
+    /// `.`, the default
+    #[default]
+    Dot,
+    /// `0`
+    Zero,
+    /// `_`
+    Underscore,
+}
+
+/// Characters used when printing a sudoku, see [`Sudoku::to_str_line_with`] and
+/// [`Sudoku::display_with`]. The default matches [`Sudoku::to_str_line`] and `Display`.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub blank: Blank,
+    /// Print digits above 9 in uppercase hex style, 10 as `A`, 11 as `B` and so on.
+    /// Digits 1 to 9 are printed the same either way, so this only matters for larger boards.
+    pub hex_digits: bool,
+}
+
+impl RenderOptions {
+    fn char(self, entry: Option<u8>) -> u8 {
+        match entry {
+            Some(num) if self.hex_digits && num > 9 => num - 10 + b'A',
+            Some(num) => num + b'0',
+            None => match self.blank {
+                Blank::Dot => b'.',
+                Blank::Zero => b'0',
+                Blank::Underscore => b'_',
+            },
+        }
+    }
+}
+
 impl Symmetry {
     // For a given cell, returns all cells that need to be either all filled or all empty to uphold the symmetry
-    fn corresponding_cells(self, cell: usize) -> Vec<usize> {
+    pub(crate) fn corresponding_cells(self, cell: usize) -> Vec<usize> {
         let row = cell / 9;
         let col = cell % 9;
         let mirr = |line| 8 - line; // mirror along the axis orthogonal to `line`
@@ -199,6 +289,14 @@ impl Symmetry {
     }
 }
 
+// Mixes the stream counter into the last 8 bytes of the seed
+fn stream_rng(mut seed: [u8; 32], stream: u64) -> StdRng {
+    for (byte, stream_byte) in seed[24..].iter_mut().zip(&stream.to_le_bytes()) {
+        *byte ^= stream_byte;
+    }
+    StdRng::from_seed(seed)
+}
+
 impl Sudoku {
     /// Generate a random, solved sudoku
     pub fn generate_solved(rng: &mut StdRng) -> Self {
@@ -214,6 +312,20 @@ impl Sudoku {
         Sudoku::generate_with_symmetry(Symmetry::HalfRotation, rng)
     }
 
+    /// Generate a random, uniquely solvable sudoku like [`Sudoku::generate`], seeded by `seed`.
+    ///
+    /// Different values of `stream` yield independent puzzles from the same seed, so several puzzles can be derived
+    /// from a single random seed. Stream 0 uses `seed` unchanged.
+    pub fn generate_from_seed(seed: [u8; 32], stream: u64) -> Self {
+        Sudoku::generate(&mut stream_rng(seed, stream))
+    }
+
+    /// Generate a random, solved sudoku like [`Sudoku::generate_solved`], seeded by `seed` and `stream`.
+    /// See [`Sudoku::generate_from_seed`].
+    pub fn generate_solved_from_seed(seed: [u8; 32], stream: u64) -> Self {
+        Sudoku::generate_solved(&mut stream_rng(seed, stream))
+    }
+
     /// Generate a random, uniquely solvable sudoku with the desired symmetry.
     ///
     /// The puzzles are minimal in that no cell can be removed without losing uniquess of the solution
@@ -223,6 +335,34 @@ impl Sudoku {
         Sudoku::generate_with_symmetry_from(Sudoku::generate_solved(rng), symmetry, rng)
     }
 
+    /// Generate a random, uniquely solvable sudoku with the desired symmetry and remove every redundant clue
+    /// afterwards, even if that breaks the symmetry.
+    ///
+    /// The puzzles are minimal in that no clue can be removed without losing uniqueness of the solution,
+    /// see [`Sudoku::is_minimal`].
+    pub fn generate_minimal(symmetry: Symmetry, rng: &mut StdRng) -> Self {
+        Sudoku::generate_with_symmetry(symmetry, rng).minimize(rng)
+    }
+
+    /// Remove clues in random order for as long as the solution stays unique. The result is minimal,
+    /// see [`Sudoku::is_minimal`].
+    ///
+    /// If the sudoku is invalid or has multiple solutions, it will be returned as is.
+    pub fn minimize(self, rng: &mut StdRng) -> Self {
+        Sudoku::generate_with_symmetry_from(self, Symmetry::None, rng)
+    }
+
+    /// Checks whether the sudoku is uniquely solvable and every clue is needed for that,
+    /// i.e. removing any single clue would allow multiple solutions.
+    pub fn is_minimal(self) -> bool {
+        self.is_uniquely_solvable()
+            && (0..N_CELLS).filter(|&cell| self.0[cell] != 0).all(|cell| {
+                let mut sudoku = self;
+                sudoku.0[cell] = 0;
+                !sudoku.is_uniquely_solvable()
+            })
+    }
+
     /// Generate a random, uniqely solvable sudoku
     /// that has the same solution as the given `sudoku` by removing the contents of some of its cells.
     ///
@@ -257,7 +397,7 @@ impl Sudoku {
             .iter_mut()
             .enumerate()
             .for_each(|(cell, place)| *place = cell);
-        cell_order.shuffle(rng);
+        EntropyPool::new(rng).shuffle(&mut cell_order);
 
         // With symmetries, many cells are equivalent.
         // If we've already visited one cell in a symmetry class, we can skip ahead
@@ -287,7 +427,7 @@ impl Sudoku {
     /// All numbers must be below 10. Empty cells are denoted by 0, clues by the numbers 1-9.
     /// The slice must be of length 81.
     pub fn from_bytes_slice(bytes: &[u8]) -> Result<Sudoku, crate::errors::FromBytesSliceError> {
-        use std::convert::TryInto;
+        use core::convert::TryInto;
         Self::_from_bytes(
             bytes
                 .try_into()
@@ -302,6 +442,41 @@ impl Sudoku {
         Self::_from_bytes(&bytes)
     }
 
+    /// Reads a sudoku packed by [`Sudoku::to_packed_bytes`].
+    /// Fails for nibbles above 9 and if the padding nibble of the last byte isn't empty.
+    pub fn from_packed_bytes(packed: [u8; PACKED_LEN]) -> Result<Sudoku, crate::errors::FromBytesError> {
+        if packed[PACKED_LEN - 1] >> 4 != 0 {
+            return Err(crate::errors::FromBytesError(()));
+        }
+        let mut cells = [0; N_CELLS];
+        for (cell, num) in cells.iter_mut().enumerate() {
+            *num = packed[cell / 2] >> (cell % 2 * 4) & 0xf;
+        }
+        Self::_from_bytes(&cells)
+    }
+
+    /// Reads a sudoku from a list of clues written by [`Sudoku::to_clue_bytes`].
+    /// The clues may come in any order.
+    pub fn from_clue_bytes(bytes: &[u8]) -> Result<Sudoku, crate::errors::FromClueBytesError> {
+        use crate::errors::FromClueBytesError;
+
+        if !bytes.len().is_multiple_of(2) {
+            return Err(FromClueBytesError::OddLength(bytes.len()));
+        }
+        let mut cells = [0; N_CELLS];
+        for clue in bytes.chunks_exact(2) {
+            let (cell, digit) = (clue[0], clue[1]);
+            if cell as usize >= N_CELLS || !(1..=9).contains(&digit) {
+                return Err(FromClueBytesError::InvalidClue { cell, digit });
+            }
+            if cells[cell as usize] != 0 {
+                return Err(FromClueBytesError::DuplicateCell(cell));
+            }
+            cells[cell as usize] = digit;
+        }
+        Ok(Sudoku(cells))
+    }
+
     fn _from_bytes(bytes: &SudokuArray) -> Result<Sudoku, crate::errors::FromBytesError> {
         match bytes.iter().fold(true, |valid, &byte| valid & (byte <= 9)) {
             true => Ok(Sudoku(*bytes)),
@@ -325,7 +500,8 @@ impl Sudoku {
     /// ..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3.. optional comment
     /// ```
     ///
-    /// Stops parsing after the first sudoku
+    /// Stops parsing after the first sudoku.
+    /// On failure, [`LineParseError::offset`] and [`LineParseError::ch`] locate the problem.
     pub fn from_str_line(s: &str) -> Result<Sudoku, LineParseError> {
         let chars = s.as_bytes();
         if let Ok(sudoku) = Sudoku::_from_str_line_fast_path(chars) {
@@ -364,7 +540,10 @@ impl Sudoku {
                 // any other char can not be part of sudoku
                 // without having both length and character wrong
                 // treat like comment, but with missing delimiter
-                _ => return Err(LineParseError::MissingCommentDelimiter),
+                _ => {
+                    let ch = s[N_CELLS..].chars().next().unwrap();
+                    return Err(LineParseError::MissingCommentDelimiter(ch));
+                }
             }
         }
 
@@ -393,9 +572,8 @@ impl Sudoku {
             }
         }
 
-        let valid_ending = chars.get(81).map_or(true, |ch| match ch {
-            b'\t' | b' ' | b'\r' | b'\n' | b';' | b',' => true,
-            _ => false,
+        let valid_ending = chars.get(81).is_none_or(|ch| {
+            matches!(ch, b'\t' | b' ' | b'\r' | b'\n' | b';' | b',')
         });
 
         match valid_ending {
@@ -529,7 +707,7 @@ impl Sudoku {
                     '0'..='9' => grid[cell as usize] = ch as u8 - b'0',
                     _ => {
                         return Err(BlockParseError::InvalidEntry(InvalidEntry {
-                            cell: cell as u8,
+                            cell,
                             ch,
                         }))
                     }
@@ -569,7 +747,7 @@ impl Sudoku {
                 if ['.', '_'].contains(&ch) {
                     row_vals[nums_in_row] = 0;
                     nums_in_row += 1;
-                } else if '0' <= ch && ch <= '9' {
+                } else if ch.is_ascii_digit() {
                     row_vals[nums_in_row] = ch as u8 - b'0';
                     nums_in_row += 1;
                 }
@@ -606,7 +784,7 @@ impl Sudoku {
         let mut nums_contained: u16 = 0;
         // same with less than 17 clues
         let mut n_clues = 0;
-        self.iter().filter_map(|id| id).for_each(|num| {
+        self.iter().flatten().for_each(|num| {
             nums_contained |= 1 << num;
             n_clues += 1;
         });
@@ -622,6 +800,47 @@ impl Sudoku {
         }
     }
 
+    /// Solve sudoku like [`Sudoku::solution`] and also return counters of the work done by the backtracking solver.
+    /// The number of guesses is a cheap proxy for the difficulty.
+    pub fn solve_with_stats(self) -> (Option<Sudoku>, SolverStats) {
+        let solver = match SudokuSolver::from_sudoku(self) {
+            Ok(solver) => solver,
+            Err(_) => return (None, SolverStats::default()),
+        };
+        let mut solution = [[0; N_CELLS]];
+        let (n_solutions, stats) = solver.solutions_up_to_buffer_with_stats(&mut solution, 2);
+        match n_solutions == 1 {
+            true => (Some(Sudoku(solution[0])), stats),
+            false => (None, stats),
+        }
+    }
+
+    /// Solve sudoku like [`Sudoku::solution`], but give up once the backtracking solver would need more than
+    /// `max_guesses` guesses. This bounds the work spent on pathological inputs.
+    ///
+    /// Returns `Ok(None)`, if the sudoku has no or multiple solutions.
+    pub fn solve_with_limit(self, max_guesses: u32) -> Result<Option<Sudoku>, Exceeded> {
+        let solver = match SudokuSolver::from_sudoku(self) {
+            Ok(solver) => solver,
+            Err(_) => return Ok(None),
+        };
+        let mut solution = [[0; N_CELLS]];
+        match solver.solutions_up_to_buffer_with_limit(&mut solution, 2, max_guesses)? {
+            1 => Ok(Some(Sudoku(solution[0]))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Solve sudoku like [`Sudoku::solution`] and return the placements the backtracking solver made on the way to
+    /// the solution, in order. Guesses that turned out wrong are left out, so replaying the placements on the sudoku
+    /// fills in the solution. Returns `None`, if the solution isn't unique.
+    pub fn solution_trace(self) -> Option<Vec<Placement>> {
+        if !self.is_uniquely_solvable() {
+            return None;
+        }
+        SudokuSolver::from_sudoku(self).ok()?.solution_trace()
+    }
+
     /// Counts number of solutions to sudoku up to `limit`.
     /// This solves the sudoku but does not return the solutions which allows for slightly faster execution.
     pub fn solutions_count_up_to(self, limit: usize) -> usize {
@@ -636,6 +855,164 @@ impl Sudoku {
         self.solutions_count_up_to(2) == 1
     }
 
+    /// Lists every pair of cells that hold the same digit in a row, column or block, ordered by house.
+    /// A digit entered 3 times in a house yields 3 conflicts. Empty cells never conflict.
+    pub fn find_conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for house in super::House::all() {
+            let cells = house.cells().into_iter().collect::<Vec<_>>();
+            for (i, &first) in cells.iter().enumerate() {
+                let digit = match self.get(first) {
+                    Some(digit) => digit,
+                    None => continue,
+                };
+                for &second in cells[i + 1..].iter().filter(|&&cell| self.get(cell) == Some(digit)) {
+                    conflicts.push(Conflict {
+                        house,
+                        digit,
+                        cells: (first, second),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Explains why the sudoku has no solution. Returns `None`, if it has at least one.
+    ///
+    /// Singles are filled in one at a time until a digit entered twice in a house, a cell without candidates
+    /// or a digit without place in a house shows up. If that never happens, the contradiction only shows up when
+    /// trying all possibilities and [`Contradiction::NoSolution`] is returned.
+    pub fn contradiction(self) -> Option<Contradiction> {
+        if self.solutions_count_up_to(1) == 1 {
+            return None;
+        }
+        if let Some(conflict) = self.find_conflicts().first() {
+            let (house, digit) = (conflict.house, conflict.digit.get());
+            return Some(Contradiction::DuplicateDigit { house, digit });
+        }
+
+        let mut sudoku = self;
+        loop {
+            let candidates = sudoku.candidates();
+            if let Some(cell) = candidates.cells_without_candidates().into_iter().next() {
+                return Some(Contradiction::NoCandidates { cell });
+            }
+
+            let mut single = None;
+            for digit in super::Digit::all() {
+                let cells_with_candidate = candidates.cells_with_candidate(digit);
+                for house in super::House::all() {
+                    let cells = house.cells();
+                    if cells.into_iter().any(|cell| sudoku.0[cell.as_index()] == digit.get()) {
+                        continue;
+                    }
+                    match (cells & cells_with_candidate).unique() {
+                        Err(_) => return Some(Contradiction::DigitImpossible { house, digit: digit.get() }),
+                        Ok(Some(cell)) => single = single.or(Some((cell, digit))),
+                        Ok(None) => {}
+                    }
+                }
+            }
+            let naked_single = || {
+                super::Cell::all().find_map(|cell| {
+                    let digit = candidates.candidates_of(cell).unique().ok()??;
+                    Some((cell, digit))
+                })
+            };
+            match single.or_else(naked_single) {
+                Some((cell, digit)) => sudoku.0[cell.as_index()] = digit.get(),
+                None => return Some(Contradiction::NoSolution),
+            }
+        }
+    }
+
+    /// Solve sudoku with the strategies humans use, see [`StrategySolver`]. [`Strategy::ALL`] contains all of them,
+    /// ordered from easiest to hardest.
+    ///
+    /// Returns the solution together with the ordered list of deductions that lead to it. If the strategies get stuck,
+    /// `Err(..)` contains the partially solved grid and the deductions made up to that point.
+    #[allow(clippy::result_large_err)]
+    pub fn solve_with_strategies(self, strategies: &[Strategy]) -> Result<(Sudoku, Deductions), (Sudoku, Deductions)> {
+        StrategySolver::from_sudoku(self).solve(strategies)
+    }
+
+    /// Checks whether the sudoku can be solved by the given `strategies` alone,
+    /// e.g. to test that a puzzle needs nothing beyond singles.
+    pub fn is_solvable_with(self, strategies: &[Strategy]) -> bool {
+        self.solve_with_strategies(strategies).is_ok()
+    }
+
+    /// Grade the difficulty of the sudoku by the hardest strategy needed to solve it, see [`Sudoku::grade_detailed`].
+    pub fn grade(self) -> Difficulty {
+        self.grade_detailed().difficulty
+    }
+
+    /// Solve sudoku with [`Strategy::ALL`] and rate it by the hardest strategy needed.
+    /// Sudokus the strategies can't solve are graded [`Difficulty::Expert`].
+    pub fn grade_detailed(self) -> Grade {
+        match self.solve_with_strategies(Strategy::ALL) {
+            Ok((_, deductions)) => Grade::from_deductions(&deductions),
+            Err(_) => Grade::unsolved(),
+        }
+    }
+
+    /// Fine-grained difficulty rating: the sum of the [`Strategy::rating`]s of all steps needed to solve the
+    /// sudoku plus [`UNSOLVED_SCORE`](crate::strategy::UNSOLVED_SCORE) for each cell the strategies can't solve.
+    ///
+    /// The score is stable across versions. It only uses the strategies that existed when it was introduced,
+    /// so strategies added later don't change it.
+    pub fn difficulty_score(self) -> u32 {
+        use crate::strategy::grade::{difficulty_score, SCORED_STRATEGIES};
+        let (solved, deductions) = match self.solve_with_strategies(SCORED_STRATEGIES) {
+            Ok(result) | Err(result) => result,
+        };
+        difficulty_score(&deductions, 81 - solved.n_clues() as u32)
+    }
+
+    /// Generate puzzles with the [`Difficulty::symmetry`] of `target` and grade them, until one of the `target`
+    /// difficulty is found. Gives up after [`MAX_GRADED_ATTEMPTS`](crate::strategy::MAX_GRADED_ATTEMPTS).
+    ///
+    /// Returns `Ok(..)` with the puzzle of the target difficulty or `Err(..)` with the first puzzle
+    /// closest to it. Both contain how many puzzles of each difficulty were generated along the way.
+    #[allow(clippy::result_large_err)]
+    pub fn generate_graded(rng: &mut StdRng, target: Difficulty) -> Result<GradedGeneration, GradedGeneration> {
+        use crate::strategy::MAX_GRADED_ATTEMPTS;
+
+        let distance = |difficulty: Difficulty| (difficulty as i32 - target as i32).abs();
+        let mut attempts_by_difficulty = [0; 4];
+        let mut closest: Option<(Sudoku, Grade)> = None;
+        for attempts in 1..=MAX_GRADED_ATTEMPTS {
+            let sudoku = Sudoku::generate_with_symmetry(target.symmetry(), rng);
+            let grade = sudoku.grade_detailed();
+            attempts_by_difficulty[grade.difficulty as usize] += 1;
+
+            if grade.difficulty == target {
+                return Ok(GradedGeneration {
+                    sudoku,
+                    grade,
+                    attempts,
+                    attempts_by_difficulty,
+                });
+            }
+            let is_closer = match closest {
+                Some((_, closest)) => distance(grade.difficulty) < distance(closest.difficulty),
+                None => true,
+            };
+            if is_closer {
+                closest = Some((sudoku, grade));
+            }
+        }
+
+        let (sudoku, grade) = closest.unwrap();
+        Err(GradedGeneration {
+            sudoku,
+            grade,
+            attempts: MAX_GRADED_ATTEMPTS,
+            attempts_by_difficulty,
+        })
+    }
+
     /// Solve sudoku and return the first `limit` solutions it finds. If less solutions exist, return only those. Return `None` if no solution exists.
     /// No specific ordering of solutions is promised. It can change across versions.
     pub fn solutions_up_to(self, limit: usize) -> Vec<Sudoku> {
@@ -685,6 +1062,40 @@ impl Sudoku {
         self.0.iter().filter(|&&num| num != 0).count() as u8
     }
 
+    /// Returns the number of cells filled in this grid that are empty in `puzzle`,
+    /// i.e. the progress of a player working on `puzzle`.
+    pub fn n_solved_cells(&self, puzzle: &Sudoku) -> u8 {
+        self.0.iter().zip(puzzle.0.iter()).filter(|&(&num, &clue)| num != 0 && clue == 0).count() as u8
+    }
+
+    /// Checks whether no row, column or block contains a digit twice. Empty cells are ignored,
+    /// so a valid partial sudoku may still have no solution.
+    pub fn is_valid_partial(&self) -> bool {
+        use crate::board::*;
+        use crate::helper::HouseArray;
+
+        let mut house_digits = HouseArray([Set::<Digit>::NONE; N_HOUSES]);
+        for (cell, &content) in Cell::all().zip(self.0.iter()) {
+            let digit = match Digit::new_checked(content) {
+                Some(digit) => digit,
+                None => continue,
+            };
+            for house in [cell.row().house(), cell.col().house(), cell.block().house()] {
+                if house_digits[house].contains(digit) {
+                    return false;
+                }
+                house_digits[house] |= digit;
+            }
+        }
+        true
+    }
+
+    /// Checks whether every filled cell of this sudoku holds the same digit in `other`.
+    /// Any sudoku is a subset of its solution and every progress grid contains the clues of its puzzle.
+    pub fn is_subset_of(&self, other: &Sudoku) -> bool {
+        self.0.iter().zip(other.0.iter()).all(|(&num, &other_num)| num == 0 || num == other_num)
+    }
+
     /// Perform various transformations that create a different but equivalent sudoku.
     /// The transformations preserve the sudoku's validity and the amount of solutions
     /// as well a the applicability of solution strategies.
@@ -701,7 +1112,6 @@ impl Sudoku {
     /// Fewer permutations exist if the sudoku is symmetrical in respect to some combination(s) of the transformations.
     /// The vast majority of sudokus do not have any such symmetries (automorphisms). The highest number of automorphisms
     /// a sudoku can have is 648 and ~99.99% of all non-equivalent sudokus have only 1, the identity transformation.
-
     // TODO: Deduplicate the shuffle_*lines_or_chutes* functions
     //       for some reason the shuffle_bands and shuffle_stacks functions work faster in their current form
     //       rather than with a generic function abstracting over both.
@@ -716,6 +1126,110 @@ impl Sudoku {
         self
     }
 
+    /// Relabels all digits, e.g. `mapping[0]` replaces every 1. This is one of the [`shuffle`](Sudoku::shuffle)
+    /// transformations and preserves validity and the amount of solutions.
+    ///
+    /// # Panics
+    ///
+    /// panics if `mapping` is not a permutation of the digits 1 to 9
+    pub fn relabel_digits(&mut self, mapping: [u8; 9]) {
+        let mut sorted = mapping;
+        sorted.sort_unstable();
+        assert!(sorted == [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        super::canonicalization::apply_digit_mapping(mapping, &mut self.0);
+    }
+
+    /// Swaps 2 rows of the same band. This is one of the [`shuffle`](Sudoku::shuffle) transformations.
+    ///
+    /// # Panics
+    ///
+    /// panics if `row1 >= 9`, `row2 >= 9` or the rows lie in different bands
+    pub fn swap_rows(&mut self, row1: u8, row2: u8) {
+        assert!(row1 < 9 && row2 < 9);
+        assert!(row1 / 3 == row2 / 3);
+        super::canonicalization::swap_rows(&mut self.0, row1, row2);
+    }
+
+    /// Swaps 2 columns of the same stack. This is one of the [`shuffle`](Sudoku::shuffle) transformations.
+    ///
+    /// # Panics
+    ///
+    /// panics if `col1 >= 9`, `col2 >= 9` or the columns lie in different stacks
+    pub fn swap_cols(&mut self, col1: u8, col2: u8) {
+        assert!(col1 < 9 && col2 < 9);
+        assert!(col1 / 3 == col2 / 3);
+        super::canonicalization::swap_cols(&mut self.0, col1, col2);
+    }
+
+    /// Swaps 2 bands, i.e. the horizontal groups of 3 blocks. This is one of the [`shuffle`](Sudoku::shuffle)
+    /// transformations.
+    ///
+    /// # Panics
+    ///
+    /// panics if `band1 >= 3` or `band2 >= 3`
+    pub fn swap_bands(&mut self, band1: u8, band2: u8) {
+        assert!(band1 < 3 && band2 < 3);
+        super::canonicalization::swap_bands(&mut self.0, band1, band2);
+    }
+
+    /// Swaps 2 stacks, i.e. the vertical groups of 3 blocks. This is one of the [`shuffle`](Sudoku::shuffle)
+    /// transformations.
+    ///
+    /// # Panics
+    ///
+    /// panics if `stack1 >= 3` or `stack2 >= 3`
+    pub fn swap_stacks(&mut self, stack1: u8, stack2: u8) {
+        assert!(stack1 < 3 && stack2 < 3);
+        super::canonicalization::swap_stacks(&mut self.0, stack1, stack2);
+    }
+
+    /// Mirrors the sudoku along the diagonal from the top left to the bottom right corner.
+    /// This is one of the [`shuffle`](Sudoku::shuffle) transformations.
+    pub fn transpose(&mut self) {
+        super::canonicalization::transpose(&mut self.0);
+    }
+
+    /// Rotates the sudoku by 90° clockwise.
+    /// This is a combination of [`transpose`](Sudoku::transpose) and column and stack swaps.
+    pub fn rotate_clockwise(&mut self) {
+        self.transpose();
+        // mirror horizontally
+        self.swap_stacks(0, 2);
+        for stack in 0..3 {
+            self.swap_cols(stack * 3, stack * 3 + 2);
+        }
+    }
+
+    /// Rotates the sudoku by 90° counterclockwise.
+    /// This is a combination of [`transpose`](Sudoku::transpose) and row and band swaps.
+    pub fn rotate_counterclockwise(&mut self) {
+        self.transpose();
+        // mirror vertically
+        self.swap_bands(0, 2);
+        for band in 0..3 {
+            self.swap_rows(band * 3, band * 3 + 2);
+        }
+    }
+
+    /// Finds the next step for a player, explained by the easiest strategy that applies.
+    ///
+    /// `self` is the puzzle and `state` the player's progress, i.e. the entered digits and pencil marks,
+    /// e.g. from [`Sudoku::candidates`]. Candidates that aren't marked are treated as eliminated.
+    /// Returns `None`, if the puzzle isn't uniquely solvable, if `state` contains a wrong digit or lacks
+    /// a candidate of the solution, or if no strategy finds a step.
+    pub fn next_hint(self, state: &super::GridState) -> Option<Hint> {
+        let solution = self.solution()?;
+        if !crate::strategy::hint::is_consistent(state, solution) {
+            return None;
+        }
+        Hint::find(StrategySolver::from_grid_state(state.cell_states()))
+    }
+
+    /// Returns the remaining candidates of all empty cells, i.e. the digits not yet entered in any of their houses.
+    pub fn candidates(&self) -> super::Candidates {
+        super::Candidates::new(*self)
+    }
+
     /// Returns the canonical representation of this sudoku and its automorphism count.
     ///
     /// All sudokus that can be translated into each other via validity preserving transformations belong to the same
@@ -734,10 +1248,8 @@ impl Sudoku {
     pub fn canonicalized(&self) -> Option<(Sudoku, usize)> {
         let solved_sudoku = if self.is_solved() {
             *self
-        } else if let Some(solved) = self.solution() {
-            solved
         } else {
-            return None;
+            self.solution()?
         };
 
         let mut sudoku = *self;
@@ -748,16 +1260,75 @@ impl Sudoku {
     }
 
     /// Returns an Iterator over sudoku, going from left to right, top to bottom
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         self.0.iter().map(num_to_opt)
     }
 
+    /// Returns an iterator over all cells and their digits, going from left to right, top to bottom.
+    /// Empty cells have no digit.
+    pub fn entries(self) -> impl Iterator<Item = (super::Cell, Option<super::Digit>)> {
+        super::Cell::all().map(move |cell| (cell, self.get(cell)))
+    }
+
+    /// Returns the digit in `cell` or `None`, if it's empty.
+    pub fn get(&self, cell: super::Cell) -> Option<super::Digit> {
+        super::Digit::new_checked(self.0[cell.as_index()])
+    }
+
+    /// Returns the cells containing `digit`.
+    pub fn cells_matching(&self, digit: super::Digit) -> Set<super::Cell> {
+        super::Cell::all().filter(|&cell| self.get(cell) == Some(digit)).collect()
+    }
+
+    /// Returns the digits entered in the cells of `house`.
+    pub fn digits_in(&self, house: super::positions::House) -> Set<super::Digit> {
+        house.cells().into_iter().filter_map(|cell| self.get(cell)).collect()
+    }
+
+    /// Returns the digits still missing in `house`, the complement of [`Sudoku::digits_in`].
+    ///
+    /// ```
+    /// use sudoku::board::positions::{Block, House};
+    /// use sudoku::board::Digit;
+    /// use sudoku::Sudoku;
+    ///
+    /// let mut grid = [0; 81];
+    /// grid[54..57].copy_from_slice(&[1, 2, 3]);
+    /// let sudoku = Sudoku::from_bytes(grid).unwrap();
+    /// let missing = sudoku.missing_in(House::from(Block::new(6)));
+    /// assert_eq!(missing.len(), 6);
+    /// assert!(!missing.contains(Digit::new(2)));
+    /// ```
+    pub fn missing_in(&self, house: super::positions::House) -> Set<super::Digit> {
+        Set::ALL.without(self.digits_in(house))
+    }
+
     /// Returns a byte array for the sudoku.
     /// Empty cells are denoted by 0, clues by the numbers 1-9.
     pub fn to_bytes(self) -> SudokuArray {
         self.0
     }
 
+    /// Returns the sudoku packed into 41 bytes, one nibble per cell, low nibble first.
+    /// The high nibble of the last byte stays empty.
+    pub fn to_packed_bytes(self) -> [u8; PACKED_LEN] {
+        let mut packed = [0; PACKED_LEN];
+        for (cell, &num) in self.0.iter().enumerate() {
+            packed[cell / 2] |= num << (cell % 2 * 4);
+        }
+        packed
+    }
+
+    /// Returns the clues as (cell, digit) byte pairs, ordered by cell.
+    /// Smaller than [`Sudoku::to_packed_bytes`] for sudokus with at most 20 clues.
+    pub fn to_clue_bytes(self) -> Vec<u8> {
+        (0..N_CELLS as u8)
+            .zip(self.0.iter())
+            .filter(|&(_, &digit)| digit != 0)
+            .flat_map(|(cell, &digit)| [cell, digit])
+            .collect()
+    }
+
     /// Returns a representation of the sudoku in line format that can be printed
     /// and which derefs into a &str
     ///
@@ -777,16 +1348,39 @@ impl Sudoku {
     /// );
     /// ```
     pub fn to_str_line(&self) -> SudokuLine {
+        self.to_str_line_with(RenderOptions::default())
+    }
+
+    /// Like [`Sudoku::to_str_line`], but printed with the characters chosen by `options`.
+    /// Lines with a blank other than `.` don't order like the sudokus they were made from.
+    ///
+    /// ```
+    /// use sudoku::board::{Blank, RenderOptions};
+    /// use sudoku::Sudoku;
+    ///
+    /// let mut grid = [0; 81];
+    /// grid[3] = 5;
+    /// let sudoku = Sudoku::from_bytes(grid).unwrap();
+    /// let options = RenderOptions { blank: Blank::Zero, ..RenderOptions::default() };
+    /// assert!(sudoku.to_str_line_with(options).starts_with("0005000"));
+    /// ```
+    pub fn to_str_line_with(&self, options: RenderOptions) -> SudokuLine {
         let mut chars = [0; N_CELLS];
         for (char_, entry) in chars.iter_mut().zip(self.iter()) {
-            *char_ = match entry {
-                Some(num) => num + b'0',
-                None => b'.',
-            };
+            *char_ = options.char(entry);
         }
         SudokuLine(chars)
     }
 
+    /// Returns a value that prints like the sudoku's `Display`, including the `{:#}` grid,
+    /// with the characters chosen by `options`.
+    pub fn display_with(&self, options: RenderOptions) -> SudokuDisplay {
+        SudokuDisplay {
+            sudoku: *self,
+            options,
+        }
+    }
+
     /// Returns a value that prints a block representation of the sudoku
     /// when formatted via the `Display` trait.
     ///
@@ -827,9 +1421,54 @@ fn num_to_opt(num: &u8) -> Option<u8> {
     if *num == 0 { None } else { Some(*num) }
 }
 
+/// Prints the sudoku in line format, see [`Sudoku::to_str_line`] and [`Sudoku::display_with`].
+/// The alternate flag (`{:#}`) prints a grid with box-drawing separators instead:
+///
+/// ```text
+/// ┌───────┬───────┬───────┐
+/// │ 5 3 . │ . 7 . │ . . . │
+/// │ 6 . . │ 1 9 5 │ . . . │
+/// │ . 9 8 │ . . . │ . 6 . │
+/// ├───────┼───────┼───────┤
+/// ...
+/// └───────┴───────┴───────┘
+/// ```
 impl fmt::Display for Sudoku {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(&self.to_str_line(), f)
+        fmt::Display::fmt(&self.display_with(RenderOptions::default()), f)
+    }
+}
+
+/// Sudoku printed with [`RenderOptions`], see [`Sudoku::display_with`]
+#[derive(Copy, Clone, Debug)]
+pub struct SudokuDisplay {
+    sudoku: Sudoku,
+    options: RenderOptions,
+}
+
+impl fmt::Display for SudokuDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let line = self.sudoku.to_str_line_with(self.options);
+        if !f.alternate() {
+            return fmt::Display::fmt(&line, f);
+        }
+
+        for (row, cells) in line.as_bytes().chunks(9).enumerate() {
+            match row {
+                0 => writeln!(f, "┌───────┬───────┬───────┐")?,
+                3 | 6 => writeln!(f, "├───────┼───────┼───────┤")?,
+                _ => {}
+            }
+            for stack in cells.chunks(3) {
+                write!(f, "│")?;
+                for &cell in stack {
+                    write!(f, " {}", cell as char)?;
+                }
+                write!(f, " ")?;
+            }
+            writeln!(f, "│")?;
+        }
+        write!(f, "└───────┴───────┴───────┘")
     }
 }
 
@@ -849,6 +1488,24 @@ impl TryFrom<&[u8]> for Sudoku {
     }
 }
 
+/// The entry of a cell, 0 for empty cells and the digit otherwise
+impl ops::Index<super::Cell> for Sudoku {
+    type Output = u8;
+
+    fn index(&self, cell: super::Cell) -> &u8 {
+        &self.0[cell.as_index()]
+    }
+}
+
+/// Parses the line format, see [`Sudoku::from_str_line`].
+impl str::FromStr for Sudoku {
+    type Err = LineParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_line(s)
+    }
+}
+
 impl From<Sudoku> for SudokuArray {
     fn from(sudoku: Sudoku) -> Self {
         sudoku.to_bytes()
@@ -858,17 +1515,17 @@ impl From<Sudoku> for SudokuArray {
 /// Container for the &str representation of a sudoku
 // MUST ALWAYS contain valid utf8
 //
-// the bytes representation uses b'.' for empty cells, which is below `0` and therefore
-// this orders just like the regular sudoku would.
+// the bytes representation uses b'.' for empty cells by default, which is below `0` and
+// therefore this orders just like the regular sudoku would.
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq)]
 pub struct SudokuLine(SudokuArray);
 
-impl std::hash::Hash for SudokuLine {
+impl core::hash::Hash for SudokuLine {
     fn hash<H>(&self, state: &mut H)
     where
-        H: std::hash::Hasher,
+        H: core::hash::Hasher,
     {
-        <str as std::hash::Hash>::hash(self, state)
+        <str as core::hash::Hash>::hash(self, state)
     }
 }
 
@@ -928,6 +1585,431 @@ mod test {
     use super::*;
     use strum::IntoEnumIterator;
 
+    #[test]
+    fn solve_with_strategies() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let (solution, deductions) = sudoku.solve_with_strategies(Strategy::ALL).unwrap();
+        assert_eq!(Some(solution), sudoku.solution());
+        assert!(deductions.len() >= 81 - sudoku.n_clues() as usize);
+    }
+
+    #[test]
+    fn solve_with_strategies_stuck() {
+        let sudoku =
+            Sudoku::from_str_line("9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9")
+                .unwrap();
+        let (grid, deductions) = sudoku.solve_with_strategies(&[Strategy::NakedSingles]).unwrap_err();
+        assert!(deductions.is_empty());
+        assert_eq!(grid, sudoku);
+    }
+
+    #[test]
+    fn solve_with_enabled_strategies() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let singles = [Strategy::HiddenSingles, Strategy::NakedSingles];
+        let solver = StrategySolver::from_sudoku(sudoku).with_strategies(&singles);
+        assert_eq!(solver.strategies(), &singles);
+
+        let (solution, deductions) = solver.solve_enabled().unwrap();
+        assert_eq!(Some(solution), sudoku.solution());
+        assert_eq!(deductions.get(0).unwrap().strategy(), Strategy::HiddenSingles);
+        assert!(deductions.iter().all(|deduction| singles.contains(&deduction.strategy())));
+
+        assert!(sudoku.is_solvable_with(&singles));
+        let hard =
+            Sudoku::from_str_line("9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9")
+                .unwrap();
+        assert!(!hard.is_solvable_with(&singles));
+    }
+
+    #[test]
+    fn display() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_str_line(line).unwrap();
+        assert_eq!(sudoku.to_string(), line);
+
+        let expected = "\
+┌───────┬───────┬───────┐
+│ 5 3 . │ . 7 . │ . . . │
+│ 6 . . │ 1 9 5 │ . . . │
+│ . 9 8 │ . . . │ . 6 . │
+├───────┼───────┼───────┤
+│ 8 . . │ . 6 . │ . . 3 │
+│ 4 . . │ 8 . 3 │ . . 1 │
+│ 7 . . │ . 2 . │ . . 6 │
+├───────┼───────┼───────┤
+│ . 6 . │ . . . │ 2 8 . │
+│ . . . │ 4 1 9 │ . . 5 │
+│ . . . │ . 8 . │ . 7 9 │
+└───────┴───────┴───────┘";
+        assert_eq!(format!("{:#}", sudoku), expected);
+    }
+
+    #[test]
+    fn house_digits() {
+        use crate::board::*;
+
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_str_line(line).unwrap();
+        let digits = |nums: &[u8]| nums.iter().map(|&num| Digit::new(num)).collect::<Set<Digit>>();
+
+        assert_eq!(sudoku.digits_in(Row::new(0).house()), digits(&[3, 5, 7]));
+        assert_eq!(sudoku.digits_in(Col::new(0).house()), digits(&[4, 5, 6, 7, 8]));
+        assert_eq!(sudoku.missing_in(Block::new(6).house()), digits(&[1, 2, 3, 4, 5, 7, 8, 9]));
+
+        let solution = sudoku.solution().unwrap();
+        for house in House::all() {
+            assert_eq!(solution.digits_in(house), Set::ALL);
+            assert!(solution.missing_in(house).is_empty());
+        }
+    }
+
+    #[test]
+    fn render_options() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_str_line(line).unwrap();
+        assert_eq!(sudoku.display_with(RenderOptions::default()).to_string(), line);
+
+        let options = RenderOptions {
+            blank: Blank::Underscore,
+            hex_digits: true,
+        };
+        assert_eq!(sudoku.to_str_line_with(options).to_string(), line.replace('.', "_"));
+        let grid = format!("{:#}", sudoku.display_with(options));
+        assert!(grid.starts_with(
+            "\
+┌───────┬───────┬───────┐
+│ 5 3 _ │ _ 7 _ │ _ _ _ │"
+        ));
+
+        let zero = RenderOptions {
+            blank: Blank::Zero,
+            ..RenderOptions::default()
+        };
+        let line_zero = sudoku.to_str_line_with(zero).to_string();
+        assert_eq!(line_zero, line.replace('.', "0"));
+        assert_eq!(Sudoku::from_str_line(&line_zero), Ok(sudoku));
+
+        assert_eq!(options.char(Some(10)), b'A');
+        assert_eq!(options.char(Some(15)), b'F');
+    }
+
+    #[test]
+    fn line_parse_errors() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        assert!(Sudoku::from_str_line(&format!("{} comment", line)).is_ok());
+        assert!(Sudoku::from_str_line(&format!("{};comment", line)).is_ok());
+
+        let err = Sudoku::from_str_line(&format!("53.é{}", &line[4..])).unwrap_err();
+        assert_eq!((err.offset(), err.ch()), (3, Some('é')));
+        assert_eq!(err.to_string(), "cell 3 contains invalid character 'é'");
+
+        let err = Sudoku::from_str_line(&line[..40]).unwrap_err();
+        assert_eq!(err, LineParseError::NotEnoughCells(40));
+        assert_eq!((err.offset(), err.ch()), (40, None));
+
+        let err = Sudoku::from_str_line(&format!("{}.", line)).unwrap_err();
+        assert_eq!(err, LineParseError::TooManyCells);
+        assert_eq!(err.offset(), 81);
+
+        let err = Sudoku::from_str_line(&format!("{}#comment", line)).unwrap_err();
+        assert_eq!((err.offset(), err.ch()), (81, Some('#')));
+    }
+
+    #[test]
+    fn generate_minimal() {
+        let mut rng = StdRng::from_seed([5; 32]);
+        let sudoku = Sudoku::generate_minimal(Symmetry::Dihedral, &mut rng);
+        assert!(sudoku.is_minimal());
+
+        let solution = sudoku.solution().unwrap();
+        let mut padded = sudoku;
+        let empty_cell = (0..81).find(|&cell| sudoku.0[cell] == 0).unwrap();
+        padded.0[empty_cell] = solution.0[empty_cell];
+        assert!(!padded.is_minimal());
+        assert_eq!(padded.minimize(&mut rng).solution(), Some(solution));
+        assert!(padded.minimize(&mut rng).is_minimal());
+    }
+
+    #[test]
+    fn find_conflicts() {
+        use super::super::{Cell, Digit, House};
+
+        let solved = Sudoku::generate_solved(&mut StdRng::seed_from_u64(0));
+        assert!(solved.find_conflicts().is_empty());
+
+        let mut grid = [0; N_CELLS];
+        grid[0] = 4;
+        grid[1] = 4;
+        grid[9] = 4;
+        grid[80] = 4;
+        let conflict = |house, first, second| Conflict {
+            house: House::new(house),
+            digit: Digit::new(4),
+            cells: (Cell::new(first), Cell::new(second)),
+        };
+        assert_eq!(
+            Sudoku(grid).find_conflicts(),
+            [conflict(0, 0, 1), conflict(9, 0, 9), conflict(18, 0, 1), conflict(18, 0, 9), conflict(18, 1, 9)]
+        );
+    }
+
+    #[test]
+    fn contradiction() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        assert_eq!(sudoku.contradiction(), None);
+
+        let mut duplicate = sudoku;
+        duplicate.0[2] = 5;
+        let contradiction = duplicate.contradiction().unwrap();
+        assert_eq!(contradiction, Contradiction::DuplicateDigit { house: super::super::House::new(0), digit: 5 });
+        assert_eq!(contradiction.to_string(), "digit 5 appears twice in row 1");
+
+        // the first cell sees 1-8 in its row and 9 in its column
+        let mut bytes = [0; 81];
+        bytes[1..9].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        bytes[9 * 4] = 9;
+        let no_candidates = Sudoku(bytes).contradiction().unwrap();
+        assert_eq!(no_candidates, Contradiction::NoCandidates { cell: super::super::Cell::new(0) });
+        assert_eq!(no_candidates.to_string(), "cell r1c1 has no candidates");
+
+        // 1 is excluded from the third row by the 1s in the first two rows and columns and the 2 in r3c3
+        let mut bytes = [0; 81];
+        bytes[3] = 1;
+        bytes[15] = 1;
+        bytes[27] = 1;
+        bytes[64] = 1;
+        bytes[20] = 2;
+        let impossible = Sudoku(bytes).contradiction().unwrap();
+        assert_eq!(impossible, Contradiction::DigitImpossible { house: super::super::House::new(2), digit: 1 });
+        assert_eq!(impossible.to_string(), "digit 1 impossible in row 3");
+    }
+
+    #[test]
+    fn solve_with_stats() {
+        let solved = Sudoku::generate_solved(&mut StdRng::seed_from_u64(0));
+        let (solution, stats) = solved.solve_with_stats();
+        assert_eq!(solution, Some(solved));
+        assert_eq!((stats.guesses, stats.backtracks), (0, 0));
+
+        // needs no guesses, only propagation
+        let easy =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let (solution, stats) = easy.solve_with_stats();
+        assert_eq!(solution, easy.solution());
+        assert_eq!((stats.guesses, stats.backtracks), (0, 0));
+        assert!(stats.propagation_passes > 0);
+
+        let empty = Sudoku([0; N_CELLS]);
+        let (solution, stats) = empty.solve_with_stats();
+        assert_eq!(solution, None);
+        assert!(stats.guesses > 0);
+    }
+
+    #[test]
+    fn error_composition() {
+        use crate::errors::Error;
+
+        fn solve(line: &str) -> Result<Sudoku, Error> {
+            let sudoku: Sudoku = line.parse()?;
+            if let Some(contradiction) = sudoku.contradiction() {
+                return Err(contradiction.into());
+            }
+            Ok(sudoku.solve_with_limit(1000)?.unwrap())
+        }
+
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        assert_eq!(solve(line), Ok(line.parse::<Sudoku>().unwrap().solution().unwrap()));
+        let err = solve(&line[..80]).unwrap_err();
+        assert_eq!(err, Error::LineParse(LineParseError::NotEnoughCells(80)));
+        assert_eq!(err.to_string(), "sudoku contains 80 cells instead of required 81");
+        let err = solve(&line.replacen('.', "5", 1)).unwrap_err();
+        assert!(matches!(err, Error::Unsolvable(Contradiction::DuplicateDigit { digit: 5, .. })));
+        assert!(std::error::Error::source(&err).is_none());
+    }
+
+    #[test]
+    fn solve_with_limit() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (sudoku, guesses) = iter::repeat_with(|| Sudoku::generate(&mut rng))
+            .map(|sudoku| (sudoku, sudoku.solve_with_stats().1.guesses))
+            .find(|&(_, guesses)| guesses > 1)
+            .unwrap();
+        assert_eq!(sudoku.solve_with_limit(guesses), Ok(sudoku.solution()));
+        assert_eq!(
+            sudoku.solve_with_limit(guesses - 1),
+            Err(Exceeded {
+                max_guesses: guesses - 1
+            })
+        );
+
+        // the second solution is found within the budget
+        let empty = Sudoku([0; N_CELLS]);
+        assert_eq!(empty.solve_with_limit(100), Ok(None));
+        assert_eq!(empty.solve_with_limit(0), Err(Exceeded { max_guesses: 0 }));
+        let mut conflicting = [0; N_CELLS];
+        conflicting[..2].copy_from_slice(&[1, 1]);
+        assert_eq!(Sudoku(conflicting).solve_with_limit(0), Ok(None));
+    }
+
+    #[test]
+    fn progress_helpers() {
+        let puzzle: Sudoku = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79"
+            .parse()
+            .unwrap();
+        let solution = puzzle.solution().unwrap();
+        assert_eq!(puzzle.n_clues(), 30);
+        assert!(puzzle.is_valid_partial() && solution.is_valid_partial());
+        assert!(puzzle.is_subset_of(&solution) && !solution.is_subset_of(&puzzle));
+        assert_eq!(puzzle.n_solved_cells(&puzzle), 0);
+        assert_eq!(solution.n_solved_cells(&puzzle), 51);
+
+        let mut progress = puzzle;
+        progress.0[2] = solution.0[2];
+        progress.0[3] = solution.0[3];
+        assert_eq!(progress.n_solved_cells(&puzzle), 2);
+        assert!(puzzle.is_subset_of(&progress) && progress.is_subset_of(&solution));
+
+        // a wrong but non-conflicting entry
+        progress.0[2] = (1..=9).find(|&num| num != solution.0[2] && !progress.0[..9].contains(&num)).unwrap();
+        assert!(puzzle.is_subset_of(&progress) && !progress.is_subset_of(&solution));
+
+        // 5 twice in the first row
+        progress.0[2] = 5;
+        assert!(!progress.is_valid_partial());
+        assert_eq!(progress.n_solved_cells(&puzzle), 2);
+    }
+
+    #[test]
+    fn cell_access() {
+        use super::super::{Cell, Digit};
+
+        let sudoku: Sudoku = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79"
+            .parse()
+            .unwrap();
+        assert_eq!(sudoku[Cell::new(1)], 3);
+        assert_eq!(sudoku[Cell::new(2)], 0);
+        assert_eq!(sudoku.get(Cell::new(1)), Some(Digit::new(3)));
+        assert_eq!(sudoku.get(Cell::new(2)), None);
+
+        let entries = sudoku.entries().collect::<Vec<_>>();
+        assert_eq!(entries.len(), N_CELLS);
+        assert_eq!(entries[4], (Cell::new(4), Some(Digit::new(7))));
+        assert!(entries.iter().all(|&(cell, digit)| digit == sudoku.get(cell)));
+
+        let fives = sudoku.cells_matching(Digit::new(5));
+        assert_eq!(fives, Set::from([Cell::new(0), Cell::new(14), Cell::new(71)]));
+        assert!(Sudoku([0; N_CELLS]).cells_matching(Digit::new(5)).is_empty());
+    }
+
+    #[test]
+    fn compact_encodings() {
+        let sudoku = Sudoku::generate(&mut StdRng::from_seed([7; 32]));
+        let packed = sudoku.to_packed_bytes();
+        assert_eq!(packed.len(), 41);
+        assert_eq!(Sudoku::from_packed_bytes(packed).unwrap(), sudoku);
+        let mut padded = packed;
+        padded[40] |= 0x10;
+        assert!(Sudoku::from_packed_bytes(padded).is_err());
+        let mut invalid = packed;
+        invalid[0] = 0x0a;
+        assert!(Sudoku::from_packed_bytes(invalid).is_err());
+
+        let clues = sudoku.to_clue_bytes();
+        assert_eq!(clues.len(), 2 * sudoku.n_clues() as usize);
+        assert_eq!(Sudoku::from_clue_bytes(&clues).unwrap(), sudoku);
+        assert_eq!(Sudoku::from_clue_bytes(&[80, 9, 0, 1]).unwrap().to_clue_bytes(), [0, 1, 80, 9]);
+        assert_eq!(Sudoku::from_clue_bytes(&[]).unwrap(), Sudoku([0; N_CELLS]));
+
+        use crate::errors::FromClueBytesError::*;
+        assert_eq!(Sudoku::from_clue_bytes(&[0]), Err(OddLength(1)));
+        assert_eq!(Sudoku::from_clue_bytes(&[81, 1]), Err(InvalidClue { cell: 81, digit: 1 }));
+        assert_eq!(Sudoku::from_clue_bytes(&[3, 0]), Err(InvalidClue { cell: 3, digit: 0 }));
+        assert_eq!(Sudoku::from_clue_bytes(&[3, 1, 3, 2]), Err(DuplicateCell(3)));
+    }
+
+    #[test]
+    fn conversions() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku: Sudoku = line.parse().unwrap();
+        assert_eq!(sudoku, Sudoku::from_str_line(line).unwrap());
+        assert_eq!("53..7".parse::<Sudoku>(), Err(LineParseError::NotEnoughCells(5)));
+
+        let bytes = sudoku.to_bytes();
+        assert_eq!(Sudoku::try_from(bytes).unwrap(), sudoku);
+        assert_eq!(Sudoku::try_from(&bytes[..]).unwrap(), sudoku);
+        assert!(Sudoku::try_from(&bytes[1..]).is_err());
+        let mut invalid = bytes;
+        invalid[0] = 10;
+        assert!(Sudoku::try_from(invalid).is_err());
+    }
+
+    #[test]
+    fn solution_trace() {
+        use crate::solver::PlacementKind;
+
+        let easy =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let hard =
+            Sudoku::from_str_line("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..")
+                .unwrap();
+        for &sudoku in &[easy, hard] {
+            let trace = sudoku.solution_trace().unwrap();
+            assert_eq!(trace.len(), N_CELLS - sudoku.n_clues() as usize);
+            let mut replay = sudoku;
+            for placement in &trace {
+                assert_eq!(replay.0[placement.cell as usize], 0);
+                replay.0[placement.cell as usize] = placement.digit;
+            }
+            assert_eq!(Some(replay), sudoku.solution());
+        }
+        let easy_trace = easy.solution_trace().unwrap();
+        assert!(easy_trace.iter().all(|placement| placement.kind != PlacementKind::Guess));
+        let hard_trace = hard.solution_trace().unwrap();
+        assert!(hard_trace.iter().any(|placement| placement.kind == PlacementKind::Guess));
+
+        assert_eq!(Sudoku([0; N_CELLS]).solution_trace(), None);
+    }
+
+    #[test]
+    fn generate_from_seed() {
+        let seed = [7; 32];
+        let sudoku = Sudoku::generate_from_seed(seed, 0);
+        assert_eq!(sudoku, Sudoku::generate(&mut StdRng::from_seed(seed)));
+        assert_eq!(Sudoku::generate_from_seed(seed, 1), Sudoku::generate_from_seed(seed, 1));
+        assert_ne!(Sudoku::generate_from_seed(seed, 1), sudoku);
+        assert_ne!(Sudoku::generate_from_seed(seed, 1), Sudoku::generate_from_seed(seed, 1 << 32));
+        assert!(Sudoku::generate_from_seed(seed, 1).is_uniquely_solvable());
+        assert!(Sudoku::generate_solved_from_seed(seed, 2).is_solved());
+    }
+
+    #[test]
+    fn borsh_packed_roundtrip() {
+        let sudoku = Sudoku::generate(&mut StdRng::from_seed([7; 32]));
+        let bytes = sudoku.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), 1 + PACKED_LEN);
+        assert_eq!(Sudoku::try_from_slice(&bytes).unwrap(), sudoku);
+    }
+
+    #[test]
+    fn borsh_reads_legacy_layout() {
+        let sudoku = Sudoku::generate(&mut StdRng::from_seed([7; 32]));
+        let legacy = sudoku.to_bytes().to_vec();
+        assert_eq!(Sudoku::try_from_slice(&legacy).unwrap(), sudoku);
+
+        let mut invalid = legacy;
+        invalid[0] = 10;
+        assert!(Sudoku::try_from_slice(&invalid).is_err());
+    }
+
     // each cell in a symmetry class must map to the same set of cells
     #[test]
     fn test_symmetry_all_cells_equivalent() {
@@ -947,6 +2029,22 @@ mod test {
         }
     }
 
+    // the clues of a generated sudoku fill either all or none of the cells of each symmetry class
+    #[test]
+    fn generate_with_symmetry_keeps_symmetry() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for symmetry in Symmetry::iter() {
+            let sudoku = Sudoku::generate_with_symmetry(symmetry, &mut rng);
+            assert!(sudoku.is_uniquely_solvable());
+            for cell in 0..81 {
+                let is_clue = sudoku.0[cell] != 0;
+                for other in symmetry.corresponding_cells(cell) {
+                    assert_eq!(sudoku.0[other] != 0, is_clue, "{:?} broken at cell {}", symmetry, cell);
+                }
+            }
+        }
+    }
+
     // More complicated symmetries can be expressed as a combination of simple symmetries.
     #[test]
     fn test_symmetry_composite_symmetries() {
@@ -996,4 +2094,41 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn transformations() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let solution = sudoku.solution().unwrap();
+        let transforms: [fn(&mut Sudoku); 7] = [
+            |sudoku| sudoku.relabel_digits([9, 8, 7, 6, 5, 4, 3, 2, 1]),
+            |sudoku| sudoku.swap_rows(3, 5),
+            |sudoku| sudoku.swap_cols(7, 6),
+            |sudoku| sudoku.swap_bands(0, 2),
+            |sudoku| sudoku.swap_stacks(1, 0),
+            Sudoku::transpose,
+            Sudoku::rotate_clockwise,
+        ];
+        for transform in &transforms {
+            let (mut transformed, mut transformed_solution) = (sudoku, solution);
+            transform(&mut transformed);
+            transform(&mut transformed_solution);
+            assert_ne!(transformed, sudoku);
+            assert_eq!(transformed.solution(), Some(transformed_solution));
+            assert_eq!(transformed.canonicalized(), sudoku.canonicalized());
+        }
+
+        let mut rotated = sudoku;
+        rotated.rotate_clockwise();
+        assert_eq!(rotated.0[8], sudoku.0[0]);
+        rotated.rotate_counterclockwise();
+        assert_eq!(rotated, sudoku);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_rows_of_different_bands() {
+        Sudoku::generate_solved(&mut StdRng::seed_from_u64(0)).swap_rows(2, 3);
+    }
 }
@@ -0,0 +1,503 @@
+//! Killer sudokus, whose cages of cells must add up to given sums without repeating a digit
+use alloc::{vec, vec::Vec};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::bitset::Set;
+use crate::board::{Cell, Digit};
+use crate::consts::*;
+use crate::constraints::Constraints;
+use crate::errors::CageError;
+use crate::helper::HouseArray;
+use crate::Sudoku;
+
+/// Largest cage created by [`KillerSudoku::generate`]
+const MAX_GENERATED_CAGE_SIZE: u8 = 4;
+
+/// A group of cells whose digits add up to a sum. No digit may repeat within a cage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cage {
+    cells: Set<Cell>,
+    sum: u8,
+}
+
+impl Cage {
+    /// Constructs a new cage. Fails if the cage is empty or distinct digits can't add up to `sum` in it.
+    pub fn new(cells: Set<Cell>, sum: u8) -> Result<Cage, CageError> {
+        let n_cells = cells.len();
+        if n_cells == 0 {
+            return Err(CageError::NoCells);
+        }
+        match sum_range(Set::ALL, n_cells) {
+            Some((min, max)) if min <= sum && sum <= max => Ok(Cage { cells, sum }),
+            _ => Err(CageError::ImpossibleSum { n_cells, sum }),
+        }
+    }
+
+    /// The cells of the cage
+    pub fn cells(&self) -> Set<Cell> {
+        self.cells
+    }
+
+    /// The sum of the cage's digits
+    pub fn sum(&self) -> u8 {
+        self.sum
+    }
+}
+
+/// A sudoku with additional [`Cage`]s. The cages don't have to cover the whole grid.
+///
+/// Further variant rules can be added with [`KillerSudoku::with_constraints`].
+/// Without cages, this is a classic sudoku with those rules.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KillerSudoku {
+    clues: Sudoku,
+    cages: Vec<Cage>,
+    constraints: Constraints,
+}
+
+impl KillerSudoku {
+    /// Constructs a killer sudoku from its clues and cages. Fails if cages overlap.
+    pub fn new(clues: Sudoku, cages: Vec<Cage>) -> Result<KillerSudoku, CageError> {
+        let mut caged_cells = Set::NONE;
+        for cage in &cages {
+            if let Some(cell) = (caged_cells & cage.cells).into_iter().next() {
+                return Err(CageError::OverlappingCages(cell.get()));
+            }
+            caged_cells |= cage.cells;
+        }
+        Ok(KillerSudoku {
+            clues,
+            cages,
+            constraints: Constraints::default(),
+        })
+    }
+
+    /// Enforces the variant rules of `constraints` in addition to the cages
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// The variant rules enforced in addition to the cages
+    pub fn constraints(&self) -> Constraints {
+        self.constraints
+    }
+
+    /// Generates a random, uniquely solvable killer sudoku.
+    ///
+    /// Cages of 1 to 4 orthogonally connected cells are laid over a solved grid, then clues are removed
+    /// in random order as long as the solution stays unique. Cages alone often fix the solution,
+    /// so most generated puzzles have few or no clues.
+    pub fn generate(rng: &mut StdRng) -> Self {
+        KillerSudoku::generate_with_constraints(Constraints::default(), rng)
+    }
+
+    /// Generates a random, uniquely solvable killer sudoku that also obeys the variant rules of `constraints`.
+    /// See [`KillerSudoku::generate`].
+    pub fn generate_with_constraints(constraints: Constraints, rng: &mut StdRng) -> Self {
+        let solution = match constraints.is_classic() {
+            true => Sudoku::generate_solved(rng),
+            false => KillerSudoku::new(Sudoku([0; N_CELLS]), vec![])
+                .unwrap()
+                .with_constraints(constraints)
+                .random_solution(rng)
+                .expect("empty grid has solutions under all constraints"),
+        };
+        let cages = random_cages(solution, rng);
+        let mut killer = KillerSudoku {
+            clues: solution,
+            cages,
+            constraints,
+        };
+        killer.remove_clues(rng);
+        killer
+    }
+
+    // Removes clues in random order as long as the solution stays unique
+    pub(crate) fn remove_clues(&mut self, rng: &mut StdRng) {
+        let mut cell_order: Vec<_> = (0..N_CELLS).collect();
+        cell_order.shuffle(rng);
+        for cell in cell_order {
+            let digit = self.clues.0[cell];
+            self.clues.0[cell] = 0;
+            if !self.is_uniquely_solvable() {
+                self.clues.0[cell] = digit;
+            }
+        }
+    }
+
+    // A random solution that respects the clues and cages
+    pub(crate) fn random_solution(&self, rng: &mut StdRng) -> Option<Sudoku> {
+        let mut solver = KillerSolver::new(self)?;
+        match solver.search_random(rng) {
+            true => Some(solver.grid),
+            false => None,
+        }
+    }
+
+    /// The given digits
+    pub fn clues(&self) -> Sudoku {
+        self.clues
+    }
+
+    /// The cages
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// Checks whether `sudoku` is a valid solution, i.e. solved, matching all clues, cages and constraints.
+    pub fn is_solution(&self, sudoku: Sudoku) -> bool {
+        let matches_clues = self.clues.0.iter().zip(sudoku.0.iter()).all(|(&clue, &num)| clue == 0 || clue == num);
+        let matches_cages = self.cages.iter().all(|cage| {
+            let nums = cage.cells.into_iter().map(|cell| sudoku.0[cell.as_index()]);
+            let digits = nums.clone().filter_map(Digit::new_checked).fold(Set::NONE, |digits, digit| digits | digit);
+            digits.len() == cage.cells.len() && nums.sum::<u8>() == cage.sum
+        });
+        let matches_constraints = Cell::all().all(|cell| {
+            let num = sudoku.0[cell.as_index()];
+            self.constraints.conflicting_cells(cell).into_iter().all(|other| sudoku.0[other.as_index()] != num)
+        });
+        sudoku.is_solved() && matches_clues && matches_cages && matches_constraints
+    }
+
+    /// Counts the number of solutions to the killer sudoku up to `limit`.
+    pub fn solutions_count_up_to(&self, limit: usize) -> usize {
+        KillerSolver::new(self).map_or(0, |mut solver| solver.solve(limit).0)
+    }
+
+    /// Checks whether the killer sudoku has one and only one solution.
+    pub fn is_uniquely_solvable(&self) -> bool {
+        self.solutions_count_up_to(2) == 1
+    }
+
+    /// Returns the solution, if it is unique.
+    pub fn solution(&self) -> Option<Sudoku> {
+        match KillerSolver::new(self)?.solve(2) {
+            (1, solution) => solution,
+            _ => None,
+        }
+    }
+}
+
+// Lays cages over the solution by growing each cage from a random unassigned cell
+// into orthogonally adjacent unassigned cells that don't repeat a digit of the cage.
+fn random_cages(solution: Sudoku, rng: &mut StdRng) -> Vec<Cage> {
+    let mut cell_order: Vec<_> = Cell::all().collect();
+    cell_order.shuffle(rng);
+
+    let mut assigned = Set::NONE;
+    let mut cages = vec![];
+    for start in cell_order {
+        if assigned.contains(start) {
+            continue;
+        }
+        let digit = |cell: Cell| Digit::new(solution.0[cell.as_index()]);
+        let size = rng.gen_range(1, MAX_GENERATED_CAGE_SIZE + 1);
+        let mut cells = start.as_set();
+        let mut digits = digit(start).as_set();
+        while cells.len() < size {
+            let candidates: Vec<_> = cells
+                .into_iter()
+                .flat_map(orthogonal_neighbors)
+                .filter(|&cell| !assigned.contains(cell) && !cells.contains(cell) && !digits.contains(digit(cell)))
+                .collect();
+            match candidates.choose(rng) {
+                Some(&cell) => {
+                    cells |= cell;
+                    digits |= digit(cell);
+                }
+                None => break,
+            }
+        }
+        assigned |= cells;
+        let sum = digits.into_iter().map(Digit::get).sum();
+        cages.push(Cage::new(cells, sum).unwrap());
+    }
+    cages
+}
+
+fn orthogonal_neighbors(cell: Cell) -> Vec<Cell> {
+    let (row, col) = (cell.get() / 9, cell.get() % 9);
+    let mut neighbors = vec![];
+    if row > 0 {
+        neighbors.push(Cell::new(cell.get() - 9));
+    }
+    if row < 8 {
+        neighbors.push(Cell::new(cell.get() + 9));
+    }
+    if col > 0 {
+        neighbors.push(Cell::new(cell.get() - 1));
+    }
+    if col < 8 {
+        neighbors.push(Cell::new(cell.get() + 1));
+    }
+    neighbors
+}
+
+// Smallest and largest sum of `n_digits` distinct digits from `digits`
+fn sum_range(digits: Set<Digit>, n_digits: u8) -> Option<(u8, u8)> {
+    if digits.len() < n_digits {
+        return None;
+    }
+    let sorted: Vec<u8> = digits.into_iter().map(Digit::get).collect();
+    let n_digits = n_digits as usize;
+    let min = sorted[..n_digits].iter().sum();
+    let max = sorted[sorted.len() - n_digits..].iter().sum();
+    Some((min, max))
+}
+
+// Backtracking solver that enforces cage sums, uniqueness within cages and the variant constraints
+// in addition to the sudoku rules. Always continues with the cell with the fewest candidates.
+struct KillerSolver {
+    grid: Sudoku,
+    cell_cage: [Option<usize>; N_CELLS],
+    conflicting_cells: Vec<Vec<Cell>>,
+    house_digits: HouseArray<Set<Digit>>,
+    cage_digits: Vec<Set<Digit>>,
+    // sum and number of cells of each cage that are still unsolved
+    cage_remainders: Vec<(u8, u8)>,
+}
+
+impl KillerSolver {
+    // Returns None, if the clues contradict each other or the cages
+    fn new(killer: &KillerSudoku) -> Option<Self> {
+        let mut cell_cage = [None; N_CELLS];
+        for (idx, cage) in killer.cages.iter().enumerate() {
+            for cell in cage.cells {
+                cell_cage[cell.as_index()] = Some(idx);
+            }
+        }
+        let mut solver = KillerSolver {
+            grid: Sudoku([0; N_CELLS]),
+            cell_cage,
+            conflicting_cells: Cell::all().map(|cell| killer.constraints.conflicting_cells(cell)).collect(),
+            house_digits: HouseArray([Set::NONE; N_HOUSES]),
+            cage_digits: vec![Set::NONE; killer.cages.len()],
+            cage_remainders: killer.cages.iter().map(|cage| (cage.sum, cage.cells.len())).collect(),
+        };
+
+        for (cell, &num) in Cell::all().zip(killer.clues.0.iter()) {
+            if num == 0 {
+                continue;
+            }
+            let digit = Digit::new(num);
+            if !solver.candidates(cell).contains(digit) {
+                return None;
+            }
+            solver.insert(cell, digit);
+        }
+        Some(solver)
+    }
+
+    fn candidates(&self, cell: Cell) -> Set<Digit> {
+        let taken = self.house_digits[cell.row()] | self.house_digits[cell.col()] | self.house_digits[cell.block()];
+        let candidates = self.conflicting_cells[cell.as_index()]
+            .iter()
+            .filter_map(|other| Digit::new_checked(self.grid.0[other.as_index()]))
+            .fold(!taken, |candidates, digit| candidates.without(digit.as_set()));
+        let cage = match self.cell_cage[cell.as_index()] {
+            Some(cage) => cage,
+            None => return candidates,
+        };
+
+        // the remaining cells of the cage must still be able to reach the remaining sum
+        let (sum, n_cells) = self.cage_remainders[cage];
+        let unused = !self.cage_digits[cage];
+        (candidates & unused)
+            .into_iter()
+            .filter(|&digit| {
+                let rest = match sum.checked_sub(digit.get()) {
+                    Some(rest) => rest,
+                    None => return false,
+                };
+                match sum_range(unused.without(digit.as_set()), n_cells - 1) {
+                    Some((min, max)) => min <= rest && rest <= max,
+                    None => false,
+                }
+            })
+            .fold(Set::NONE, |set, digit| set | digit)
+    }
+
+    fn insert(&mut self, cell: Cell, digit: Digit) {
+        self.toggle(cell, digit);
+        self.grid.0[cell.as_index()] = digit.get();
+        if let Some(cage) = self.cell_cage[cell.as_index()] {
+            let (sum, n_cells) = &mut self.cage_remainders[cage];
+            *sum -= digit.get();
+            *n_cells -= 1;
+        }
+    }
+
+    fn remove(&mut self, cell: Cell, digit: Digit) {
+        self.toggle(cell, digit);
+        self.grid.0[cell.as_index()] = 0;
+        if let Some(cage) = self.cell_cage[cell.as_index()] {
+            let (sum, n_cells) = &mut self.cage_remainders[cage];
+            *sum += digit.get();
+            *n_cells += 1;
+        }
+    }
+
+    fn toggle(&mut self, cell: Cell, digit: Digit) {
+        self.house_digits[cell.row()] ^= digit;
+        self.house_digits[cell.col()] ^= digit;
+        self.house_digits[cell.block()] ^= digit;
+        if let Some(cage) = self.cell_cage[cell.as_index()] {
+            self.cage_digits[cage] ^= digit;
+        }
+    }
+
+    // Returns the number of solutions found up to `limit` and the first solution
+    fn solve(&mut self, limit: usize) -> (usize, Option<Sudoku>) {
+        let mut n_solutions = 0;
+        let mut first_solution = None;
+        self.search(limit, &mut n_solutions, &mut first_solution);
+        (n_solutions, first_solution)
+    }
+
+    // Unsolved cell with the fewest candidates. None, if the grid is solved.
+    fn most_constrained_cell(&self) -> Option<(Cell, Set<Digit>)> {
+        let mut best: Option<(Cell, Set<Digit>)> = None;
+        for cell in Cell::all().filter(|cell| self.grid.0[cell.as_index()] == 0) {
+            let candidates = self.candidates(cell);
+            let is_better = match best {
+                Some((_, best_candidates)) => candidates.len() < best_candidates.len(),
+                None => true,
+            };
+            if is_better {
+                best = Some((cell, candidates));
+                if candidates.len() <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    // Fills the grid with a random solution. Returns false, if there is none.
+    fn search_random(&mut self, rng: &mut StdRng) -> bool {
+        let (cell, candidates) = match self.most_constrained_cell() {
+            Some(best) => best,
+            None => return true,
+        };
+        let mut candidates: Vec<_> = candidates.into_iter().collect();
+        candidates.shuffle(rng);
+        for digit in candidates {
+            self.insert(cell, digit);
+            if self.search_random(rng) {
+                return true;
+            }
+            self.remove(cell, digit);
+        }
+        false
+    }
+
+    fn search(&mut self, limit: usize, n_solutions: &mut usize, first_solution: &mut Option<Sudoku>) {
+        let (cell, candidates) = match self.most_constrained_cell() {
+            Some(best) => best,
+            None => {
+                *n_solutions += 1;
+                first_solution.get_or_insert(self.grid);
+                return;
+            }
+        };
+        for digit in candidates {
+            self.insert(cell, digit);
+            self.search(limit, n_solutions, first_solution);
+            self.remove(cell, digit);
+            if *n_solutions >= limit {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn cells(cells: &[u8]) -> Set<Cell> {
+        cells.iter().fold(Set::NONE, |set, &cell| set | Cell::new(cell))
+    }
+
+    #[test]
+    fn cage_sums() {
+        assert!(Cage::new(cells(&[0, 1]), 3).is_ok());
+        assert!(Cage::new(cells(&[0, 1]), 17).is_ok());
+        assert_eq!(Cage::new(cells(&[0, 1]), 18), Err(CageError::ImpossibleSum { n_cells: 2, sum: 18 }));
+        assert_eq!(Cage::new(cells(&[0, 1, 2]), 5), Err(CageError::ImpossibleSum { n_cells: 3, sum: 5 }));
+        assert_eq!(Cage::new(Set::NONE, 5), Err(CageError::NoCells));
+
+        let cage1 = Cage::new(cells(&[0, 1]), 3).unwrap();
+        let cage2 = Cage::new(cells(&[1, 2]), 3).unwrap();
+        let empty = Sudoku([0; N_CELLS]);
+        assert_eq!(KillerSudoku::new(empty, vec![cage1, cage2]), Err(CageError::OverlappingCages(1)));
+    }
+
+    #[test]
+    fn cages_enforced() {
+        // without the first 2 rows, the sudoku has multiple solutions
+        let solution = Sudoku::generate_solved(&mut StdRng::seed_from_u64(0));
+        let mut clues = solution;
+        for cell in 0..18 {
+            clues.0[cell] = 0;
+        }
+        assert!(clues.solutions_count_up_to(2) > 1);
+        assert!(KillerSudoku::new(clues, vec![]).unwrap().solutions_count_up_to(2) > 1);
+
+        // single cell cages over the first row fix it
+        let row_cages = |sum_of_first: u8| -> Vec<_> {
+            (0..9)
+                .map(|cell| {
+                    let sum = if cell == 0 { sum_of_first } else { solution.0[cell as usize] };
+                    Cage::new(cells(&[cell]), sum).unwrap()
+                })
+                .collect()
+        };
+        let killer = KillerSudoku::new(clues, row_cages(solution.0[0])).unwrap();
+        assert_eq!(killer.solution(), Some(solution));
+        assert!(killer.is_solution(solution));
+
+        // the first cage repeats a digit of the row
+        let killer = KillerSudoku::new(clues, row_cages(solution.0[1])).unwrap();
+        assert_eq!(killer.solutions_count_up_to(2), 0);
+        assert!(!killer.is_solution(solution));
+    }
+
+    #[test]
+    fn generate() {
+        let killer = KillerSudoku::generate(&mut StdRng::seed_from_u64(0));
+        let solution = killer.solution().unwrap();
+        assert!(killer.is_solution(solution));
+
+        let caged_cells = killer.cages().iter().fold(Set::NONE, |set, cage| set | cage.cells());
+        assert_eq!(caged_cells, Set::ALL);
+        assert!(killer.cages().iter().all(|cage| cage.cells().len() <= MAX_GENERATED_CAGE_SIZE));
+    }
+
+    #[test]
+    fn generate_with_constraints() {
+        for &(anti_knight, anti_king) in &[(true, false), (false, true)] {
+            let constraints = Constraints {
+                anti_knight,
+                anti_king,
+                ..Constraints::default()
+            };
+            let killer = KillerSudoku::generate_with_constraints(constraints, &mut StdRng::seed_from_u64(0));
+            assert_eq!(killer.constraints(), constraints);
+            let solution = killer.solution().unwrap();
+            assert!(killer.is_solution(solution));
+            for cell in Cell::all() {
+                for other in constraints.conflicting_cells(cell) {
+                    assert_ne!(solution.0[cell.as_index()], solution.0[other.as_index()]);
+                }
+            }
+            // regular solved sudokus rarely satisfy the constraints
+            let regular = Sudoku::generate_solved(&mut StdRng::seed_from_u64(0));
+            assert!(!killer.clone().with_constraints(constraints).is_solution(regular));
+        }
+    }
+}
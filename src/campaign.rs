@@ -0,0 +1,38 @@
+use near_sdk::serde::Serialize;
+
+use crate::Difficulty;
+
+/// Campaign levels in the order they have to be completed
+pub const LEVELS: [Difficulty; 12] = [
+    Difficulty::Easy,
+    Difficulty::Easy,
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Medium,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Hard,
+    Difficulty::Hard,
+    Difficulty::Expert,
+    Difficulty::Expert,
+    Difficulty::Expert,
+];
+
+/// Difficulty of `level`, levels are numbered from 1
+pub fn level_difficulty(level: u32) -> Option<Difficulty> {
+    level
+        .checked_sub(1)
+        .and_then(|idx| LEVELS.get(idx as usize))
+        .copied()
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CampaignProgress {
+    pub completed_levels: u32,
+    pub total_levels: u32,
+    /// Level of the unfinished game, if it belongs to the campaign
+    pub current_level: Option<u32>,
+    /// Difficulty of the first uncompleted level, `None` once the campaign is completed
+    pub next_difficulty: Option<Difficulty>,
+}
@@ -0,0 +1,35 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::Timestamp;
+use std::collections::BTreeMap;
+
+use crate::difficulty::Difficulty;
+
+/// Lifetime achievements of a player, left alone when a new season starts
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Career {
+    /// Season points scored over all seasons
+    pub points: u128,
+    /// Seasons ended with the player on top of the points board
+    pub seasons_won: u32,
+    pub races_entered: u32,
+    /// Races finished first
+    pub races_won: u32,
+}
+
+/// Lifetime stats of a player, see [`crate::Contract::get_career`]
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CareerRequest {
+    pub sloved_count: U128,
+    pub practice_sloved_count: u32,
+    pub coop_sloved_count: u32,
+    pub best_time: Option<Timestamp>,
+    /// Fastest ranked solve per difficulty, `None` if none was solved
+    pub best_times: BTreeMap<Difficulty, Option<Timestamp>>,
+    pub points: U128,
+    pub seasons_won: u32,
+    pub races_entered: u32,
+    pub races_won: u32,
+}
@@ -0,0 +1,98 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, Balance, Timestamp};
+
+use crate::{prize, race, reward, score, snapshot};
+
+/// Tuning parameters the owner can change without a redeploy, see [`crate::Contract::update_config`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Config {
+    /// Places served by [`crate::Contract::get_leaderboard_page`], tracked by the leaderboard
+    /// events and kept in the period standings
+    pub leaderboard_size: u32,
+    /// Points of a daily reward without streak bonus
+    pub daily_reward_points: U128,
+    /// Streak length from which on the daily reward stops growing
+    pub max_streak_multiplier: u32,
+    /// Percentage of the freed storage deposit paid for removing stale players
+    pub cleanup_reward_percent: u32,
+    /// Percentage added to the score of solves without hints
+    pub no_hint_bonus_percent: u32,
+    /// Time in milliseconds a prize can be claimed after its period settled
+    pub prize_claim_period: Timestamp,
+    /// Time in milliseconds after the start from which on a race can be settled with paid places left
+    pub race_time_limit: Timestamp,
+    /// Number of the most recent ended periods whose snapshots can't be pruned
+    pub snapshot_retention: u64,
+    /// Record the entries of every game, not just of players sharing replays,
+    /// see [`crate::Contract::get_move_history`]. Their storage is held from the prepaid balance
+    /// like for shared replays, games of players who can't pay it aren't recorded.
+    pub record_moves: bool,
+    /// Time in milliseconds added to the solve time for every wrong `finish_game` submission.
+    /// Submissions rejected before a change keep the penalty they were charged.
+    pub wrong_submission_penalty: Timestamp,
+    /// Time in milliseconds a player has to wait before abandoning an unfinished game for a new one
+    pub start_game_cooldown: Timestamp,
+    /// Fee in yoctonear charged for every ranked game, paid into the prize pool
+    pub game_fee: U128,
+    /// Length in milliseconds of the leaderboard periods, starting with the running one
+    pub period_length: Timestamp,
+    /// Deposit locked by [`crate::Contract::verify`], without the storage of the verified rankings.
+    /// Stakes locked before a change are returned in full.
+    pub verification_stake: U128,
+    /// Time in milliseconds without a started or finished game after which a player can be removed
+    /// by [`crate::Contract::cleanup_stale`]
+    pub stale_period: Timestamp,
+    /// Fastest plausible solve in milliseconds, indexed by [`crate::Difficulty`].
+    /// Faster solutions are rejected, wrong submissions don't count towards it.
+    pub min_solve_times: [Timestamp; 4],
+    /// Percentages of the pot paid to the first finishers of a race, in finishing order.
+    /// Races settled after a change pay out by the new schedule.
+    pub race_payout_schedule: Vec<u32>,
+    /// Storage in bytes assumed per player. It is charged on registration and refunded on removal
+    /// by the size configured at the time.
+    pub player_size: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            leaderboard_size: crate::LEADERBOARD_SIZE,
+            daily_reward_points: U128(reward::DAILY_REWARD_POINTS),
+            max_streak_multiplier: reward::MAX_STREAK_MULTIPLIER,
+            cleanup_reward_percent: crate::CLEANUP_REWARD_PERCENT,
+            no_hint_bonus_percent: score::NO_HINT_BONUS_PERCENT,
+            prize_claim_period: prize::PRIZE_CLAIM_PERIOD,
+            race_time_limit: race::RACE_TIME_LIMIT,
+            snapshot_retention: snapshot::SNAPSHOT_RETENTION,
+            record_moves: false,
+            wrong_submission_penalty: crate::DEFAULT_WRONG_SUBMISSION_PENALTY,
+            start_game_cooldown: crate::DEFAULT_START_GAME_COOLDOWN,
+            game_fee: U128(crate::DEFAULT_GAME_FEE),
+            period_length: crate::DEFAULT_PERIOD_LENGTH,
+            verification_stake: U128(crate::DEFAULT_VERIFICATION_STAKE),
+            stale_period: crate::DEFAULT_STALE_PERIOD,
+            min_solve_times: [0; 4],
+            race_payout_schedule: race::DEFAULT_RACE_PAYOUT_SCHEDULE.to_vec(),
+            player_size: crate::PLAYER_SIZE,
+        }
+    }
+}
+
+impl Config {
+    pub fn is_valid(&self) -> bool {
+        self.leaderboard_size > 0
+            && self.max_streak_multiplier > 0
+            && self.cleanup_reward_percent <= 100
+            && self.period_length > 0
+            && !self.race_payout_schedule.is_empty()
+            && self.race_payout_schedule.iter().sum::<u32>() <= 100
+    }
+
+    /// Storage deposit of a player, see [`Config::player_size`]
+    pub fn player_deposit(&self) -> Balance {
+        self.player_size as Balance * env::STORAGE_PRICE_PER_BYTE
+    }
+}
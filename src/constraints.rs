@@ -0,0 +1,66 @@
+//! Additional rules of sudoku variants that forbid the same digit in cells related by a chess move
+use alloc::{vec, vec::Vec};
+use crate::board::Cell;
+
+/// Optional variant rules, enforced in addition to the sudoku rules and any cages.
+///
+/// Start from [`Constraints::default()`], which enables none of them, and change the fields as needed.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Constraints {
+    /// Cells a knight's move apart can't hold the same digit
+    pub anti_knight: bool,
+    /// Cells a king's move apart, i.e. diagonally touching, can't hold the same digit.
+    /// Orthogonally touching cells already share a house.
+    pub anti_king: bool,
+}
+
+impl Constraints {
+    /// Whether no variant rule is enabled
+    pub fn is_classic(self) -> bool {
+        self == Constraints::default()
+    }
+
+    // Cells that can't hold the same digit as `cell` due to the enabled variant rules
+    pub(crate) fn conflicting_cells(self, cell: Cell) -> Vec<Cell> {
+        const KNIGHT_MOVES: [(i8, i8); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+        const KING_MOVES: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+        let mut moves = vec![];
+        if self.anti_knight {
+            moves.extend_from_slice(&KNIGHT_MOVES);
+        }
+        if self.anti_king {
+            moves.extend_from_slice(&KING_MOVES);
+        }
+        let (row, col) = ((cell.get() / 9) as i8, (cell.get() % 9) as i8);
+        moves
+            .into_iter()
+            .map(|(row_offset, col_offset)| (row + row_offset, col + col_offset))
+            .filter(|&(row, col)| (0..9).contains(&row) && (0..9).contains(&col))
+            .map(|(row, col)| Cell::new((row * 9 + col) as u8))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conflicting_cells() {
+        let anti_knight = Constraints {
+            anti_knight: true,
+            ..Constraints::default()
+        };
+        let anti_king = Constraints {
+            anti_king: true,
+            ..Constraints::default()
+        };
+        assert!(Constraints::default().conflicting_cells(Cell::new(40)).is_empty());
+        assert_eq!(anti_knight.conflicting_cells(Cell::new(40)).len(), 8);
+        assert_eq!(anti_knight.conflicting_cells(Cell::new(0)), [Cell::new(11), Cell::new(19)]);
+        assert_eq!(anti_king.conflicting_cells(Cell::new(0)), [Cell::new(10)]);
+        assert_eq!(anti_king.conflicting_cells(Cell::new(40)).len(), 4);
+    }
+}
@@ -1,6 +1,12 @@
-pub const N_CELLS: usize = 81;
-pub const N_HOUSES: usize = 27; // rows, columns, blocks, 9 each
+// The board dimensions are derived from the box order, the side length of a block.
+// Only order 3 (9x9) is supported. Board, bitsets and solver still assume 9 digits and 81 cells
+// in their array sizes and bit layouts, so other orders need more than changing this constant.
+// Grids of other orders are `sized::SizedSudoku`.
+pub const BOX_ORDER: usize = 3;
+pub const N_DIGITS: usize = BOX_ORDER * BOX_ORDER;
+pub const N_CELLS: usize = N_DIGITS * N_DIGITS;
+pub const N_HOUSES: usize = 3 * N_DIGITS; // rows, columns, blocks, 9 each
 
 //pub const ROW_OFFSET: u8 = 0;
-pub const COL_OFFSET: u8 = 9;
-pub const BLOCK_OFFSET: u8 = 18;
+pub const COL_OFFSET: u8 = N_DIGITS as u8;
+pub const BLOCK_OFFSET: u8 = 2 * N_DIGITS as u8;
@@ -0,0 +1,6592 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap, Vector};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, near_bindgen, AccountId, Balance, BlockHeight, PanicOnDefault, Promise, StorageUsage,
+    Timestamp, ONE_NEAR,
+};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use std::collections::BTreeMap;
+use std::collections::{HashMap};
+use std::convert::TryInto;
+
+use crate::{
+    archive, attestation, campaign, coop, events, generation, metadata, novelty, prize, race,
+    reward, score, sponsor, storage, web4,
+};
+
+use crate::archive::{ArchiveDates, ArchivedPuzzle, ArchivedPuzzleRequest};
+use crate::attestation::{Attestation, AttestationRequest};
+use crate::board::Sudoku;
+use crate::campaign::CampaignProgress;
+use crate::career::{Career, CareerRequest};
+use crate::config::Config;
+use crate::coop::{CoopGame, CoopGameRequest};
+use crate::difficulty::Difficulty;
+use crate::errors::ContractError;
+use crate::events::LeaderboardChange;
+use crate::export::{ExportedPlayer, LeaderboardsExport, PlayersExport, EXPORT_VERSION};
+use crate::generation::PendingGeneration;
+use crate::metadata::{ContractMetadata, DifficultyRequest, GameMode, StandardRequest};
+use crate::novelty::RecentPuzzles;
+use crate::pool::{PoolDraw, PooledPuzzle};
+use crate::prize::{Prize, PrizeRequest};
+use crate::profile::Profile;
+use crate::race::{Race, RaceRequest, RaceStatus};
+use crate::ranking::RankIndex;
+use crate::replay::Replay;
+use crate::sized_game::{BoardSize, SizedBoards, SizedGame, SizedGameRequest, SIZED_RANKING_SIZE};
+use crate::snapshot::{Snapshot, SnapshotRequest, Standings, StandingsEntry};
+use crate::sponsor::{Sponsorship, SponsorshipRequest};
+use crate::stats::{DifficultyStats, DifficultyStatsRequest, SolveStats, StatisticsRequest};
+use crate::storage::{SideRecord, StorageReport};
+use crate::web4::{Web4Request, Web4Response};
+use crate::SudokuTwoDimensionalArray;
+
+/// The solve record of a player's last ranked game. Records of the legacy layouts lack the
+/// fields that are `Option` because of them.
+#[derive(Clone)]
+pub struct LastSlovedGame {
+    sudoku: Sudoku,
+    time_end: Timestamp,
+    time_start: Timestamp,
+    /// Hash of the recorded entries, see [`Replay::hash`]
+    moves_hash: Option<[u8; 32]>,
+    difficulty: Option<Difficulty>,
+    hints_used: Option<u32>,
+    /// The solve set a new best time
+    personal_best: Option<bool>,
+}
+
+/// First byte of the versioned borsh encoding of [`LastSlovedGame`]. Never the first byte of a
+/// sudoku, which starts the legacy encoding.
+const LAST_SLOVED_GAME_TAG: u8 = 0xfe;
+const LAST_SLOVED_GAME_VERSION: u8 = 1;
+
+// Borsh stores solve records as `LAST_SLOVED_GAME_TAG` and the version followed by the fields.
+// The legacy layouts without tag, `sudoku, time_end, time_start` optionally followed by
+// `moves_hash`, are still read and get rewritten on the next write, see `Contract::migrate_players`.
+impl BorshSerialize for LastSlovedGame {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[LAST_SLOVED_GAME_TAG, LAST_SLOVED_GAME_VERSION])?;
+        BorshSerialize::serialize(&self.sudoku, writer)?;
+        BorshSerialize::serialize(&self.time_end, writer)?;
+        BorshSerialize::serialize(&self.time_start, writer)?;
+        BorshSerialize::serialize(&self.moves_hash, writer)?;
+        BorshSerialize::serialize(&self.difficulty, writer)?;
+        BorshSerialize::serialize(&self.hints_used, writer)?;
+        BorshSerialize::serialize(&self.personal_best, writer)
+    }
+}
+
+impl BorshDeserialize for LastSlovedGame {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        if buf.first() != Some(&LAST_SLOVED_GAME_TAG) {
+            let sudoku = BorshDeserialize::deserialize(buf)?;
+            let time_end = BorshDeserialize::deserialize(buf)?;
+            let time_start = BorshDeserialize::deserialize(buf)?;
+            // Legacy records are stored under their own key, so anything left is the
+            // `moves_hash` of records written once move histories were recorded.
+            let moves_hash = match buf.is_empty() {
+                true => None,
+                false => BorshDeserialize::deserialize(buf)?,
+            };
+            return Ok(Self {
+                sudoku,
+                time_end,
+                time_start,
+                moves_hash,
+                difficulty: None,
+                hints_used: None,
+                personal_best: None,
+            });
+        }
+        if buf.get(1) != Some(&LAST_SLOVED_GAME_VERSION) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "unknown solve record version",
+            ));
+        }
+
+        *buf = &buf[2..];
+        Ok(Self {
+            sudoku: BorshDeserialize::deserialize(buf)?,
+            time_end: BorshDeserialize::deserialize(buf)?,
+            time_start: BorshDeserialize::deserialize(buf)?,
+            moves_hash: BorshDeserialize::deserialize(buf)?,
+            difficulty: BorshDeserialize::deserialize(buf)?,
+            hints_used: BorshDeserialize::deserialize(buf)?,
+            personal_best: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+/// First phase of [`Contract::request_puzzle`], the puzzle is generated by [`Contract::claim_puzzle`]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingPuzzle {
+    /// sha256 of the secret revealed when claiming
+    commitment: [u8; 32],
+    requested_height: BlockHeight,
+}
+
+pub struct Player {
+    sudoku: Option<Sudoku>,
+    progress: Option<Sudoku>,
+    /// Candidate notes, see [`NOTES_LEN`]
+    notes: SideRecord<Vec<u8>>,
+    start_time: Timestamp,
+    start_height: BlockHeight,
+    wrong_submissions: u32,
+    /// Milliseconds added to the time of the current game by wrong submissions, each one adds
+    /// the [`Contract::wrong_submission_penalty`] in place when it was rejected
+    penalty: Timestamp,
+    /// Campaign level of the current game
+    level: Option<u32>,
+    difficulty: Difficulty,
+    /// The current game is unranked, see [`Contract::start_practice`]
+    practice: bool,
+    /// Hints taken in the current game, solves without hints score a bonus
+    hints_used: u32,
+    /// Date of the archived puzzle played in the current game, see [`Contract::start_archived`]
+    archive_date: Option<u64>,
+    pending_puzzle: Option<PendingPuzzle>,
+
+    generated_sudoku_count: u128,
+    pub(crate) sloved_sudoku_count: u128,
+    practice_generated_count: u32,
+    practice_sloved_count: u32,
+    archive_sloved_count: u32,
+
+    last_sloved_game: SideRecord<LastSlovedGame>,
+
+    pub(crate) best_time: Option<Timestamp>,
+    /// Best solve time measured in blocks, see [`BoardKind::BlockTime`]
+    best_block_time: Option<Timestamp>,
+    /// Indexed by [`Difficulty`]
+    stats: [DifficultyStats; 4],
+    solve_stats: SolveStats,
+
+    profile: Option<Profile>,
+
+    completed_levels: u32,
+
+    points: u128,
+    /// Points scored by solves in the season ending at `points_season_end`, see [`score::score`]
+    season_points: u128,
+    points_season_end: Timestamp,
+    /// Consecutive days the daily reward was claimed on
+    reward_streak: u32,
+    /// UTC day of the last daily reward claim, see [`reward::day`]
+    last_reward_day: Option<u64>,
+
+    /// Prepaid game fees, so games can be started without attaching a deposit
+    balance: Balance,
+
+    /// Locked deposit of a verified player, 0 if unverified
+    pub(crate) stake: Balance,
+    stake_unlock_at: Timestamp,
+
+    /// Solved co-op games, they don't count for the leaderboards
+    coop_sloved_count: u32,
+    career: Career,
+
+    /// Opted into recording replays, see [`Contract::set_share_replays`]
+    share_replays: bool,
+    /// Entries of the current game, recorded while `share_replays` is set
+    replay: SideRecord<Replay>,
+    /// Replay of the last finished game
+    last_replay: SideRecord<Replay>,
+
+    /// The pooled puzzle of the current game, see [`Contract::start_game`]
+    pool_draw: Option<PoolDraw>,
+    /// Held from the prepaid balance for the storage of the replays while they are kept, see
+    /// [`Contract::set_share_replays`]
+    replay_deposit: Balance,
+    /// Dates of the archived puzzles solved, their completion records go with the player
+    archive_dates: ArchiveDates,
+}
+
+/// First byte of the versioned borsh encoding of [`Player`]. Never the first byte of the legacy
+/// encoding, which starts with the `Option` tag of the sudoku.
+const PLAYER_TAG: u8 = 0xfe;
+const PLAYER_VERSION: u8 = 5;
+/// Key prefixes of the records of a player stored under their own keys
+const LAST_SLOVED_GAME_PREFIX: &[u8] = b"l";
+const LAST_REPLAY_PREFIX: &[u8] = b"r";
+const NOTES_PREFIX: &[u8] = b"e";
+const REPLAY_PREFIX: &[u8] = b"i";
+
+// Borsh stores players as `PLAYER_TAG` and the version followed by the fields. The legacy layout
+// without tag, `sudoku, start_time, generated_sudoku_count, sloved_sudoku_count,
+// last_sloved_game, best_time` with the solve record inline, is still read and gets rewritten by
+// `Contract::migrate_players`. The fields added since then start out like for a new player.
+impl BorshSerialize for Player {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[PLAYER_TAG, PLAYER_VERSION])?;
+        BorshSerialize::serialize(&self.sudoku, writer)?;
+        BorshSerialize::serialize(&self.progress, writer)?;
+        BorshSerialize::serialize(&self.notes, writer)?;
+        BorshSerialize::serialize(&self.start_time, writer)?;
+        BorshSerialize::serialize(&self.start_height, writer)?;
+        BorshSerialize::serialize(&self.wrong_submissions, writer)?;
+        BorshSerialize::serialize(&self.penalty, writer)?;
+        BorshSerialize::serialize(&self.level, writer)?;
+        BorshSerialize::serialize(&self.difficulty, writer)?;
+        BorshSerialize::serialize(&self.practice, writer)?;
+        BorshSerialize::serialize(&self.hints_used, writer)?;
+        BorshSerialize::serialize(&self.archive_date, writer)?;
+        BorshSerialize::serialize(&self.pending_puzzle, writer)?;
+        BorshSerialize::serialize(&self.generated_sudoku_count, writer)?;
+        BorshSerialize::serialize(&self.sloved_sudoku_count, writer)?;
+        BorshSerialize::serialize(&self.practice_generated_count, writer)?;
+        BorshSerialize::serialize(&self.practice_sloved_count, writer)?;
+        BorshSerialize::serialize(&self.archive_sloved_count, writer)?;
+        BorshSerialize::serialize(&self.last_sloved_game, writer)?;
+        BorshSerialize::serialize(&self.best_time, writer)?;
+        BorshSerialize::serialize(&self.best_block_time, writer)?;
+        BorshSerialize::serialize(&self.stats, writer)?;
+        BorshSerialize::serialize(&self.solve_stats, writer)?;
+        BorshSerialize::serialize(&self.profile, writer)?;
+        BorshSerialize::serialize(&self.completed_levels, writer)?;
+        BorshSerialize::serialize(&self.points, writer)?;
+        BorshSerialize::serialize(&self.season_points, writer)?;
+        BorshSerialize::serialize(&self.points_season_end, writer)?;
+        BorshSerialize::serialize(&self.reward_streak, writer)?;
+        BorshSerialize::serialize(&self.last_reward_day, writer)?;
+        BorshSerialize::serialize(&self.balance, writer)?;
+        BorshSerialize::serialize(&self.stake, writer)?;
+        BorshSerialize::serialize(&self.stake_unlock_at, writer)?;
+        BorshSerialize::serialize(&self.coop_sloved_count, writer)?;
+        BorshSerialize::serialize(&self.career, writer)?;
+        BorshSerialize::serialize(&self.share_replays, writer)?;
+        BorshSerialize::serialize(&self.replay, writer)?;
+        BorshSerialize::serialize(&self.last_replay, writer)?;
+        BorshSerialize::serialize(&self.pool_draw, writer)?;
+        BorshSerialize::serialize(&self.replay_deposit, writer)?;
+        BorshSerialize::serialize(&self.archive_dates, writer)
+    }
+}
+
+impl BorshDeserialize for Player {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        if buf.first() != Some(&PLAYER_TAG) {
+            return Player::deserialize_legacy(buf);
+        }
+        // version 1 lacks `pool_draw`, versions before 3 lack `replay_deposit` and versions before
+        // 4 lack `archive_dates`. Versions before 5 keep notes, replay and archive dates inline.
+        let version = match buf.get(1) {
+            Some(&version @ 1..=PLAYER_VERSION) => version,
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "unknown player version",
+                ))
+            }
+        };
+
+        *buf = &buf[2..];
+        let mut player = Self {
+            sudoku: BorshDeserialize::deserialize(buf)?,
+            progress: BorshDeserialize::deserialize(buf)?,
+            notes: match version {
+                1..=4 => SideRecord::Inline(BorshDeserialize::deserialize(buf)?),
+                _ => BorshDeserialize::deserialize(buf)?,
+            },
+            start_time: BorshDeserialize::deserialize(buf)?,
+            start_height: BorshDeserialize::deserialize(buf)?,
+            wrong_submissions: BorshDeserialize::deserialize(buf)?,
+            penalty: BorshDeserialize::deserialize(buf)?,
+            level: BorshDeserialize::deserialize(buf)?,
+            difficulty: BorshDeserialize::deserialize(buf)?,
+            practice: BorshDeserialize::deserialize(buf)?,
+            hints_used: BorshDeserialize::deserialize(buf)?,
+            archive_date: BorshDeserialize::deserialize(buf)?,
+            pending_puzzle: BorshDeserialize::deserialize(buf)?,
+            generated_sudoku_count: BorshDeserialize::deserialize(buf)?,
+            sloved_sudoku_count: BorshDeserialize::deserialize(buf)?,
+            practice_generated_count: BorshDeserialize::deserialize(buf)?,
+            practice_sloved_count: BorshDeserialize::deserialize(buf)?,
+            archive_sloved_count: BorshDeserialize::deserialize(buf)?,
+            last_sloved_game: BorshDeserialize::deserialize(buf)?,
+            best_time: BorshDeserialize::deserialize(buf)?,
+            best_block_time: BorshDeserialize::deserialize(buf)?,
+            stats: BorshDeserialize::deserialize(buf)?,
+            solve_stats: BorshDeserialize::deserialize(buf)?,
+            profile: BorshDeserialize::deserialize(buf)?,
+            completed_levels: BorshDeserialize::deserialize(buf)?,
+            points: BorshDeserialize::deserialize(buf)?,
+            season_points: BorshDeserialize::deserialize(buf)?,
+            points_season_end: BorshDeserialize::deserialize(buf)?,
+            reward_streak: BorshDeserialize::deserialize(buf)?,
+            last_reward_day: BorshDeserialize::deserialize(buf)?,
+            balance: BorshDeserialize::deserialize(buf)?,
+            stake: BorshDeserialize::deserialize(buf)?,
+            stake_unlock_at: BorshDeserialize::deserialize(buf)?,
+            coop_sloved_count: BorshDeserialize::deserialize(buf)?,
+            career: BorshDeserialize::deserialize(buf)?,
+            share_replays: BorshDeserialize::deserialize(buf)?,
+            replay: match version {
+                1..=4 => SideRecord::Inline(BorshDeserialize::deserialize(buf)?),
+                _ => BorshDeserialize::deserialize(buf)?,
+            },
+            last_replay: BorshDeserialize::deserialize(buf)?,
+            pool_draw: match version {
+                1 => None,
+                _ => BorshDeserialize::deserialize(buf)?,
+            },
+            replay_deposit: 0,
+            archive_dates: ArchiveDates::Inline(Vec::new()),
+        };
+        // sharing was charged the replay storage before it was tracked
+        player.replay_deposit = match version {
+            1 | 2 if player.share_replays => LEGACY_REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE,
+            1 | 2 => 0,
+            _ => BorshDeserialize::deserialize(buf)?,
+        };
+        player.archive_dates = match version {
+            1..=3 => ArchiveDates::Inline(Vec::new()),
+            4 => ArchiveDates::Inline(BorshDeserialize::deserialize(buf)?),
+            _ => BorshDeserialize::deserialize(buf)?,
+        };
+        Ok(player)
+    }
+}
+
+/// A grid sent by a client, either as nested 9×9 array or in the 81 character line format
+/// (see [`Sudoku::from_str_line`]), e.g. `"483921657967345821..."`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(untagged)]
+pub enum SudokuInput {
+    Array(SudokuTwoDimensionalArray),
+    Line(String),
+}
+
+impl SudokuInput {
+    fn to_two_dimensional_array(&self) -> SudokuTwoDimensionalArray {
+        match self {
+            SudokuInput::Array(array) => *array,
+            SudokuInput::Line(line) => Sudoku::from_str_line(line)
+                .unwrap_or_else(|_| ContractError::InvalidSudokuLine.panic())
+                .to_two_dimensional_array(),
+        }
+    }
+}
+
+impl From<SudokuTwoDimensionalArray> for SudokuInput {
+    fn from(array: SudokuTwoDimensionalArray) -> Self {
+        SudokuInput::Array(array)
+    }
+}
+
+impl From<&str> for SudokuInput {
+    fn from(line: &str) -> Self {
+        SudokuInput::Line(line.to_string())
+    }
+}
+
+/// Outcome of [`Contract::verify_solution`]
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyResult {
+    Valid,
+    /// The puzzle isn't an 81 character line
+    InvalidPuzzle,
+    /// The solution isn't an 81 character line
+    InvalidSolution,
+    /// The solution has empty cells
+    Incomplete,
+    /// A digit appears twice in a row, column or block
+    RulesViolated,
+    /// The solution doesn't keep a clue of the puzzle
+    CluesChanged,
+}
+
+/// Outcome of [`Contract::finish_and_start`]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FinishAndStartRequest {
+    /// Whether the submitted grid solved the previous game
+    pub solved: bool,
+    /// Whether the next game was started, see [`Contract::finish_and_start`]
+    pub started: bool,
+    /// With the solved game in `last_sloved_game` and the next one in `sudoku` if `started`
+    pub player: PlayerRequest,
+}
+
+/// Maintenance done by [`Contract::tick`]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TickRequest {
+    /// The leaderboard period running after the tick
+    pub period_id: u64,
+    /// Whether the daily puzzle of the current day was archived by this tick
+    pub daily_archived: bool,
+    /// Races started or cancelled because their start time passed
+    pub races_started: u32,
+    /// Running races settled because their time limit passed
+    pub races_settled: u32,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LastSlovedGameRequest {
+    sudoku: SudokuTwoDimensionalArray,
+    time_end: Timestamp,
+    time_start: Timestamp,
+    /// Hex encoded hash of the move history, if it was recorded
+    moves_hash: Option<String>,
+    /// Unknown for games solved before it was recorded, like `hints_used` and `personal_best`
+    difficulty: Option<Difficulty>,
+    clue_count: u8,
+    hints_used: Option<u32>,
+    personal_best: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlayerRequest {
+    sudoku: Option<SudokuTwoDimensionalArray>,
+    progress: Option<SudokuTwoDimensionalArray>,
+    notes: Option<Base64VecU8>,
+    clue_count: u8,
+    filled_count: u8,
+    progress_percent: u8,
+    start_time: Timestamp,
+    wrong_submissions: u32,
+    /// Milliseconds added to the solve time by wrong submissions so far
+    penalty: Timestamp,
+    level: Option<u32>,
+    difficulty: Difficulty,
+    practice: bool,
+    hints_used: u32,
+    archive_date: Option<u64>,
+    /// Index of the pooled puzzle the current game was drawn from, see [`Contract::start_game`]
+    pool_index: Option<u64>,
+    /// Block height of the pending puzzle request, see [`Contract::request_puzzle`]
+    pending_puzzle_height: Option<BlockHeight>,
+    /// The puzzle of [`Contract::start_game`] isn't finished yet, see [`Contract::continue_generation`]
+    generation_pending: bool,
+    /// The solve just submitted wasn't attested, the balance didn't cover the storage
+    attestation_skipped: bool,
+
+    generated_sudoku_count: U128,
+    sloved_sudoku_count: U128,
+    practice_generated_count: u32,
+    practice_sloved_count: u32,
+    archive_sloved_count: u32,
+    /// Number of the current or last game, see [`Contract::get_replay`]
+    game_id: u64,
+
+    last_sloved_game: Option<LastSlovedGameRequest>,
+
+    best_time: Option<Timestamp>,
+    best_block_time: Option<Timestamp>,
+    stats: BTreeMap<Difficulty, DifficultyStatsRequest>,
+
+    profile: Option<Profile>,
+
+    points: U128,
+    season_points: U128,
+    points_season_end: Timestamp,
+    reward_streak: u32,
+
+    balance: U128,
+
+    verified: bool,
+    stake: U128,
+    stake_unlock_at: Timestamp,
+
+    coop_sloved_count: u32,
+
+    share_replays: bool,
+    /// The running kids or marathon game, only filled in by [`Contract::start_game`] and
+    /// [`Contract::get_player`]
+    sized_game: Option<SizedGameRequest>,
+}
+
+/// Default of [`Config::player_size`]
+pub(crate) const PLAYER_SIZE: u64 = 1430;
+/// Candidate notes are a bitmask of 81 cells × 9 digits, bit `cell * 9 + digit - 1` counted from
+/// the least significant bit of the first byte. The 7 padding bits have to be zero.
+const NOTES_LEN: usize = 92;
+/// Default of [`Config::wrong_submission_penalty`]
+pub(crate) const DEFAULT_WRONG_SUBMISSION_PENALTY: Timestamp = 30_000;
+/// Default of [`Config::start_game_cooldown`]
+pub(crate) const DEFAULT_START_GAME_COOLDOWN: Timestamp = 30_000;
+/// Default of [`Config::leaderboard_size`]
+pub(crate) const LEADERBOARD_SIZE: u32 = 10;
+/// Storage of a player's entries in the verified rankings
+const VERIFIED_SIZE: u128 = 568;
+/// Storage of the replays of a player sharing them, the current and the last one with
+/// [`replay::REPLAY_MAX_EVENTS`] entries each under the keys of the longest account ids
+const REPLAY_SIZE: u128 = 1780;
+/// Replay storage charged by layouts before version 3, which kept the current replay inline
+const LEGACY_REPLAY_SIZE: u128 = 1700;
+/// Default of [`Config::verification_stake`]
+pub(crate) const DEFAULT_VERIFICATION_STAKE: Balance = 5 * ONE_NEAR;
+/// Default of [`Config::period_length`]
+pub(crate) const DEFAULT_PERIOD_LENGTH: Timestamp = 7 * 86_400_000;
+/// Default of [`Config::game_fee`]
+pub(crate) const DEFAULT_GAME_FEE: Balance = ONE_NEAR / 100;
+/// Default of [`Config::stale_period`]
+pub(crate) const DEFAULT_STALE_PERIOD: Timestamp = 365 * 86_400_000;
+/// Default of [`Config::cleanup_reward_percent`]
+pub(crate) const CLEANUP_REWARD_PERCENT: u32 = 10;
+/// Milliseconds per block used to convert solve durations in blocks to time
+const AVERAGE_BLOCK_TIME: Timestamp = 1_000;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Leaderboard {
+    ranking_by_count: RankIndex,
+    ranking_by_time: RankIndex,
+    ranking_by_block_time: RankIndex,
+
+    /// Season points of the running season, see [`Leaderboard::new_season`]
+    ranking_by_points: RankIndex,
+
+    /// Rankings of verified players only, see [`Contract::verify`]
+    verified_by_count: RankIndex,
+    verified_by_time: RankIndex,
+    verified_by_block_time: RankIndex,
+    verified_by_points: RankIndex,
+
+    /// Solves and best times within the running leaderboard period, see
+    /// [`Leaderboard::new_period`]. Snapshots and prizes of a period are taken from these.
+    period_by_count: RankIndex,
+    period_by_time: RankIndex,
+    period_verified_by_count: RankIndex,
+    period_verified_by_time: RankIndex,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub account_id: AccountId,
+    /// Solved sudokus for [`BoardKind::Count`], best time in milliseconds for the time boards,
+    /// season points for [`BoardKind::Points`]
+    pub value: U128,
+    pub profile: Option<Profile>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RankRequest {
+    pub entry: LeaderboardEntry,
+    /// The entry one rank better, `None` for first place
+    pub above: Option<LeaderboardEntry>,
+    /// The entry one rank worse, `None` for last place
+    pub below: Option<LeaderboardEntry>,
+}
+
+/// One of the rankings kept in the [`Leaderboard`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum BoardKind {
+    /// Most solved sudokus first
+    Count,
+    /// Fastest single solve first, timed by block timestamps
+    Time,
+    /// Fastest single solve first, timed by block heights times [`AVERAGE_BLOCK_TIME`].
+    /// Unlike timestamps, heights can't be shifted by the validators producing the blocks.
+    BlockTime,
+    /// Most season points first, see [`score::score`]
+    Points,
+}
+
+impl BoardKind {
+    pub const ALL: [BoardKind; 4] = [
+        BoardKind::Count,
+        BoardKind::Time,
+        BoardKind::BlockTime,
+        BoardKind::Points,
+    ];
+
+    /// Maps a board value to a key that sorts first place first, and a key back to the value.
+    pub(crate) fn sort_key(self, value: u128) -> u128 {
+        match self {
+            BoardKind::Count | BoardKind::Points => u128::MAX - value,
+            BoardKind::Time | BoardKind::BlockTime => value,
+        }
+    }
+}
+
+impl Leaderboard {
+    fn new() -> Self {
+        Self {
+            ranking_by_count: RankIndex::new(b"rc"),
+            ranking_by_time: RankIndex::new(b"rt"),
+            ranking_by_block_time: RankIndex::new(b"rb"),
+            ranking_by_points: RankIndex::new(b"rp"),
+            verified_by_count: RankIndex::new(b"vc"),
+            verified_by_time: RankIndex::new(b"vt"),
+            verified_by_block_time: RankIndex::new(b"vb"),
+            verified_by_points: RankIndex::new(b"vp"),
+            period_by_count: Self::period_ranking(b"mc", 0),
+            period_by_time: Self::period_ranking(b"mt", 0),
+            period_verified_by_count: Self::period_ranking(b"qc", 0),
+            period_verified_by_time: Self::period_ranking(b"qt", 0),
+        }
+    }
+
+    fn period_ranking(prefix: &[u8], period_id: u64) -> RankIndex {
+        RankIndex::new(&[prefix, &period_id.to_le_bytes()[..]].concat())
+    }
+
+    /// Starts empty period rankings for `period_id`. Entries of the previous period stay in
+    /// storage under their old prefix.
+    fn new_period(&mut self, period_id: u64) {
+        self.period_by_count = Self::period_ranking(b"mc", period_id);
+        self.period_by_time = Self::period_ranking(b"mt", period_id);
+        self.period_verified_by_count = Self::period_ranking(b"qc", period_id);
+        self.period_verified_by_time = Self::period_ranking(b"qt", period_id);
+    }
+
+    /// Counts a ranked solve of `account_id` in the running period. `time` is `None` for
+    /// assisted solves, which have no time.
+    fn record_period_solve(
+        &mut self,
+        account_id: &AccountId,
+        time: Option<Timestamp>,
+        verified: bool,
+    ) {
+        let mut rankings = vec![(&mut self.period_by_count, &mut self.period_by_time)];
+        if verified {
+            rankings.push((
+                &mut self.period_verified_by_count,
+                &mut self.period_verified_by_time,
+            ));
+        }
+        for (by_count, by_time) in rankings {
+            let solves = by_count
+                .key(account_id)
+                .map_or(0, |key| BoardKind::Count.sort_key(key));
+            by_count.insert(account_id, BoardKind::Count.sort_key(solves + 1));
+            if let Some(time) = time {
+                let best = by_time.key(account_id).map_or(time as u128, |key| {
+                    BoardKind::Time.sort_key(key).min(time as u128)
+                });
+                by_time.insert(account_id, BoardKind::Time.sort_key(best));
+            }
+        }
+    }
+
+    /// Starts empty points rankings for the season ending at `season_end`. Entries of the
+    /// previous season stay in storage under their old prefix.
+    fn new_season(&mut self, season_end: Timestamp) {
+        let season = season_end.to_le_bytes();
+        self.ranking_by_points = RankIndex::new(&[b"rs", &season[..]].concat());
+        self.verified_by_points = RankIndex::new(&[b"vs", &season[..]].concat());
+    }
+
+    pub(crate) fn ranking(&self, board: BoardKind) -> &RankIndex {
+        match board {
+            BoardKind::Count => &self.ranking_by_count,
+            BoardKind::Time => &self.ranking_by_time,
+            BoardKind::BlockTime => &self.ranking_by_block_time,
+            BoardKind::Points => &self.ranking_by_points,
+        }
+    }
+
+    pub(crate) fn verified_ranking(&self, board: BoardKind) -> &RankIndex {
+        match board {
+            BoardKind::Count => &self.verified_by_count,
+            BoardKind::Time => &self.verified_by_time,
+            BoardKind::BlockTime => &self.verified_by_block_time,
+            BoardKind::Points => &self.verified_by_points,
+        }
+    }
+
+    /// The first `size` entries of `board` as (value, account id), see [`BoardKind::sort_key`]
+    fn top(&self, board: BoardKind, size: usize) -> Vec<(u128, AccountId)> {
+        self.ranking(board)
+            .iter()
+            .take(size)
+            .map(|(key, account_id)| (board.sort_key(key), account_id))
+            .collect()
+    }
+
+    fn ranks(&self, board: BoardKind, size: usize) -> HashMap<AccountId, u32> {
+        self.top(board, size)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (_, account_id))| (account_id, idx as u32 + 1))
+            .collect()
+    }
+
+    fn rank_changes(
+        board: BoardKind,
+        before: &HashMap<AccountId, u32>,
+        after: &HashMap<AccountId, u32>,
+    ) -> Vec<LeaderboardChange> {
+        let mut accounts: Vec<&AccountId> = before
+            .keys()
+            .chain(after.keys().filter(|a| !before.contains_key(a)))
+            .collect();
+        accounts.sort();
+
+        accounts
+            .into_iter()
+            .filter(|account_id| before.get(account_id) != after.get(account_id))
+            .map(|account_id| LeaderboardChange {
+                board,
+                account_id: account_id.clone(),
+                old_rank: before.get(account_id).copied(),
+                new_rank: after.get(account_id).copied(),
+            })
+            .collect()
+    }
+
+    /// Runs `update` and logs every rank that changed on any board.
+    fn track_changes(&mut self, size: usize, update: impl FnOnce(&mut Self)) {
+        let before: Vec<_> = BoardKind::ALL
+            .iter()
+            .map(|&board| self.ranks(board, size))
+            .collect();
+
+        update(self);
+
+        let changes: Vec<_> = BoardKind::ALL
+            .iter()
+            .zip(before)
+            .flat_map(|(&board, before)| {
+                Self::rank_changes(board, &before, &self.ranks(board, size))
+            })
+            .collect();
+        events::leaderboard_update(&changes);
+    }
+
+    /// The top `size` of every board, counting only the solves of the running period.
+    fn standings(&self, period_id: u64, size: usize) -> Standings {
+        let top = |ranking: &RankIndex, board: BoardKind| {
+            ranking
+                .iter()
+                .take(size)
+                .map(|(key, account_id)| StandingsEntry {
+                    account_id,
+                    value: U128(board.sort_key(key)),
+                })
+                .collect()
+        };
+
+        Standings {
+            period_id,
+            by_count: top(&self.period_by_count, BoardKind::Count),
+            by_time: top(&self.period_by_time, BoardKind::Time),
+            verified_by_count: top(&self.period_verified_by_count, BoardKind::Count),
+            verified_by_time: top(&self.period_verified_by_time, BoardKind::Time),
+        }
+    }
+
+    /// Storage of the entries of `account_id` in all rankings, including those of the running
+    /// period and season
+    pub fn storage_size(&self, account_id: &AccountId) -> StorageUsage {
+        [
+            &self.ranking_by_count,
+            &self.ranking_by_time,
+            &self.ranking_by_block_time,
+            &self.ranking_by_points,
+            &self.verified_by_count,
+            &self.verified_by_time,
+            &self.verified_by_block_time,
+            &self.verified_by_points,
+            &self.period_by_count,
+            &self.period_by_time,
+            &self.period_verified_by_count,
+            &self.period_verified_by_time,
+        ]
+        .iter()
+        .map(|ranking| ranking.storage_size(account_id))
+        .sum()
+    }
+
+    pub fn remove_player(&mut self, account_id: &AccountId, size: usize) {
+        self.period_by_count.remove(account_id);
+        self.period_by_time.remove(account_id);
+        self.remove_verified(account_id);
+        self.track_changes(size, |leaderboard| {
+            leaderboard.ranking_by_count.remove(account_id);
+            leaderboard.ranking_by_time.remove(account_id);
+            leaderboard.ranking_by_block_time.remove(account_id);
+            leaderboard.ranking_by_points.remove(account_id);
+        });
+    }
+
+    /// Ranks the solve count and best time of a player read from the legacy layout, whose places
+    /// were only kept in the top maps of the first deployment, see [`Contract::migrate_players`]
+    fn insert_legacy(&mut self, account_id: &AccountId, player: &Player) {
+        if player.sloved_sudoku_count > 0 {
+            self.ranking_by_count.insert(
+                account_id,
+                BoardKind::Count.sort_key(player.sloved_sudoku_count),
+            );
+        }
+        if let Some(best_time) = player.best_time {
+            self.ranking_by_time
+                .insert(account_id, BoardKind::Time.sort_key(best_time as u128));
+        }
+    }
+
+    pub fn work_player(&mut self, player: &Player, size: usize) {
+        let account_id = env::predecessor_account_id();
+        if player.verified() {
+            self.insert_verified(&account_id, player);
+        }
+        self.track_changes(size, |leaderboard| {
+            leaderboard.ranking_by_count.insert(
+                &account_id,
+                BoardKind::Count.sort_key(player.sloved_sudoku_count),
+            );
+            // players with only assisted solves have no time
+            if let Some(best_time) = player.best_time {
+                leaderboard
+                    .ranking_by_time
+                    .insert(&account_id, BoardKind::Time.sort_key(best_time as u128));
+            }
+            if let Some(best_block_time) = player.best_block_time {
+                leaderboard.ranking_by_block_time.insert(
+                    &account_id,
+                    BoardKind::BlockTime.sort_key(best_block_time as u128),
+                );
+            }
+            leaderboard.ranking_by_points.insert(
+                &account_id,
+                BoardKind::Points.sort_key(player.season_points),
+            );
+        });
+    }
+
+    /// Adds `player` to the verified rankings once it solved a sudoku, to the time rankings
+    /// once it solved one unassisted.
+    pub fn insert_verified(&mut self, account_id: &AccountId, player: &Player) {
+        if player.sloved_sudoku_count == 0 {
+            return;
+        }
+        self.verified_by_count.insert(
+            account_id,
+            BoardKind::Count.sort_key(player.sloved_sudoku_count),
+        );
+        if let Some(best_time) = player.best_time {
+            self.verified_by_time
+                .insert(account_id, BoardKind::Time.sort_key(best_time as u128));
+        }
+        if let Some(best_block_time) = player.best_block_time {
+            self.verified_by_block_time.insert(
+                account_id,
+                BoardKind::BlockTime.sort_key(best_block_time as u128),
+            );
+        }
+        self.verified_by_points
+            .insert(account_id, BoardKind::Points.sort_key(player.season_points));
+        if let Some(key) = self.period_by_count.key(account_id) {
+            self.period_verified_by_count.insert(account_id, key);
+        }
+        if let Some(key) = self.period_by_time.key(account_id) {
+            self.period_verified_by_time.insert(account_id, key);
+        }
+    }
+
+    pub fn remove_verified(&mut self, account_id: &AccountId) {
+        self.verified_by_count.remove(account_id);
+        self.verified_by_time.remove(account_id);
+        self.verified_by_block_time.remove(account_id);
+        self.verified_by_points.remove(account_id);
+        self.period_verified_by_count.remove(account_id);
+        self.period_verified_by_time.remove(account_id);
+    }
+}
+
+impl Player {
+    /// A registered player without a game, see [`Contract::register`]
+    pub fn new(account_id: &AccountId, balance: Balance) -> Player {
+        Self {
+            sudoku: None,
+            progress: None,
+            notes: SideRecord::new(NOTES_PREFIX, account_id),
+            generated_sudoku_count: 0,
+            sloved_sudoku_count: 0,
+            practice_generated_count: 0,
+            practice_sloved_count: 0,
+            archive_sloved_count: 0,
+            start_time: env::block_timestamp_ms(),
+            start_height: env::block_height(),
+            wrong_submissions: 0,
+            penalty: 0,
+            level: None,
+            difficulty: Difficulty::Expert,
+            practice: false,
+            hints_used: 0,
+            archive_date: None,
+            pending_puzzle: None,
+
+            last_sloved_game: SideRecord::new(LAST_SLOVED_GAME_PREFIX, account_id),
+
+            best_time: None,
+            best_block_time: None,
+            stats: Default::default(),
+            solve_stats: Default::default(),
+
+            profile: None,
+
+            completed_levels: 0,
+
+            points: 0,
+            season_points: 0,
+            points_season_end: 0,
+            reward_streak: 0,
+            last_reward_day: None,
+
+            balance,
+
+            stake: 0,
+            stake_unlock_at: 0,
+
+            coop_sloved_count: 0,
+            career: Career::default(),
+
+            share_replays: false,
+            replay: SideRecord::new(REPLAY_PREFIX, account_id),
+            last_replay: SideRecord::new(LAST_REPLAY_PREFIX, account_id),
+            pool_draw: None,
+            replay_deposit: 0,
+            archive_dates: ArchiveDates::Indexed(0),
+        }
+    }
+
+    /// Reads the legacy layout, see the borsh encoding of [`Player`]. A running game keeps its
+    /// start time, its time in blocks is counted from the block it's read in.
+    fn deserialize_legacy(buf: &mut &[u8]) -> std::io::Result<Player> {
+        let sudoku = BorshDeserialize::deserialize(buf)?;
+        let start_time = BorshDeserialize::deserialize(buf)?;
+        let generated_sudoku_count = BorshDeserialize::deserialize(buf)?;
+        let sloved_sudoku_count = BorshDeserialize::deserialize(buf)?;
+        let last_sloved_game = match <u8 as BorshDeserialize>::deserialize(buf)? {
+            0 => None,
+            1 => Some(LastSlovedGame {
+                sudoku: BorshDeserialize::deserialize(buf)?,
+                time_end: BorshDeserialize::deserialize(buf)?,
+                time_start: BorshDeserialize::deserialize(buf)?,
+                moves_hash: None,
+                difficulty: None,
+                hints_used: None,
+                personal_best: None,
+            }),
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "invalid option tag",
+                ))
+            }
+        };
+        let best_time = BorshDeserialize::deserialize(buf)?;
+
+        Ok(Self {
+            sudoku,
+            progress: None,
+            notes: SideRecord::Inline(None),
+            generated_sudoku_count,
+            sloved_sudoku_count,
+            practice_generated_count: 0,
+            practice_sloved_count: 0,
+            archive_sloved_count: 0,
+            start_time,
+            start_height: env::block_height(),
+            wrong_submissions: 0,
+            penalty: 0,
+            level: None,
+            difficulty: Difficulty::Expert,
+            practice: false,
+            hints_used: 0,
+            archive_date: None,
+            pending_puzzle: None,
+
+            last_sloved_game: SideRecord::Inline(last_sloved_game),
+
+            best_time,
+            best_block_time: None,
+            stats: Default::default(),
+            solve_stats: Default::default(),
+
+            profile: None,
+
+            completed_levels: 0,
+
+            points: 0,
+            season_points: 0,
+            points_season_end: 0,
+            reward_streak: 0,
+            last_reward_day: None,
+
+            balance: 0,
+
+            stake: 0,
+            stake_unlock_at: 0,
+
+            coop_sloved_count: 0,
+            career: Career::default(),
+
+            share_replays: false,
+            replay: SideRecord::Inline(None),
+            last_replay: SideRecord::Inline(None),
+            pool_draw: None,
+            replay_deposit: 0,
+            archive_dates: ArchiveDates::Inline(Vec::new()),
+        })
+    }
+
+    pub fn new_game(
+        self,
+        sudoku: Sudoku,
+        difficulty: Difficulty,
+        level: Option<u32>,
+        practice: bool,
+        record_moves: bool,
+    ) -> Player {
+        let game_id = self.game_id() + 1;
+        let mut notes = self.notes;
+        notes.remove();
+        let mut replay = self.replay;
+        // only recorded once its storage is paid, see `Contract::start`
+        match self.replay_deposit > 0 && (self.share_replays || record_moves) {
+            true => replay.set(&Replay::new(game_id)),
+            false => replay.remove(),
+        }
+        Self {
+            sudoku: Some(sudoku),
+            progress: None,
+            notes,
+            generated_sudoku_count: self.generated_sudoku_count + !practice as u128,
+            sloved_sudoku_count: self.sloved_sudoku_count,
+            practice_generated_count: self.practice_generated_count + practice as u32,
+            practice_sloved_count: self.practice_sloved_count,
+            archive_sloved_count: self.archive_sloved_count,
+            start_time: env::block_timestamp_ms(),
+            start_height: env::block_height(),
+            wrong_submissions: 0,
+            penalty: 0,
+            level,
+            difficulty,
+            practice,
+            hints_used: 0,
+            archive_date: None,
+            pending_puzzle: self.pending_puzzle,
+            last_sloved_game: self.last_sloved_game,
+            best_time: self.best_time,
+            best_block_time: self.best_block_time,
+            stats: self.stats,
+            solve_stats: if self.sudoku.is_some() && !self.practice {
+                let mut solve_stats = self.solve_stats;
+                solve_stats.abandon();
+                solve_stats
+            } else {
+                self.solve_stats
+            },
+            profile: self.profile,
+            completed_levels: self.completed_levels,
+            points: self.points,
+            season_points: self.season_points,
+            points_season_end: self.points_season_end,
+            reward_streak: self.reward_streak,
+            last_reward_day: self.last_reward_day,
+            balance: self.balance,
+            stake: self.stake,
+            stake_unlock_at: self.stake_unlock_at,
+            coop_sloved_count: self.coop_sloved_count,
+            career: self.career,
+            share_replays: self.share_replays,
+            replay,
+            last_replay: self.last_replay,
+            pool_draw: None,
+            replay_deposit: self.replay_deposit,
+            archive_dates: self.archive_dates,
+        }
+    }
+
+    /// The penalty of the wrong submissions is added to the solve time.
+    /// The score of the solve is added to the points of the season ending at `season_end`.
+    /// Assisted solves count like any other, but their times don't make it into the best times.
+    pub fn finish_game(self, season_end: Timestamp, config: &Config) -> Player {
+        let time = self.elapsed();
+        let season_points = if self.points_season_end == season_end {
+            self.season_points
+        } else {
+            0
+        };
+        let block_time =
+            (env::block_height() - self.start_height) * AVERAGE_BLOCK_TIME + self.penalty;
+        let assisted = self.assisted();
+        let personal_best = !assisted && time < self.best_time.unwrap_or(u64::MAX);
+        let mut stats = self.stats;
+        stats[self.difficulty as usize].record(time);
+        let mut solve_stats = self.solve_stats;
+        solve_stats.record(time, self.hints_used);
+        let mut replay = self.replay;
+        let recorded = replay.get();
+        replay.remove();
+        let mut notes = self.notes;
+        notes.remove();
+        let mut last_sloved_game = self.last_sloved_game;
+        last_sloved_game.set(&LastSlovedGame {
+            sudoku: self.sudoku.unwrap(),
+            time_start: self.start_time,
+            time_end: env::block_timestamp_ms(),
+            moves_hash: recorded.as_ref().map(|replay| replay.hash()),
+            difficulty: Some(self.difficulty),
+            hints_used: Some(self.hints_used),
+            personal_best: Some(personal_best),
+        });
+        let mut last_replay = self.last_replay;
+        if let Some(recorded) = &recorded {
+            last_replay.set(recorded);
+        }
+        let score = score::score(
+            self.difficulty,
+            time,
+            self.hints_used,
+            config.no_hint_bonus_percent,
+        );
+        let mut career = self.career;
+        career.points += score;
+
+        Self {
+            sudoku: None,
+            progress: None,
+            notes,
+            generated_sudoku_count: self.generated_sudoku_count,
+            sloved_sudoku_count: self.sloved_sudoku_count + 1,
+            practice_generated_count: self.practice_generated_count,
+            practice_sloved_count: self.practice_sloved_count,
+            archive_sloved_count: self.archive_sloved_count,
+
+            start_time: env::block_timestamp_ms(),
+            start_height: env::block_height(),
+            wrong_submissions: 0,
+            penalty: 0,
+            level: None,
+            difficulty: self.difficulty,
+            practice: false,
+            hints_used: 0,
+            archive_date: None,
+            pending_puzzle: self.pending_puzzle,
+
+            last_sloved_game,
+
+            best_time: if personal_best {
+                Some(time)
+            } else {
+                self.best_time
+            },
+            best_block_time: match assisted {
+                true => self.best_block_time,
+                false => Some(
+                    self.best_block_time
+                        .map_or(block_time, |best| best.min(block_time)),
+                ),
+            },
+            stats,
+            solve_stats,
+
+            profile: self.profile,
+
+            completed_levels: match self.level {
+                Some(level) => self.completed_levels.max(level),
+                None => self.completed_levels,
+            },
+
+            points: self.points,
+            season_points: season_points + score,
+            points_season_end: season_end,
+            reward_streak: self.reward_streak,
+            last_reward_day: self.last_reward_day,
+            balance: self.balance,
+            stake: self.stake,
+            stake_unlock_at: self.stake_unlock_at,
+            coop_sloved_count: self.coop_sloved_count,
+            career,
+            share_replays: self.share_replays,
+            replay,
+            last_replay,
+            pool_draw: None,
+            replay_deposit: self.replay_deposit,
+            archive_dates: self.archive_dates,
+        }
+    }
+
+    /// Ends a practice game, only the practice and archive counters are updated.
+    pub fn finish_practice(mut self) -> Player {
+        if let Some(replay) = self.replay.get() {
+            self.last_replay.set(&replay);
+        }
+        self.replay.remove();
+        self.notes.remove();
+        Self {
+            sudoku: None,
+            progress: None,
+            wrong_submissions: 0,
+            penalty: 0,
+            practice: false,
+            hints_used: 0,
+            archive_date: None,
+            practice_sloved_count: self.practice_sloved_count + 1,
+            archive_sloved_count: self.archive_sloved_count + self.archive_date.is_some() as u32,
+            ..self
+        }
+    }
+
+    pub fn get(&self) -> PlayerRequest {
+        let clue_count = self.sudoku.map_or(0, |sudoku| sudoku.n_clues());
+        let filled_count = self
+            .progress
+            .or(self.sudoku)
+            .map_or(0, |sudoku| sudoku.n_clues());
+
+        PlayerRequest {
+            sudoku: self
+                .sudoku
+                .as_ref()
+                .map(|sudoku| sudoku.to_two_dimensional_array()),
+            progress: self
+                .progress
+                .map(|progress| progress.to_two_dimensional_array()),
+            notes: self.notes.get().map(Base64VecU8::from),
+            clue_count,
+            filled_count,
+            progress_percent: match self.sudoku {
+                Some(_) => {
+                    ((filled_count - clue_count) as u32 * 100 / (81 - clue_count) as u32) as u8
+                }
+                None => 0,
+            },
+            generated_sudoku_count: U128::from(self.generated_sudoku_count),
+            sloved_sudoku_count: U128::from(self.sloved_sudoku_count),
+            practice_generated_count: self.practice_generated_count,
+            practice_sloved_count: self.practice_sloved_count,
+            archive_sloved_count: self.archive_sloved_count,
+            game_id: self.game_id(),
+            start_time: self.start_time,
+            wrong_submissions: self.wrong_submissions,
+            penalty: self.penalty,
+            level: self.level,
+            difficulty: self.difficulty,
+            practice: self.practice,
+            hints_used: self.hints_used,
+            archive_date: self.archive_date,
+            pool_index: self.pool_draw.map(|draw| draw.index),
+            pending_puzzle_height: self
+                .pending_puzzle
+                .as_ref()
+                .map(|pending| pending.requested_height),
+            generation_pending: false,
+            attestation_skipped: false,
+
+            last_sloved_game: self
+                .last_sloved_game
+                .get()
+                .map(|last_game| LastSlovedGameRequest {
+                    sudoku: last_game.sudoku.to_two_dimensional_array(),
+                    time_end: last_game.time_end,
+                    time_start: last_game.time_start,
+                    moves_hash: last_game
+                        .moves_hash
+                        .map(|hash| hash.iter().map(|byte| format!("{:02x}", byte)).collect()),
+                    difficulty: last_game.difficulty,
+                    clue_count: last_game.sudoku.n_clues(),
+                    hints_used: last_game.hints_used,
+                    personal_best: last_game.personal_best,
+                }),
+            best_time: self.best_time,
+            best_block_time: self.best_block_time,
+            stats: Difficulty::ALL
+                .iter()
+                .map(|&difficulty| (difficulty, self.stats[difficulty as usize].get()))
+                .collect(),
+            profile: self.profile.clone(),
+            points: U128::from(self.points),
+            season_points: U128::from(self.season_points),
+            points_season_end: self.points_season_end,
+            reward_streak: self.reward_streak,
+            balance: U128::from(self.balance),
+            verified: self.verified(),
+            stake: U128::from(self.stake),
+            stake_unlock_at: self.stake_unlock_at,
+            coop_sloved_count: self.coop_sloved_count,
+            share_replays: self.share_replays,
+            sized_game: None,
+        }
+    }
+
+    pub fn statistics(&self) -> StatisticsRequest {
+        self.solve_stats.get(&self.stats)
+    }
+
+    pub fn career(&self) -> CareerRequest {
+        CareerRequest {
+            sloved_count: U128(self.sloved_sudoku_count),
+            practice_sloved_count: self.practice_sloved_count,
+            coop_sloved_count: self.coop_sloved_count,
+            best_time: self.best_time,
+            best_times: Difficulty::ALL
+                .iter()
+                .map(|&difficulty| (difficulty, self.stats[difficulty as usize].get().best_time))
+                .collect(),
+            points: U128(self.career.points),
+            seasons_won: self.career.seasons_won,
+            races_entered: self.career.races_entered,
+            races_won: self.career.races_won,
+        }
+    }
+
+    /// Solves of games in which hints were taken, see [`Contract::reveal_cell`], are assisted
+    pub fn assisted(&self) -> bool {
+        self.hints_used > 0
+    }
+
+    /// Time in milliseconds since the current game started, including the penalty of wrong submissions
+    pub fn elapsed(&self) -> Timestamp {
+        env::block_timestamp_ms() - self.start_time + self.penalty
+    }
+
+    /// Ranked and practice games are numbered from 1 in the order they were started
+    pub fn game_id(&self) -> u64 {
+        self.generated_sudoku_count as u64 + self.practice_generated_count as u64
+    }
+
+    /// Adds the cells in which `grid` differs from the saved progress to the replay,
+    /// if one is recorded.
+    pub fn record_entries(&mut self, grid: &Sudoku) {
+        let time = env::block_timestamp_ms() - self.start_time;
+        let before = self.progress.or(self.sudoku).unwrap();
+        if let Some(mut replay) = self.replay.get() {
+            replay.record(&before, grid, time);
+            self.replay.set(&replay);
+        }
+    }
+
+    /// Verified players locked a stake and qualify for the verified leaderboards.
+    pub fn verified(&self) -> bool {
+        self.stake > 0
+    }
+
+    /// Checks that `array` is a partially filled grid that keeps all clues of the current sudoku.
+    pub fn progress_valid(&self, array: &SudokuTwoDimensionalArray) -> bool {
+        array.iter().flatten().all(|&num| num <= 9)
+            && self
+                .sudoku
+                .unwrap()
+                .is_subset_of(&Sudoku::from_two_dimensional_array(array))
+    }
+
+    /// Checks whether `array` is completely filled with digits and keeps the clues of the
+    /// current sudoku.
+    pub fn sudoku_eq(&self, array: &SudokuTwoDimensionalArray) -> bool {
+        let grid = Sudoku::from_two_dimensional_array(array);
+        grid.0.iter().all(|num| (1..=9).contains(num)) && self.sudoku.unwrap().is_subset_of(&grid)
+    }
+}
+
+/// Charges the storage used since `storage_before` to the attached deposit and refunds the rest,
+/// or refunds the deposit together with the freed storage.
+fn settle_storage_deposit(account_id: AccountId, storage_before: u64) {
+    let storage_after = env::storage_usage();
+    let refund = if storage_after > storage_before {
+        let cost = (storage_after - storage_before) as u128 * env::STORAGE_PRICE_PER_BYTE;
+        if env::attached_deposit() < cost {
+            ContractError::InsufficientDeposit { expected: cost }.panic();
+        }
+        env::attached_deposit() - cost
+    } else {
+        env::attached_deposit()
+            + (storage_before - storage_after) as u128 * env::STORAGE_PRICE_PER_BYTE
+    };
+    if refund > 0 {
+        Promise::new(account_id).transfer(refund);
+    }
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    pub owner_id: AccountId,
+    pub players: UnorderedMap<AccountId, Player>,
+    pub leaderboard: Leaderboard,
+    /// End of the running season in milliseconds, stakes locked now unlock at this time
+    pub season_end: Timestamp,
+    /// Sum of all locked stakes held in escrow
+    pub total_staked: Balance,
+    /// Leaderboard period running since `period_start`
+    pub period_id: u64,
+    pub period_start: Timestamp,
+    /// Final standings of every ended period that had one
+    pub snapshots: LookupMap<u64, Snapshot>,
+    /// Fees of the running period, paid out to the winners of the verified boards when it ends
+    pub prize_pool: Balance,
+    /// Sum of all prizes not claimed yet
+    pub unclaimed_prizes: Balance,
+    pub prizes: LookupMap<AccountId, Prize>,
+    /// Suspected cheaters, they keep playing but are left out of leaderboards and prizes
+    pub flagged: LookupSet<AccountId>,
+    pub coop_games: LookupMap<u64, CoopGame>,
+    /// Id of the next created co-op game
+    pub next_coop_id: u64,
+    pub races: LookupMap<u64, Race>,
+    /// Id of the next created race
+    pub next_race_id: u64,
+    /// Daily puzzles by UTC day, see [`reward::day`]
+    pub archive: LookupMap<u64, ArchivedPuzzle>,
+    /// Archived puzzles solved per account, they don't count for the leaderboards
+    pub archive_completions: LookupSet<(AccountId, u64)>,
+    /// Dates of the solved archived puzzles by account and index, see [`ArchiveDates`]
+    pub archive_dates: LookupMap<(AccountId, u32), u64>,
+    /// Ranked games have to be started by [`Contract::request_puzzle`] and [`Contract::claim_puzzle`]
+    pub require_commitment: bool,
+    pub config: Config,
+    /// Funds added to the prize pools of running and future periods, kept for display once paid in
+    pub sponsorships: LookupMap<u64, Vec<Sponsorship>>,
+    /// Periods paying no prizes, their sponsors get refunded
+    pub cancelled_periods: LookupSet<u64>,
+    /// Donated funds covering the storage deposit of new players, first come first served
+    pub registration_pool: Balance,
+    /// Players whose storage deposit was paid by the registration pool, it gets the deposit back
+    /// once they are removed
+    pub pool_funded: LookupSet<AccountId>,
+    /// Canonical forms of the last issued puzzles, new games get none of them
+    pub recent_puzzles: RecentPuzzles,
+    /// Puzzle generations of [`Contract::start_game`] that ran short of gas, by account
+    pub pending_generations: LookupMap<AccountId, PendingGeneration>,
+    /// Ranked solves by account and index, see [`Contract::verify_attestation`]
+    pub attestations: LookupMap<(AccountId, u64), Attestation>,
+    /// Attested solves by account, kept when the player is deleted so indices are never reused
+    pub attestation_counts: LookupMap<AccountId, u64>,
+    /// Running kids and marathon games by account, see [`Contract::start_game`]
+    pub sized_games: LookupMap<AccountId, SizedGame>,
+    pub sized_boards: SizedBoards,
+    /// Puzzles uploaded by the owner, see [`Contract::add_puzzles`]
+    pub puzzle_pool: Vector<PooledPuzzle>,
+}
+
+/// Root state of the first deployment, upgraded by [`Contract::migrate`]
+#[derive(BorshDeserialize, BorshSerialize)]
+struct LegacyContract {
+    /// Players in the legacy layout, see the borsh encoding of [`Player`]
+    players: UnorderedMap<AccountId, Player>,
+    leaderboard: LegacyLeaderboard,
+}
+
+/// Only the first places of the count and time boards, the rankings are rebuilt from the players
+#[derive(BorshDeserialize, BorshSerialize)]
+struct LegacyLeaderboard {
+    top_by_count: HashMap<AccountId, u128>,
+    top_by_time: HashMap<AccountId, Timestamp>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            owner_id: env::predecessor_account_id(),
+            players: UnorderedMap::new(b"p".to_vec()),
+            leaderboard: Leaderboard::new(),
+            season_end: 0,
+            total_staked: 0,
+            period_id: 0,
+            period_start: env::block_timestamp_ms(),
+            snapshots: LookupMap::new(b"s".to_vec()),
+            prize_pool: 0,
+            unclaimed_prizes: 0,
+            prizes: LookupMap::new(b"z".to_vec()),
+            flagged: LookupSet::new(b"f".to_vec()),
+            coop_games: LookupMap::new(b"c".to_vec()),
+            next_coop_id: 0,
+            races: LookupMap::new(b"x".to_vec()),
+            next_race_id: 0,
+            archive: LookupMap::new(b"d".to_vec()),
+            archive_completions: LookupSet::new(b"a".to_vec()),
+            archive_dates: LookupMap::new(b"h".to_vec()),
+            require_commitment: false,
+            config: Config::default(),
+            sponsorships: LookupMap::new(b"o".to_vec()),
+            cancelled_periods: LookupSet::new(b"k".to_vec()),
+            registration_pool: 0,
+            pool_funded: LookupSet::new(b"j".to_vec()),
+            recent_puzzles: RecentPuzzles::new(b"n"),
+            pending_generations: LookupMap::new(b"g".to_vec()),
+            attestations: LookupMap::new(b"w".to_vec()),
+            attestation_counts: LookupMap::new(b"u".to_vec()),
+            sized_games: LookupMap::new(b"y".to_vec()),
+            sized_boards: SizedBoards::new(),
+            puzzle_pool: Vector::new(b"b".to_vec()),
+        }
+    }
+
+    /// Upgrades the state of the first deployment, which kept only the players and the top maps.
+    /// Everything added since starts out like in [`Contract::new`], owned by `owner_id`. The
+    /// players keep their legacy layout and stay unranked until [`Contract::migrate_players`]
+    /// rewrites them.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(owner_id: AccountId) -> Self {
+        let legacy: LegacyContract =
+            env::state_read().unwrap_or_else(|| ContractError::NoLegacyState.panic());
+        let mut contract = Self::new();
+        contract.owner_id = owner_id;
+        contract.players = legacy.players;
+        contract
+    }
+
+    fn assert_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            ContractError::NotOwner.panic();
+        }
+    }
+
+    /// Starts a season ending at `season_end` (block timestamp in milliseconds).
+    /// Stakes locked in earlier seasons keep their unlock time. The leader of the points board
+    /// of the previous season, if there was one, is credited with a season win.
+    pub fn set_season_end(&mut self, season_end: Timestamp) {
+        self.assert_owner();
+        if season_end != self.season_end {
+            let winner = match self.season_end {
+                0 => None,
+                _ => self.leaderboard.ranking(BoardKind::Points).iter().next(),
+            };
+            if let Some((_, account_id)) = winner {
+                if let Some(mut player) = self.players.get(&account_id) {
+                    player.career.seasons_won += 1;
+                    self.players.insert(&account_id, &player);
+                }
+            }
+            self.season_end = season_end;
+            self.leaderboard.new_season(season_end);
+        }
+    }
+
+    /// Flags `account_id` as suspected cheater and removes it from all leaderboards.
+    pub fn flag_player(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.flagged.insert(&account_id);
+        self.leaderboard
+            .remove_player(&account_id, self.config.leaderboard_size as usize);
+        // the storage deposit of the entries is forfeited, a later first solve pays it again
+        self.sized_boards.remove_player(&account_id);
+    }
+
+    /// Lifts the flag of `account_id`, its leaderboard entries return with the next finished game.
+    pub fn unflag_player(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.flagged.remove(&account_id);
+    }
+
+    pub fn is_flagged(&self, account_id: AccountId) -> bool {
+        self.flagged.contains(&account_id)
+    }
+
+    /// Requires ranked games to be seeded by the two-phase [`Contract::request_puzzle`] flow.
+    /// Meanwhile no other game counting for the boards or paying prizes can be started:
+    /// campaign levels, new races and archived puzzles are refused and no daily puzzle is archived.
+    pub fn set_require_commitment(&mut self, require_commitment: bool) {
+        self.assert_owner();
+        self.require_commitment = require_commitment;
+    }
+
+    /// Refuses starts seeded by the block alone while [`Contract::set_require_commitment`] is set
+    fn assert_no_commitment_required(&self) {
+        if self.require_commitment {
+            ContractError::CommitmentRequired.panic();
+        }
+    }
+
+    /// Replaces all tuning parameters at once, see [`Config`]
+    pub fn update_config(&mut self, config: Config) {
+        self.assert_owner();
+        if !config.is_valid() {
+            ContractError::InvalidConfig.panic();
+        }
+        self.config = config;
+    }
+
+    pub fn get_config(&self) -> Config {
+        self.config.clone()
+    }
+
+    /// Appends puzzles generated off-chain to the pool, each line as written by the `poolgen`
+    /// tool (see [`PooledPuzzle::parse`]). The attached deposit pays their storage, the rest is
+    /// refunded. Returns the size of the pool.
+    #[payable]
+    pub fn add_puzzles(&mut self, puzzles: Vec<String>) -> u64 {
+        self.assert_owner();
+        let storage_before = env::storage_usage();
+        for (index, line) in puzzles.iter().enumerate() {
+            let puzzle = PooledPuzzle::parse(line).unwrap_or_else(|| {
+                ContractError::InvalidPooledPuzzle {
+                    index: index as u32,
+                }
+                .panic()
+            });
+            self.puzzle_pool.push(&puzzle);
+        }
+        settle_storage_deposit(env::predecessor_account_id(), storage_before);
+        self.puzzle_pool.len()
+    }
+
+    pub fn get_pool_size(&self) -> u64 {
+        self.puzzle_pool.len()
+    }
+
+    /// Serves the embedded web client for web4 gateways, e.g. `<contract>.near.page`.
+    pub fn web4_get(&self, request: Web4Request) -> Web4Response {
+        web4::get(&request, &env::current_account_id())
+    }
+
+    /// Versions, game modes, difficulties and standards of this deployment, so clients can
+    /// detect features instead of assuming them.
+    pub fn contract_metadata(&self) -> ContractMetadata {
+        ContractMetadata {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            state_version: metadata::STATE_VERSION,
+            game_modes: GameMode::ALL.to_vec(),
+            require_commitment: self.require_commitment,
+            difficulties: Difficulty::ALL
+                .iter()
+                .map(|&difficulty| DifficultyRequest {
+                    difficulty,
+                    min_solve_time: self.config.min_solve_times[difficulty as usize],
+                })
+                .collect(),
+            standards: metadata::STANDARDS
+                .iter()
+                .map(|&(standard, version)| StandardRequest {
+                    standard: standard.to_string(),
+                    version: version.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Cancels the running or a future period. It pays no prizes and its sponsors can reclaim
+    /// their funds with [`Contract::refund_sponsorship`].
+    pub fn cancel_period(&mut self, period_id: u64) {
+        self.assert_owner();
+        self.roll_period();
+        if period_id < self.period_id {
+            ContractError::PeriodEnded.panic();
+        }
+        self.cancelled_periods.insert(&period_id);
+    }
+
+    /// Snapshots the leaderboards if the current period ended, credits the prizes of its winners
+    /// and advances to the period containing the current block.
+    /// Periods without a game transaction get no snapshot. Cancelled periods pay no prizes,
+    /// their fees stay in the pool for the next period.
+    fn roll_period(&mut self) {
+        let now = env::block_timestamp_ms();
+        if now < self.period_start + self.config.period_length {
+            return;
+        }
+
+        let snapshot = Snapshot::new(
+            self.leaderboard
+                .standings(self.period_id, self.config.leaderboard_size as usize),
+        );
+        let payouts = if self.cancelled_periods.contains(&self.period_id) {
+            vec![]
+        } else {
+            if let Some(sponsorships) = self.sponsorships.get(&self.period_id) {
+                self.prize_pool += sponsor::total(&sponsorships);
+            }
+            prize::payouts(self.prize_pool, snapshot.standings())
+        };
+        for (account_id, amount) in payouts {
+            // the prize of a flagged winner stays in the pool
+            if self.flagged.contains(&account_id) {
+                continue;
+            }
+            let mut prize = self.prizes.get(&account_id).unwrap_or_default();
+            prize.amount += amount;
+            prize.expires_at = now + self.config.prize_claim_period;
+            self.prizes.insert(&account_id, &prize);
+            self.prize_pool -= amount;
+            self.unclaimed_prizes += amount;
+        }
+        self.snapshots.insert(&self.period_id, &snapshot);
+
+        let elapsed = (now - self.period_start) / self.config.period_length;
+        self.period_id += elapsed;
+        self.period_start += elapsed * self.config.period_length;
+        self.leaderboard.new_period(self.period_id);
+    }
+
+    /// Archives the daily puzzle of the current UTC day unless it is archived already. Returns
+    /// whether it was archived now, the caller pays [`archive::ARCHIVED_PUZZLE_SIZE`] then.
+    fn archive_daily(&mut self) -> bool {
+        let date = reward::day(env::block_timestamp_ms());
+        if self.require_commitment || self.archive.contains_key(&date) {
+            return false;
+        }
+
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+        let difficulty = archive::daily_difficulty(date);
+        self.archive.insert(
+            &date,
+            &ArchivedPuzzle {
+                sudoku: difficulty.generate(&mut rnd),
+                difficulty,
+            },
+        );
+        true
+    }
+
+    /// Rewrites up to `limit` players starting at index `from_index`, so players and last solved
+    /// games get the versioned layouts, boards still stored in the legacy byte per cell layout get
+    /// packed and solve records kept inline by legacy players move under their own keys. Legacy
+    /// players, read from the state of the first deployment after [`Contract::migrate`], are
+    /// ranked by their solve count and best time on the way.
+    /// Returns the index to continue from, the migration is done once it reaches the number of
+    /// players.
+    pub fn migrate_players(&mut self, from_index: u64, limit: u64) -> u64 {
+        self.assert_owner();
+
+        let end = from_index.saturating_add(limit).min(self.players.len());
+        for index in from_index..end {
+            let account_id = self.players.keys_as_vector().get(index).unwrap();
+            let mut player = self.players.get(&account_id).unwrap();
+            let legacy = matches!(player.last_sloved_game, SideRecord::Inline(_));
+            if legacy && !self.flagged.contains(&account_id) {
+                self.leaderboard.insert_legacy(&account_id, &player);
+            }
+            player
+                .last_sloved_game
+                .migrate(LAST_SLOVED_GAME_PREFIX, &account_id);
+            player.last_replay.migrate(LAST_REPLAY_PREFIX, &account_id);
+            player.notes.migrate(NOTES_PREFIX, &account_id);
+            player.replay.migrate(REPLAY_PREFIX, &account_id);
+            player
+                .archive_dates
+                .migrate(&mut self.archive_dates, &account_id);
+            self.players.insert(&account_id, &player);
+        }
+
+        end
+    }
+
+    /// Starts a ranked 9x9 game, or with `size` a kids (4x4) or marathon (16x16) game, see
+    /// [`Contract::finish_sized_game`].
+    ///
+    /// Ranked 9x9 puzzles are drawn from the pool while it has puzzles (see
+    /// [`Contract::add_puzzles`]), each draw transformed on its own so players drawing the same
+    /// pooled puzzle can't share answers. Otherwise they are generated for the game.
+    pub fn start_game(&mut self, size: Option<BoardSize>) -> PlayerRequest {
+        self.assert_no_commitment_required();
+        if let Some(size) = size {
+            return self.start_sized(size);
+        }
+        let account_id = env::predecessor_account_id();
+        if self.pending_generations.contains_key(&account_id) {
+            ContractError::GenerationPending.panic();
+        }
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        if self.puzzle_pool.is_empty() {
+            return self.resume_generation(PendingGeneration::new(seed));
+        }
+
+        let draw = PoolDraw::new(seed, self.puzzle_pool.len());
+        let pooled = self.puzzle_pool.get(draw.index).unwrap();
+        self.start(draw.apply(pooled.sudoku), pooled.difficulty, None, false);
+        let mut player = self.players.get(&account_id).unwrap();
+        player.pool_draw = Some(draw);
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Replaces the running kids or marathon game by a new one of `size`, once the
+    /// [`Config::start_game_cooldown`] since its start has passed. It is free, but its storage is
+    /// held from the prepaid balance until it ends, and with the first solve of a size also the
+    /// storage of the entries in its leaderboards.
+    fn start_sized(&mut self, size: BoardSize) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        if let Some(game) = self.sized_games.get(&account_id) {
+            let available_at = game.start_time + self.config.start_game_cooldown;
+            if env::block_timestamp_ms() < available_at {
+                ContractError::Cooldown { available_at }.panic();
+            }
+            player.balance += game.deposit;
+        }
+        let mut storage = size.game_size();
+        if !self.sized_boards.is_ranked(&account_id, size) {
+            storage += SIZED_RANKING_SIZE;
+        }
+        let deposit = storage * env::STORAGE_PRICE_PER_BYTE;
+        if player.balance < deposit {
+            ContractError::InsufficientBalance { expected: deposit }.panic();
+        }
+        player.balance -= deposit;
+
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+        let game = SizedGame::generate(size, &mut rnd, env::block_timestamp_ms(), deposit);
+        self.sized_games.insert(&account_id, &game);
+        self.players.insert(&account_id, &player);
+        let mut request = player.get();
+        request.sized_game = Some(game.get());
+        request
+    }
+
+    /// Submits the solution of the running kids or marathon game, in the line format of
+    /// [`crate::sized::SizedSudoku::from_str_line`]. Solves count for the leaderboards of their
+    /// size, see [`Contract::get_sized_leaderboard_page`]. Returns whether the game was solved.
+    pub fn finish_sized_game(&mut self, line: String) -> bool {
+        let account_id = env::predecessor_account_id();
+        let game = self
+            .sized_games
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::NoActiveGame.panic());
+        if !game.is_solution(&line) {
+            return false;
+        }
+
+        let mut player = self.players.get(&account_id).unwrap();
+        self.sized_games.remove(&account_id);
+        // the rest of the deposit pays the leaderboard entries of a first solve
+        let game_deposit = game.size.game_size() * env::STORAGE_PRICE_PER_BYTE;
+        player.balance += game_deposit;
+        if self.flagged.contains(&account_id) {
+            player.balance += game.deposit - game_deposit;
+        } else {
+            let time = env::block_timestamp_ms() - game.start_time;
+            self.sized_boards.record_solve(&account_id, game.size, time);
+        }
+        self.players.insert(&account_id, &player);
+        true
+    }
+
+    pub fn get_sized_game(&self, account_id: AccountId) -> Option<SizedGameRequest> {
+        self.sized_games.get(&account_id).map(|game| game.get())
+    }
+
+    /// The leaderboard of kids or marathon games, only [`BoardKind::Count`] and
+    /// [`BoardKind::Time`] are kept. Unlike the 9x9 boards they list every player who solved one.
+    pub fn get_sized_leaderboard_page(
+        &self,
+        size: BoardSize,
+        board: BoardKind,
+        from_rank: u32,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        let from = from_rank.saturating_sub(1) as usize;
+        self.sized_boards
+            .ranking(size, board)
+            .unwrap_or_else(|| ContractError::BoardNotSized.panic())
+            .iter()
+            .enumerate()
+            .skip(from)
+            .take(limit as usize)
+            .map(|(position, (key, account_id))| LeaderboardEntry {
+                rank: position as u32 + 1,
+                value: U128(board.sort_key(key)),
+                profile: self
+                    .players
+                    .get(&account_id)
+                    .and_then(|player| player.profile),
+                account_id,
+            })
+            .collect()
+    }
+
+    /// Continues the puzzle generation of [`Contract::start_game`], if it ran short of gas.
+    /// The game starts as soon as the puzzle is finished, until then
+    /// `generation_pending` is set in the response and this has to be called again.
+    /// The game is timed from the `start_game` call.
+    pub fn continue_generation(&mut self) -> PlayerRequest {
+        let generation = self
+            .pending_generations
+            .get(&env::predecessor_account_id())
+            .unwrap_or_else(|| ContractError::NoPendingGeneration.panic());
+        self.resume_generation(generation)
+    }
+
+    /// Starts the game once `generation` is finished, otherwise stores it for
+    /// [`Contract::continue_generation`]. The game fee is charged once the puzzle is done, the
+    /// storage of the stored generation is held from the prepaid balance until then.
+    fn resume_generation(&mut self, mut generation: PendingGeneration) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        let deposit = generation::PENDING_GENERATION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        let stored = self.pending_generations.contains_key(&account_id);
+
+        let recent_puzzles = &self.recent_puzzles;
+        match generation.resume(
+            |sudoku| recent_puzzles.contains(sudoku),
+            generation::out_of_gas,
+        ) {
+            Some(sudoku) => {
+                self.recent_puzzles.insert(&sudoku);
+                if !stored {
+                    return self.start(sudoku, sudoku.grade(), None, false);
+                }
+                self.pending_generations.remove(&account_id);
+                player.balance += deposit;
+                self.players.insert(&account_id, &player);
+                self.start(sudoku, sudoku.grade(), None, false);
+
+                let mut player = self.players.get(&account_id).unwrap();
+                player.start_time = generation.start_time;
+                player.start_height = generation.start_height;
+                self.players.insert(&account_id, &player);
+                player.get()
+            }
+            None => {
+                if !stored {
+                    if player.balance < deposit {
+                        ContractError::InsufficientBalance { expected: deposit }.panic();
+                    }
+                    player.balance -= deposit;
+                    self.players.insert(&account_id, &player);
+                }
+                self.pending_generations.insert(&account_id, &generation);
+                let mut request = player.get();
+                request.generation_pending = true;
+                request
+            }
+        }
+    }
+
+    /// First phase of starting a ranked game whose puzzle the block producer can't influence.
+    /// `commitment` is the sha256 of a secret of at least 32 random bytes, kept by the client until
+    /// [`Contract::claim_puzzle`] in a later block. Replaces any pending request.
+    pub fn request_puzzle(&mut self, commitment: Base64VecU8) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        let commitment: [u8; 32] = Vec::from(commitment)
+            .try_into()
+            .unwrap_or_else(|_| ContractError::InvalidCommitment.panic());
+
+        player.pending_puzzle = Some(PendingPuzzle {
+            commitment,
+            requested_height: env::block_height(),
+        });
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Second phase of [`Contract::request_puzzle`]: starts a ranked game on a puzzle seeded by
+    /// `secret` and the random seed of the current block. Neither the player, who can't know the
+    /// seed of a later block, nor the block producer, who doesn't know the secret, controls it.
+    pub fn claim_puzzle(&mut self, secret: Base64VecU8) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        let pending = player
+            .pending_puzzle
+            .take()
+            .unwrap_or_else(|| ContractError::NoPendingPuzzle.panic());
+        if env::block_height() <= pending.requested_height {
+            ContractError::PuzzleNotReady {
+                available_at_height: pending.requested_height + 1,
+            }
+            .panic();
+        }
+        let secret: Vec<u8> = secret.into();
+        if secret.len() < 32 || env::sha256_array(&secret) != pending.commitment {
+            ContractError::InvalidCommitment.panic();
+        }
+        self.players.insert(&account_id, &player);
+
+        let seed = env::sha256_array(&[&secret[..], &env::random_seed()].concat());
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+
+        let sudoku = self.generate_novel(&mut rnd, &Sudoku::generate);
+        self.start(sudoku, sudoku.grade(), None, false)
+    }
+
+    /// Starts an unranked game. It is free and finished with [`Contract::finish_practice`],
+    /// which leaves the solved count, best times and leaderboards untouched.
+    pub fn start_practice(&mut self) -> PlayerRequest {
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+
+        let sudoku = self.generate_novel(&mut rnd, &Sudoku::generate);
+        self.start(sudoku, sudoku.grade(), None, true)
+    }
+
+    /// Replays the daily puzzle of `date` (UTC days since the unix epoch), a day before the
+    /// current one, as a practice game. The first solve of every date is recorded. Unless the
+    /// date was solved before, the attached deposit has to cover the storage of that record. It
+    /// is held until the solve and credited to the prepaid balance if the game is left unsolved,
+    /// the rest of the deposit is refunded.
+    #[payable]
+    pub fn start_archived(&mut self, date: u64) -> PlayerRequest {
+        self.assert_no_commitment_required();
+        if date >= reward::day(env::block_timestamp_ms()) {
+            ContractError::ArchiveNotClosed.panic();
+        }
+        let account_id = env::predecessor_account_id();
+        let puzzle = self
+            .archive
+            .get(&date)
+            .unwrap_or_else(|| ContractError::ArchiveNotFound.panic());
+        let expected = match self
+            .archive_completions
+            .contains(&(account_id.clone(), date))
+        {
+            true => 0,
+            false => archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE,
+        };
+        if env::attached_deposit() < expected {
+            ContractError::InsufficientDeposit { expected }.panic();
+        }
+
+        self.start(puzzle.sudoku, puzzle.difficulty, None, true);
+        let mut player = self.players.get(&account_id).unwrap();
+        player.archive_date = Some(date);
+        self.players.insert(&account_id, &player);
+        if env::attached_deposit() > expected {
+            Promise::new(account_id).transfer(env::attached_deposit() - expected);
+        }
+        player.get()
+    }
+
+    /// Starts campaign level `level` (counting from 1). All previous levels have to be completed.
+    pub fn start_level(&mut self, level: u32) -> PlayerRequest {
+        self.assert_no_commitment_required();
+        let difficulty = campaign::level_difficulty(level)
+            .unwrap_or_else(|| ContractError::UnknownLevel { level }.panic());
+        let completed_levels = self
+            .players
+            .get(&env::predecessor_account_id())
+            .map_or(0, |player| player.completed_levels);
+        if level > completed_levels + 1 {
+            ContractError::LevelLocked { level }.panic();
+        }
+
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+
+        let sudoku = self.generate_novel(&mut rnd, &|rnd| difficulty.generate(rnd));
+        self.start(sudoku, difficulty, Some(level), false)
+    }
+
+    /// Generates puzzles until one isn't a transformed copy of a recently issued puzzle,
+    /// at most [`novelty::MAX_ATTEMPTS`] times, and records the result as issued.
+    fn generate_novel(
+        &mut self,
+        rnd: &mut StdRng,
+        generate: &dyn Fn(&mut StdRng) -> Sudoku,
+    ) -> Sudoku {
+        let mut sudoku = generate(rnd);
+        for _ in 1..novelty::MAX_ATTEMPTS {
+            if !self.recent_puzzles.contains(&sudoku) {
+                break;
+            }
+            sudoku = generate(rnd);
+        }
+        self.recent_puzzles.insert(&sudoku);
+        sudoku
+    }
+
+    /// Games outside the campaign and practice are ranked and charged the game fee,
+    /// which is taken from the prepaid balance.
+    fn start(
+        &mut self,
+        sudoku: Sudoku,
+        difficulty: Difficulty,
+        level: Option<u32>,
+        practice: bool,
+    ) -> PlayerRequest {
+        self.roll_period();
+        let fee = match level {
+            Some(_) => 0,
+            None if practice => 0,
+            None => self.config.game_fee.0,
+        };
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_some()
+            && env::block_timestamp_ms() < player.start_time + self.config.start_game_cooldown
+        {
+            ContractError::Cooldown {
+                available_at: player.start_time + self.config.start_game_cooldown,
+            }
+            .panic()
+        }
+        if player.balance < fee {
+            ContractError::InsufficientBalance { expected: fee }.panic();
+        }
+        player.balance -= fee;
+        self.prize_pool += fee;
+
+        // the move history holds the storage of the replays like sharing them does, games of
+        // players who can't pay it aren't recorded
+        let replay_deposit = REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        if self.config.record_moves
+            && player.replay_deposit == 0
+            && player.balance >= replay_deposit
+        {
+            player.balance -= replay_deposit;
+            player.replay_deposit = replay_deposit;
+        }
+
+        // the first game of the day archives the daily puzzle if its player can pay the storage,
+        // otherwise a later one does
+        let archive_cost = archive::ARCHIVED_PUZZLE_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        if player.balance >= archive_cost && self.archive_daily() {
+            player.balance -= archive_cost;
+        }
+        self.refund_archive_deposit(&account_id, &mut player);
+
+        let player = player.new_game(
+            sudoku,
+            difficulty,
+            level,
+            practice,
+            self.config.record_moves,
+        );
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Registers the caller. The storage of the player is paid by the registration pool while it
+    /// has funds left, otherwise the attached deposit has to cover it. The pool gets its deposit
+    /// back once the player is removed.
+    /// The rest of the deposit is credited to the prepaid balance for game fees.
+    #[payable]
+    pub fn register(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        if self.players.get(&account_id).is_some() {
+            ContractError::AlreadyRegistered.panic();
+        }
+        let expected = self.config.player_deposit();
+        let balance = if self.registration_pool >= expected {
+            self.registration_pool -= expected;
+            self.pool_funded.insert(&account_id);
+            env::attached_deposit()
+        } else if env::attached_deposit() >= expected {
+            env::attached_deposit() - expected
+        } else {
+            ContractError::InsufficientDeposit { expected }.panic()
+        };
+
+        let player = Player::new(&account_id, balance);
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Adds the attached deposit to the registration pool paying the storage of new players.
+    #[payable]
+    pub fn fund_registrations(&mut self) -> U128 {
+        self.registration_pool += env::attached_deposit();
+        U128(self.registration_pool)
+    }
+
+    /// Adds the attached deposit to the prepaid balance of the caller.
+    #[payable]
+    pub fn top_up(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        player.balance += env::attached_deposit();
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Transfers the whole prepaid balance back to the caller. Returns the amount.
+    pub fn withdraw_balance(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        let amount = player.balance;
+        player.balance = 0;
+        self.players.insert(&account_id, &player);
+        if amount > 0 {
+            Promise::new(account_id).transfer(amount);
+        }
+        U128(amount)
+    }
+
+    /// Returns `None` and counts a wrong submission if `array` isn't a solution of the current sudoku.
+    /// Solutions submitted faster than the minimal solve time of the sudoku's difficulty are rejected.
+    /// Solves are attested (see [`Contract::verify_attestation`]) while the prepaid balance covers
+    /// the storage of the attestation, otherwise `attestation_skipped` is set in the response.
+    pub fn finish_game(&mut self, array: &SudokuInput) -> Option<PlayerRequest> {
+        self.roll_period();
+        let array = &array.to_two_dimensional_array();
+        let mut player = self
+            .players
+            .get(&env::predecessor_account_id())
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_none() {
+            ContractError::NoActiveGame.panic();
+        }
+        if player.practice {
+            ContractError::PracticeGame.panic();
+        }
+
+        // the solution of a pooled game has to solve the pooled puzzle mapped by its draw as well
+        let pooled = player
+            .pool_draw
+            .map(|draw| draw.apply(self.puzzle_pool.get(draw.index).unwrap().sudoku));
+        let solution = Sudoku::from_two_dimensional_array(array);
+        if solution.is_solved()
+            && player.sudoku_eq(array)
+            && pooled.is_none_or(|puzzle| puzzle.is_subset_of(&solution))
+        {
+            let available_at =
+                player.start_time + self.config.min_solve_times[player.difficulty as usize];
+            if env::block_timestamp_ms() < available_at {
+                ContractError::SolveTooFast { available_at }.panic();
+            }
+            player.record_entries(&Sudoku::from_two_dimensional_array(array));
+            let account_id = env::predecessor_account_id();
+            let attestation = Attestation::new(&player.sudoku.unwrap(), player.elapsed());
+            let time = match player.assisted() {
+                true => None,
+                false => Some(player.elapsed()),
+            };
+            let mut new_player = player.finish_game(self.season_end, &self.config);
+            let attested = self.attest(&account_id, attestation, &mut new_player);
+
+            if !self.flagged.contains(&account_id) {
+                self.leaderboard
+                    .work_player(&new_player, self.config.leaderboard_size as usize);
+                self.leaderboard
+                    .record_period_solve(&account_id, time, new_player.verified());
+            }
+
+            let mut request = self.players.insert(&account_id, &new_player).unwrap().get();
+            request.attestation_skipped = !attested;
+            Some(request)
+        } else {
+            player.wrong_submissions += 1;
+            player.penalty += self.config.wrong_submission_penalty;
+            self.players.insert(&env::predecessor_account_id(), &player);
+            None
+        }
+    }
+
+    /// Stores `attestation` as the next attested solve of `account_id`, if the balance of `player`
+    /// covers its storage. Returns whether it was stored.
+    fn attest(
+        &mut self,
+        account_id: &AccountId,
+        attestation: Attestation,
+        player: &mut Player,
+    ) -> bool {
+        let (index, mut cost) = match self.attestation_counts.get(account_id) {
+            Some(count) => (count, 0),
+            None => (
+                0,
+                attestation::ATTESTATION_COUNT_SIZE * env::STORAGE_PRICE_PER_BYTE,
+            ),
+        };
+        cost += attestation::ATTESTATION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        if player.balance < cost {
+            return false;
+        }
+        player.balance -= cost;
+        events::solve_attested(&[attestation.get(account_id.clone(), index)]);
+        self.attestations
+            .insert(&(account_id.clone(), index), &attestation);
+        self.attestation_counts.insert(account_id, &(index + 1));
+        true
+    }
+
+    /// Like [`Contract::finish_game`], but only the cells filled by the player are submitted as
+    /// (row, column, digit), rows and columns counted from 0. They are merged onto the clues
+    /// of the current sudoku before verification.
+    pub fn finish_game_delta(&mut self, entries: Vec<(u8, u8, u8)>) -> Option<PlayerRequest> {
+        let player = self
+            .players
+            .get(&env::predecessor_account_id())
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        let mut array = player
+            .sudoku
+            .unwrap_or_else(|| ContractError::NoActiveGame.panic())
+            .to_two_dimensional_array();
+
+        for (row, col, digit) in entries {
+            let cell = array
+                .get_mut(row as usize)
+                .and_then(|row| row.get_mut(col as usize))
+                .unwrap_or_else(|| ContractError::InvalidEntries.panic());
+            if *cell != 0 || !(1..=9).contains(&digit) {
+                ContractError::InvalidEntries.panic();
+            }
+            *cell = digit;
+        }
+
+        self.finish_game(&SudokuInput::Array(array))
+    }
+
+    /// Finishes the current game and starts the next ranked one in the same transaction.
+    /// If `array` isn't the solution, a wrong submission is counted and no game is started.
+    /// The solve also counts if [`Contract::start_game`] would refuse the next game, e.g. because
+    /// the balance doesn't cover the game fee, only `started` is false then.
+    pub fn finish_and_start(&mut self, array: &SudokuInput) -> FinishAndStartRequest {
+        let account_id = env::predecessor_account_id();
+        let solved = self.finish_game(array).is_some();
+        let player = self.players.get(&account_id).unwrap();
+        let started = solved
+            && !self.require_commitment
+            && !self.pending_generations.contains_key(&account_id)
+            && player.balance >= self.config.game_fee.0;
+        let player = match started {
+            true => self.start_game(None),
+            false => player.get(),
+        };
+        FinishAndStartRequest {
+            solved,
+            started,
+            player,
+        }
+    }
+
+    /// Returns `None` and counts a wrong submission if `array` isn't a solution of the current
+    /// practice sudoku.
+    pub fn finish_practice(&mut self, array: &SudokuInput) -> Option<PlayerRequest> {
+        let array = &array.to_two_dimensional_array();
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_none() || !player.practice {
+            ContractError::NoActiveGame.panic();
+        }
+
+        if Sudoku::from_two_dimensional_array(array).is_solved() && player.sudoku_eq(array) {
+            if let Some(date) = player.archive_date {
+                if self.archive_completions.insert(&(account_id.clone(), date)) {
+                    player
+                        .archive_dates
+                        .push(&mut self.archive_dates, &account_id, date);
+                }
+            }
+            player.record_entries(&Sudoku::from_two_dimensional_array(array));
+            let player = player.finish_practice();
+            self.players.insert(&account_id, &player);
+            Some(player.get())
+        } else {
+            player.wrong_submissions += 1;
+            self.players.insert(&account_id, &player);
+            None
+        }
+    }
+
+    /// Restarts the timer of the current game on the same sudoku. Saved progress, notes and
+    /// wrong submissions are cleared, the generated sudoku count stays unchanged.
+    /// A restarted ranked game continues unranked and has to be finished with
+    /// [`Contract::finish_practice`], so it never counts for best times, the boards or points.
+    pub fn restart_current(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_none() {
+            ContractError::NoActiveGame.panic();
+        }
+
+        player.progress = None;
+        player.notes.remove();
+        player.wrong_submissions = 0;
+        player.penalty = 0;
+        player.practice = true;
+        if let Some(replay) = player.replay.get() {
+            player.replay.set(&Replay::new(replay.game_id));
+        }
+        player.start_time = env::block_timestamp_ms();
+        player.start_height = env::block_height();
+        self.players.insert(&account_id, &player);
+
+        player.get()
+    }
+
+    /// Stores a partially filled grid of the current game so it can be resumed later.
+    /// The changed cells are added to the replay, if the player shares replays.
+    pub fn save_progress(&mut self, array: &SudokuTwoDimensionalArray) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_none() {
+            ContractError::NoActiveGame.panic();
+        }
+        if !player.progress_valid(array) {
+            ContractError::InvalidProgress.panic();
+        }
+
+        let progress = Sudoku::from_two_dimensional_array(array);
+        player.record_entries(&progress);
+        player.progress = Some(progress);
+        self.players.insert(&account_id, &player);
+
+        player.get()
+    }
+
+    /// Enters the digit of the cell at `row` and `column`, counted from 0, into the saved progress
+    /// of the current game. Every revealed cell counts as a hint, which forfeits the no-hint
+    /// bonus and keeps the solve off the time boards.
+    pub fn reveal_cell(&mut self, row: u8, column: u8) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        let sudoku = player
+            .sudoku
+            .unwrap_or_else(|| ContractError::NoActiveGame.panic());
+        let cell = row as usize * 9 + column as usize;
+        if row >= 9 || column >= 9 || sudoku.0[cell] != 0 {
+            ContractError::InvalidEntries.panic();
+        }
+
+        let mut progress = player.progress.unwrap_or(sudoku);
+        progress.0[cell] = sudoku.solution().unwrap().0[cell];
+        player.record_entries(&progress);
+        player.progress = Some(progress);
+        player.hints_used += 1;
+        self.players.insert(&account_id, &player);
+
+        player.get()
+    }
+
+    /// Stores the candidate notes of the current game, see [`NOTES_LEN`] for the layout.
+    pub fn update_notes(&mut self, bitmask: Base64VecU8) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        if player.sudoku.is_none() {
+            ContractError::NoActiveGame.panic();
+        }
+        let bitmask: Vec<u8> = bitmask.into();
+        if bitmask.len() != NOTES_LEN || bitmask[NOTES_LEN - 1] >> (81 * 9 % 8) != 0 {
+            ContractError::InvalidNotes.panic();
+        }
+
+        player.notes.set(&bitmask);
+        self.players.insert(&account_id, &player);
+
+        player.get()
+    }
+
+    pub fn check_sloved(&self, array: &SudokuInput) -> bool {
+        Sudoku::from_two_dimensional_array(&array.to_two_dimensional_array()).is_solved()
+    }
+
+    /// Checks `solution` against `puzzle`, both in the 81 character line format. No state is read,
+    /// so any sudoku can be verified, not just the ones handed out by this contract.
+    pub fn verify_solution(&self, puzzle: String, solution: String) -> VerifyResult {
+        let puzzle = match Sudoku::from_str_line(&puzzle) {
+            Ok(puzzle) => puzzle,
+            Err(_) => return VerifyResult::InvalidPuzzle,
+        };
+        let solution = match Sudoku::from_str_line(&solution) {
+            Ok(solution) => solution,
+            Err(_) => return VerifyResult::InvalidSolution,
+        };
+
+        if solution.n_clues() < 81 {
+            VerifyResult::Incomplete
+        } else if !solution.is_solved() {
+            VerifyResult::RulesViolated
+        } else if puzzle
+            .0
+            .iter()
+            .zip(solution.0.iter())
+            .any(|(&clue, &num)| clue != 0 && clue != num)
+        {
+            VerifyResult::CluesChanged
+        } else {
+            VerifyResult::Valid
+        }
+    }
+
+    /// Whether `puzzle`, in the 81 character line format, has exactly one solution. Unparsable
+    /// puzzles are not unique.
+    pub fn check_unique(&self, puzzle: String) -> bool {
+        Sudoku::from_str_line(&puzzle).is_ok_and(Sudoku::is_uniquely_solvable)
+    }
+
+    pub fn get_archived_puzzle(&self, date: u64) -> Option<ArchivedPuzzleRequest> {
+        self.archive.get(&date).map(|puzzle| puzzle.get(date))
+    }
+
+    pub fn is_archive_completed(&self, account_id: AccountId, date: u64) -> bool {
+        self.archive_completions.contains(&(account_id, date))
+    }
+
+    pub fn get_player(&self, account_id: AccountId) -> Option<PlayerRequest> {
+        self.players.get(&account_id).map(|player| {
+            let mut request = player.get();
+            request.sized_game = self.sized_games.get(&account_id).map(|game| game.get());
+            request
+        })
+    }
+
+    /// Solve time distribution, hint usage and abandoned games of the ranked games of `account_id`.
+    pub fn get_statistics(&self, account_id: AccountId) -> Option<StatisticsRequest> {
+        self.players
+            .get(&account_id)
+            .map(|player| player.statistics())
+    }
+
+    /// Lifetime totals of `account_id`, unaffected by the start of a new season
+    pub fn get_career(&self, account_id: AccountId) -> Option<CareerRequest> {
+        self.players.get(&account_id).map(|player| player.career())
+    }
+
+    /// Credits the deposit of [`Contract::start_archived`] back if the archived game of `player`
+    /// ends unsolved
+    fn refund_archive_deposit(&self, account_id: &AccountId, player: &mut Player) {
+        if self.archive_deposit_held(account_id, player) {
+            player.balance += archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        }
+    }
+
+    /// Whether `player` runs an archived game it hasn't solved yet, so its deposit is held
+    fn archive_deposit_held(&self, account_id: &AccountId, player: &Player) -> bool {
+        player.archive_date.is_some_and(|date| {
+            !self
+                .archive_completions
+                .contains(&(account_id.clone(), date))
+        })
+    }
+
+    pub fn delete_player(&mut self) {
+        // a locked stake can't be forfeited, it is returned once unlocked
+        if self
+            .players
+            .get(&env::predecessor_account_id())
+            .is_some_and(|player| player.verified())
+        {
+            self.unlock_stake();
+        }
+        self.remove_player(&env::predecessor_account_id());
+    }
+
+    fn remove_player(&mut self, account_id: &AccountId) {
+        if let Some(mut player) = self.players.remove(account_id) {
+            player.last_sloved_game.remove();
+            player.notes.remove();
+            player.replay.remove();
+            player.last_replay.remove();
+            player.balance += player.replay_deposit;
+            self.refund_archive_deposit(account_id, &mut player);
+            for date in player
+                .archive_dates
+                .remove(&mut self.archive_dates, account_id)
+            {
+                self.archive_completions.remove(&(account_id.clone(), date));
+                player.balance += archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+            }
+            if self.pool_funded.remove(account_id) {
+                self.registration_pool += self.config.player_deposit();
+            }
+            if self.pending_generations.remove(account_id).is_some() {
+                player.balance += generation::PENDING_GENERATION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+            }
+            if let Some(game) = self.sized_games.get(account_id) {
+                self.sized_games.remove(account_id);
+                player.balance += game.deposit;
+            }
+            for _ in self.sized_boards.remove_player(account_id) {
+                player.balance += SIZED_RANKING_SIZE * env::STORAGE_PRICE_PER_BYTE;
+            }
+            if player.balance > 0 {
+                Promise::new(account_id.clone()).transfer(player.balance);
+            }
+        }
+        self.leaderboard
+            .remove_player(account_id, self.config.leaderboard_size as usize);
+    }
+
+    /// Removes the players of `accounts` that haven't started or finished a game for the stale
+    /// period and pays the caller [`Config::cleanup_reward_percent`] of the freed storage deposit
+    /// the contract keeps, at most the player deposit of each removed player. Players registered
+    /// from the registration pool return their deposit to the pool and earn no reward.
+    /// Players with a locked stake and unknown accounts are skipped. Returns the reward.
+    pub fn cleanup_stale(&mut self, accounts: Vec<AccountId>) -> U128 {
+        let mut freed = 0;
+        for account_id in accounts {
+            let stale = self.players.get(&account_id).is_some_and(|player| {
+                !player.verified()
+                    && env::block_timestamp_ms() >= player.start_time + self.config.stale_period
+            });
+            if !stale {
+                continue;
+            }
+            // deposits held from the balance are refunded to the player
+            let kept = match self.pool_funded.contains(&account_id) {
+                true => 0,
+                false => self.config.player_size,
+            };
+            let storage_before = env::storage_usage();
+            self.remove_player(&account_id);
+            let removed = storage_before.saturating_sub(env::storage_usage());
+            freed += removed.min(kept) as u128;
+        }
+
+        let reward =
+            freed * env::STORAGE_PRICE_PER_BYTE * self.config.cleanup_reward_percent as u128 / 100;
+        if reward > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(reward);
+        }
+        U128(reward)
+    }
+
+    /// Credits the daily reward points, at most once per UTC day and only if the caller solved a
+    /// sudoku on that day. Claims on consecutive days multiply the reward by the streak length,
+    /// up to [`Config::max_streak_multiplier`].
+    pub fn claim_daily_reward(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        let today = reward::day(env::block_timestamp_ms());
+        if player.last_reward_day == Some(today) {
+            ContractError::AlreadyClaimed {
+                available_at: (today + 1) * reward::DAY,
+            }
+            .panic();
+        }
+        let solved_today = player
+            .last_sloved_game
+            .get()
+            .is_some_and(|game| reward::day(game.time_end) == today);
+        if !solved_today {
+            ContractError::NoSolveToday.panic();
+        }
+
+        player.reward_streak = match player.last_reward_day {
+            Some(day) if day + 1 == today => player.reward_streak + 1,
+            _ => 1,
+        };
+        player.last_reward_day = Some(today);
+        player.points += reward::reward_points(player.reward_streak, &self.config);
+
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Locks the attached deposit until the end of the running season and adds the caller to the
+    /// verified leaderboards. The deposit has to be the verification stake plus the storage of the
+    /// verified rankings, it is returned in full by [`Contract::unlock_stake`].
+    #[payable]
+    pub fn verify(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        if player.verified() {
+            ContractError::AlreadyVerified.panic();
+        }
+        if env::block_timestamp_ms() >= self.season_end {
+            ContractError::NoSeason.panic();
+        }
+        let expected =
+            self.config.verification_stake.0 + VERIFIED_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        if env::attached_deposit() != expected {
+            ContractError::WrongDeposit { expected }.panic();
+        }
+
+        player.stake = expected;
+        player.stake_unlock_at = self.season_end;
+        self.total_staked += expected;
+        if !self.flagged.contains(&account_id) {
+            self.leaderboard.insert_verified(&account_id, &player);
+        }
+        self.players.insert(&account_id, &player);
+        player.get()
+    }
+
+    /// Returns the locked stake once the season it was locked for has ended
+    /// and removes the caller from the verified leaderboards.
+    pub fn unlock_stake(&mut self) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        if !player.verified() {
+            ContractError::NotVerified.panic();
+        }
+        if env::block_timestamp_ms() < player.stake_unlock_at {
+            ContractError::StakeLocked {
+                unlock_at: player.stake_unlock_at,
+            }
+            .panic();
+        }
+
+        let stake = player.stake;
+        player.stake = 0;
+        self.total_staked -= stake;
+        self.leaderboard.remove_verified(&account_id);
+        self.players.insert(&account_id, &player);
+        Promise::new(account_id).transfer(stake);
+        player.get()
+    }
+
+    /// Transfers the prizes won by the caller, they have to be claimed within
+    /// [`Config::prize_claim_period`] after their period ended.
+    pub fn claim_prize(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let prize = self
+            .prizes
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PrizeNotFound.panic());
+        if env::block_timestamp_ms() >= prize.expires_at {
+            ContractError::PrizeExpired {
+                expired_at: prize.expires_at,
+            }
+            .panic();
+        }
+
+        self.prizes.remove(&account_id);
+        self.unclaimed_prizes -= prize.amount;
+        Promise::new(account_id).transfer(prize.amount);
+        U128(prize.amount)
+    }
+
+    /// Returns the expired prizes of `accounts` to the prize pool. Returns the amount recovered.
+    pub fn sweep_expired_prizes(&mut self, accounts: Vec<AccountId>) -> U128 {
+        let mut recovered = 0;
+        for account_id in accounts {
+            match self.prizes.get(&account_id) {
+                Some(prize) if env::block_timestamp_ms() >= prize.expires_at => {
+                    self.prizes.remove(&account_id);
+                    recovered += prize.amount;
+                }
+                _ => {}
+            }
+        }
+        self.unclaimed_prizes -= recovered;
+        self.prize_pool += recovered;
+        U128(recovered)
+    }
+
+    /// Sets the nickname and avatar shown next to the caller's account.
+    /// Additional storage has to be paid with the attached deposit, the rest is refunded.
+    #[payable]
+    pub fn set_profile(&mut self, nickname: String, avatar_url: Option<String>) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+        player.profile = Some(Profile::new(nickname, avatar_url).unwrap_or_else(|e| e.panic()));
+
+        let storage_before = env::storage_usage();
+        self.players.insert(&account_id, &player);
+        settle_storage_deposit(account_id, storage_before);
+
+        player.get()
+    }
+
+    /// Opts into recording the entries of the caller's games, starting with the next one,
+    /// so others can replay them with [`Contract::get_replay`]. The storage of the replays is
+    /// paid from the prepaid balance. Opting out deletes the stored replays and credits their
+    /// storage back, unless [`Config::record_moves`] keeps them as move history. Their storage
+    /// stays paid then, until the player opts in again or is deleted.
+    pub fn set_share_replays(&mut self, share_replays: bool) -> PlayerRequest {
+        let account_id = env::predecessor_account_id();
+        let mut player = self
+            .players
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::PlayerNotFound.panic());
+
+        let deposit = REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        match (player.share_replays, share_replays) {
+            (false, true) if player.replay_deposit == 0 => {
+                if player.balance < deposit {
+                    ContractError::InsufficientBalance { expected: deposit }.panic();
+                }
+                player.balance -= deposit;
+                player.replay_deposit = deposit;
+            }
+            // the move history stays for auditing, and its storage stays paid
+            (true, false) if !self.config.record_moves => {
+                player.replay.remove();
+                player.last_replay.remove();
+                player.balance += player.replay_deposit;
+                player.replay_deposit = 0;
+            }
+            _ => {}
+        }
+        player.share_replays = share_replays;
+        self.players.insert(&account_id, &player);
+
+        player.get()
+    }
+
+    /// Starts a co-op game shared by the caller and `partners`, who all have to be registered.
+    /// Additional storage has to be paid with the attached deposit, the rest is refunded.
+    /// Returns the id of the game.
+    #[payable]
+    pub fn create_coop(&mut self, partners: Vec<AccountId>) -> u64 {
+        let creator = env::predecessor_account_id();
+        let mut participants = vec![creator.clone()];
+        participants.extend(partners);
+
+        let mut unique = participants.clone();
+        unique.sort();
+        unique.dedup();
+        if unique.len() != participants.len()
+            || !(2..=coop::COOP_MAX_PLAYERS).contains(&participants.len())
+            || participants
+                .iter()
+                .any(|account_id| self.players.get(account_id).is_none())
+        {
+            ContractError::InvalidParticipants.panic();
+        }
+
+        let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+        let mut rnd: StdRng = SeedableRng::from_seed(seed);
+
+        let game_id = self.next_coop_id;
+        self.next_coop_id += 1;
+
+        let storage_before = env::storage_usage();
+        self.coop_games.insert(
+            &game_id,
+            &CoopGame {
+                creator: creator.clone(),
+                participants,
+                sudoku: Sudoku::generate(&mut rnd),
+                progress: None,
+                start_time: env::block_timestamp_ms(),
+            },
+        );
+        settle_storage_deposit(creator, storage_before);
+
+        game_id
+    }
+
+    fn coop_game(&self, game_id: u64) -> CoopGame {
+        let game = self
+            .coop_games
+            .get(&game_id)
+            .unwrap_or_else(|| ContractError::CoopGameNotFound.panic());
+        if !game.is_participant(&env::predecessor_account_id()) {
+            ContractError::NotParticipant.panic();
+        }
+        game
+    }
+
+    pub fn save_coop_progress(
+        &mut self,
+        game_id: u64,
+        array: &SudokuTwoDimensionalArray,
+    ) -> CoopGameRequest {
+        let mut game = self.coop_game(game_id);
+        if !game.progress_valid(array) {
+            ContractError::InvalidProgress.panic();
+        }
+
+        game.progress = Some(Sudoku::from_two_dimensional_array(array));
+        self.coop_games.insert(&game_id, &game);
+        game.get()
+    }
+
+    /// Returns `false` if `array` isn't the solution. Otherwise the solve is credited to all
+    /// participants and the storage of the game is refunded to its creator.
+    pub fn finish_coop(&mut self, game_id: u64, array: &SudokuInput) -> bool {
+        let game = self.coop_game(game_id);
+        if !game.is_solution(&array.to_two_dimensional_array()) {
+            return false;
+        }
+
+        for account_id in &game.participants {
+            if let Some(mut player) = self.players.get(account_id) {
+                player.coop_sloved_count += 1;
+                self.players.insert(account_id, &player);
+            }
+        }
+
+        let storage_before = env::storage_usage();
+        self.coop_games.remove(&game_id);
+        let freed = storage_before.saturating_sub(env::storage_usage()) as u128;
+        Promise::new(game.creator).transfer(freed * env::STORAGE_PRICE_PER_BYTE);
+        true
+    }
+
+    pub fn get_coop_game(&self, game_id: u64) -> Option<CoopGameRequest> {
+        self.coop_games.get(&game_id).map(|game| game.get())
+    }
+
+    /// Opens a race lobby for `max_players` entrants paying `entry_fee` each, starting in
+    /// `start_delay` milliseconds. The storage of the race, including the sudoku revealed at the
+    /// start, has to be paid with the attached deposit, the rest is refunded. The storage deposit
+    /// is returned once the race is settled or cancelled. Returns the id of the race.
+    #[payable]
+    pub fn create_race(
+        &mut self,
+        entry_fee: U128,
+        max_players: u32,
+        start_delay: Timestamp,
+    ) -> u64 {
+        self.assert_no_commitment_required();
+        if !(2..=race::RACE_MAX_PLAYERS).contains(&max_players) {
+            ContractError::InvalidRace.panic();
+        }
+
+        let creator = env::predecessor_account_id();
+        let race_id = self.next_race_id;
+        self.next_race_id += 1;
+
+        let storage_before = env::storage_usage();
+        let mut race = Race {
+            creator: creator.clone(),
+            entry_fee: entry_fee.0,
+            max_players,
+            start_at: env::block_timestamp_ms() + start_delay,
+            status: RaceStatus::Open,
+            entrants: vec![],
+            sudoku: None,
+            finishers: vec![],
+            deposit: 0,
+        };
+        self.races.insert(&race_id, &race);
+        // the deposit has a fixed size, recording it leaves the storage of the race unchanged
+        let storage = (env::storage_usage() - storage_before) as u128 + race::RACE_SUDOKU_SIZE;
+        race.deposit = storage * env::STORAGE_PRICE_PER_BYTE;
+        self.races.insert(&race_id, &race);
+        if env::attached_deposit() < race.deposit {
+            ContractError::InsufficientDeposit {
+                expected: race.deposit,
+            }
+            .panic();
+        }
+        if env::attached_deposit() > race.deposit {
+            Promise::new(creator).transfer(env::attached_deposit() - race.deposit);
+        }
+
+        race_id
+    }
+
+    fn race(&self, race_id: u64) -> Race {
+        self.races
+            .get(&race_id)
+            .unwrap_or_else(|| ContractError::RaceNotFound.panic())
+    }
+
+    /// Enters the race. The attached deposit has to cover the entry fee and
+    /// [`race::entry_deposit`] for the storage of the entry, the rest is refunded. The storage
+    /// deposit is returned once the race is settled or cancelled.
+    #[payable]
+    pub fn join_race(&mut self, race_id: u64) {
+        let account_id = env::predecessor_account_id();
+        let mut race = self.race(race_id);
+        if race.status != RaceStatus::Open
+            || env::block_timestamp_ms() >= race.start_at
+            || race.entrants.len() >= race.max_players as usize
+            || race.entrants.contains(&account_id)
+        {
+            ContractError::RaceClosed.panic();
+        }
+
+        race.entrants.push(account_id.clone());
+        self.races.insert(&race_id, &race);
+        let expected = race.entry_fee + race::entry_deposit(&account_id);
+        if env::attached_deposit() < expected {
+            ContractError::InsufficientDeposit { expected }.panic();
+        }
+        if let Some(mut player) = self.players.get(&account_id) {
+            player.career.races_entered += 1;
+            self.players.insert(&account_id, &player);
+        }
+        if env::attached_deposit() > expected {
+            Promise::new(account_id).transfer(env::attached_deposit() - expected);
+        }
+    }
+
+    /// Starts the race once its start time has passed, anybody may call this. A full race reveals
+    /// its sudoku, any other race is cancelled and the entry fees are refunded.
+    pub fn start_race(&mut self, race_id: u64) -> RaceRequest {
+        let mut race = self.race(race_id);
+        if race.status != RaceStatus::Open {
+            ContractError::RaceClosed.panic();
+        }
+        if env::block_timestamp_ms() < race.start_at {
+            ContractError::RaceNotStarted {
+                start_at: race.start_at,
+            }
+            .panic();
+        }
+
+        self.launch(race_id, &mut race);
+        race.get()
+    }
+
+    /// Reveals the sudoku of a full race. Any other race is cancelled and removed, the entry fees
+    /// and all storage deposits are refunded.
+    fn launch(&mut self, race_id: u64, race: &mut Race) {
+        if race.entrants.len() < race.max_players as usize {
+            race.status = RaceStatus::Cancelled;
+            for account_id in &race.entrants {
+                Promise::new(account_id.clone())
+                    .transfer(race.entry_fee + race::entry_deposit(account_id));
+            }
+            Promise::new(race.creator.clone()).transfer(race.deposit);
+            self.end_race(race_id, race);
+        } else {
+            let seed: [u8; 32] = env::random_seed().try_into().unwrap();
+            let mut rnd: StdRng = SeedableRng::from_seed(seed);
+            race.status = RaceStatus::Running;
+            race.sudoku = Some(Sudoku::generate(&mut rnd));
+            self.races.insert(&race_id, race);
+        }
+    }
+
+    /// Removes a settled or cancelled race, its final state is logged
+    fn end_race(&mut self, race_id: u64, race: &Race) {
+        self.races.remove(&race_id);
+        events::race_ended(&[race.result(race_id)]);
+    }
+
+    /// Returns `false` if `array` isn't the solution. Otherwise the caller takes the next place
+    /// and the race is settled once all paid places are taken.
+    pub fn finish_race(&mut self, race_id: u64, array: &SudokuInput) -> bool {
+        let account_id = env::predecessor_account_id();
+        let mut race = self.race(race_id);
+        if race.status != RaceStatus::Running {
+            ContractError::RaceNotRunning.panic();
+        }
+        if !race.entrants.contains(&account_id) {
+            ContractError::NotParticipant.panic();
+        }
+        if race.finishers.contains(&account_id)
+            || !race.is_solution(&array.to_two_dimensional_array())
+        {
+            return false;
+        }
+
+        // flagged finishers aren't paid, so the win goes to the first one that is
+        let first = race
+            .finishers
+            .iter()
+            .chain([&account_id])
+            .find(|finisher| !self.flagged.contains(finisher));
+        if first == Some(&account_id) {
+            if let Some(mut player) = self.players.get(&account_id) {
+                player.career.races_won += 1;
+                self.players.insert(&account_id, &player);
+            }
+        }
+        race.finishers.push(account_id);
+        match race.places_taken(&self.config.race_payout_schedule) {
+            true => self.settle(race_id, &mut race),
+            false => {
+                self.races.insert(&race_id, &race);
+            }
+        }
+        true
+    }
+
+    /// Settles a running race whose time limit has passed with paid places left, anybody may
+    /// call this.
+    pub fn settle_race(&mut self, race_id: u64) {
+        let mut race = self.race(race_id);
+        if race.status != RaceStatus::Running {
+            ContractError::RaceNotRunning.panic();
+        }
+        let ends_at = race.start_at + self.config.race_time_limit;
+        if env::block_timestamp_ms() < ends_at
+            && !race.places_taken(&self.config.race_payout_schedule)
+        {
+            ContractError::RaceNotOver { ends_at }.panic();
+        }
+
+        self.settle(race_id, &mut race);
+    }
+
+    /// Pays the pot out to the finishers, flagged accounts are skipped. Whatever is left goes
+    /// into the prize pool. The race is removed, the entrants and the creator get their storage
+    /// deposits back.
+    fn settle(&mut self, race_id: u64, race: &mut Race) {
+        let pot = race.pot();
+        let finishers: Vec<AccountId> = race
+            .finishers
+            .iter()
+            .filter(|account_id| !self.flagged.contains(account_id))
+            .cloned()
+            .collect();
+
+        let payouts = race::payouts(pot, &self.config.race_payout_schedule, &finishers);
+        for account_id in &race.entrants {
+            let payout = payouts
+                .iter()
+                .find(|(winner, _)| winner == account_id)
+                .map_or(0, |(_, amount)| *amount);
+            Promise::new(account_id.clone()).transfer(payout + race::entry_deposit(account_id));
+        }
+        self.prize_pool += pot - payouts.iter().map(|(_, amount)| amount).sum::<Balance>();
+        Promise::new(race.creator.clone()).transfer(race.deposit);
+        race.status = RaceStatus::Settled;
+        self.end_race(race_id, race);
+    }
+
+    /// Time based maintenance that otherwise waits for a player transaction crossing the
+    /// boundary, anybody may call this, e.g. a Croncat task or another keeper. Closes the ended
+    /// leaderboard period, archives the daily puzzle if the attached deposit covers
+    /// [`archive::ARCHIVED_PUZZLE_SIZE`] and starts or settles the races of `race_ids` that are
+    /// due. Unknown races and races not due yet are skipped. The unused deposit is refunded.
+    #[payable]
+    pub fn tick(&mut self, race_ids: Vec<u64>) -> TickRequest {
+        self.roll_period();
+        let archive_cost = archive::ARCHIVED_PUZZLE_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        let daily_archived = env::attached_deposit() >= archive_cost && self.archive_daily();
+        let refund = match daily_archived {
+            true => env::attached_deposit() - archive_cost,
+            false => env::attached_deposit(),
+        };
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        let now = env::block_timestamp_ms();
+        let mut races_started = 0;
+        let mut races_settled = 0;
+        for race_id in race_ids {
+            let mut race = match self.races.get(&race_id) {
+                Some(race) => race,
+                None => continue,
+            };
+            match race.status {
+                RaceStatus::Open if now >= race.start_at => {
+                    self.launch(race_id, &mut race);
+                    races_started += 1;
+                }
+                RaceStatus::Running if now >= race.start_at + self.config.race_time_limit => {
+                    self.settle(race_id, &mut race);
+                    races_settled += 1;
+                }
+                _ => {}
+            }
+        }
+
+        TickRequest {
+            period_id: self.period_id,
+            daily_archived,
+            races_started,
+            races_settled,
+        }
+    }
+
+    /// Settled and cancelled races are removed, their results are logged as `race_ended` events.
+    pub fn get_race(&self, race_id: u64) -> Option<RaceRequest> {
+        self.races.get(&race_id).map(|race| race.get())
+    }
+
+    /// Returns up to `limit` entries of `board` starting at `from_rank` (1 is first place).
+    /// Ties are broken by account id.
+    pub fn get_leaderboard_page(
+        &self,
+        board: BoardKind,
+        from_rank: u32,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        self.leaderboard
+            .top(board, self.config.leaderboard_size as usize)
+            .into_iter()
+            .enumerate()
+            .skip(from_rank.saturating_sub(1) as usize)
+            .take(limit as usize)
+            .map(|(idx, (value, account_id))| LeaderboardEntry {
+                rank: idx as u32 + 1,
+                value: U128(value),
+                profile: self
+                    .players
+                    .get(&account_id)
+                    .and_then(|player| player.profile),
+                account_id,
+            })
+            .collect()
+    }
+
+    /// Like [`Contract::get_leaderboard_page`], but ranks only verified players.
+    /// These are the leaderboards prizes are paid out for.
+    pub fn get_verified_leaderboard_page(
+        &self,
+        board: BoardKind,
+        from_rank: u32,
+        limit: u32,
+    ) -> Vec<LeaderboardEntry> {
+        let from = from_rank.saturating_sub(1);
+        self.leaderboard
+            .verified_ranking(board)
+            .iter_from(from as u64)
+            .zip(from..)
+            .take(limit as usize)
+            .map(|((key, account_id), position)| LeaderboardEntry {
+                rank: position + 1,
+                value: U128(board.sort_key(key)),
+                profile: self
+                    .players
+                    .get(&account_id)
+                    .and_then(|player| player.profile),
+                account_id,
+            })
+            .collect()
+    }
+
+    /// Adds the attached deposit, minus the storage of the entry, to the prize pool of the running
+    /// or a future period. `name` and `url` are shown with the period and validated like profiles.
+    #[payable]
+    pub fn sponsor_period(&mut self, period_id: u64, name: String, url: Option<String>) {
+        self.roll_period();
+        if period_id < self.period_id {
+            ContractError::PeriodEnded.panic();
+        }
+        if self.cancelled_periods.contains(&period_id) {
+            ContractError::PeriodCancelled.panic();
+        }
+        let profile = Profile::new(name, url).unwrap_or_else(|e| e.panic());
+
+        let mut sponsorships = self.sponsorships.get(&period_id).unwrap_or_default();
+        sponsorships.push(Sponsorship {
+            sponsor: env::predecessor_account_id(),
+            amount: 0,
+            name: profile.nickname,
+            url: profile.avatar_url,
+        });
+        let storage_before = env::storage_usage();
+        self.sponsorships.insert(&period_id, &sponsorships);
+        let cost = (env::storage_usage() - storage_before) as u128 * env::STORAGE_PRICE_PER_BYTE;
+        if env::attached_deposit() <= cost {
+            ContractError::InsufficientDeposit { expected: cost + 1 }.panic();
+        }
+
+        sponsorships.last_mut().unwrap().amount = env::attached_deposit() - cost;
+        self.sponsorships.insert(&period_id, &sponsorships);
+    }
+
+    /// Returns the funds the caller sponsored for `period_id` if the period was cancelled or ended
+    /// without any game transaction. Returns the refunded amount.
+    pub fn refund_sponsorship(&mut self, period_id: u64) -> U128 {
+        self.roll_period();
+        let refundable = self.cancelled_periods.contains(&period_id)
+            || (period_id < self.period_id && self.snapshots.get(&period_id).is_none());
+        if !refundable {
+            ContractError::NoRefund.panic();
+        }
+
+        let account_id = env::predecessor_account_id();
+        let (refunds, sponsorships): (Vec<_>, Vec<_>) = self
+            .sponsorships
+            .get(&period_id)
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|sponsorship| sponsorship.sponsor == account_id);
+        let amount = sponsor::total(&refunds);
+        if amount == 0 {
+            ContractError::NoRefund.panic();
+        }
+
+        self.sponsorships.insert(&period_id, &sponsorships);
+        Promise::new(account_id).transfer(amount);
+        U128(amount)
+    }
+
+    pub fn get_sponsorships(&self, period_id: u64) -> Vec<SponsorshipRequest> {
+        self.sponsorships
+            .get(&period_id)
+            .unwrap_or_default()
+            .iter()
+            .map(|sponsorship| sponsorship.get())
+            .collect()
+    }
+
+    /// Complete stored records of up to `limit` players starting at index `from_index`,
+    /// for indexers mirroring the contract state.
+    pub fn export_players(&self, from_index: u64, limit: u64) -> PlayersExport {
+        let keys = self.players.keys_as_vector();
+        let values = self.players.values_as_vector();
+        let end = from_index.saturating_add(limit).min(keys.len());
+        let players = (from_index..end)
+            .map(|index| {
+                let player = values.get(index).unwrap();
+                ExportedPlayer {
+                    account_id: keys.get(index).unwrap(),
+                    record: player.try_to_vec().unwrap().into(),
+                    last_sloved_game: player
+                        .last_sloved_game
+                        .get()
+                        .map(|game| game.try_to_vec().unwrap().into()),
+                }
+            })
+            .collect();
+
+        PlayersExport {
+            version: EXPORT_VERSION,
+            total: keys.len(),
+            players,
+        }
+    }
+
+    pub fn export_leaderboards(&self) -> LeaderboardsExport {
+        let size = self.config.leaderboard_size as usize;
+        LeaderboardsExport {
+            version: EXPORT_VERSION,
+            top_by_count: self
+                .leaderboard
+                .top(BoardKind::Count, size)
+                .into_iter()
+                .map(|(count, account_id)| (account_id, U128(count)))
+                .collect(),
+            top_by_time: self
+                .leaderboard
+                .top(BoardKind::Time, size)
+                .into_iter()
+                .map(|(time, account_id)| (account_id, time as Timestamp))
+                .collect(),
+            period_id: self.period_id,
+            season_end: self.season_end,
+        }
+    }
+
+    /// Measured storage of the records of `account_id` and the deposit held for them,
+    /// together with the storage of the whole contract.
+    pub fn get_storage_report(&self, account_id: AccountId) -> Option<StorageReport> {
+        let player = self.players.get(&account_id)?;
+        let attestation_count = self.attestation_counts.get(&account_id).unwrap_or(0);
+        let mut player_bytes = storage::unordered_map_size(&self.players, &account_id)
+            + player.last_sloved_game.storage_size()
+            + player.last_replay.storage_size()
+            + player.notes.storage_size()
+            + player.replay.storage_size()
+            + self.leaderboard.storage_size(&account_id)
+            + self.sized_boards.storage_size(&account_id)
+            + storage::lookup_size(&self.prizes, &account_id)
+            + storage::lookup_size(&self.flagged, &account_id)
+            + storage::lookup_size(&self.pool_funded, &account_id)
+            + storage::lookup_size(&self.pending_generations, &account_id)
+            + storage::lookup_size(&self.sized_games, &account_id)
+            + storage::lookup_size(&self.attestation_counts, &account_id);
+        for index in 0..attestation_count {
+            player_bytes += storage::lookup_size(&self.attestations, &(account_id.clone(), index));
+        }
+        let archive_dates = player.archive_dates.get(&self.archive_dates, &account_id);
+        for (index, date) in (0u32..).zip(archive_dates) {
+            player_bytes +=
+                storage::lookup_size(&self.archive_completions, &(account_id.clone(), date))
+                    + storage::lookup_size(&self.archive_dates, &(account_id.clone(), index));
+        }
+
+        let mut player_bytes_paid = 0;
+        if player.verified() {
+            player_bytes_paid += VERIFIED_SIZE;
+        }
+        if self.pending_generations.contains_key(&account_id) {
+            player_bytes_paid += generation::PENDING_GENERATION_SIZE;
+        }
+        let ranked_sizes = BoardSize::ALL
+            .iter()
+            .filter(|&&size| self.sized_boards.is_ranked(&account_id, size))
+            .count();
+        player_bytes_paid += ranked_sizes as u128 * SIZED_RANKING_SIZE;
+        player_bytes_paid += player.archive_dates.len() as u128 * archive::ARCHIVE_COMPLETION_SIZE;
+        if attestation_count > 0 {
+            player_bytes_paid += attestation::ATTESTATION_COUNT_SIZE
+                + attestation_count as u128 * attestation::ATTESTATION_SIZE;
+        }
+        let mut player_deposit = self.config.player_deposit()
+            + player_bytes_paid * env::STORAGE_PRICE_PER_BYTE
+            + player.replay_deposit;
+        if let Some(game) = self.sized_games.get(&account_id) {
+            player_deposit += game.deposit;
+        }
+        if self.archive_deposit_held(&account_id, &player) {
+            player_deposit += archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        }
+        Some(StorageReport {
+            player_bytes,
+            player_deposit: U128(player_deposit),
+            contract_bytes: env::storage_usage(),
+            contract_deposit: U128(env::storage_usage() as u128 * env::STORAGE_PRICE_PER_BYTE),
+        })
+    }
+
+    /// Final standings of `period_id` together with their hash.
+    /// `None` while the period is running and for periods without any game transaction.
+    pub fn get_snapshot(&self, period_id: u64) -> Option<SnapshotRequest> {
+        self.snapshots
+            .get(&period_id)
+            .map(|snapshot| snapshot.get())
+    }
+
+    /// Compacts the snapshots of `period_ids` that ended more than
+    /// [`Config::snapshot_retention`] periods ago to the first [`snapshot::ARCHIVED_PLACES`]
+    /// of every board, anybody may call this. With `remove` the snapshots and the sponsorships
+    /// of their periods are deleted instead, which only the owner may do. The full snapshots
+    /// are logged in a `snapshots_pruned` event first.
+    /// Periods within the retention, without snapshot or already compacted are skipped.
+    /// Returns the number of pruned snapshots.
+    pub fn prune_snapshots(&mut self, period_ids: Vec<u64>, remove: bool) -> u32 {
+        if remove {
+            self.assert_owner();
+        }
+        self.roll_period();
+
+        let mut pruned = vec![];
+        for period_id in period_ids {
+            if period_id + self.config.snapshot_retention >= self.period_id {
+                continue;
+            }
+            let mut snapshot = match self.snapshots.get(&period_id) {
+                Some(snapshot) if remove || !snapshot.compacted() => snapshot,
+                _ => continue,
+            };
+            pruned.push(snapshot.get());
+            if remove {
+                self.snapshots.remove(&period_id);
+                // sponsorships of periods without snapshot are refundable
+                self.sponsorships.remove(&period_id);
+            } else {
+                snapshot.compact();
+                self.snapshots.insert(&period_id, &snapshot);
+            }
+        }
+
+        events::snapshots_pruned(&pruned);
+        pruned.len() as u32
+    }
+
+    pub fn get_prize_pool(&self) -> U128 {
+        U128(self.prize_pool)
+    }
+
+    /// Funds left for free registrations
+    pub fn get_registration_pool(&self) -> U128 {
+        U128(self.registration_pool)
+    }
+
+    pub fn get_prize(&self, account_id: AccountId) -> Option<PrizeRequest> {
+        self.prizes.get(&account_id).map(|prize| prize.get())
+    }
+
+    /// Entries of the finished game `game_id` of `account_id`, to be played back as a ghost.
+    /// Only the last finished game of players sharing their replays is kept.
+    pub fn get_replay(&self, account_id: AccountId, game_id: u64) -> Option<Replay> {
+        let player = self.players.get(&account_id)?;
+        if !player.share_replays {
+            return None;
+        }
+        player
+            .last_replay
+            .get()
+            .filter(|replay| replay.game_id == game_id)
+    }
+
+    /// Attestation `index` of `account_id`, counted from 0 over the attested ranked solves.
+    /// Attestations outlive the player and are never replaced, a player who registers again
+    /// continues the count.
+    pub fn verify_attestation(
+        &self,
+        account_id: AccountId,
+        index: u64,
+    ) -> Option<AttestationRequest> {
+        self.attestations
+            .get(&(account_id.clone(), index))
+            .map(|attestation| attestation.get(account_id, index))
+    }
+
+    /// Ordered entries of the finished game `game_id` of `account_id`, recorded for every player
+    /// whose prepaid balance covers their storage while [`Config::record_moves`] is set. Their
+    /// hash is kept in the last solved game.
+    pub fn get_move_history(&self, account_id: AccountId, game_id: u64) -> Option<Replay> {
+        self.players
+            .get(&account_id)?
+            .last_replay
+            .get()
+            .filter(|replay| replay.game_id == game_id)
+    }
+
+    pub fn get_campaign_progress(&self, account_id: AccountId) -> CampaignProgress {
+        let player = self.players.get(&account_id);
+        let completed_levels = player.as_ref().map_or(0, |player| player.completed_levels);
+
+        CampaignProgress {
+            completed_levels,
+            total_levels: campaign::LEVELS.len() as u32,
+            current_level: player.and_then(|player| player.level),
+            next_difficulty: campaign::level_difficulty(completed_levels + 1),
+        }
+    }
+
+    /// Returns the rank of `account_id` among all players on `board`, together with its neighbours.
+    pub fn get_rank(&self, account_id: AccountId, board: BoardKind) -> Option<RankRequest> {
+        let ranking = self.leaderboard.ranking(board);
+        let position = ranking.position(&account_id)?;
+
+        let entry = |position: u64| {
+            ranking
+                .get(position)
+                .map(|(key, account_id)| LeaderboardEntry {
+                    rank: position as u32 + 1,
+                    value: U128(board.sort_key(key)),
+                    profile: self
+                        .players
+                        .get(&account_id)
+                        .and_then(|player| player.profile),
+                    account_id,
+                })
+        };
+
+        Some(RankRequest {
+            entry: entry(position).unwrap(),
+            above: position.checked_sub(1).and_then(entry),
+            below: entry(position + 1),
+        })
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+    use near_sdk::{Gas, ONE_NEAR};
+
+    use super::*;
+    use crate::sized::{Sudoku16, Sudoku4};
+    use crate::{novelty, replay, snapshot};
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
+
+    /// Registers `account` with a balance covering the fees of 100 games
+    fn register(contract: &mut Contract, account: AccountId) {
+        let mut context = get_context(account);
+        context.attached_deposit(contract.config.player_deposit() + 100 * DEFAULT_GAME_FEE);
+        testing_env!(context.build());
+        contract.register();
+    }
+
+    fn start_game(contract: &mut Contract, account: AccountId) {
+        if contract.players.get(&account).is_none() {
+            register(contract, account.clone());
+        }
+        let mut context = get_context(account);
+        context.block_timestamp(0);
+        testing_env!(context.build());
+
+        contract.start_game(None);
+        contract.assert_invariants();
+    }
+
+    /// Plays a ranked game solved after `time` milliseconds and returns its graded difficulty
+    fn play(contract: &mut Contract, account: AccountId, time: Timestamp) -> Difficulty {
+        let mut context = get_context(account.clone());
+        start_game(contract, account.clone());
+
+        let sudoku = contract.players.get(&account).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        context.block_timestamp(time * 1_000_000);
+        testing_env!(context.build());
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+        contract.assert_invariants();
+        sudoku.grade()
+    }
+
+    #[test]
+    fn leaderboard() {
+        let mut contract = Contract::new();
+        contract.config.start_game_cooldown = 0;
+
+        play(&mut contract, accounts(0), 1000);
+        start_game(&mut contract, accounts(0));
+        play(&mut contract, accounts(0), 900);
+        play(&mut contract, accounts(0), 900);
+        start_game(&mut contract, accounts(0));
+        play(&mut contract, accounts(0), 900);
+
+        play(&mut contract, accounts(1), 950);
+        play(&mut contract, accounts(1), 800);
+
+        play(&mut contract, accounts(2), 100);
+
+        play(&mut contract, accounts(3), 1000);
+        play(&mut contract, accounts(3), 1000);
+        play(&mut contract, accounts(3), 1000);
+        play(&mut contract, accounts(3), 1000);
+        play(&mut contract, accounts(3), 1000);
+
+        let ranking = |board| -> Vec<(u32, AccountId, u128)> {
+            contract
+                .get_leaderboard_page(board, 1, 10)
+                .into_iter()
+                .map(|entry| (entry.rank, entry.account_id, entry.value.0))
+                .collect()
+        };
+        assert_eq!(
+            ranking(BoardKind::Count),
+            vec![
+                (1, accounts(3), 5),
+                (2, accounts(0), 4),
+                (3, accounts(1), 2),
+                (4, accounts(2), 1)
+            ]
+        );
+        assert_eq!(
+            ranking(BoardKind::Time),
+            vec![
+                (1, accounts(2), 100),
+                (2, accounts(1), 800),
+                (3, accounts(0), 900),
+                (4, accounts(3), 1000)
+            ]
+        );
+
+        let page = contract.get_leaderboard_page(BoardKind::Time, 2, 2);
+        assert_eq!(page.len(), 2);
+        assert_eq!((page[0].rank, &page[0].account_id), (2, &accounts(1)));
+        assert_eq!((page[1].rank, &page[1].account_id), (3, &accounts(0)));
+        assert!(contract
+            .get_leaderboard_page(BoardKind::Time, 5, 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn rank_beyond_leaderboard() {
+        let mut contract = Contract::new();
+
+        let players: Vec<AccountId> = (0..LEADERBOARD_SIZE as u64 + 2)
+            .map(|i| format!("player{}.near", i).parse().unwrap())
+            .collect();
+        for (i, account_id) in players.iter().enumerate() {
+            play(&mut contract, account_id.clone(), 100 + i as u64);
+        }
+
+        let last = players.last().unwrap().clone();
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Time, 1, 100).len(),
+            LEADERBOARD_SIZE as usize
+        );
+
+        let rank = contract.get_rank(last.clone(), BoardKind::Time).unwrap();
+        assert_eq!(rank.entry.rank, players.len() as u32);
+        assert_eq!(rank.entry.value, U128(100 + players.len() as u128 - 1));
+        assert_eq!(rank.above.unwrap().account_id, players[players.len() - 2]);
+        assert!(rank.below.is_none());
+
+        let rank = contract
+            .get_rank(players[0].clone(), BoardKind::Time)
+            .unwrap();
+        assert_eq!(rank.entry.rank, 1);
+        assert!(rank.above.is_none());
+        assert_eq!(rank.below.unwrap().account_id, players[1]);
+
+        assert!(contract.get_rank(accounts(5), BoardKind::Count).is_none());
+    }
+
+    #[test]
+    fn period_snapshot() {
+        let mut contract = Contract::new();
+        contract.config.period_length = 10_000;
+
+        play(&mut contract, accounts(0), 1000);
+        assert!(contract.get_snapshot(0).is_none());
+
+        // finishing in period 2 closes period 0 before the solve counts
+        play(&mut contract, accounts(1), 25_000);
+        assert_eq!(contract.period_id, 2);
+        assert!(contract.get_snapshot(1).is_none());
+
+        let snapshot = contract.get_snapshot(0).unwrap();
+        assert_eq!(snapshot.taken_at, 25_000);
+        assert_eq!(
+            snapshot.standings.by_time,
+            vec![StandingsEntry {
+                account_id: accounts(0),
+                value: U128(1000),
+            }]
+        );
+        let hash = env::sha256(&snapshot.standings.try_to_vec().unwrap());
+        assert_eq!(
+            snapshot.hash,
+            hash.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        );
+
+        // only solves of the period itself count, accounts(0) didn't solve in period 2
+        play(&mut contract, accounts(2), 35_000);
+        let standings = contract.get_snapshot(2).unwrap().standings;
+        assert_eq!(
+            standings.by_count,
+            vec![StandingsEntry {
+                account_id: accounts(1),
+                value: U128(1),
+            }]
+        );
+        assert_eq!(
+            standings.by_time,
+            vec![StandingsEntry {
+                account_id: accounts(1),
+                value: U128(25_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn prune_snapshots() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.config.period_length = 10_000;
+        contract.update_config(Config {
+            snapshot_retention: 1,
+            ..contract.get_config()
+        });
+        for (i, account_id) in [accounts(1), accounts(2), accounts(3), accounts(4)]
+            .iter()
+            .enumerate()
+        {
+            play(&mut contract, account_id.clone(), 1000 + i as u64);
+        }
+        // closes period 0, period 1 is within the retention once period 2 runs
+        play(&mut contract, accounts(1), 15_000);
+        play(&mut contract, accounts(1), 25_000);
+        assert_eq!(contract.period_id, 2);
+        let hash = contract.get_snapshot(0).unwrap().hash;
+
+        testing_env!(get_context(accounts(5)).build());
+        assert_eq!(contract.prune_snapshots(vec![0, 1, 7], false), 1);
+        assert!(get_logs()[0].contains(r#""event":"snapshots_pruned""#));
+        let snapshot = contract.get_snapshot(0).unwrap();
+        assert!(snapshot.compacted);
+        assert_eq!(snapshot.standings.by_time.len(), snapshot::ARCHIVED_PLACES);
+        assert_eq!(snapshot.hash, hash);
+        assert!(!contract.get_snapshot(1).unwrap().compacted);
+        assert_eq!(contract.prune_snapshots(vec![0], false), 0);
+
+        testing_env!(get_context(accounts(0)).build());
+        assert_eq!(contract.prune_snapshots(vec![0], true), 1);
+        assert!(contract.get_snapshot(0).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_OWNER")]
+    fn remove_snapshots_not_owner() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        testing_env!(get_context(accounts(1)).build());
+        contract.prune_snapshots(vec![0], true);
+    }
+
+    /// Lets accounts(1) win both verified boards of period 0 and settles it at 25 seconds.
+    fn settle_prize_period() -> Contract {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_season_end(u64::MAX);
+        contract.config.period_length = 10_000;
+
+        play(&mut contract, accounts(1), 1000);
+        verify(&mut contract, accounts(1), 2000);
+        play(&mut contract, accounts(2), 25_000);
+        contract
+    }
+
+    #[test]
+    fn prize_pool() {
+        let mut contract = settle_prize_period();
+
+        // two fees were collected, first place gets half of each board's half of the pool
+        let prize = contract.get_prize(accounts(1)).unwrap();
+        assert_eq!(prize.amount, U128(DEFAULT_GAME_FEE));
+        assert_eq!(prize.expires_at, 25_000 + prize::PRIZE_CLAIM_PERIOD);
+        assert_eq!(contract.get_prize_pool(), U128(DEFAULT_GAME_FEE));
+        assert!(contract.get_prize(accounts(2)).is_none());
+
+        let mut context = get_context(accounts(1));
+        context.block_timestamp(26_000 * 1_000_000);
+        testing_env!(context.build());
+        assert_eq!(contract.claim_prize(), U128(DEFAULT_GAME_FEE));
+        assert_eq!(contract.unclaimed_prizes, 0);
+        assert!(contract.get_prize(accounts(1)).is_none());
+    }
+
+    #[test]
+    fn sweep_expired_prizes() {
+        let mut contract = settle_prize_period();
+
+        let mut context = get_context(accounts(2));
+        context.block_timestamp((25_000 + prize::PRIZE_CLAIM_PERIOD) * 1_000_000);
+        testing_env!(context.build());
+        assert_eq!(
+            contract.sweep_expired_prizes(vec![accounts(1), accounts(2)]),
+            U128(DEFAULT_GAME_FEE)
+        );
+        assert_eq!(contract.get_prize_pool(), U128(2 * DEFAULT_GAME_FEE));
+        assert_eq!(contract.unclaimed_prizes, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PRIZE_EXPIRED")]
+    fn claim_expired_prize() {
+        let mut contract = settle_prize_period();
+
+        let mut context = get_context(accounts(1));
+        context.block_timestamp((25_000 + prize::PRIZE_CLAIM_PERIOD) * 1_000_000);
+        testing_env!(context.build());
+        contract.claim_prize();
+    }
+
+    #[test]
+    fn delete_player_leaves_leaderboard_consistent() {
+        let mut contract = Contract::new();
+
+        play(&mut contract, accounts(0), 1000);
+        play(&mut contract, accounts(1), 900);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.delete_player();
+        contract.assert_invariants();
+
+        let ranked = |board| contract.leaderboard.ranking(board).key(&accounts(0));
+        assert_eq!(ranked(BoardKind::Count), None);
+        assert_eq!(ranked(BoardKind::Time), None);
+        assert!(contract
+            .leaderboard
+            .ranking(BoardKind::Count)
+            .key(&accounts(1))
+            .is_some());
+    }
+
+    #[test]
+    fn delete_player_removes_last_sloved_game() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        assert!(contract
+            .get_player(accounts(0))
+            .unwrap()
+            .last_sloved_game
+            .is_some());
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.delete_player();
+        assert!(!env::storage_has_key(
+            &[b"l", accounts(0).as_str().as_bytes()].concat()
+        ));
+    }
+
+    #[test]
+    fn assisted_solve() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let cell = sudoku.0.iter().position(|&num| num == 0).unwrap() as u8;
+        contract.reveal_cell(cell / 9, cell % 9);
+        assert!(contract.players.get(&accounts(0)).unwrap().assisted());
+
+        let solution = sudoku.solution().unwrap();
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+        contract.assert_invariants();
+
+        let player = contract.get_player(accounts(0)).unwrap();
+        assert_eq!(player.sloved_sudoku_count, U128(1));
+        assert_eq!(player.best_time, None);
+        assert_eq!(player.best_block_time, None);
+        assert_eq!(player.stats[&sudoku.grade()].solved_count, 1);
+        assert!(contract
+            .get_leaderboard_page(BoardKind::Time, 1, 10)
+            .is_empty());
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Count, 1, 10)[0].account_id,
+            accounts(0)
+        );
+
+        // the next unassisted solve sets the best time
+        play(&mut contract, accounts(0), 2000);
+        assert_eq!(
+            contract.get_player(accounts(0)).unwrap().best_time,
+            Some(2000)
+        );
+    }
+
+    #[test]
+    fn block_time_board() {
+        let mut contract = Contract::new();
+        let games = [(accounts(0), 5, 1000), (accounts(1), 3, 100_000)];
+        for (account, height, time) in games.iter().cloned() {
+            start_game(&mut contract, account.clone());
+            let solution = contract.players.get(&account).unwrap().sudoku.unwrap();
+            let mut context = get_context(account);
+            context.block_index(height);
+            context.block_timestamp(time * 1_000_000);
+            testing_env!(context.build());
+            contract.finish_game(
+                &solution
+                    .solution()
+                    .unwrap()
+                    .to_two_dimensional_array()
+                    .into(),
+            );
+        }
+
+        let page = contract.get_leaderboard_page(BoardKind::BlockTime, 1, 10);
+        assert_eq!(page[0].account_id, accounts(1));
+        assert_eq!(page[0].value, U128(3 * AVERAGE_BLOCK_TIME as u128));
+        assert_eq!(page[1].value, U128(5 * AVERAGE_BLOCK_TIME as u128));
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Time, 1, 1)[0].account_id,
+            accounts(0)
+        );
+        assert_eq!(
+            contract
+                .get_rank(accounts(0), BoardKind::BlockTime)
+                .unwrap()
+                .entry
+                .rank,
+            2
+        );
+    }
+
+    #[test]
+    fn cleanup_stale() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        play(&mut contract, accounts(1), DEFAULT_STALE_PERIOD);
+
+        let deposit = contract.config.player_deposit();
+        let mut context = get_context(accounts(3));
+        context.attached_deposit(deposit);
+        testing_env!(context.build());
+        contract.fund_registrations();
+        contract.register();
+
+        // the deposit of a player registered from the pool goes back to the pool, without reward
+        let mut context = get_context(accounts(2));
+        context.block_timestamp((1000 + DEFAULT_STALE_PERIOD) * 1_000_000);
+        testing_env!(context.build());
+        assert_eq!(contract.cleanup_stale(vec![accounts(3)]), U128(0));
+        assert_eq!(contract.get_registration_pool(), U128(deposit));
+        assert!(transfers_to(&accounts(2)).is_empty());
+
+        let storage_before = env::storage_usage();
+        let reward = contract.cleanup_stale(vec![accounts(0), accounts(1), accounts(3)]);
+        contract.assert_invariants();
+
+        let freed = storage_before - env::storage_usage();
+        let freed = freed.min(contract.config.player_size) as u128;
+        assert_eq!(
+            reward,
+            U128(freed * env::STORAGE_PRICE_PER_BYTE * CLEANUP_REWARD_PERCENT as u128 / 100)
+        );
+        assert!(contract.get_player(accounts(0)).is_none());
+        assert!(contract.get_player(accounts(1)).is_some());
+    }
+
+    #[test]
+    fn flag_player() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(1), 1000);
+        play(&mut contract, accounts(2), 2000);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.flag_player(accounts(1));
+        contract.assert_invariants();
+        assert!(contract.is_flagged(accounts(1)));
+        assert!(contract.get_rank(accounts(1), BoardKind::Time).is_none());
+
+        // flagged players keep playing without entering the leaderboards
+        play(&mut contract, accounts(1), 500);
+        assert_eq!(
+            contract.get_player(accounts(1)).unwrap().best_time,
+            Some(500)
+        );
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Time, 1, 10)[0].account_id,
+            accounts(2)
+        );
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.unflag_player(accounts(1));
+        play(&mut contract, accounts(1), 800);
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Time, 1, 10)[0].account_id,
+            accounts(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_SOLVE_TOO_FAST{\"available_at\":20000}")]
+    fn solve_too_fast() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.config.min_solve_times = [20_000; 4];
+        play(&mut contract, accounts(1), 19_999);
+    }
+
+    #[test]
+    fn solve_at_min_solve_time() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.config.min_solve_times = [20_000, 20_000, 20_000, 30_000];
+        assert_ne!(play(&mut contract, accounts(1), 20_000), Difficulty::Expert);
+        assert_eq!(
+            contract.get_player(accounts(1)).unwrap().best_time,
+            Some(20_000)
+        );
+    }
+
+    fn create_coop(contract: &mut Contract, partners: Vec<AccountId>) -> u64 {
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        contract.create_coop(partners)
+    }
+
+    #[test]
+    fn coop_game() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        start_game(&mut contract, accounts(1));
+        let game_id = create_coop(&mut contract, vec![accounts(1)]);
+
+        let sudoku = contract.coop_games.get(&game_id).unwrap().sudoku;
+        let solution = sudoku.solution().unwrap().to_two_dimensional_array();
+        let mut progress = sudoku.to_two_dimensional_array();
+        progress[0] = solution[0];
+
+        testing_env!(get_context(accounts(1)).build());
+        let game = contract.save_coop_progress(game_id, &progress);
+        assert_eq!(game.progress, Some(progress));
+        assert!(!contract.finish_coop(game_id, &progress.into()));
+        assert!(contract.finish_coop(game_id, &solution.into()));
+
+        assert!(contract.get_coop_game(game_id).is_none());
+        for account_id in [accounts(0), accounts(1)].iter() {
+            let player = contract.get_player(account_id.clone()).unwrap();
+            assert_eq!(player.coop_sloved_count, 1);
+            assert_eq!(player.sloved_sudoku_count, U128(0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_PARTICIPANT")]
+    fn coop_game_not_participant() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        start_game(&mut contract, accounts(1));
+        let game_id = create_coop(&mut contract, vec![accounts(1)]);
+
+        testing_env!(get_context(accounts(2)).build());
+        contract.save_coop_progress(game_id, &[[0; 9]; 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_PARTICIPANTS")]
+    fn coop_game_unregistered_partner() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        create_coop(&mut contract, vec![accounts(1)]);
+    }
+
+    #[test]
+    fn points_board() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 600_000);
+        let first = play(&mut contract, accounts(1), 90_000);
+        let second = play(&mut contract, accounts(1), 90_000);
+
+        let points = |difficulty| score::score(difficulty, 90_000, 0, score::NO_HINT_BONUS_PERCENT);
+        let page = contract.get_leaderboard_page(BoardKind::Points, 1, 10);
+        assert_eq!(page[0].account_id, accounts(1));
+        assert_eq!(page[0].value, U128(points(first) + points(second)));
+        assert_eq!(page[1].account_id, accounts(0));
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_season_end(1_000_000);
+        assert!(contract
+            .get_leaderboard_page(BoardKind::Points, 1, 10)
+            .is_empty());
+
+        let third = play(&mut contract, accounts(1), 90_000);
+        let player = contract.get_player(accounts(1)).unwrap();
+        assert_eq!(player.season_points, U128(points(third)));
+        assert_eq!(player.points_season_end, 1_000_000);
+        contract.assert_invariants();
+    }
+
+    #[test]
+    fn career() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_season_end(1_000_000);
+        play(&mut contract, accounts(0), 600_000);
+        let mut difficulties = vec![
+            play(&mut contract, accounts(1), 90_000),
+            play(&mut contract, accounts(1), 90_000),
+        ];
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_season_end(2_000_000);
+        difficulties.push(play(&mut contract, accounts(1), 90_000));
+
+        let points: u128 = difficulties
+            .iter()
+            .map(|&difficulty| score::score(difficulty, 90_000, 0, score::NO_HINT_BONUS_PERCENT))
+            .sum();
+        let career = contract.get_career(accounts(1)).unwrap();
+        assert_eq!(career.sloved_count, U128(3));
+        assert_eq!(career.points, U128(points));
+        assert_eq!(career.seasons_won, 1);
+        for difficulty in Difficulty::ALL {
+            let best_time = difficulties.contains(&difficulty).then_some(90_000);
+            assert_eq!(career.best_times[&difficulty], best_time);
+        }
+        assert_eq!(contract.get_career(accounts(0)).unwrap().seasons_won, 0);
+
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        contract.start_race(race_id);
+        let solution = contract.races.get(&race_id).unwrap().sudoku.unwrap().solution().unwrap();
+        testing_env!(get_context(accounts(1)).build());
+        contract.finish_race(race_id, &solution.to_two_dimensional_array().into());
+        let career = contract.get_career(accounts(1)).unwrap();
+        assert_eq!(career.races_entered, 1);
+        assert_eq!(career.races_won, 1);
+    }
+
+    #[test]
+    fn race_won_by_first_unflagged_finisher() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            register(&mut contract, account_id);
+        }
+        testing_env!(get_context(accounts(0)).build());
+        contract.flag_player(accounts(1));
+
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        contract.start_race(race_id);
+        let sudoku = contract.races.get(&race_id).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap().to_two_dimensional_array();
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            testing_env!(get_context(account_id).build());
+            assert!(contract.finish_race(race_id, &solution.into()));
+        }
+        let races_won = |account_id| contract.get_career(account_id).unwrap().races_won;
+        assert_eq!(races_won(accounts(1)), 0);
+        assert_eq!(races_won(accounts(2)), 1);
+        assert_eq!(races_won(accounts(3)), 0);
+    }
+
+    #[test]
+    fn reveal_cell() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        let cell = sudoku.0.iter().position(|&num| num == 0).unwrap();
+
+        let player = contract.reveal_cell(cell as u8 / 9, cell as u8 % 9);
+        assert_eq!(player.hints_used, 1);
+        assert_eq!(
+            player.progress.unwrap()[cell / 9][cell % 9],
+            solution.0[cell]
+        );
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(90_000 * 1_000_000);
+        testing_env!(context.build());
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+        let player = contract.get_player(accounts(0)).unwrap();
+        assert_eq!(
+            player.season_points,
+            U128(score::score(
+                sudoku.grade(),
+                90_000,
+                1,
+                score::NO_HINT_BONUS_PERCENT
+            ))
+        );
+        assert_eq!(
+            contract
+                .get_statistics(accounts(0))
+                .unwrap()
+                .hint_usage_percent,
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let mut contract = Contract::new();
+        contract.config.start_game_cooldown = 0;
+        start_game(&mut contract, accounts(0));
+        let first = play(&mut contract, accounts(0), 90_000);
+        let second = play(&mut contract, accounts(0), 30_000);
+
+        let statistics = contract.get_statistics(accounts(0)).unwrap();
+        assert_eq!(statistics.solved_count, 2);
+        assert_eq!(statistics.abandoned_count, 1);
+        assert_eq!(statistics.solve_rate_percent, Some(66));
+        assert_eq!(statistics.average_time, Some(60_000));
+        assert_eq!(statistics.histogram[0].count, 1);
+        assert_eq!(statistics.histogram[1].count, 1);
+        assert_eq!(statistics.hint_usage_percent, Some(0));
+        let solved_count = statistics
+            .by_difficulty
+            .iter()
+            .filter(|(difficulty, _)| [first, second].contains(difficulty))
+            .map(|(_, stats)| stats.solved_count)
+            .sum::<u64>();
+        assert_eq!(solved_count, 2);
+        assert!(contract.get_statistics(accounts(1)).is_none());
+    }
+
+    #[test]
+    fn restart_current() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let mut progress = sudoku.to_two_dimensional_array();
+        progress[0] = sudoku.solution().unwrap().to_two_dimensional_array()[0];
+        contract.save_progress(&progress);
+        contract.finish_game(&progress.into());
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(5000 * 1_000_000);
+        testing_env!(context.build());
+        let player = contract.restart_current();
+        assert_eq!(player.sudoku, Some(sudoku.to_two_dimensional_array()));
+        assert_eq!(player.progress, None);
+        assert_eq!(player.start_time, 5000);
+        assert_eq!(player.wrong_submissions, 0);
+        assert_eq!(player.generated_sudoku_count, U128(1));
+        assert!(player.practice);
+
+        let solution = sudoku.solution().unwrap().to_two_dimensional_array();
+        let player = contract.finish_practice(&solution.into()).unwrap();
+        assert_eq!(player.sloved_sudoku_count, U128(0));
+        assert_eq!(player.best_time, None);
+        assert!(contract.get_rank(accounts(0), BoardKind::Count).is_none());
+    }
+
+    #[test]
+    fn verify_solution() {
+        let contract = Contract::new();
+        let sudoku = Sudoku::generate(&mut StdRng::from_seed([3; 32]));
+        let solution = sudoku.solution().unwrap();
+        let puzzle = sudoku.to_str_line().to_string();
+        let line = solution.to_str_line().to_string();
+
+        assert_eq!(
+            contract.verify_solution(puzzle.clone(), line.clone()),
+            VerifyResult::Valid
+        );
+        assert_eq!(
+            contract.verify_solution("123".to_string(), line.clone()),
+            VerifyResult::InvalidPuzzle
+        );
+        assert_eq!(
+            contract.verify_solution(puzzle.clone(), puzzle.clone()),
+            VerifyResult::Incomplete
+        );
+
+        let mut swapped = solution.to_two_dimensional_array();
+        swapped[0][0] = swapped[0][1];
+        assert_eq!(
+            contract.verify_solution(
+                puzzle.clone(),
+                Sudoku::from_two_dimensional_array(&swapped)
+                    .to_str_line()
+                    .to_string()
+            ),
+            VerifyResult::RulesViolated
+        );
+
+        let other = Sudoku::generate(&mut StdRng::from_seed([4; 32]));
+        assert_eq!(
+            contract.verify_solution(other.to_str_line().to_string(), line),
+            VerifyResult::CluesChanged
+        );
+    }
+
+    #[test]
+    fn check_unique() {
+        let contract = Contract::new();
+        let sudoku = Sudoku::generate(&mut StdRng::from_seed([3; 32]));
+        assert!(contract.check_unique(sudoku.to_str_line().to_string()));
+        assert!(!contract.check_unique(".".repeat(81)));
+        assert!(!contract.check_unique("123".to_string()));
+
+        // a clue conflicting with the solution leaves no solution at all
+        let mut conflicting = sudoku.to_two_dimensional_array();
+        let empty = conflicting[0].iter().position(|&num| num == 0).unwrap();
+        conflicting[0][empty] =
+            sudoku.solution().unwrap().to_two_dimensional_array()[0][(empty + 1) % 9];
+        let conflicting = Sudoku::from_two_dimensional_array(&conflicting);
+        assert!(!contract.check_unique(conflicting.to_str_line().to_string()));
+    }
+
+    #[test]
+    fn archived_puzzle() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        let puzzle = contract.get_archived_puzzle(0).unwrap();
+        assert_eq!(puzzle.difficulty, archive::daily_difficulty(0));
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(reward::DAY * 1_000_000);
+        context.attached_deposit(archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE);
+        testing_env!(context.build());
+        let player = contract.start_archived(0);
+        assert_eq!(player.sudoku, Some(puzzle.sudoku));
+        assert_eq!(player.archive_date, Some(0));
+        assert!(contract.get_archived_puzzle(1).is_some());
+
+        let solution = Sudoku::from_two_dimensional_array(&puzzle.sudoku)
+            .solution()
+            .unwrap();
+        let player = contract
+            .finish_practice(&solution.to_two_dimensional_array().into())
+            .unwrap();
+        assert_eq!(player.archive_sloved_count, 1);
+        assert_eq!(player.sloved_sudoku_count, U128(1));
+        assert!(contract.is_archive_completed(accounts(0), 0));
+        assert!(!contract.is_archive_completed(accounts(0), 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_ARCHIVE_NOT_FOUND")]
+    fn archived_puzzle_not_found() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        testing_env!(get_context(accounts(0))
+            .block_timestamp(6 * reward::DAY * 1_000_000)
+            .build());
+        contract.start_archived(5);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_ARCHIVE_NOT_CLOSED")]
+    fn archived_puzzle_of_today() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        contract.start_archived(0);
+    }
+
+    #[test]
+    fn archived_puzzle_deposit() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        let puzzle = contract.get_archived_puzzle(0).unwrap();
+        let deposit = archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE;
+
+        let mut context = get_context(accounts(0));
+        context
+            .block_timestamp(reward::DAY * 1_000_000)
+            .attached_deposit(2 * deposit);
+        testing_env!(context.build());
+        let balance = contract.start_archived(0).balance.0;
+
+        // giving the game up credits the deposit to the prepaid balance
+        let start_time = reward::DAY + contract.config.start_game_cooldown;
+        context
+            .block_timestamp(start_time * 1_000_000)
+            .attached_deposit(0);
+        testing_env!(context.build());
+        assert_eq!(contract.start_practice().balance.0, balance + deposit);
+
+        // the solve keeps it for the completion record, which goes with the player
+        context
+            .block_timestamp((start_time + contract.config.start_game_cooldown) * 1_000_000)
+            .attached_deposit(deposit);
+        testing_env!(context.build());
+        contract.start_archived(0);
+        let solution = Sudoku::from_two_dimensional_array(&puzzle.sudoku)
+            .solution()
+            .unwrap();
+        let player = contract
+            .finish_practice(&solution.to_two_dimensional_array().into())
+            .unwrap();
+        assert_eq!(player.balance.0, balance + deposit);
+        assert!(contract.is_archive_completed(accounts(0), 0));
+
+        contract.delete_player();
+        assert!(!contract.is_archive_completed(accounts(0), 0));
+    }
+
+    #[test]
+    fn finish_and_start() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+
+        let result = contract.finish_and_start(&[[0; 9]; 9].into());
+        assert!(!result.solved);
+        assert_eq!(result.player.wrong_submissions, 1);
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        let result = contract
+            .finish_and_start(&sudoku.solution().unwrap().to_two_dimensional_array().into());
+        assert!(result.solved);
+        assert_eq!(result.player.sloved_sudoku_count, U128(1));
+        assert_eq!(result.player.generated_sudoku_count, U128(2));
+        assert_eq!(result.player.start_time, 1000);
+        assert_eq!(
+            result.player.last_sloved_game.unwrap().sudoku,
+            sudoku.to_two_dimensional_array()
+        );
+        assert!(result.started);
+        assert!(result.player.sudoku.is_some());
+        contract.assert_invariants();
+    }
+
+    #[test]
+    fn finish_and_start_without_balance() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let mut player = contract.players.get(&accounts(0)).unwrap();
+        player.balance = DEFAULT_GAME_FEE - 1;
+        contract.players.insert(&accounts(0), &player);
+
+        let solution = player.sudoku.unwrap().solution().unwrap();
+        let result = contract.finish_and_start(&solution.to_two_dimensional_array().into());
+        assert!(result.solved);
+        assert!(!result.started);
+        assert!(result.player.sudoku.is_none());
+        assert_eq!(result.player.sloved_sudoku_count, U128(1));
+        assert!(result.player.last_sloved_game.is_some());
+    }
+
+    #[test]
+    fn two_phase_puzzle() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_require_commitment(true);
+        register(&mut contract, accounts(1));
+
+        let secret = vec![7; 32];
+        let mut context = get_context(accounts(1));
+        context.block_index(10);
+        testing_env!(context.build());
+        let player = contract.request_puzzle(env::sha256(&secret).into());
+        assert_eq!(player.pending_puzzle_height, Some(10));
+        assert!(player.sudoku.is_none());
+
+        context.block_index(11);
+        testing_env!(context.build());
+        let player = contract.claim_puzzle(secret.into());
+        assert!(player.sudoku.is_some());
+        assert_eq!(player.pending_puzzle_height, None);
+        assert_eq!(contract.get_prize_pool(), U128(DEFAULT_GAME_FEE));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PUZZLE_NOT_READY{\"available_at_height\":11}")]
+    fn two_phase_puzzle_same_block() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(1));
+
+        let secret = vec![7; 32];
+        let mut context = get_context(accounts(1));
+        context.block_index(10);
+        testing_env!(context.build());
+        contract.request_puzzle(env::sha256(&secret).into());
+        contract.claim_puzzle(secret.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_COMMITMENT")]
+    fn two_phase_puzzle_wrong_secret() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(1));
+
+        let mut context = get_context(accounts(1));
+        testing_env!(context.build());
+        contract.request_puzzle(env::sha256(&[7; 32]).into());
+        context.block_index(1);
+        testing_env!(context.build());
+        contract.claim_puzzle(vec![8; 32].into());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COMMITMENT_REQUIRED")]
+    fn start_game_requires_commitment() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_require_commitment(true);
+        start_game(&mut contract, accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COMMITMENT_REQUIRED")]
+    fn start_level_requires_commitment() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_require_commitment(true);
+        register(&mut contract, accounts(1));
+        contract.start_level(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COMMITMENT_REQUIRED")]
+    fn create_race_requires_commitment() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_require_commitment(true);
+        contract.create_race(U128(ONE_NEAR), 2, 1000);
+    }
+
+    #[test]
+    fn finish_and_start_requires_commitment() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let solution = contract
+            .players
+            .get(&accounts(0))
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+
+        contract.set_require_commitment(true);
+        let result = contract.finish_and_start(&solution.to_two_dimensional_array().into());
+        assert!(result.solved);
+        assert!(!result.started);
+    }
+
+    #[test]
+    fn update_config() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        for (i, account_id) in [accounts(1), accounts(2), accounts(3)].iter().enumerate() {
+            play(&mut contract, account_id.clone(), 1000 + i as u64);
+        }
+
+        testing_env!(get_context(accounts(0)).build());
+        let config = Config {
+            leaderboard_size: 2,
+            daily_reward_points: U128(50),
+            ..contract.get_config()
+        };
+        contract.update_config(config.clone());
+        assert_eq!(contract.get_config(), config);
+        contract.assert_invariants();
+
+        let page = contract.get_leaderboard_page(BoardKind::Time, 1, 10);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[1].account_id, accounts(2));
+        assert_eq!(
+            contract
+                .get_leaderboard_page(BoardKind::Points, 1, 10)
+                .len(),
+            2
+        );
+
+        let mut context = get_context(accounts(1));
+        context.block_timestamp(2000 * 1_000_000);
+        testing_env!(context.build());
+        assert_eq!(contract.claim_daily_reward().points, U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_CONFIG")]
+    fn update_config_invalid() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.update_config(Config {
+            leaderboard_size: 0,
+            ..Config::default()
+        });
+    }
+
+    fn sponsor_period(contract: &mut Contract, account: AccountId, period_id: u64) {
+        let mut context = get_context(account);
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        contract.sponsor_period(
+            period_id,
+            "sudoku club".to_string(),
+            Some("https://example.com".to_string()),
+        );
+    }
+
+    #[test]
+    fn sponsored_prize_pool() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_season_end(u64::MAX);
+        contract.config.period_length = 10_000;
+        sponsor_period(&mut contract, accounts(3), 0);
+        let sponsored = contract.get_sponsorships(0)[0].amount.0;
+        assert!(sponsored > ONE_NEAR * 99 / 100);
+
+        play(&mut contract, accounts(1), 1000);
+        verify(&mut contract, accounts(1), 2000);
+        play(&mut contract, accounts(2), 25_000);
+
+        // the sponsored funds are paid out with the fees of the period
+        let prize = contract.get_prize(accounts(1)).unwrap();
+        assert_eq!(prize.amount, U128(DEFAULT_GAME_FEE + sponsored / 2));
+        assert_eq!(contract.get_sponsorships(0)[0].name, "sudoku club");
+    }
+
+    #[test]
+    fn refund_cancelled_sponsorship() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        sponsor_period(&mut contract, accounts(3), 2);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.cancel_period(2);
+
+        testing_env!(get_context(accounts(3)).build());
+        let refund = contract.refund_sponsorship(2);
+        assert!(refund.0 > ONE_NEAR * 99 / 100);
+        assert!(contract.get_sponsorships(2).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_REFUND")]
+    fn refund_running_sponsorship() {
+        let mut contract = Contract::new();
+        sponsor_period(&mut contract, accounts(3), 0);
+        contract.refund_sponsorship(0);
+    }
+
+    #[test]
+    fn sponsored_registration() {
+        let mut contract = Contract::new();
+        let storage = contract.config.player_deposit();
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(storage + storage / 2);
+        testing_env!(context.build());
+        contract.fund_registrations();
+
+        testing_env!(get_context(accounts(1)).build());
+        let player = contract.register();
+        assert_eq!(player.balance, U128(0));
+        assert_eq!(contract.get_registration_pool(), U128(storage / 2));
+
+        // the pool can't cover a second registration
+        let mut context = get_context(accounts(2));
+        context.attached_deposit(storage + 1);
+        testing_env!(context.build());
+        assert_eq!(contract.register().balance, U128(1));
+        assert_eq!(contract.get_registration_pool(), U128(storage / 2));
+
+        // deleting the sponsored player returns its deposit to the pool
+        testing_env!(get_context(accounts(1)).build());
+        contract.delete_player();
+        assert_eq!(
+            contract.get_registration_pool(),
+            U128(storage + storage / 2)
+        );
+        testing_env!(get_context(accounts(2)).build());
+        contract.delete_player();
+        assert_eq!(
+            contract.get_registration_pool(),
+            U128(storage + storage / 2)
+        );
+    }
+
+    #[test]
+    fn export_players() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        start_game(&mut contract, accounts(1));
+
+        let export = contract.export_players(1, 10);
+        assert_eq!(export.version, EXPORT_VERSION);
+        assert_eq!(export.total, 2);
+        assert_eq!(export.players.len(), 1);
+        assert_eq!(export.players[0].account_id, accounts(1));
+        assert!(export.players[0].last_sloved_game.is_none());
+
+        let exported = &contract.export_players(0, 1).players[0];
+        assert!(exported.last_sloved_game.is_some());
+        let player = Player::try_from_slice(&Vec::from(exported.record.clone())).unwrap();
+        assert_eq!(player.sloved_sudoku_count, 1);
+        assert_eq!(
+            contract.export_leaderboards().top_by_time[&accounts(0)],
+            1000
+        );
+    }
+
+    #[test]
+    fn storage_report() {
+        let mut contract = Contract::new();
+        testing_env!(get_context(accounts(0)).build());
+        assert!(contract.get_storage_report(accounts(0)).is_none());
+
+        let storage_before = env::storage_usage();
+        register(&mut contract, accounts(0));
+        let report = contract.get_storage_report(accounts(0)).unwrap();
+        assert_eq!(report.player_bytes, env::storage_usage() - storage_before);
+        assert_eq!(
+            report.player_deposit,
+            U128(contract.config.player_deposit())
+        );
+        assert_eq!(report.contract_bytes, env::storage_usage());
+
+        // the solve adds ranking entries and an attestation, the attestations outlive the player
+        play(&mut contract, accounts(0), 1000);
+        let report = contract.get_storage_report(accounts(0)).unwrap();
+        let attestation_bytes = attestation::ATTESTATION_COUNT_SIZE + attestation::ATTESTATION_SIZE;
+        assert_eq!(
+            report.player_deposit.0,
+            contract.config.player_deposit() + attestation_bytes * env::STORAGE_PRICE_PER_BYTE
+        );
+        let kept = storage::lookup_size(&contract.attestation_counts, &accounts(0))
+            + storage::lookup_size(&contract.attestations, &(accounts(0), 0u64));
+        assert!(kept > 0);
+        assert!(contract.leaderboard.storage_size(&accounts(0)) > 0);
+
+        // the report follows every record the player adds or removes, the storage usage restarts
+        // with every new context, so the rest happens in one
+        let mut context = get_context(accounts(0));
+        context
+            .block_timestamp(reward::DAY * 1_000_000)
+            .attached_deposit(archive::ARCHIVE_COMPLETION_SIZE * env::STORAGE_PRICE_PER_BYTE);
+        testing_env!(context.build());
+        contract.set_share_replays(true);
+        let puzzle = contract.start_archived(0).sudoku.unwrap();
+        let solution = Sudoku::from_two_dimensional_array(&puzzle)
+            .solution()
+            .unwrap()
+            .to_two_dimensional_array();
+        let mut progress = puzzle;
+        let empty = progress[0].iter().position(|&num| num == 0).unwrap();
+        progress[0][empty] = solution[0][empty];
+
+        let measure = |contract: &Contract| {
+            let report = contract.get_storage_report(accounts(0)).unwrap();
+            (report.player_bytes, env::storage_usage())
+        };
+        let mut before = measure(&contract);
+        let mut assert_measured = |contract: &Contract| {
+            let after = measure(contract);
+            assert_eq!(
+                after.0 as i64 - before.0 as i64,
+                after.1 as i64 - before.1 as i64
+            );
+            before = after;
+        };
+        contract.update_notes(vec![0; NOTES_LEN].into());
+        assert_measured(&contract);
+        contract.save_progress(&progress);
+        assert_measured(&contract);
+        contract.finish_practice(&solution.into()).unwrap();
+        assert!(contract.is_archive_completed(accounts(0), 0));
+        assert_measured(&contract);
+        contract.start_game(Some(BoardSize::Kids));
+        assert_measured(&contract);
+
+        let report = contract.get_storage_report(accounts(0)).unwrap();
+        let storage_before = env::storage_usage();
+        contract.delete_player();
+        assert_eq!(
+            report.player_bytes - kept,
+            storage_before - env::storage_usage()
+        );
+    }
+
+    #[test]
+    fn novel_puzzles() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        start_game(&mut contract, accounts(1));
+
+        // both games are generated from the same random seed
+        let first = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let second = contract.players.get(&accounts(1)).unwrap().sudoku.unwrap();
+        assert_ne!(first.canonicalized(), second.canonicalized());
+    }
+
+    #[test]
+    fn practice_game() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        contract.start_practice();
+        let solution = contract
+            .players
+            .get(&accounts(0))
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+
+        context.block_timestamp(1500 * 1_000_000);
+        testing_env!(context.build());
+        let player = contract
+            .finish_practice(&solution.to_two_dimensional_array().into())
+            .unwrap();
+        assert_eq!(player.practice_generated_count, 1);
+        assert_eq!(player.practice_sloved_count, 1);
+        assert_eq!(player.generated_sudoku_count, U128(1));
+        assert_eq!(player.sloved_sudoku_count, U128(1));
+        assert_eq!(player.best_time, Some(1000));
+        assert!(player.sudoku.is_none());
+        assert_eq!(
+            contract.leaderboard.top(BoardKind::Count, 10),
+            vec![(1, accounts(0))]
+        );
+        assert_eq!(contract.get_prize_pool(), U128(DEFAULT_GAME_FEE));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PRACTICE_GAME")]
+    fn practice_game_not_ranked() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        contract.start_practice();
+
+        let solution = contract
+            .players
+            .get(&accounts(0))
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+    }
+
+    fn create_race(contract: &mut Contract, entrants: &[AccountId]) -> u64 {
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        let race_id = contract.create_race(U128(ONE_NEAR), 3, 1000);
+        for account_id in entrants {
+            let mut context = get_context(account_id.clone());
+            context.attached_deposit(2 * ONE_NEAR);
+            testing_env!(context.build());
+            contract.join_race(race_id);
+        }
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        race_id
+    }
+
+    #[test]
+    fn race() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        let race = contract.start_race(race_id);
+        assert_eq!(race.status, RaceStatus::Running);
+
+        let solution = contract
+            .races
+            .get(&race_id)
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+        let solution = solution.to_two_dimensional_array();
+        for account_id in [accounts(3), accounts(1)].iter() {
+            testing_env!(get_context(account_id.clone()).build());
+            assert!(!contract.finish_race(race_id, &[[0; 9]; 9].into()));
+            assert!(contract.finish_race(race_id, &solution.into()));
+            assert!(!contract.finish_race(race_id, &solution.into()));
+        }
+        assert_eq!(
+            contract.get_race(race_id).unwrap().status,
+            RaceStatus::Running
+        );
+
+        let deposit = contract.races.get(&race_id).unwrap().deposit;
+        assert!(deposit > 0);
+        testing_env!(get_context(accounts(2)).build());
+        assert!(contract.finish_race(race_id, &solution.into()));
+        assert!(contract.get_race(race_id).is_none());
+        assert_eq!(
+            get_logs(),
+            vec![concat!(
+                r#"EVENT_JSON:{"standard":"sudoku","version":"1.0.0","event":"race_ended","data":["#,
+                r#"{"race_id":0,"status":"settled","entrants":["bob","charlie","danny"],"#,
+                r#""finishers":["danny","bob","charlie"]}]}"#
+            )]
+        );
+        assert_eq!(contract.get_prize_pool(), U128(0));
+        assert_eq!(transfers_to(&accounts(0)), vec![deposit]);
+        // the pot of 3 NEAR goes out by the payout schedule, with the storage of the entries
+        for (account_id, payout) in [(accounts(3), 60), (accounts(1), 30), (accounts(2), 10)] {
+            assert_eq!(
+                transfers_to(&account_id),
+                vec![3 * ONE_NEAR * payout / 100 + race::entry_deposit(&account_id)]
+            );
+        }
+    }
+
+    #[test]
+    fn race_storage() {
+        let mut contract = Contract::new();
+        let price = env::STORAGE_PRICE_PER_BYTE;
+        // the storage usage restarts with every new context, so it's measured within each call
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        let storage_before = env::storage_usage();
+        let race_id = contract.create_race(U128(ONE_NEAR), 2, 1000);
+        let mut stored = (env::storage_usage() - storage_before) as u128;
+        let deposit = contract.races.get(&race_id).unwrap().deposit;
+        assert_eq!(deposit, (stored + race::RACE_SUDOKU_SIZE) * price);
+
+        // joining pays for the entry and the place among the finishers
+        for account_id in [accounts(1), accounts(2)] {
+            let mut context = get_context(account_id.clone());
+            context.attached_deposit(2 * ONE_NEAR);
+            testing_env!(context.build());
+            let storage_before = env::storage_usage();
+            contract.join_race(race_id);
+            let joined = (env::storage_usage() - storage_before) as u128;
+            assert_eq!(2 * joined * price, race::entry_deposit(&account_id));
+            stored += joined;
+        }
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        let storage_before = env::storage_usage();
+        contract.start_race(race_id);
+        let started = (env::storage_usage() - storage_before) as u128;
+        assert_eq!(started, race::RACE_SUDOKU_SIZE);
+        stored += started;
+
+        let sudoku = contract.races.get(&race_id).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap().to_two_dimensional_array();
+        testing_env!(get_context(accounts(1)).build());
+        let storage_before = env::storage_usage();
+        assert!(contract.finish_race(race_id, &solution.into()));
+        let finished = (env::storage_usage() - storage_before) as u128;
+        assert_eq!(2 * finished * price, race::entry_deposit(&accounts(1)));
+        stored += finished;
+
+        // the last place settles the race and frees all of it
+        testing_env!(get_context(accounts(2)).build());
+        let storage_before = env::storage_usage();
+        assert!(contract.finish_race(race_id, &solution.into()));
+        assert_eq!((storage_before - env::storage_usage()) as u128, stored);
+        assert!(contract.races.get(&race_id).is_none());
+    }
+
+    /// Amounts transferred to `account_id` by the last call
+    fn transfers_to(account_id: &AccountId) -> Vec<Balance> {
+        get_created_receipts()
+            .into_iter()
+            .filter(|receipt| &receipt.receiver_id == account_id)
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                VmAction::Transfer { deposit } => Some(deposit),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cancelled_race_refunds() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2)]);
+        let deposit = contract.races.get(&race_id).unwrap().deposit;
+        assert_eq!(contract.start_race(race_id).status, RaceStatus::Cancelled);
+        assert!(contract.races.get(&race_id).is_none());
+        assert_eq!(transfers_to(&accounts(0)), vec![deposit]);
+        for account_id in [accounts(1), accounts(2)] {
+            assert_eq!(
+                transfers_to(&account_id),
+                vec![ONE_NEAR + race::entry_deposit(&account_id)]
+            );
+        }
+    }
+
+    #[test]
+    fn race_settles_after_time_limit() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        contract.start_race(race_id);
+
+        let solution = contract
+            .races
+            .get(&race_id)
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+        testing_env!(get_context(accounts(2)).build());
+        assert!(contract.finish_race(race_id, &solution.to_two_dimensional_array().into()));
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp((1000 + race::RACE_TIME_LIMIT) * 1_000_000);
+        testing_env!(context.build());
+        contract.settle_race(race_id);
+        assert!(contract.get_race(race_id).is_none());
+        assert_eq!(contract.get_prize_pool(), U128(3 * ONE_NEAR * 40 / 100));
+    }
+
+    #[test]
+    fn race_cancelled_if_not_full() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2)]);
+        let race = contract.start_race(race_id);
+        assert_eq!(race.status, RaceStatus::Cancelled);
+        assert!(race.sudoku.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_RACE_NOT_STARTED")]
+    fn race_start_too_early() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        testing_env!(get_context(accounts(0)).build());
+        contract.start_race(race_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_RACE_CLOSED")]
+    fn race_full() {
+        let mut contract = Contract::new();
+        let race_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        let mut context = get_context(accounts(4));
+        context.attached_deposit(2 * ONE_NEAR);
+        testing_env!(context.build());
+        contract.join_race(race_id);
+    }
+
+    #[test]
+    fn contract_metadata() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.config.min_solve_times[Difficulty::Hard as usize] = 20_000;
+
+        let metadata = contract.contract_metadata();
+        assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.state_version, metadata::STATE_VERSION);
+        assert!(metadata.game_modes.contains(&GameMode::Race));
+        assert!(!metadata.require_commitment);
+        assert_eq!(metadata.difficulties.len(), 4);
+        assert_eq!(metadata.difficulties[Difficulty::Hard as usize].min_solve_time, 20_000);
+        assert_eq!(metadata.standards[0].standard, "nep297");
+    }
+
+    #[test]
+    fn tick() {
+        let mut contract = Contract::new();
+        contract.config.period_length = 10_000;
+        let open_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        let running_id = create_race(&mut contract, &[accounts(1), accounts(2), accounts(3)]);
+        contract.start_race(running_id);
+
+        // the daily puzzle waits for a deposit covering its storage
+        assert!(!contract.tick(vec![]).daily_archived);
+        let mut context = get_context(accounts(0));
+        context
+            .block_timestamp(1000 * 1_000_000)
+            .attached_deposit(archive::ARCHIVED_PUZZLE_SIZE * env::STORAGE_PRICE_PER_BYTE);
+        testing_env!(context.build());
+        let request = contract.tick(vec![open_id, running_id, 99]);
+        assert_eq!(request.period_id, 0);
+        assert!(request.daily_archived);
+        assert_eq!(request.races_started, 1);
+        assert_eq!(request.races_settled, 0);
+        assert_eq!(
+            contract.get_race(open_id).unwrap().status,
+            RaceStatus::Running
+        );
+
+        let mut context = get_context(accounts(4));
+        context.block_timestamp((1000 + race::RACE_TIME_LIMIT) * 1_000_000);
+        testing_env!(context.build());
+        let request = contract.tick(vec![open_id, running_id]);
+        assert_eq!(request.period_id, (1000 + race::RACE_TIME_LIMIT) / 10_000);
+        assert!(contract.get_snapshot(0).is_some());
+        assert_eq!(request.races_settled, 2);
+        assert!(contract.get_race(running_id).is_none());
+
+        // nothing left to do
+        let request = contract.tick(vec![open_id, running_id]);
+        assert!(!request.daily_archived);
+        assert_eq!(request.races_settled, 0);
+    }
+
+    #[test]
+    fn leaderboard_events() {
+        let mut contract = Contract::new();
+        let get_logs = || -> Vec<String> {
+            get_logs()
+                .into_iter()
+                .filter(|log| log.contains(r#""event":"leaderboard_update""#))
+                .collect()
+        };
+
+        play(&mut contract, accounts(0), 1000);
+        assert_eq!(
+            get_logs(),
+            vec![concat!(
+                r#"EVENT_JSON:{"standard":"sudoku","version":"1.0.0","event":"leaderboard_update","data":["#,
+                r#"{"board":"count","account_id":"alice","old_rank":null,"new_rank":1},"#,
+                r#"{"board":"time","account_id":"alice","old_rank":null,"new_rank":1},"#,
+                r#"{"board":"block_time","account_id":"alice","old_rank":null,"new_rank":1},"#,
+                r#"{"board":"points","account_id":"alice","old_rank":null,"new_rank":1}]}"#
+            )]
+        );
+
+        play(&mut contract, accounts(1), 500);
+        assert_eq!(
+            get_logs(),
+            vec![concat!(
+                r#"EVENT_JSON:{"standard":"sudoku","version":"1.0.0","event":"leaderboard_update","data":["#,
+                r#"{"board":"count","account_id":"bob","old_rank":null,"new_rank":2},"#,
+                r#"{"board":"time","account_id":"alice","old_rank":1,"new_rank":2},"#,
+                r#"{"board":"time","account_id":"bob","old_rank":null,"new_rank":1},"#,
+                r#"{"board":"block_time","account_id":"bob","old_rank":null,"new_rank":2},"#,
+                r#"{"board":"points","account_id":"bob","old_rank":null,"new_rank":2}]}"#
+            )]
+        );
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.delete_player();
+        assert_eq!(
+            get_logs(),
+            vec![concat!(
+                r#"EVENT_JSON:{"standard":"sudoku","version":"1.0.0","event":"leaderboard_update","data":["#,
+                r#"{"board":"count","account_id":"bob","old_rank":2,"new_rank":null},"#,
+                r#"{"board":"time","account_id":"alice","old_rank":2,"new_rank":1},"#,
+                r#"{"board":"time","account_id":"bob","old_rank":1,"new_rank":null},"#,
+                r#"{"board":"block_time","account_id":"bob","old_rank":2,"new_rank":null},"#,
+                r#"{"board":"points","account_id":"bob","old_rank":2,"new_rank":null}]}"#
+            )]
+        );
+    }
+
+    #[test]
+    fn solution_of_other_puzzle() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let other = Sudoku::generate_solved(&mut StdRng::seed_from_u64(5));
+        assert!(other.is_solved() && !sudoku.is_subset_of(&other));
+
+        assert!(contract
+            .finish_game(&other.to_two_dimensional_array().into())
+            .is_none());
+        let player = contract.players.get(&accounts(0)).unwrap();
+        assert_eq!(player.wrong_submissions, 1);
+        assert_eq!(player.sloved_sudoku_count, 0);
+
+        testing_env!(get_context(accounts(1)).build());
+        register(&mut contract, accounts(1));
+        contract.start_practice();
+        assert!(contract
+            .finish_practice(&other.to_two_dimensional_array().into())
+            .is_none());
+        assert_eq!(
+            contract
+                .players
+                .get(&accounts(1))
+                .unwrap()
+                .practice_sloved_count,
+            0
+        );
+    }
+
+    #[test]
+    fn wrong_submissions_penalty() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        let mut wrong = solution.to_two_dimensional_array();
+        wrong[0].swap(0, 1);
+
+        assert!(contract.finish_game(&wrong.into()).is_none());
+        assert!(contract.finish_game(&[[0; 9]; 9].into()).is_none());
+        let player = contract.get_player(accounts(0)).unwrap();
+        assert_eq!(player.wrong_submissions, 2);
+        assert_eq!(player.penalty, 2 * DEFAULT_WRONG_SUBMISSION_PENALTY);
+
+        // Only later submissions are charged the changed penalty
+        contract.config.wrong_submission_penalty = 10_000;
+        assert!(contract.finish_game(&wrong.into()).is_none());
+        let penalty = 2 * DEFAULT_WRONG_SUBMISSION_PENALTY + 10_000;
+        assert_eq!(contract.get_player(accounts(0)).unwrap().penalty, penalty);
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000);
+        testing_env!(context.build());
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+
+        let player = contract.players.get(&accounts(0)).unwrap();
+        assert_eq!(player.best_time, Some(1000 + penalty));
+        assert_eq!(player.wrong_submissions, 0);
+        assert_eq!(player.penalty, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_OWNER")]
+    fn update_config_not_owner() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.update_config(Config::default());
+    }
+
+    /// Lines as written by the `poolgen` tool
+    fn pool_lines(count: u64) -> Vec<String> {
+        (0..count)
+            .map(|seed| {
+                let sudoku = Sudoku::generate(&mut StdRng::seed_from_u64(seed));
+                let difficulty = sudoku.grade();
+                format!(
+                    "{} {} {}",
+                    sudoku.to_str_line(),
+                    difficulty.name(),
+                    sudoku.difficulty_score()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn add_puzzles() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new();
+
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        let lines = pool_lines(3);
+        assert_eq!(contract.add_puzzles(lines[..2].to_vec()), 2);
+        assert_eq!(contract.add_puzzles(lines[2..].to_vec()), 3);
+        assert_eq!(contract.get_pool_size(), 3);
+        let pooled = contract.puzzle_pool.get(2).unwrap();
+        assert_eq!(pooled.sudoku.to_str_line().to_string(), lines[2][..81]);
+        assert_eq!(pooled.difficulty, pooled.sudoku.grade());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_POOLED_PUZZLE{\"index\":1}")]
+    fn add_puzzles_without_unique_solution() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new();
+
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        let mut lines = pool_lines(1);
+        lines.push(format!("{} easy 0", ".".repeat(81)));
+        contract.add_puzzles(lines);
+    }
+
+    #[test]
+    fn pooled_games() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new();
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        contract.add_puzzles(pool_lines(1));
+        let pooled = contract.puzzle_pool.get(0).unwrap();
+
+        let mut puzzles = vec![];
+        for (account, seed) in [(accounts(1), 1), (accounts(2), 2)] {
+            register(&mut contract, account.clone());
+            let mut context = get_context(account.clone());
+            context.random_seed([seed; 32]);
+            testing_env!(context.build());
+            let request = contract.start_game(None);
+            assert_eq!(request.pool_index, Some(0));
+            assert_eq!(request.difficulty, pooled.difficulty);
+            puzzles.push(contract.players.get(&account).unwrap().sudoku.unwrap());
+        }
+        // both draws are transformed copies of the pooled puzzle
+        assert_ne!(puzzles[0], puzzles[1]);
+        assert_eq!(
+            novelty::canonical_hash(&puzzles[0]),
+            novelty::canonical_hash(&pooled.sudoku)
+        );
+        assert_eq!(
+            novelty::canonical_hash(&puzzles[1]),
+            novelty::canonical_hash(&pooled.sudoku)
+        );
+
+        // the solution of the first draw doesn't solve the second one
+        let solution = puzzles[0].solution().unwrap();
+        let mut context = get_context(accounts(2));
+        context.block_timestamp(1_000_000_000_000);
+        testing_env!(context.build());
+        assert!(contract
+            .finish_game(&solution.to_two_dimensional_array().into())
+            .is_none());
+
+        let mut context = get_context(accounts(1));
+        context.block_timestamp(1_000_000_000_000);
+        testing_env!(context.build());
+        assert!(contract
+            .finish_game(&solution.to_two_dimensional_array().into())
+            .is_some());
+        let player = contract.players.get(&accounts(1)).unwrap();
+        assert_eq!(player.sloved_sudoku_count, 1);
+        assert!(player.pool_draw.is_none());
+    }
+
+    #[test]
+    fn migrate_players() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(1), 100);
+        start_game(&mut contract, accounts(2));
+        let players: Vec<_> = contract
+            .players
+            .keys()
+            .map(|id| contract.get_player(id))
+            .collect();
+
+        testing_env!(get_context(accounts(0)).build());
+        assert_eq!(contract.migrate_players(0, 1), 1);
+        assert_eq!(contract.migrate_players(1, 10), 2);
+        assert_eq!(contract.migrate_players(2, 10), 2);
+        contract.assert_invariants();
+        for (account_id, player) in contract.players.keys().zip(players) {
+            assert_eq!(
+                contract.get_player(account_id).unwrap().sudoku,
+                player.unwrap().sudoku
+            );
+        }
+    }
+
+    #[test]
+    fn last_sloved_game_details() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 2000);
+        let difficulty = play(&mut contract, accounts(0), 3000);
+
+        let player = contract.get_player(accounts(0)).unwrap();
+        let last_game = player.last_sloved_game.unwrap();
+        assert_eq!(last_game.difficulty, Some(difficulty));
+        assert_eq!(
+            last_game.clue_count,
+            Sudoku::from_two_dimensional_array(&last_game.sudoku).n_clues()
+        );
+        assert_eq!(last_game.hints_used, Some(0));
+        assert_eq!(last_game.personal_best, Some(false));
+    }
+
+    #[test]
+    fn last_sloved_game_legacy_layout() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        let key = [b"l", accounts(0).as_str().as_bytes()].concat();
+        let mut grid = [0; 81];
+        grid[0] = 5;
+        let sudoku = Sudoku::from_bytes(grid).unwrap();
+
+        // packed sudoku (tag 0xff, 5 in the low nibble of the first cell pair), time_end 200
+        // and time_start 100, as written before the move history hash was added
+        let unhashed = [
+            &[0xff, 5][..],
+            &[0; 40],
+            &[200, 0, 0, 0, 0, 0, 0, 0],
+            &[100, 0, 0, 0, 0, 0, 0, 0],
+        ]
+        .concat();
+        env::storage_write(&key, &unhashed);
+        let last_game = contract
+            .get_player(accounts(0))
+            .unwrap()
+            .last_sloved_game
+            .unwrap();
+        assert_eq!(last_game.sudoku, sudoku.to_two_dimensional_array());
+        assert_eq!((last_game.time_start, last_game.time_end), (100, 200));
+        assert_eq!(last_game.moves_hash, None);
+        assert_eq!(last_game.difficulty, None);
+        assert_eq!(last_game.personal_best, None);
+
+        // the same record followed by `Some` move history hash
+        let hashed = [&unhashed[..], &[1], &[0xab; 32]].concat();
+        env::storage_write(&key, &hashed);
+        let last_game = contract
+            .get_player(accounts(0))
+            .unwrap()
+            .last_sloved_game
+            .unwrap();
+        assert_eq!(last_game.moves_hash, Some("ab".repeat(32)));
+        assert_eq!(last_game.hints_used, None);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.migrate_players(0, 10);
+        assert_eq!(
+            env::storage_read(&key).unwrap()[..2],
+            [LAST_SLOVED_GAME_TAG, LAST_SLOVED_GAME_VERSION]
+        );
+        let last_game = contract
+            .get_player(accounts(0))
+            .unwrap()
+            .last_sloved_game
+            .unwrap();
+        assert_eq!(last_game.time_end, 200);
+        assert_eq!(last_game.moves_hash, Some("ab".repeat(32)));
+    }
+
+    #[test]
+    fn migrate_first_deployment() {
+        testing_env!(get_context(accounts(1)).build());
+        env::state_write(&LegacyContract {
+            players: UnorderedMap::new(b"p".to_vec()),
+            leaderboard: LegacyLeaderboard {
+                top_by_count: HashMap::from([(accounts(2), 3)]),
+                top_by_time: HashMap::from([(accounts(2), 900)]),
+            },
+        });
+
+        let contract = Contract::migrate(accounts(0));
+        assert_eq!(contract.owner_id, accounts(0));
+        assert_eq!(contract.config.player_size, PLAYER_SIZE);
+        assert!(contract.leaderboard.top(BoardKind::Count, 10).is_empty());
+    }
+
+    /// A player of the first deployment without running game and last solved game
+    fn legacy_player(sloved_sudoku_count: u128, best_time: Timestamp) -> Vec<u8> {
+        [
+            &[0][..],
+            &100u64.to_le_bytes(),
+            &(sloved_sudoku_count + 1).to_le_bytes(),
+            &sloved_sudoku_count.to_le_bytes(),
+            &[0],
+            &[1],
+            &best_time.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn migrate_first_deployment_players() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut legacy = LegacyContract {
+            players: UnorderedMap::new(b"p".to_vec()),
+            leaderboard: LegacyLeaderboard {
+                top_by_count: HashMap::new(),
+                top_by_time: HashMap::new(),
+            },
+        };
+        for (account_id, count, time) in [(accounts(1), 3, 900), (accounts(2), 5, 1200)] {
+            let key = account_id.try_to_vec().unwrap();
+            legacy.players.insert_raw(&key, &legacy_player(count, time));
+        }
+        env::state_write(&legacy);
+
+        let mut contract = Contract::migrate(accounts(0));
+        let player = contract.get_player(accounts(1)).unwrap();
+        assert_eq!(player.sloved_sudoku_count, U128(3));
+        assert_eq!(player.best_time, Some(900));
+        assert!(contract
+            .get_leaderboard_page(BoardKind::Count, 1, 10)
+            .is_empty());
+
+        assert_eq!(contract.migrate_players(0, 10), 2);
+        let count_page = contract.get_leaderboard_page(BoardKind::Count, 1, 10);
+        assert_eq!(count_page[0].account_id, accounts(2));
+        assert_eq!(count_page[0].value, U128(5));
+        let time_page = contract.get_leaderboard_page(BoardKind::Time, 1, 10);
+        assert_eq!(time_page[0].account_id, accounts(1));
+        assert_eq!(time_page[0].value, U128(900));
+        let player = contract.players.get(&accounts(2)).unwrap();
+        assert_eq!(
+            player.try_to_vec().unwrap()[..2],
+            [PLAYER_TAG, PLAYER_VERSION]
+        );
+        contract.assert_invariants();
+
+        // the rewritten players aren't ranked twice
+        contract.migrate_players(0, 10);
+        assert_eq!(contract.leaderboard.ranking(BoardKind::Count).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_LEGACY_STATE")]
+    fn migrate_without_state() {
+        testing_env!(get_context(accounts(0)).build());
+        Contract::migrate(accounts(0));
+    }
+
+    #[test]
+    fn player_legacy_layout() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        let mut grid = [0; 81];
+        grid[0] = 5;
+        let sudoku = Sudoku::from_bytes(grid).unwrap();
+
+        // `Some` sudoku a byte per cell, start_time 100, 3 generated and 2 solved sudokus, the
+        // inline last solved game with time_end 200 and time_start 100, and best_time 90
+        let legacy = [
+            &[1][..],
+            &grid,
+            &100u64.to_le_bytes(),
+            &3u128.to_le_bytes(),
+            &2u128.to_le_bytes(),
+            &[1],
+            &grid,
+            &200u64.to_le_bytes(),
+            &100u64.to_le_bytes(),
+            &[1],
+            &90u64.to_le_bytes(),
+        ]
+        .concat();
+        let player = Player::try_from_slice(&legacy).unwrap();
+        assert_eq!(player.sudoku, Some(sudoku));
+        assert_eq!(player.start_time, 100);
+        assert_eq!(
+            (player.generated_sudoku_count, player.sloved_sudoku_count),
+            (3, 2)
+        );
+        assert_eq!(player.best_time, Some(90));
+        assert_eq!(player.balance, 0);
+        assert!(Player::try_from_slice(&legacy[..legacy.len() - 1]).is_err());
+
+        contract.players.insert(&accounts(0), &player);
+        let last_game = contract
+            .get_player(accounts(0))
+            .unwrap()
+            .last_sloved_game
+            .unwrap();
+        assert_eq!(last_game.sudoku, sudoku.to_two_dimensional_array());
+        assert_eq!((last_game.time_start, last_game.time_end), (100, 200));
+        assert_eq!(last_game.difficulty, None);
+
+        let key = [b"l", accounts(0).as_str().as_bytes()].concat();
+        assert!(!env::storage_has_key(&key));
+        contract.migrate_players(0, 10);
+        assert_eq!(
+            env::storage_read(&key).unwrap()[..2],
+            [LAST_SLOVED_GAME_TAG, LAST_SLOVED_GAME_VERSION]
+        );
+        let mut player = contract.players.get(&accounts(0)).unwrap();
+        assert_eq!(
+            player.try_to_vec().unwrap()[..2],
+            [PLAYER_TAG, PLAYER_VERSION]
+        );
+        assert_eq!(player.last_sloved_game.get().unwrap().time_end, 200);
+        assert_eq!(player.sudoku, Some(sudoku));
+
+        // version 4 stores notes, replay and archive dates like the inline records without their
+        // tag. The replay is followed by the last replay, `pool_draw`, `replay_deposit` and the
+        // archive dates.
+        player.notes = SideRecord::Inline(None);
+        player.replay = SideRecord::Inline(None);
+        player.archive_dates = ArchiveDates::Inline(vec![7]);
+        let mut version_4 = player.try_to_vec().unwrap();
+        let notes_at = 2 + player.sudoku.try_to_vec().unwrap().len() + 1;
+        let archive_at = version_4.len() - 13;
+        let replay_at = archive_at - 16 - 1 - player.last_replay.try_to_vec().unwrap().len() - 2;
+        for at in [archive_at, replay_at, notes_at] {
+            assert_eq!(version_4.remove(at), 0);
+        }
+        version_4[1] = 4;
+        let player = Player::try_from_slice(&version_4).unwrap();
+        assert!(matches!(player.notes, SideRecord::Inline(None)));
+        assert!(matches!(player.replay, SideRecord::Inline(None)));
+        assert_eq!(
+            player
+                .archive_dates
+                .get(&contract.archive_dates, &accounts(0)),
+            vec![7]
+        );
+        contract.players.insert(&accounts(0), &player);
+        contract.migrate_players(0, 10);
+        let player = contract.players.get(&accounts(0)).unwrap();
+        assert!(matches!(player.archive_dates, ArchiveDates::Indexed(1)));
+        assert_eq!(contract.archive_dates.get(&(accounts(0), 0)), Some(7));
+        assert!(matches!(player.notes, SideRecord::Keyed(_)));
+        assert!(matches!(player.replay, SideRecord::Keyed(_)));
+
+        // version 3 ends before `archive_dates`, version 2 before `replay_deposit` and version 1
+        // before `pool_draw`
+        let mut version_3 = version_4;
+        version_3.truncate(version_3.len() - 12);
+        version_3[1] = 3;
+        let player = Player::try_from_slice(&version_3).unwrap();
+        assert_eq!(player.archive_dates.len(), 0);
+        let mut version_2 = version_3;
+        version_2.truncate(version_2.len() - 16);
+        version_2[1] = 2;
+        let player = Player::try_from_slice(&version_2).unwrap();
+        assert_eq!(player.replay_deposit, 0);
+        let mut version_1 = version_2;
+        assert_eq!(version_1.pop(), Some(0));
+        version_1[1] = 1;
+        let player = Player::try_from_slice(&version_1).unwrap();
+        assert!(player.pool_draw.is_none());
+        assert_eq!(player.best_time, Some(90));
+    }
+
+    #[test]
+    fn start_game_cooldown() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(DEFAULT_START_GAME_COOLDOWN * 1_000_000);
+        testing_env!(context.build());
+        contract.start_game(None);
+
+        let player = contract.players.get(&accounts(0)).unwrap();
+        assert_eq!(player.generated_sudoku_count, 2);
+        assert_eq!(player.start_time, DEFAULT_START_GAME_COOLDOWN);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COOLDOWN{\"available_at\":30000}")]
+    fn start_game_during_cooldown() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        start_game(&mut contract, accounts(0));
+    }
+
+    #[test]
+    fn continue_generation() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        let balance = contract.players.get(&accounts(0)).unwrap().balance;
+
+        // less than the reserve, so every call takes a single step of the generation
+        let mut context = get_context(accounts(0));
+        context.prepaid_gas(Gas(generation::GENERATION_GAS_RESERVE.0 - 1));
+        testing_env!(context.build());
+        let request = contract.start_game(None);
+        assert!(request.generation_pending);
+        assert!(request.sudoku.is_none());
+        assert_eq!(
+            contract.players.get(&accounts(0)).unwrap().balance,
+            balance - generation::PENDING_GENERATION_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+
+        // the game is timed from start_game
+        context.block_timestamp(2000 * 1_000_000).block_index(5);
+        testing_env!(context.build());
+        let mut n_calls = 1;
+        while contract.continue_generation().generation_pending {
+            n_calls += 1;
+        }
+        assert!(n_calls > 1);
+        contract.assert_invariants();
+
+        let player = contract.players.get(&accounts(0)).unwrap();
+        assert!(player.sudoku.unwrap().is_uniquely_solvable());
+        assert_eq!(player.generated_sudoku_count, 1);
+        assert_eq!(player.start_time, 0);
+        assert_eq!(player.start_height, 0);
+        // the first game of the day archives the daily puzzle
+        assert_eq!(
+            player.balance,
+            balance
+                - DEFAULT_GAME_FEE
+                - archive::ARCHIVED_PUZZLE_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+        assert!(contract.pending_generations.get(&accounts(0)).is_none());
+    }
+
+    #[test]
+    fn delete_player_with_pending_generation() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        let mut context = get_context(accounts(0));
+        context.prepaid_gas(Gas(generation::GENERATION_GAS_RESERVE.0 - 1));
+        testing_env!(context.build());
+        assert!(contract.start_game(None).generation_pending);
+
+        contract.delete_player();
+        assert!(contract.pending_generations.get(&accounts(0)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_PENDING_GENERATION")]
+    fn continue_generation_without_pending() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        contract.continue_generation();
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_GENERATION_PENDING")]
+    fn start_game_while_generation_pending() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        let mut context = get_context(accounts(0));
+        context.prepaid_gas(Gas(generation::GENERATION_GAS_RESERVE.0 - 1));
+        testing_env!(context.build());
+        contract.start_game(None);
+        contract.start_game(None);
+    }
+
+    fn play_level(contract: &mut Contract, account: AccountId, level: u32) {
+        if contract.players.get(&account).is_none() {
+            register(contract, account.clone());
+        }
+        testing_env!(get_context(account.clone()).build());
+        contract.start_level(level);
+
+        let solution = contract
+            .players
+            .get(&account)
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+        contract.assert_invariants();
+    }
+
+    #[test]
+    fn campaign() {
+        let mut contract = Contract::new();
+
+        let progress = contract.get_campaign_progress(accounts(0));
+        assert_eq!(progress.completed_levels, 0);
+        assert_eq!(progress.next_difficulty, Some(Difficulty::Easy));
+
+        play_level(&mut contract, accounts(0), 1);
+        play_level(&mut contract, accounts(0), 2);
+        // replaying a completed level doesn't lose progress
+        play_level(&mut contract, accounts(0), 1);
+
+        let progress = contract.get_campaign_progress(accounts(0));
+        assert_eq!(progress.completed_levels, 2);
+        assert_eq!(progress.total_levels, campaign::LEVELS.len() as u32);
+        assert_eq!(progress.current_level, None);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.start_level(3);
+        assert_eq!(
+            contract.get_campaign_progress(accounts(0)).current_level,
+            Some(3)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_LEVEL_LOCKED{\"level\":2}")]
+    fn campaign_level_locked() {
+        let mut contract = Contract::new();
+        play_level(&mut contract, accounts(0), 2);
+    }
+
+    #[test]
+    fn difficulty_stats() {
+        let mut contract = Contract::new();
+        play_level(&mut contract, accounts(0), 1);
+        assert_eq!(play(&mut contract, accounts(0), 100), Difficulty::Medium);
+        assert_eq!(play(&mut contract, accounts(0), 300), Difficulty::Easy);
+        assert_eq!(play(&mut contract, accounts(0), 500), Difficulty::Easy);
+
+        let stats = contract.get_player(accounts(0)).unwrap().stats;
+        assert_eq!(stats[&Difficulty::Easy].solved_count, 3);
+        assert_eq!(stats[&Difficulty::Medium].solved_count, 1);
+        assert_eq!(stats[&Difficulty::Expert].solved_count, 0);
+        assert_eq!(stats[&Difficulty::Expert].best_time, None);
+        assert_eq!(
+            stats[&Difficulty::Easy],
+            DifficultyStatsRequest {
+                solved_count: 3,
+                best_time: Some(0),
+                average_time: Some(266),
+            }
+        );
+    }
+
+    fn claim_daily_reward(
+        contract: &mut Contract,
+        account: AccountId,
+        time: Timestamp,
+    ) -> PlayerRequest {
+        let mut context = get_context(account);
+        context.block_timestamp(time * 1_000_000);
+        testing_env!(context.build());
+        contract.claim_daily_reward()
+    }
+
+    #[test]
+    fn daily_reward_streak() {
+        let mut contract = Contract::new();
+
+        play(&mut contract, accounts(0), 1000);
+        let player = claim_daily_reward(&mut contract, accounts(0), 2000);
+        assert_eq!((player.points, player.reward_streak), (U128(10), 1));
+
+        play(&mut contract, accounts(0), reward::DAY + 1000);
+        let player = claim_daily_reward(&mut contract, accounts(0), reward::DAY + 2000);
+        assert_eq!((player.points, player.reward_streak), (U128(30), 2));
+
+        // skipping a day resets the streak
+        play(&mut contract, accounts(0), 3 * reward::DAY + 1000);
+        let player = claim_daily_reward(&mut contract, accounts(0), 3 * reward::DAY + 2000);
+        assert_eq!((player.points, player.reward_streak), (U128(40), 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_ALREADY_CLAIMED{\"available_at\":86400000}")]
+    fn daily_reward_claimed_twice() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        claim_daily_reward(&mut contract, accounts(0), 2000);
+        claim_daily_reward(&mut contract, accounts(0), 3000);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_SOLVE_TODAY")]
+    fn daily_reward_without_solve() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        claim_daily_reward(&mut contract, accounts(0), reward::DAY + 1000);
+    }
+
+    #[test]
+    fn update_notes() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let mut notes = vec![0; NOTES_LEN];
+        notes[0] = 0b1010_0001;
+        notes[NOTES_LEN - 1] = 0b1;
+        let player = contract.update_notes(notes.clone().into());
+        assert_eq!(player.notes, Some(notes.clone().into()));
+        assert_eq!(
+            contract.get_player(accounts(0)).unwrap().notes,
+            Some(notes.into())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_NOTES")]
+    fn update_notes_with_padding_bits() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let mut notes = vec![0; NOTES_LEN];
+        notes[NOTES_LEN - 1] = 0b10;
+        contract.update_notes(notes.into());
+    }
+
+    #[test]
+    fn finish_game_with_line() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let solution = contract
+            .players
+            .get(&accounts(0))
+            .unwrap()
+            .sudoku
+            .unwrap()
+            .solution()
+            .unwrap();
+        let line: &str = &solution.to_str_line();
+        assert!(contract.check_sloved(&line.into()));
+        assert!(contract.finish_game(&line.into()).is_some());
+        assert_eq!(
+            contract
+                .players
+                .get(&accounts(0))
+                .unwrap()
+                .sloved_sudoku_count,
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SUDOKU_LINE")]
+    fn finish_game_with_short_line() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        contract.finish_game(&"123".into());
+    }
+
+    /// Entries of the empty cells of the current sudoku of `account` taken from its solution
+    fn solution_entries(contract: &Contract, account: AccountId) -> Vec<(u8, u8, u8)> {
+        let sudoku = contract.players.get(&account).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        (0..81)
+            .filter(|&cell| sudoku.0[cell] == 0)
+            .map(|cell| (cell as u8 / 9, cell as u8 % 9, solution.0[cell]))
+            .collect()
+    }
+
+    #[test]
+    fn finish_game_delta() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let mut entries = solution_entries(&contract, accounts(0));
+
+        let (row, col, digit) = entries.pop().unwrap();
+        assert!(contract.finish_game_delta(entries.clone()).is_none());
+        assert_eq!(
+            contract.players.get(&accounts(0)).unwrap().wrong_submissions,
+            1
+        );
+
+        entries.push((row, col, digit));
+        assert!(contract.finish_game_delta(entries).is_some());
+        assert_eq!(
+            contract.players.get(&accounts(0)).unwrap().sloved_sudoku_count,
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_ENTRIES")]
+    fn finish_game_delta_overwriting_clue() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let clue = (0..81).find(|&cell| sudoku.0[cell] != 0).unwrap();
+        let mut entries = solution_entries(&contract, accounts(0));
+        entries.push((clue as u8 / 9, clue as u8 % 9, sudoku.0[clue]));
+
+        contract.finish_game_delta(entries);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_ENTRIES")]
+    fn finish_game_delta_out_of_range() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        contract.finish_game_delta(vec![(9, 0, 1)]);
+    }
+
+    #[test]
+    fn save_progress() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let player = contract.players.get(&accounts(0)).unwrap();
+        let sudoku = player.sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        let clue_count = sudoku.n_clues();
+        assert_eq!(player.get().filled_count, clue_count);
+        assert_eq!(player.get().progress_percent, 0);
+
+        // fill in half of the empty cells
+        let mut progress = sudoku;
+        let empty_cells: Vec<usize> = (0..81).filter(|&cell| sudoku.0[cell] == 0).collect();
+        for &cell in &empty_cells[..empty_cells.len() / 2] {
+            progress.0[cell] = solution.0[cell];
+        }
+
+        let player = contract.save_progress(&progress.to_two_dimensional_array());
+        assert_eq!(player.clue_count, clue_count);
+        assert_eq!(
+            player.filled_count as usize,
+            clue_count as usize + empty_cells.len() / 2
+        );
+        assert_eq!(
+            player.progress_percent as usize,
+            empty_cells.len() / 2 * 100 / empty_cells.len()
+        );
+        assert_eq!(player.progress, Some(progress.to_two_dimensional_array()));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_PROGRESS")]
+    fn save_progress_overwriting_clue() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let mut progress = sudoku.to_two_dimensional_array();
+        let (x, y) = (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .find(|&(x, y)| progress[x][y] != 0)
+            .unwrap();
+        progress[x][y] = progress[x][y] % 9 + 1;
+
+        contract.save_progress(&progress);
+    }
+
+    #[test]
+    fn replay() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_share_replays(true);
+        start_game(&mut contract, accounts(0));
+
+        let sudoku = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let solution = sudoku.solution().unwrap();
+        let cell = (0..81).find(|&cell| sudoku.0[cell] == 0).unwrap();
+        let mut progress = sudoku;
+        progress.0[cell] = solution.0[cell];
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(2000 * 1_000_000);
+        testing_env!(context.build());
+        let game_id = contract
+            .save_progress(&progress.to_two_dimensional_array())
+            .game_id;
+        // unfinished games can't be replayed
+        assert!(contract.get_replay(accounts(0), game_id).is_none());
+
+        context.block_timestamp(5000 * 1_000_000);
+        testing_env!(context.build());
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+
+        let replay = contract.get_replay(accounts(0), game_id).unwrap();
+        assert_eq!(
+            replay.events[0],
+            replay::ReplayEvent {
+                cell: cell as u8,
+                digit: solution.0[cell],
+                time: 2000,
+            }
+        );
+        assert_eq!(replay.events.len(), 81 - sudoku.n_clues() as usize);
+        assert!(replay.events[1..].iter().all(|event| event.time == 5000));
+        assert!(contract.get_replay(accounts(0), game_id + 1).is_none());
+
+        // opting out deletes the replay and credits its storage back
+        let balance = contract.players.get(&accounts(0)).unwrap().balance;
+        contract.set_share_replays(false);
+        assert!(contract.get_replay(accounts(0), game_id).is_none());
+        assert_eq!(
+            contract.players.get(&accounts(0)).unwrap().balance,
+            balance + REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+    }
+
+    #[test]
+    fn attestations() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let puzzle = contract.players.get(&accounts(0)).unwrap().sudoku.unwrap();
+        let balance = contract.players.get(&accounts(0)).unwrap().balance;
+        assert!(contract.finish_game(&[[0; 9]; 9].into()).is_none());
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(1000 * 1_000_000).block_index(7);
+        testing_env!(context.build());
+        let solution = puzzle.solution().unwrap();
+        contract.finish_game(&solution.to_two_dimensional_array().into());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"solve_attested""#)));
+
+        // transformed copies of the puzzle share the hash
+        let shuffled = puzzle.shuffled(&mut StdRng::seed_from_u64(1));
+        assert_eq!(
+            contract.verify_attestation(accounts(0), 0),
+            Some(AttestationRequest {
+                account_id: accounts(0),
+                index: 0,
+                puzzle_hash: Attestation::new(&shuffled, 0)
+                    .get(accounts(0), 0)
+                    .puzzle_hash,
+                elapsed: 1000 + DEFAULT_WRONG_SUBMISSION_PENALTY,
+                block_height: 7,
+                timestamp: 1000,
+            })
+        );
+        assert!(contract.verify_attestation(accounts(0), 1).is_none());
+        assert!(contract.verify_attestation(accounts(1), 0).is_none());
+        assert_eq!(
+            contract.players.get(&accounts(0)).unwrap().balance,
+            balance
+                - (attestation::ATTESTATION_SIZE + attestation::ATTESTATION_COUNT_SIZE)
+                    * env::STORAGE_PRICE_PER_BYTE
+        );
+
+        // registering again continues the count instead of replacing the attestation
+        let first = contract.verify_attestation(accounts(0), 0);
+        testing_env!(get_context(accounts(0)).build());
+        contract.delete_player();
+        play(&mut contract, accounts(0), 500);
+        assert_eq!(contract.verify_attestation(accounts(0), 0), first);
+        assert_eq!(
+            contract.verify_attestation(accounts(0), 1).unwrap().elapsed,
+            500
+        );
+    }
+
+    #[test]
+    fn attestation_skipped() {
+        let mut contract = Contract::new();
+        start_game(&mut contract, accounts(0));
+        let mut player = contract.players.get(&accounts(0)).unwrap();
+        player.balance = 0;
+        contract.players.insert(&accounts(0), &player);
+
+        let solution = player.sudoku.unwrap().solution().unwrap();
+        let request = contract
+            .finish_game(&solution.to_two_dimensional_array().into())
+            .unwrap();
+        assert!(request.attestation_skipped);
+        assert!(contract.verify_attestation(accounts(0), 0).is_none());
+        assert!(!get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"solve_attested""#)));
+    }
+
+    #[test]
+    fn move_history() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.update_config(Config {
+            record_moves: true,
+            ..contract.get_config()
+        });
+        play(&mut contract, accounts(1), 1000);
+        // the storage of the history is held from the prepaid balance
+        assert_eq!(
+            contract.players.get(&accounts(1)).unwrap().replay_deposit,
+            REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+
+        let player = contract.get_player(accounts(1)).unwrap();
+        let last_game = player.last_sloved_game.unwrap();
+        let history = contract
+            .get_move_history(accounts(1), player.game_id)
+            .unwrap();
+        let n_clues = Sudoku::from_two_dimensional_array(&last_game.sudoku).n_clues();
+        assert_eq!(history.events.len(), 81 - n_clues as usize);
+        let hash: String = history
+            .hash()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(last_game.moves_hash, Some(hash));
+
+        // sharing replays is a separate choice
+        assert!(!player.share_replays);
+        assert!(contract.get_replay(accounts(1), player.game_id).is_none());
+
+        // opting out keeps the history, and its storage stays paid until the player is deleted
+        testing_env!(get_context(accounts(1)).build());
+        let balance = contract.set_share_replays(true).balance.0;
+        let deposit = contract.get_storage_report(accounts(1)).unwrap().player_deposit.0;
+        assert_eq!(contract.set_share_replays(false).balance.0, balance);
+        assert!(contract
+            .get_move_history(accounts(1), player.game_id)
+            .is_some());
+        assert_eq!(
+            contract.get_storage_report(accounts(1)).unwrap().player_deposit.0,
+            deposit
+        );
+        assert_eq!(contract.set_share_replays(true).balance.0, balance);
+        assert_eq!(contract.set_share_replays(false).balance.0, balance);
+        assert_eq!(
+            contract.players.get(&accounts(1)).unwrap().replay_deposit,
+            REPLAY_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+    }
+
+    #[test]
+    fn move_history_unpaid() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.update_config(Config {
+            record_moves: true,
+            ..contract.get_config()
+        });
+        let mut context = get_context(accounts(1));
+        context.attached_deposit(contract.config.player_deposit());
+        testing_env!(context.build());
+        contract.register();
+
+        // a practice game is free, but without a balance for its storage it isn't recorded
+        contract.start_practice();
+        let player = contract.players.get(&accounts(1)).unwrap();
+        assert_eq!(player.replay_deposit, 0);
+        assert!(player.replay.get().is_none());
+        let solution = player.sudoku.unwrap().solution().unwrap();
+        contract.finish_practice(&solution.to_two_dimensional_array().into());
+        let game_id = contract.get_player(accounts(1)).unwrap().game_id;
+        assert!(contract.get_move_history(accounts(1), game_id).is_none());
+    }
+
+    #[test]
+    fn replay_not_shared() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+        let game_id = contract.get_player(accounts(0)).unwrap().game_id;
+        assert!(contract.get_replay(accounts(0), game_id).is_none());
+    }
+
+    #[test]
+    fn set_profile() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(ONE_NEAR);
+        testing_env!(context.build());
+        let player = contract.set_profile("bozon".to_string(), Some("ipfs://avatar".to_string()));
+
+        let profile = Profile {
+            nickname: "bozon".to_string(),
+            avatar_url: Some("ipfs://avatar".to_string()),
+        };
+        assert_eq!(player.profile, Some(profile.clone()));
+        assert_eq!(
+            contract.get_leaderboard_page(BoardKind::Count, 1, 1)[0].profile,
+            Some(profile)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INSUFFICIENT_DEPOSIT")]
+    fn set_profile_without_deposit() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(0), 1000);
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_profile("bozon".to_string(), None);
+    }
+
+    fn verify(contract: &mut Contract, account: AccountId, time: Timestamp) -> PlayerRequest {
+        let mut context = get_context(account);
+        context.block_timestamp(time * 1_000_000);
+        context.attached_deposit(
+            contract.config.verification_stake.0 + VERIFIED_SIZE * env::STORAGE_PRICE_PER_BYTE,
+        );
+        testing_env!(context.build());
+        let player = contract.verify();
+        contract.assert_invariants();
+        player
+    }
+
+    fn unlock_stake(contract: &mut Contract, account: AccountId, time: Timestamp) -> PlayerRequest {
+        let mut context = get_context(account);
+        context.block_timestamp(time * 1_000_000);
+        testing_env!(context.build());
+        let player = contract.unlock_stake();
+        contract.assert_invariants();
+        player
+    }
+
+    #[test]
+    fn verified_leaderboard() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_season_end(10_000);
+
+        play(&mut contract, accounts(1), 1000);
+        play(&mut contract, accounts(2), 500);
+        assert!(verify(&mut contract, accounts(1), 2000).verified);
+        assert_eq!(
+            contract.total_staked,
+            DEFAULT_VERIFICATION_STAKE + VERIFIED_SIZE * env::STORAGE_PRICE_PER_BYTE
+        );
+
+        let page = contract.get_verified_leaderboard_page(BoardKind::Time, 1, 10);
+        assert_eq!(page.len(), 1);
+        assert_eq!((page[0].rank, &page[0].account_id), (1, &accounts(1)));
+        assert_eq!(page[0].value, U128(1000));
+
+        let player = unlock_stake(&mut contract, accounts(1), 10_000);
+        assert!(!player.verified);
+        assert_eq!(contract.total_staked, 0);
+        assert!(contract
+            .get_verified_leaderboard_page(BoardKind::Time, 1, 10)
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_STAKE_LOCKED{\"unlock_at\":10000}")]
+    fn unlock_stake_during_season() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = Contract::new();
+        contract.set_season_end(10_000);
+
+        play(&mut contract, accounts(1), 1000);
+        verify(&mut contract, accounts(1), 2000);
+        // a new season doesn't move the unlock time of earlier stakes
+        testing_env!(get_context(accounts(0)).build());
+        contract.set_season_end(20_000);
+        unlock_stake(&mut contract, accounts(1), 9000);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_SEASON")]
+    fn verify_without_season() {
+        let mut contract = Contract::new();
+        play(&mut contract, accounts(1), 1000);
+        verify(&mut contract, accounts(1), 2000);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INSUFFICIENT_DEPOSIT{\"expected\":\"14300000000000000000000\"}")]
+    fn register_with_insufficient_deposit() {
+        let mut contract = Contract::new();
+
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(1);
+        testing_env!(context.build());
+
+        contract.register();
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PLAYER_NOT_FOUND")]
+    fn start_game_unregistered() {
+        let mut contract = Contract::new();
+        testing_env!(get_context(accounts(0)).build());
+        contract.start_game(None);
+    }
+
+    #[test]
+    fn prepaid_balance() {
+        let mut contract = Contract::new();
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(contract.config.player_deposit() + DEFAULT_GAME_FEE);
+        testing_env!(context.build());
+        let player = contract.register();
+        assert_eq!(player.balance, U128(DEFAULT_GAME_FEE));
+        assert!(player.sudoku.is_none());
+
+        testing_env!(get_context(accounts(0)).build());
+        let player = contract.start_game(None);
+        assert_eq!(player.balance, U128(0));
+        assert_eq!(player.generated_sudoku_count, U128(1));
+        assert_eq!(contract.get_prize_pool(), U128(DEFAULT_GAME_FEE));
+
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(3 * DEFAULT_GAME_FEE);
+        testing_env!(context.build());
+        assert_eq!(contract.top_up().balance, U128(3 * DEFAULT_GAME_FEE));
+        testing_env!(get_context(accounts(0)).build());
+        assert_eq!(contract.withdraw_balance(), U128(3 * DEFAULT_GAME_FEE));
+        assert_eq!(contract.get_player(accounts(0)).unwrap().balance, U128(0));
+    }
+
+    #[test]
+    fn deposit_above_game_fee() {
+        let mut contract = Contract::new();
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(contract.config.player_deposit() + 3 * DEFAULT_GAME_FEE);
+        testing_env!(context.build());
+        contract.register();
+
+        // only the fee goes to the prize pool, the rest stays withdrawable besides the storage of
+        // the daily puzzle archived by the first game of the day
+        testing_env!(get_context(accounts(0)).build());
+        let balance =
+            2 * DEFAULT_GAME_FEE - archive::ARCHIVED_PUZZLE_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        assert_eq!(contract.start_game(None).balance, U128(balance));
+        assert_eq!(contract.get_prize_pool(), U128(DEFAULT_GAME_FEE));
+        assert_eq!(contract.withdraw_balance(), U128(balance));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INSUFFICIENT_BALANCE")]
+    fn start_game_without_balance() {
+        let mut contract = Contract::new();
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(contract.config.player_deposit());
+        testing_env!(context.build());
+        contract.register();
+        contract.start_game(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACTIVE_GAME")]
+    fn finish_without_active_game() {
+        let mut contract = Contract::new();
+
+        play(&mut contract, accounts(0), 1000);
+
+        contract.finish_game(&[[0; 9]; 9].into());
+    }
+
+    /// Starts a kids or marathon game of `account` and returns its solution
+    fn start_sized(contract: &mut Contract, account: AccountId, size: BoardSize) -> String {
+        if contract.players.get(&account).is_none() {
+            register(contract, account.clone());
+        }
+        testing_env!(get_context(account).build());
+        sized_solution(contract.start_game(Some(size)).sized_game.unwrap())
+    }
+
+    fn sized_solution(game: SizedGameRequest) -> String {
+        match game.size {
+            BoardSize::Kids => Sudoku4::from_str_line(&game.sudoku)
+                .unwrap()
+                .solution()
+                .unwrap()
+                .to_str_line(),
+            BoardSize::Marathon => Sudoku16::from_str_line(&game.sudoku)
+                .unwrap()
+                .solution()
+                .unwrap()
+                .to_str_line(),
+        }
+    }
+
+    #[test]
+    fn sized_games() {
+        let mut contract = Contract::new();
+        register(&mut contract, accounts(0));
+        let balance = |contract: &Contract| contract.players.get(&accounts(0)).unwrap().balance;
+        let start_balance = balance(&contract);
+        let ranking_deposit = SIZED_RANKING_SIZE * env::STORAGE_PRICE_PER_BYTE;
+        let game_deposit = |size: BoardSize| size.game_size() * env::STORAGE_PRICE_PER_BYTE;
+
+        let kids = start_sized(&mut contract, accounts(0), BoardSize::Kids);
+        assert_eq!(kids.len(), 16);
+        assert_eq!(
+            balance(&contract),
+            start_balance - game_deposit(BoardSize::Kids) - ranking_deposit
+        );
+        // the 9x9 game is untouched
+        let player = contract.get_player(accounts(0)).unwrap();
+        assert!(player.sudoku.is_none());
+        assert_eq!(player.sized_game.unwrap().size, BoardSize::Kids);
+
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(5_000 * 1_000_000);
+        testing_env!(context.build());
+        assert!(!contract.finish_sized_game("1".repeat(16)));
+        assert!(contract.finish_sized_game(kids));
+        assert!(contract.get_sized_game(accounts(0)).is_none());
+        assert_eq!(balance(&contract), start_balance - ranking_deposit);
+
+        let ranking = |contract: &Contract, size, board| -> Vec<(u32, AccountId, u128)> {
+            contract
+                .get_sized_leaderboard_page(size, board, 1, 10)
+                .into_iter()
+                .map(|entry| (entry.rank, entry.account_id, entry.value.0))
+                .collect()
+        };
+        assert_eq!(
+            ranking(&contract, BoardSize::Kids, BoardKind::Count),
+            vec![(1, accounts(0), 1)]
+        );
+        assert_eq!(
+            ranking(&contract, BoardSize::Kids, BoardKind::Time),
+            vec![(1, accounts(0), 5_000)]
+        );
+        assert!(ranking(&contract, BoardSize::Marathon, BoardKind::Count).is_empty());
+        assert!(contract
+            .get_leaderboard_page(BoardKind::Count, 1, 10)
+            .is_empty());
+
+        // after the cooldown a new game replaces the running one and returns its deposit
+        start_sized(&mut contract, accounts(0), BoardSize::Marathon);
+        let mut context = get_context(accounts(0));
+        context.block_timestamp(DEFAULT_START_GAME_COOLDOWN * 1_000_000);
+        testing_env!(context.build());
+        let game = contract.start_game(Some(BoardSize::Kids)).sized_game;
+        let kids = sized_solution(game.unwrap());
+        assert_eq!(
+            balance(&contract),
+            start_balance - ranking_deposit - game_deposit(BoardSize::Kids)
+        );
+        let mut context = get_context(accounts(0));
+        context.block_timestamp((DEFAULT_START_GAME_COOLDOWN + 3_000) * 1_000_000);
+        testing_env!(context.build());
+        assert!(contract.finish_sized_game(kids));
+        assert_eq!(
+            ranking(&contract, BoardSize::Kids, BoardKind::Count),
+            vec![(1, accounts(0), 2)]
+        );
+        assert_eq!(
+            ranking(&contract, BoardSize::Kids, BoardKind::Time),
+            vec![(1, accounts(0), 3_000)]
+        );
+
+        start_sized(&mut contract, accounts(0), BoardSize::Marathon);
+        contract.delete_player();
+        assert!(contract.get_sized_game(accounts(0)).is_none());
+        assert!(ranking(&contract, BoardSize::Kids, BoardKind::Count).is_empty());
+        assert!(contract.get_player(accounts(0)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COOLDOWN{\"available_at\":30000}")]
+    fn start_sized_during_cooldown() {
+        let mut contract = Contract::new();
+        start_sized(&mut contract, accounts(0), BoardSize::Kids);
+        start_sized(&mut contract, accounts(0), BoardSize::Marathon);
+    }
+
+    #[test]
+    fn sized_game_storage() {
+        let mut contract = Contract::new();
+        // the storage usage restarts with every new context, so no helper setting one is used
+        register(&mut contract, accounts(0));
+        for &size in &BoardSize::ALL {
+            let storage_before = env::storage_usage();
+            let solution = sized_solution(contract.start_game(Some(size)).sized_game.unwrap());
+            let game_bytes = env::storage_usage() - storage_before;
+            assert!(game_bytes as u128 <= size.game_size());
+            assert!(contract.finish_sized_game(solution));
+            let ranking_bytes = env::storage_usage() - storage_before;
+            assert!(ranking_bytes as u128 <= SIZED_RANKING_SIZE);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_BOARD_NOT_SIZED")]
+    fn sized_leaderboard_without_points() {
+        let contract = Contract::new();
+        contract.get_sized_leaderboard_page(BoardSize::Kids, BoardKind::Points, 1, 10);
+    }
+}
@@ -0,0 +1,61 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use near_sdk::{AccountId, Timestamp};
+
+use crate::{Sudoku, SudokuTwoDimensionalArray};
+
+/// Maximal number of accounts sharing a co-op game
+pub const COOP_MAX_PLAYERS: usize = 4;
+
+/// A sudoku shared by several players, any of them may save progress or submit the solution
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CoopGame {
+    /// Paid the storage of the game and gets it refunded once the game is solved
+    pub creator: AccountId,
+    pub participants: Vec<AccountId>,
+    pub sudoku: Sudoku,
+    pub progress: Option<Sudoku>,
+    pub start_time: Timestamp,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CoopGameRequest {
+    pub creator: AccountId,
+    pub participants: Vec<AccountId>,
+    pub sudoku: SudokuTwoDimensionalArray,
+    pub progress: Option<SudokuTwoDimensionalArray>,
+    pub start_time: Timestamp,
+}
+
+impl CoopGame {
+    pub fn is_participant(&self, account_id: &AccountId) -> bool {
+        self.participants.contains(account_id)
+    }
+
+    /// Checks that `array` is a partially filled grid that keeps all clues of the sudoku.
+    pub fn progress_valid(&self, array: &SudokuTwoDimensionalArray) -> bool {
+        let clues = self.sudoku.to_two_dimensional_array();
+        array
+            .iter()
+            .flatten()
+            .zip(clues.iter().flatten())
+            .all(|(&num, &clue)| num <= 9 && (clue == 0 || clue == num))
+    }
+
+    pub fn is_solution(&self, array: &SudokuTwoDimensionalArray) -> bool {
+        self.progress_valid(array) && Sudoku::from_two_dimensional_array(array).is_solved()
+    }
+
+    pub fn get(&self) -> CoopGameRequest {
+        CoopGameRequest {
+            creator: self.creator.clone(),
+            participants: self.participants.clone(),
+            sudoku: self.sudoku.to_two_dimensional_array(),
+            progress: self
+                .progress
+                .map(|progress| progress.to_two_dimensional_array()),
+            start_time: self.start_time,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+#[cfg(feature = "std")]
+use near_sdk::serde::{Deserialize, Serialize};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::{Sudoku, Symmetry};
+
+/// How hard a puzzle handed out by the contract is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "std",
+    derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)
+)]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+#[cfg_attr(feature = "std", serde(rename_all = "snake_case"))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Lowercase name, as in the puzzle pools of the `poolgen` tool
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Inverse of [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Difficulty::ALL.iter().copied().find(|difficulty| difficulty.name() == name)
+    }
+
+    /// Minimal puzzles get padded with clues from their solution until they have at least this many.
+    fn min_clues(self) -> u8 {
+        match self {
+            Difficulty::Easy => 36,
+            Difficulty::Medium => 30,
+            Difficulty::Hard => 26,
+            Difficulty::Expert => 0,
+        }
+    }
+
+    /// Symmetry of the clue positions. The stricter symmetries keep more clues,
+    /// which suits the easier puzzles.
+    pub fn symmetry(self) -> Symmetry {
+        match self {
+            Difficulty::Easy => Symmetry::Dihedral,
+            Difficulty::Medium => Symmetry::QuarterRotation,
+            Difficulty::Hard => Symmetry::HalfRotation,
+            Difficulty::Expert => Symmetry::DiagonalMirror,
+        }
+    }
+
+    /// Generates a uniquely solvable sudoku of this difficulty with clues laid out in its [`symmetry`](Self::symmetry).
+    pub fn generate(self, rng: &mut StdRng) -> Sudoku {
+        let symmetry = self.symmetry();
+        let mut sudoku = Sudoku::generate_with_symmetry(symmetry, rng);
+        if sudoku.n_clues() >= self.min_clues() {
+            return sudoku;
+        }
+
+        // pad with whole symmetry classes so the layout stays symmetric
+        let solution = sudoku.solution().unwrap();
+        let mut empty_cells: Vec<usize> = (0..81).filter(|&cell| sudoku.0[cell] == 0).collect();
+        empty_cells.shuffle(rng);
+        for cell in empty_cells {
+            if sudoku.n_clues() >= self.min_clues() {
+                break;
+            }
+            for cell in symmetry.corresponding_cells(cell) {
+                sudoku.0[cell] = solution.0[cell];
+            }
+        }
+
+        sudoku
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generate() {
+        let mut rng = StdRng::from_seed([7; 32]);
+        for &difficulty in &Difficulty::ALL {
+            let sudoku = difficulty.generate(&mut rng);
+            assert!(sudoku.is_uniquely_solvable());
+            assert!(sudoku.n_clues() >= difficulty.min_clues());
+
+            let symmetry = difficulty.symmetry();
+            for cell in 0..81 {
+                let is_clue = sudoku.0[cell] != 0;
+                assert!(symmetry
+                    .corresponding_cells(cell)
+                    .iter()
+                    .all(|&other| (sudoku.0[other] != 0) == is_clue));
+            }
+        }
+    }
+}
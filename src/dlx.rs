@@ -0,0 +1,310 @@
+//! Dancing links (DLX) solver, an independent implementation of the sudoku rules
+//!
+//! Sudoku is solved as an exact cover problem with Knuth's Algorithm X. It's considerably slower than the
+//! default solver behind [`Sudoku::solution`], but simple enough to serve as an oracle for cross-checking it.
+//! Only compiled with the `dlx` feature.
+use crate::consts::*;
+use crate::Sudoku;
+
+/// Each cell needs a digit and each digit is needed once per row, column and block
+const N_COLUMNS: usize = 4 * N_CELLS;
+/// One option per digit in each cell
+const N_OPTIONS: usize = N_CELLS * N_DIGITS;
+/// The root header and the column headers come first, then 4 nodes per option
+const N_NODES: usize = 1 + N_COLUMNS + 4 * N_OPTIONS;
+const ROOT: usize = 0;
+
+/// Returns the unique solution of `sudoku` or `None`, if it has no or multiple solutions.
+pub fn solution(sudoku: Sudoku) -> Option<Sudoku> {
+    let mut solutions = solutions_iter(sudoku);
+    match (solutions.next(), solutions.next()) {
+        (Some(solution), None) => Some(solution),
+        _ => None,
+    }
+}
+
+/// Counts the solutions of `sudoku` up to `limit`.
+pub fn count_solutions_up_to(sudoku: Sudoku, limit: usize) -> usize {
+    solutions_iter(sudoku).take(limit).count()
+}
+
+/// Returns an iterator over all solutions of `sudoku`. The solutions are searched lazily, one per call to `next`.
+pub fn solutions_iter(sudoku: Sudoku) -> Solutions {
+    Solutions::new(sudoku)
+}
+
+/// Iterator over the solutions of a sudoku, see [`solutions_iter`]
+#[derive(Clone, Debug)]
+pub struct Solutions {
+    links: Links,
+    givens: [u8; N_CELLS],
+    // the node of each chosen option, from the first to the latest choice
+    choices: Vec<usize>,
+    // whether the next step is to undo the latest choice
+    backtrack: bool,
+    exhausted: bool,
+}
+
+impl Solutions {
+    fn new(sudoku: Sudoku) -> Self {
+        let mut links = Links::new();
+        let mut exhausted = false;
+        for (cell, &digit) in sudoku.0.iter().enumerate().filter(|&(_, &digit)| digit != 0) {
+            let node = option_node(cell * N_DIGITS + digit as usize - 1);
+            // an already covered column means a clue conflicts with an earlier one
+            exhausted |= !links.select(node);
+        }
+        Solutions {
+            links,
+            givens: sudoku.0,
+            choices: vec![],
+            backtrack: false,
+            exhausted,
+        }
+    }
+
+    fn solution(&self) -> Sudoku {
+        let mut grid = self.givens;
+        for &node in &self.choices {
+            let option = (node - option_node(0)) / 4;
+            grid[option / N_DIGITS] = (option % N_DIGITS) as u8 + 1;
+        }
+        Sudoku(grid)
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Sudoku;
+
+    fn next(&mut self) -> Option<Sudoku> {
+        let links = &mut self.links;
+        while !self.exhausted {
+            if self.backtrack {
+                let node = match self.choices.pop() {
+                    Some(node) => node,
+                    None => {
+                        self.exhausted = true;
+                        break;
+                    }
+                };
+                links.uncover_others(node);
+                let column = links.column[node];
+                let next = links.down[node];
+                if next == column {
+                    links.uncover(column);
+                } else {
+                    links.cover_others(next);
+                    self.choices.push(next);
+                    self.backtrack = false;
+                }
+                continue;
+            }
+
+            if links.right[ROOT] == ROOT {
+                self.backtrack = true;
+                return Some(self.solution());
+            }
+            let column = links.smallest_column();
+            if links.size[column] == 0 {
+                self.backtrack = true;
+                continue;
+            }
+            links.cover(column);
+            let node = links.down[column];
+            links.cover_others(node);
+            self.choices.push(node);
+        }
+        None
+    }
+}
+
+// The first of the 4 nodes of an option
+fn option_node(option: usize) -> usize {
+    1 + N_COLUMNS + 4 * option
+}
+
+// The constraint columns satisfied by an option, as indices of their headers
+fn option_columns(option: usize) -> [usize; 4] {
+    let (cell, digit) = (option / N_DIGITS, option % N_DIGITS);
+    let (row, col) = (cell / 9, cell % 9);
+    let block = row / 3 * 3 + col / 3;
+    [
+        1 + cell,
+        1 + N_CELLS + row * 9 + digit,
+        1 + 2 * N_CELLS + col * 9 + digit,
+        1 + 3 * N_CELLS + block * 9 + digit,
+    ]
+}
+
+// The toroidal doubly linked lists of the exact cover matrix, stored as indices into flat arrays
+#[derive(Clone, Debug)]
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    // number of options remaining in each column, indexed by header
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new() -> Self {
+        let mut links = Links {
+            left: (0..N_NODES).collect(),
+            right: (0..N_NODES).collect(),
+            up: (0..N_NODES).collect(),
+            down: (0..N_NODES).collect(),
+            column: (0..N_NODES).collect(),
+            size: vec![0; 1 + N_COLUMNS],
+        };
+        for header in 0..=N_COLUMNS {
+            links.left[header] = if header == ROOT { N_COLUMNS } else { header - 1 };
+            links.right[header] = if header == N_COLUMNS { ROOT } else { header + 1 };
+        }
+        for option in 0..N_OPTIONS {
+            let first = option_node(option);
+            for (i, &column) in option_columns(option).iter().enumerate() {
+                let node = first + i;
+                links.left[node] = first + (i + 3) % 4;
+                links.right[node] = first + (i + 1) % 4;
+                links.column[node] = column;
+                // append at the bottom of the column
+                links.up[node] = links.up[column];
+                links.down[node] = column;
+                let last = links.up[column];
+                links.down[last] = node;
+                links.up[column] = node;
+                links.size[column] += 1;
+            }
+        }
+        links
+    }
+
+    // The column with the fewest remaining options. The root must not be the only header left.
+    fn smallest_column(&self) -> usize {
+        let mut smallest = self.right[ROOT];
+        let mut column = self.right[smallest];
+        while column != ROOT {
+            if self.size[column] < self.size[smallest] {
+                smallest = column;
+            }
+            column = self.right[column];
+        }
+        smallest
+    }
+
+    // Chooses the option of `node` outright. Returns false, if one of its columns is already covered.
+    fn select(&mut self, node: usize) -> bool {
+        let mut current = node;
+        loop {
+            let column = self.column[current];
+            if self.right[self.left[column]] != column {
+                return false;
+            }
+            current = self.right[current];
+            if current == node {
+                break;
+            }
+        }
+        self.cover(self.column[node]);
+        self.cover_others(node);
+        true
+    }
+
+    fn cover(&mut self, column: usize) {
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut row = self.down[column];
+        while row != column {
+            let mut node = self.right[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, column: usize) {
+        let mut row = self.up[column];
+        while row != column {
+            let mut node = self.left[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = column;
+        self.left[right] = column;
+    }
+
+    // Covers the columns of the option of `node` other than the column of `node` itself
+    fn cover_others(&mut self, node: usize) {
+        let mut current = self.right[node];
+        while current != node {
+            self.cover(self.column[current]);
+            current = self.right[current];
+        }
+    }
+
+    // Reverts `cover_others` for `node`
+    fn uncover_others(&mut self, node: usize) {
+        let mut current = self.left[node];
+        while current != node {
+            self.uncover(self.column[current]);
+            current = self.left[current];
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn matches_default_solver() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let sudoku = Sudoku::generate(&mut rng);
+            assert_eq!(solution(sudoku), sudoku.solution());
+            assert_eq!(count_solutions_up_to(sudoku, 2), 1);
+
+            // removing clues of a minimal puzzle creates further solutions
+            let mut ambiguous = sudoku;
+            for digit in ambiguous.0.iter_mut().filter(|digit| **digit != 0).take(5) {
+                *digit = 0;
+            }
+            assert_eq!(count_solutions_up_to(ambiguous, 50), ambiguous.solutions_count_up_to(50));
+            assert_eq!(solution(ambiguous), None);
+        }
+    }
+
+    #[test]
+    fn solutions_iter() {
+        let solved = Sudoku::generate_solved(&mut StdRng::seed_from_u64(1));
+        assert_eq!(super::solutions_iter(solved).collect::<Vec<_>>(), [solved]);
+
+        let empty = Sudoku([0; N_CELLS]);
+        let solutions = super::solutions_iter(empty).take(3).collect::<Vec<_>>();
+        assert_eq!(solutions.len(), 3);
+        assert!(solutions.iter().all(|solution| solution.is_solved()));
+        assert!(solutions[0] != solutions[1] && solutions[1] != solutions[2]);
+
+        let mut conflicting = [0; N_CELLS];
+        conflicting[0] = 1;
+        conflicting[1] = 1;
+        assert_eq!(super::solutions_iter(Sudoku(conflicting)).next(), None);
+    }
+}
@@ -1,28 +1,199 @@
-//! Errors that may occur when reading sudokus
+//! Errors that may occur when reading sudokus and errors the contract aborts with
+//!
+//! The library errors implement `Display` everywhere and `std::error::Error` with the `std` feature.
 #[cfg(doc)]
 use crate::Sudoku;
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
+use near_sdk::{env, Balance, BlockHeight, Timestamp};
 
 /// Error for [`Sudoku::from_bytes`]
-#[derive(Debug, thiserror::Error)]
-#[error("byte array contains entries >9")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FromBytesError(pub(crate) ());
 
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("byte array contains entries >9")
+    }
+}
+
 /// Error for [`Sudoku::from_bytes_slice`]
-#[derive(Debug, thiserror::Error)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum FromBytesSliceError {
     /// Slice is not 81 long
-    #[error("byte slice should have length 81, found {0}")]
     WrongLength(usize),
     /// Slice contains invalid entries
-    #[error(transparent)]
     FromBytesError(FromBytesError),
 }
 
-use crate::board::{block, col, row};
+impl fmt::Display for FromBytesSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromBytesSliceError::WrongLength(len) => {
+                write!(f, "byte slice should have length 81, found {}", len)
+            }
+            FromBytesSliceError::FromBytesError(err) => err.fmt(f),
+        }
+    }
+}
+
+/// Error for the `TryFrom<u8>` conversions of the [position types](crate::board::positions)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OutOfRange {
+    /// The rejected number
+    pub value: u8,
+    /// The exclusive upper bound of the type
+    pub limit: u8,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is out of range, expected 0..{}", self.value, self.limit)
+    }
+}
+
+/// Error for the `TryFrom` conversions of [`Digit`](crate::board::Digit)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InvalidDigit {
+    /// The number is not in 1-9
+    Number(u8),
+    /// The char is not one of '1'-'9'
+    Char(char),
+}
+
+impl fmt::Display for InvalidDigit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidDigit::Number(num) => write!(f, "{} is not a digit from 1 to 9", num),
+            InvalidDigit::Char(ch) => write!(f, "'{}' is not a digit from 1 to 9", ch),
+        }
+    }
+}
+
+/// Error for [`Sudoku::solve_with_limit`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Exceeded {
+    /// The number of guesses the solver was allowed to make
+    pub max_guesses: u32,
+}
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "solver needed more than {} guesses", self.max_guesses)
+    }
+}
+
+/// The first contradiction found in a sudoku without solution, see [`Sudoku::contradiction`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Contradiction {
+    /// The digit is entered more than once in the house
+    DuplicateDigit {
+        /// The house containing the duplicates
+        house: House,
+        /// The duplicated digit
+        digit: u8,
+    },
+    /// All digits are excluded from the empty cell
+    NoCandidates {
+        /// The empty cell
+        cell: Cell,
+    },
+    /// The digit is missing from the house and excluded from all its empty cells
+    DigitImpossible {
+        /// The house missing the digit
+        house: House,
+        /// The missing digit
+        digit: u8,
+    },
+    /// Filling in singles found no contradiction, it only shows up when trying all possibilities
+    NoSolution,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Contradiction::DuplicateDigit { house, digit } => {
+                write!(f, "digit {} appears twice in {}", digit, house)
+            }
+            Contradiction::NoCandidates { cell } => write!(f, "cell {} has no candidates", cell),
+            Contradiction::DigitImpossible { house, digit } => {
+                write!(f, "digit {} impossible in {}", digit, house)
+            }
+            Contradiction::NoSolution => f.write_str("sudoku has no solution"),
+        }
+    }
+}
+
+/// Error for [`Sudoku::from_clue_bytes`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FromClueBytesError {
+    /// The slice doesn't consist of (cell, digit) pairs
+    OddLength(usize),
+    /// The cell is not below 81 or the digit not in 1-9
+    InvalidClue {
+        /// Cell number, should be in 0..=80
+        cell: u8,
+        /// Digit, should be in 1..=9
+        digit: u8,
+    },
+    /// The cell is given more than once
+    DuplicateCell(u8),
+}
+
+impl fmt::Display for FromClueBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromClueBytesError::OddLength(len) => {
+                write!(f, "clue list should have an even length, found {}", len)
+            }
+            FromClueBytesError::InvalidClue { cell, digit } => {
+                write!(f, "invalid clue with cell {} and digit {}", cell, digit)
+            }
+            FromClueBytesError::DuplicateCell(cell) => {
+                write!(f, "cell {} is given more than once", cell)
+            }
+        }
+    }
+}
+
+/// Error for [`SudokuBuilder`](crate::board::SudokuBuilder)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BuildError {
+    /// The digit is already entered in another cell of the same row, column or block
+    Conflict {
+        /// The cell the digit should be entered in
+        cell: Cell,
+        /// The rejected digit
+        digit: u8,
+        /// The cell already holding the digit
+        peer: Cell,
+    },
+    /// The clues allow no or multiple solutions
+    NotUnique {
+        /// The number of solutions, counted up to 2
+        n_solutions: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::Conflict { cell, digit, peer } => {
+                write!(f, "digit {} in cell {} clashes with cell {}", digit, cell, peer)
+            }
+            BuildError::NotUnique { n_solutions } => {
+                write!(f, "sudoku has {} solutions instead of 1", n_solutions)
+            }
+        }
+    }
+}
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+use crate::bitset::InvalidBits;
+use crate::board::{block, col, row, Cell, House};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 /// An invalid sudoku entry encountered during parsing.
-#[error("cell {cell} contains invalid character '{ch}'")]
 pub struct InvalidEntry {
     /// Cell number goes from 0..=80, 0..=8 for first line, 9..=17 for 2nd and so on
     pub cell: u8,
@@ -30,6 +201,11 @@ pub struct InvalidEntry {
     pub ch: char,
 }
 
+impl fmt::Display for InvalidEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cell {} contains invalid character '{}'", self.cell, self.ch)
+    }
+}
 impl InvalidEntry {
     /// Row index from 0..=8, topmost row is 0
     #[inline]
@@ -48,51 +224,571 @@ impl InvalidEntry {
     }
 }
 
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Error for lax block format parsing. Contains the number of rows found.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, thiserror::Error)]
-#[error("input ended after {0} valid rows")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct NotEnoughRows(pub u8);
 
+impl fmt::Display for NotEnoughRows {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input ended after {} valid rows", self.0)
+    }
+}
+
 /// A structure representing an error caused when parsing the sudoku
-#[derive(Clone, Debug, Eq, Hash, PartialEq, thiserror::Error)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum BlockParseError {
     /// Non-digit, non-placeholder encountered. Field delimiters chars in unexpected places also cause this
-    #[error(transparent)]
     InvalidEntry(InvalidEntry),
     /// Line contains (>9 valid entries) or (<9 and no invalids)
     /// Returns index of row (0-8)
-    #[error("a sudoku line should have 9 entries, found {0}")]
     InvalidLineLength(u8),
     /// Input ends with less than 9 rows. Returns number of rows encountered.
-    #[error("a sudoku should have 9 rows, found only {0}")]
     NotEnoughRows(u8),
     /// If field delimiter is in place after 3rd number in 1st row
     /// all other horizontal and vertical field delimiters must be present or this is emitted
-    #[error("inconsistent field delimiters")]
     IncorrectFieldDelimiter,
     /// More than 9 lines are supplied and the 10th line is not pure whitespace
-    #[error("a sudoku should have 9 rows, found a 10th")]
     TooManyRows,
     /// Non-digit, non-placeholder after completed line encountered but without space
-    #[error("missing comment delimiter in line {0}")]
     MissingCommentDelimiter(u8),
 }
 
+impl fmt::Display for BlockParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockParseError::InvalidEntry(err) => err.fmt(f),
+            BlockParseError::InvalidLineLength(len) => {
+                write!(f, "a sudoku line should have 9 entries, found {}", len)
+            }
+            BlockParseError::NotEnoughRows(n_rows) => {
+                write!(f, "a sudoku should have 9 rows, found only {}", n_rows)
+            }
+            BlockParseError::IncorrectFieldDelimiter => f.write_str("inconsistent field delimiters"),
+            BlockParseError::TooManyRows => f.write_str("a sudoku should have 9 rows, found a 10th"),
+            BlockParseError::MissingCommentDelimiter(row) => {
+                write!(f, "missing comment delimiter in line {}", row)
+            }
+        }
+    }
+}
+
 /// A structure representing an error caused when parsing the sudoku
-#[derive(Clone, Debug, Eq, Hash, PartialEq, thiserror::Error)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum LineParseError {
     /// Accepted values are numbers 1...9 and '0', '.' or '_' for empty cells
-    #[error(transparent)]
     InvalidEntry(InvalidEntry),
     /// Returns number of cells supplied
-    #[error("sudoku contains {0} cells instead of required 81")]
     NotEnoughCells(u8),
     /// Returned if >=82 valid cell positions are supplied
-    #[error("sudoku contains more than 81 cells or is missing comment delimiter")]
     TooManyCells,
-    /// Comments must be delimited by a space or tab.
-    #[error("missing comment delimiter")]
-    MissingCommentDelimiter,
+    /// Comments must be delimited by whitespace, a comma or a semicolon. Contains the first char of the comment.
+    MissingCommentDelimiter(char),
+}
+
+impl fmt::Display for LineParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineParseError::InvalidEntry(err) => err.fmt(f),
+            LineParseError::NotEnoughCells(n_cells) => {
+                write!(f, "sudoku contains {} cells instead of required 81", n_cells)
+            }
+            LineParseError::TooManyCells => {
+                f.write_str("sudoku contains more than 81 cells or is missing comment delimiter")
+            }
+            LineParseError::MissingCommentDelimiter(ch) => {
+                write!(f, "missing comment delimiter before '{}'", ch)
+            }
+        }
+    }
+}
+
+impl LineParseError {
+    /// Byte offset of the error in the parsed string
+    pub fn offset(&self) -> usize {
+        match *self {
+            LineParseError::InvalidEntry(InvalidEntry { cell, .. }) => cell as usize,
+            LineParseError::NotEnoughCells(n_cells) => n_cells as usize,
+            LineParseError::TooManyCells | LineParseError::MissingCommentDelimiter(_) => 81,
+        }
+    }
+
+    /// The offending char, if the error was caused by one
+    pub fn ch(&self) -> Option<char> {
+        match *self {
+            LineParseError::InvalidEntry(InvalidEntry { ch, .. })
+            | LineParseError::MissingCommentDelimiter(ch) => Some(ch),
+            LineParseError::NotEnoughCells(_) | LineParseError::TooManyCells => None,
+        }
+    }
+}
+
+/// Error for [`GridState::from_str_block`](crate::board::GridState::from_str_block)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum GridStateParseError {
+    /// An entry contains something else than the digits 1-9 or is not just `'_'`
+    InvalidEntry(String),
+    /// Returns the number of cells found
+    WrongCellCount(usize),
+}
+
+impl fmt::Display for GridStateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridStateParseError::InvalidEntry(entry) => write!(f, "invalid entry '{}'", entry),
+            GridStateParseError::WrongCellCount(n_cells) => {
+                write!(f, "a grid state should have 81 cells, found {}", n_cells)
+            }
+        }
+    }
+}
+
+/// Error for invalid cages of a [`KillerSudoku`](crate::cages::KillerSudoku)
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum CageError {
+    /// A cage must contain at least one cell
+    NoCells,
+    /// The sum can't be reached with distinct digits in a cage of this size
+    ImpossibleSum { n_cells: u8, sum: u8 },
+    /// Returns the first cell that lies in more than one cage
+    OverlappingCages(u8),
+}
+
+impl fmt::Display for CageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CageError::NoCells => f.write_str("a cage needs at least one cell"),
+            CageError::ImpossibleSum { n_cells, sum } => {
+                write!(f, "a cage of {} cells can't sum up to {}", n_cells, sum)
+            }
+            CageError::OverlappingCages(cell) => {
+                write!(f, "cell {} lies in more than one cage", cell)
+            }
+        }
+    }
+}
+
+/// Error for the constructors of [`SizedSudoku`](crate::sized::SizedSudoku)
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SizedSudokuError {
+    /// Returns the number of cells found
+    WrongCellCount(usize),
+    /// The char is not a digit of the grid size, `.`, `0` or `_`
+    InvalidEntry {
+        /// Cell number, counted row by row from 0
+        cell: usize,
+        /// The rejected char
+        ch: char,
+    },
+    /// The digit is larger than the number of rows
+    InvalidDigit {
+        /// Cell number, counted row by row from 0
+        cell: usize,
+        /// The rejected digit
+        digit: u8,
+    },
+}
+
+impl fmt::Display for SizedSudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SizedSudokuError::WrongCellCount(n_cells) => {
+                write!(f, "sudoku has {} cells, which is not the square of a grid size", n_cells)
+            }
+            SizedSudokuError::InvalidEntry { cell, ch } => {
+                write!(f, "cell {} contains invalid character '{}'", cell, ch)
+            }
+            SizedSudokuError::InvalidDigit { cell, digit } => {
+                write!(f, "cell {} contains digit {}, which is too large for the grid", cell, digit)
+            }
+        }
+    }
+}
+
+/// Any error of the sudoku library, so applications can combine them with `?`
+///
+/// Every specific error converts into it with `From`. The errors of the contract itself are
+/// [`ContractError`] and not part of it.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// See [`LineParseError`]
+    LineParse(LineParseError),
+    /// See [`BlockParseError`]
+    BlockParse(BlockParseError),
+    /// See [`NotEnoughRows`]
+    NotEnoughRows(NotEnoughRows),
+    /// See [`GridStateParseError`]
+    GridStateParse(GridStateParseError),
+    /// See [`FromBytesError`]
+    FromBytes(FromBytesError),
+    /// See [`FromBytesSliceError`]
+    FromBytesSlice(FromBytesSliceError),
+    /// See [`FromClueBytesError`]
+    FromClueBytes(FromClueBytesError),
+    /// See [`OutOfRange`]
+    OutOfRange(OutOfRange),
+    /// See [`InvalidDigit`]
+    InvalidDigit(InvalidDigit),
+    /// See [`InvalidBits`]
+    InvalidBits(InvalidBits),
+    /// The sudoku has no solution, see [`Contradiction`]
+    Unsolvable(Contradiction),
+    /// See [`Exceeded`]
+    Exceeded(Exceeded),
+    /// See [`BuildError`]
+    Build(BuildError),
+    /// See [`CageError`]
+    Cage(CageError),
+    /// See [`SizedSudokuError`]
+    Sized(SizedSudokuError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::LineParse(err) => err.fmt(f),
+            Error::BlockParse(err) => err.fmt(f),
+            Error::NotEnoughRows(err) => err.fmt(f),
+            Error::GridStateParse(err) => err.fmt(f),
+            Error::FromBytes(err) => err.fmt(f),
+            Error::FromBytesSlice(err) => err.fmt(f),
+            Error::FromClueBytes(err) => err.fmt(f),
+            Error::OutOfRange(err) => err.fmt(f),
+            Error::InvalidDigit(err) => err.fmt(f),
+            Error::InvalidBits(err) => err.fmt(f),
+            Error::Unsolvable(err) => err.fmt(f),
+            Error::Exceeded(err) => err.fmt(f),
+            Error::Build(err) => err.fmt(f),
+            Error::Cage(err) => err.fmt(f),
+            Error::Sized(err) => err.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_from_errors {
+    ($($variant:ident($error:ty)),* $(,)?) => {$(
+        impl From<$error> for Error {
+            fn from(err: $error) -> Self {
+                Error::$variant(err)
+            }
+        }
+    )*};
+}
+
+impl_from_errors!(
+    LineParse(LineParseError),
+    BlockParse(BlockParseError),
+    NotEnoughRows(NotEnoughRows),
+    GridStateParse(GridStateParseError),
+    FromBytes(FromBytesError),
+    FromBytesSlice(FromBytesSliceError),
+    FromClueBytes(FromClueBytesError),
+    OutOfRange(OutOfRange),
+    InvalidDigit(InvalidDigit),
+    InvalidBits(InvalidBits),
+    Unsolvable(Contradiction),
+    Exceeded(Exceeded),
+    Build(BuildError),
+    Cage(CageError),
+    Sized(SizedSudokuError),
+);
+
+macro_rules! impl_std_errors {
+    ($($error:ty),* $(,)?) => {$(
+        #[cfg(feature = "std")]
+        impl std::error::Error for $error {}
+    )*};
+}
+
+impl_std_errors!(
+    FromBytesError,
+    FromBytesSliceError,
+    OutOfRange,
+    InvalidDigit,
+    Exceeded,
+    Contradiction,
+    FromClueBytesError,
+    BuildError,
+    InvalidEntry,
+    NotEnoughRows,
+    BlockParseError,
+    LineParseError,
+    GridStateParseError,
+    CageError,
+    InvalidBits,
+    SizedSudokuError,
+    Error,
+);
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Errors the contract aborts with.
+///
+/// Each error is rendered as a stable machine-readable code, optionally followed by a JSON object
+/// with details, e.g. `ERR_WRONG_DEPOSIT{"expected":"4030000000000000000000"}`.
+/// Frontends should match on the code, never on the rest of the message.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, thiserror::Error)]
+pub enum ContractError {
+    /// The attached deposit does not match the required amount
+    #[error("ERR_WRONG_DEPOSIT{{\"expected\":\"{expected}\"}}")]
+    WrongDeposit {
+        /// Deposit in yoctonear that has to be attached
+        expected: Balance,
+    },
+    /// The caller isn't registered, see `register`
+    #[error("ERR_PLAYER_NOT_FOUND")]
+    PlayerNotFound,
+    /// The caller has no unfinished game
+    #[error("ERR_NO_ACTIVE_GAME")]
+    NoActiveGame,
+    /// The method can only be called by the contract owner
+    #[error("ERR_NOT_OWNER")]
+    NotOwner,
+    /// An unfinished game can't be replaced by a new one yet
+    #[error("ERR_COOLDOWN{{\"available_at\":{available_at}}}")]
+    Cooldown {
+        /// Block timestamp in milliseconds from which on a new game can be started
+        available_at: Timestamp,
+    },
+    /// The campaign has no such level
+    #[error("ERR_UNKNOWN_LEVEL{{\"level\":{level}}}")]
+    UnknownLevel {
+        /// The requested level
+        level: u32,
+    },
+    /// The previous campaign level hasn't been completed yet
+    #[error("ERR_LEVEL_LOCKED{{\"level\":{level}}}")]
+    LevelLocked {
+        /// The requested level
+        level: u32,
+    },
+    /// A submitted sudoku in line format couldn't be parsed
+    #[error("ERR_INVALID_SUDOKU_LINE")]
+    InvalidSudokuLine,
+    /// Saved progress changes a clue or contains entries >9
+    #[error("ERR_INVALID_PROGRESS")]
+    InvalidProgress,
+    /// Notes bitmask has the wrong length or sets padding bits
+    #[error("ERR_INVALID_NOTES")]
+    InvalidNotes,
+    /// The attached deposit does not cover the additional storage
+    #[error("ERR_INSUFFICIENT_DEPOSIT{{\"expected\":\"{expected}\"}}")]
+    InsufficientDeposit {
+        /// Minimal deposit in yoctonear that has to be attached
+        expected: Balance,
+    },
+    /// Nickname has the wrong length or contains forbidden characters
+    #[error("ERR_INVALID_NICKNAME")]
+    InvalidNickname,
+    /// Avatar url is too long, has an unsupported scheme or contains whitespace
+    #[error("ERR_INVALID_AVATAR_URL")]
+    InvalidAvatarUrl,
+    /// The daily reward has already been claimed today
+    #[error("ERR_ALREADY_CLAIMED{{\"available_at\":{available_at}}}")]
+    AlreadyClaimed {
+        /// Block timestamp in milliseconds from which on the next reward can be claimed
+        available_at: Timestamp,
+    },
+    /// The daily reward requires a sudoku solved on the same UTC day
+    #[error("ERR_NO_SOLVE_TODAY")]
+    NoSolveToday,
+    /// No season is running, stakes can't be locked
+    #[error("ERR_NO_SEASON")]
+    NoSeason,
+    /// The caller already locked a stake
+    #[error("ERR_ALREADY_VERIFIED")]
+    AlreadyVerified,
+    /// The caller has no locked stake
+    #[error("ERR_NOT_VERIFIED")]
+    NotVerified,
+    /// The stake is locked until the end of the season it was locked for
+    #[error("ERR_STAKE_LOCKED{{\"unlock_at\":{unlock_at}}}")]
+    StakeLocked {
+        /// Block timestamp in milliseconds from which on the stake can be unlocked
+        unlock_at: Timestamp,
+    },
+    /// The caller has no unclaimed prize
+    #[error("ERR_PRIZE_NOT_FOUND")]
+    PrizeNotFound,
+    /// The prize wasn't claimed in time and returns to the pool
+    #[error("ERR_PRIZE_EXPIRED{{\"expired_at\":{expired_at}}}")]
+    PrizeExpired {
+        /// Block timestamp in milliseconds from which on the prize couldn't be claimed anymore
+        expired_at: Timestamp,
+    },
+    /// The solution came in faster than humanly plausible for the sudoku's difficulty
+    #[error("ERR_SOLVE_TOO_FAST{{\"available_at\":{available_at}}}")]
+    SolveTooFast {
+        /// Block timestamp in milliseconds from which on the solution is accepted
+        available_at: Timestamp,
+    },
+    /// Co-op participants have to be 2 to 4 distinct registered players
+    #[error("ERR_INVALID_PARTICIPANTS")]
+    InvalidParticipants,
+    /// There is no co-op game with this id, or it has been solved already
+    #[error("ERR_COOP_GAME_NOT_FOUND")]
+    CoopGameNotFound,
+    /// The caller doesn't take part in the co-op game or race
+    #[error("ERR_NOT_PARTICIPANT")]
+    NotParticipant,
+    /// Races need 2 to 50 players
+    #[error("ERR_INVALID_RACE")]
+    InvalidRace,
+    /// There is no race with this id
+    #[error("ERR_RACE_NOT_FOUND")]
+    RaceNotFound,
+    /// The race is full, has started or the caller already joined it
+    #[error("ERR_RACE_CLOSED")]
+    RaceClosed,
+    /// The start time of the race hasn't been reached yet
+    #[error("ERR_RACE_NOT_STARTED{{\"start_at\":{start_at}}}")]
+    RaceNotStarted {
+        /// Block timestamp in milliseconds from which on the race can be started
+        start_at: Timestamp,
+    },
+    /// The race isn't running, solutions can't be submitted
+    #[error("ERR_RACE_NOT_RUNNING")]
+    RaceNotRunning,
+    /// Paid places are left and the time limit of the race hasn't passed yet
+    #[error("ERR_RACE_NOT_OVER{{\"ends_at\":{ends_at}}}")]
+    RaceNotOver {
+        /// Block timestamp in milliseconds from which on the race can be settled
+        ends_at: Timestamp,
+    },
+    /// Practice games have to be finished with `finish_practice`
+    #[error("ERR_PRACTICE_GAME")]
+    PracticeGame,
+    /// No daily puzzle was archived on this date
+    #[error("ERR_ARCHIVE_NOT_FOUND")]
+    ArchiveNotFound,
+    /// The caller is registered already
+    #[error("ERR_ALREADY_REGISTERED")]
+    AlreadyRegistered,
+    /// The prepaid balance doesn't cover the game fee, see `top_up`
+    #[error("ERR_INSUFFICIENT_BALANCE{{\"expected\":\"{expected}\"}}")]
+    InsufficientBalance {
+        /// Minimal balance in yoctonear needed to start the game
+        expected: Balance,
+    },
+    /// Ranked games have to be started with `request_puzzle` and `claim_puzzle`, other games
+    /// counting for the boards or paying prizes are refused
+    #[error("ERR_COMMITMENT_REQUIRED")]
+    CommitmentRequired,
+    /// The commitment isn't a sha256 hash or the secret doesn't match it
+    #[error("ERR_INVALID_COMMITMENT")]
+    InvalidCommitment,
+    /// The caller has no pending puzzle request
+    #[error("ERR_NO_PENDING_PUZZLE")]
+    NoPendingPuzzle,
+    /// Pending puzzles can only be claimed in a later block than the request
+    #[error("ERR_PUZZLE_NOT_READY{{\"available_at_height\":{available_at_height}}}")]
+    PuzzleNotReady {
+        /// Block height from which on the puzzle can be claimed
+        available_at_height: BlockHeight,
+    },
+    /// The leaderboard size, streak multiplier and period length can't be 0, percentages can't exceed 100
+    /// and the race payout schedule needs at least one place
+    #[error("ERR_INVALID_CONFIG")]
+    InvalidConfig,
+    /// The leaderboard period has already ended
+    #[error("ERR_PERIOD_ENDED")]
+    PeriodEnded,
+    /// The leaderboard period was cancelled and pays no prizes
+    #[error("ERR_PERIOD_CANCELLED")]
+    PeriodCancelled,
+    /// The caller has no sponsorship for the period, or the period paid its prizes
+    #[error("ERR_NO_REFUND")]
+    NoRefund,
+    /// The caller has no puzzle generation left unfinished by `start_game`
+    #[error("ERR_NO_PENDING_GENERATION")]
+    NoPendingGeneration,
+    /// The caller's puzzle generation of `start_game` has to be finished by `continue_generation` first
+    #[error("ERR_GENERATION_PENDING")]
+    GenerationPending,
+    /// A submitted entry is out of range, fills a clue or fills a cell twice
+    #[error("ERR_INVALID_ENTRIES")]
+    InvalidEntries,
+    /// Kids and marathon games are only ranked by solve count and time
+    #[error("ERR_BOARD_NOT_SIZED")]
+    BoardNotSized,
+    /// A line of `add_puzzles` isn't a uniquely solvable puzzle followed by a difficulty
+    #[error("ERR_INVALID_POOLED_PUZZLE{{\"index\":{index}}}")]
+    InvalidPooledPuzzle {
+        /// Position of the line, counted from 0
+        index: u32,
+    },
+    /// The daily puzzle of the current day can only be replayed once the day is over
+    #[error("ERR_ARCHIVE_NOT_CLOSED")]
+    ArchiveNotClosed,
+    /// [`crate::Contract::migrate`] found no state of the first deployment to upgrade
+    #[error("ERR_NO_LEGACY_STATE")]
+    NoLegacyState,
+}
+
+#[cfg(feature = "std")]
+impl ContractError {
+    /// The stable code identifying this error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::WrongDeposit { .. } => "ERR_WRONG_DEPOSIT",
+            ContractError::PlayerNotFound => "ERR_PLAYER_NOT_FOUND",
+            ContractError::NoActiveGame => "ERR_NO_ACTIVE_GAME",
+            ContractError::NotOwner => "ERR_NOT_OWNER",
+            ContractError::Cooldown { .. } => "ERR_COOLDOWN",
+            ContractError::UnknownLevel { .. } => "ERR_UNKNOWN_LEVEL",
+            ContractError::LevelLocked { .. } => "ERR_LEVEL_LOCKED",
+            ContractError::InvalidSudokuLine => "ERR_INVALID_SUDOKU_LINE",
+            ContractError::InvalidProgress => "ERR_INVALID_PROGRESS",
+            ContractError::InvalidNotes => "ERR_INVALID_NOTES",
+            ContractError::InsufficientDeposit { .. } => "ERR_INSUFFICIENT_DEPOSIT",
+            ContractError::InvalidNickname => "ERR_INVALID_NICKNAME",
+            ContractError::InvalidAvatarUrl => "ERR_INVALID_AVATAR_URL",
+            ContractError::AlreadyClaimed { .. } => "ERR_ALREADY_CLAIMED",
+            ContractError::NoSolveToday => "ERR_NO_SOLVE_TODAY",
+            ContractError::NoSeason => "ERR_NO_SEASON",
+            ContractError::AlreadyVerified => "ERR_ALREADY_VERIFIED",
+            ContractError::NotVerified => "ERR_NOT_VERIFIED",
+            ContractError::StakeLocked { .. } => "ERR_STAKE_LOCKED",
+            ContractError::PrizeNotFound => "ERR_PRIZE_NOT_FOUND",
+            ContractError::PrizeExpired { .. } => "ERR_PRIZE_EXPIRED",
+            ContractError::SolveTooFast { .. } => "ERR_SOLVE_TOO_FAST",
+            ContractError::InvalidParticipants => "ERR_INVALID_PARTICIPANTS",
+            ContractError::CoopGameNotFound => "ERR_COOP_GAME_NOT_FOUND",
+            ContractError::NotParticipant => "ERR_NOT_PARTICIPANT",
+            ContractError::InvalidRace => "ERR_INVALID_RACE",
+            ContractError::RaceNotFound => "ERR_RACE_NOT_FOUND",
+            ContractError::RaceClosed => "ERR_RACE_CLOSED",
+            ContractError::RaceNotStarted { .. } => "ERR_RACE_NOT_STARTED",
+            ContractError::RaceNotRunning => "ERR_RACE_NOT_RUNNING",
+            ContractError::RaceNotOver { .. } => "ERR_RACE_NOT_OVER",
+            ContractError::PracticeGame => "ERR_PRACTICE_GAME",
+            ContractError::ArchiveNotFound => "ERR_ARCHIVE_NOT_FOUND",
+            ContractError::AlreadyRegistered => "ERR_ALREADY_REGISTERED",
+            ContractError::InsufficientBalance { .. } => "ERR_INSUFFICIENT_BALANCE",
+            ContractError::CommitmentRequired => "ERR_COMMITMENT_REQUIRED",
+            ContractError::InvalidCommitment => "ERR_INVALID_COMMITMENT",
+            ContractError::NoPendingPuzzle => "ERR_NO_PENDING_PUZZLE",
+            ContractError::PuzzleNotReady { .. } => "ERR_PUZZLE_NOT_READY",
+            ContractError::InvalidConfig => "ERR_INVALID_CONFIG",
+            ContractError::PeriodEnded => "ERR_PERIOD_ENDED",
+            ContractError::PeriodCancelled => "ERR_PERIOD_CANCELLED",
+            ContractError::NoRefund => "ERR_NO_REFUND",
+            ContractError::NoPendingGeneration => "ERR_NO_PENDING_GENERATION",
+            ContractError::GenerationPending => "ERR_GENERATION_PENDING",
+            ContractError::InvalidEntries => "ERR_INVALID_ENTRIES",
+            ContractError::BoardNotSized => "ERR_BOARD_NOT_SIZED",
+            ContractError::InvalidPooledPuzzle { .. } => "ERR_INVALID_POOLED_PUZZLE",
+            ContractError::ArchiveNotClosed => "ERR_ARCHIVE_NOT_CLOSED",
+            ContractError::NoLegacyState => "ERR_NO_LEGACY_STATE",
+        }
+    }
+
+    /// Aborts the current call with this error
+    pub fn panic(&self) -> ! {
+        env::panic_str(&self.to_string())
+    }
 }
@@ -0,0 +1,69 @@
+// Structured logs following the NEP-297 event format:
+// `EVENT_JSON:{"standard":"sudoku","version":"1.0.0","event":...,"data":[...]}`
+
+use near_sdk::serde::Serialize;
+use near_sdk::{env, serde_json, AccountId};
+
+use crate::attestation::AttestationRequest;
+use crate::race::RaceResult;
+use crate::snapshot::SnapshotRequest;
+use crate::BoardKind;
+
+const STANDARD: &str = "sudoku";
+const VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct Event<'a, T: Serialize> {
+    standard: &'static str,
+    version: &'static str,
+    event: &'static str,
+    data: &'a [T],
+}
+
+fn emit<T: Serialize>(event: &'static str, data: &[T]) {
+    if data.is_empty() {
+        return;
+    }
+
+    let event = Event {
+        standard: STANDARD,
+        version: VERSION,
+        event,
+        data,
+    };
+    env::log_str(&format!(
+        "EVENT_JSON:{}",
+        serde_json::to_string(&event).unwrap()
+    ));
+}
+
+/// An account entered (`old_rank == None`), moved within or was evicted from (`new_rank == None`) a leaderboard.
+/// Ranks start at 1.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardChange {
+    pub board: BoardKind,
+    pub account_id: AccountId,
+    pub old_rank: Option<u32>,
+    pub new_rank: Option<u32>,
+}
+
+pub(crate) fn leaderboard_update(changes: &[LeaderboardChange]) {
+    emit("leaderboard_update", changes);
+}
+
+/// Full snapshots about to be compacted or removed, see `Contract::prune_snapshots`
+pub(crate) fn snapshots_pruned(snapshots: &[SnapshotRequest]) {
+    emit("snapshots_pruned", snapshots);
+}
+
+/// A ranked solve was attested, see `Contract::verify_attestation`
+pub(crate) fn solve_attested(attestations: &[AttestationRequest]) {
+    emit("solve_attested", attestations);
+}
+
+/// Races removed once they were settled or cancelled, see `Contract::settle_race`
+pub(crate) fn race_ended(results: &[RaceResult]) {
+    emit("race_ended", results);
+}
@@ -0,0 +1,40 @@
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::Serialize;
+use near_sdk::{AccountId, Timestamp};
+
+use std::collections::HashMap;
+
+/// Version of the exported records. Bumped with every change to the borsh layout of
+/// [`crate::Player`] or [`crate::LastSlovedGame`], so indexers can pick the matching decoder.
+pub const EXPORT_VERSION: u32 = 7;
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExportedPlayer {
+    pub account_id: AccountId,
+    /// Borsh serialized [`crate::Player`] as stored
+    pub record: Base64VecU8,
+    /// Borsh serialized [`crate::LastSlovedGame`], which the player record only references
+    pub last_sloved_game: Option<Base64VecU8>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlayersExport {
+    pub version: u32,
+    /// Number of players, the export is done once `from_index + limit` reaches it
+    pub total: u64,
+    pub players: Vec<ExportedPlayer>,
+}
+
+/// First [`crate::Config::leaderboard_size`] places of the count and time boards. The full
+/// rankings are derived from the player records and not exported.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardsExport {
+    pub version: u32,
+    pub top_by_count: HashMap<AccountId, U128>,
+    pub top_by_time: HashMap<AccountId, Timestamp>,
+    pub period_id: u64,
+    pub season_end: Timestamp,
+}
@@ -0,0 +1,144 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, BlockHeight, Gas, Timestamp};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::board::Sudoku;
+use crate::generator::EntropyPool;
+use crate::novelty;
+use crate::Symmetry;
+
+/// Gas kept back for storing the generation state or starting the game once the puzzle is done
+pub const GENERATION_GAS_RESERVE: Gas = Gas(50 * Gas::ONE_TERA.0);
+
+/// Storage of a pending generation, paid from the prepaid balance until the puzzle is done
+pub const PENDING_GENERATION_SIZE: u128 = 300;
+
+/// Clue layout of the puzzles, the same as [`Sudoku::generate`]
+const SYMMETRY: Symmetry = Symmetry::HalfRotation;
+
+/// Puzzle generation of [`Contract::start_game`](crate::Contract::start_game) that can be spread
+/// over several transactions, see [`Contract::continue_generation`](crate::Contract::continue_generation).
+///
+/// Clues are removed from a solved grid one symmetry class at a time, so the work can be
+/// interrupted after any class and resumed from the stored state. The stored state reveals the
+/// solution, so the game is timed from the call starting the generation.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingGeneration {
+    pub start_time: Timestamp,
+    pub start_height: BlockHeight,
+    seed: [u8; 32],
+    /// Number of puzzles generated before the current one, all of them were issued recently
+    attempt: u32,
+    /// The solved grid with the clues removed so far
+    sudoku: Sudoku,
+    /// Cells not tried yet, the next one is taken from the end
+    remaining_cells: Vec<u8>,
+}
+
+impl PendingGeneration {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self::with_attempt(seed, 0, env::block_timestamp_ms(), env::block_height())
+    }
+
+    fn with_attempt(
+        seed: [u8; 32],
+        attempt: u32,
+        start_time: Timestamp,
+        start_height: BlockHeight,
+    ) -> Self {
+        let attempt_seed = env::sha256_array(&[&seed[..], &attempt.to_le_bytes()].concat());
+        let mut rng = StdRng::from_seed(attempt_seed);
+        let sudoku = Sudoku::generate_solved(&mut rng);
+        let mut remaining_cells: Vec<u8> = (0..81).collect();
+        EntropyPool::new(&mut rng).shuffle(&mut remaining_cells);
+        Self {
+            start_time,
+            start_height,
+            seed,
+            attempt,
+            sudoku,
+            remaining_cells,
+        }
+    }
+
+    /// Removes clues while the solution stays unique, until no cell is left to try or
+    /// `should_pause` returns true. At least one cell is tried per call.
+    ///
+    /// Returns the finished puzzle. Puzzles for which `is_recent` holds are replaced by new ones,
+    /// up to [`novelty::MAX_ATTEMPTS`] puzzles in total.
+    pub fn resume(
+        &mut self,
+        is_recent: impl Fn(&Sudoku) -> bool,
+        mut should_pause: impl FnMut() -> bool,
+    ) -> Option<Sudoku> {
+        loop {
+            while let Some(cell) = self.remaining_cells.pop() {
+                let cells = SYMMETRY.corresponding_cells(cell as usize);
+                self.remaining_cells
+                    .retain(|&other| !cells.contains(&(other as usize)));
+                let mut sudoku = self.sudoku;
+                for cell in cells {
+                    sudoku.0[cell] = 0;
+                }
+                if sudoku.is_uniquely_solvable() {
+                    self.sudoku = sudoku;
+                }
+                if !self.remaining_cells.is_empty() && should_pause() {
+                    return None;
+                }
+            }
+
+            if self.attempt + 1 >= novelty::MAX_ATTEMPTS || !is_recent(&self.sudoku) {
+                return Some(self.sudoku);
+            }
+            *self = Self::with_attempt(
+                self.seed,
+                self.attempt + 1,
+                self.start_time,
+                self.start_height,
+            );
+        }
+    }
+}
+
+/// Whether the gas left falls short of [`GENERATION_GAS_RESERVE`]
+pub fn out_of_gas() -> bool {
+    env::used_gas().0 + GENERATION_GAS_RESERVE.0 > env::prepaid_gas().0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume() {
+        let seed = [3; 32];
+        let finished = PendingGeneration::new(seed)
+            .resume(|_| false, || false)
+            .unwrap();
+        assert!(finished.is_uniquely_solvable());
+        assert!(finished.n_clues() < 40);
+
+        // one cell per call gives the same puzzle
+        let mut generation = PendingGeneration::new(seed);
+        let mut n_calls = 1;
+        let sudoku = loop {
+            if let Some(sudoku) = generation.resume(|_| false, || true) {
+                break sudoku;
+            }
+            let bytes = generation.try_to_vec().unwrap();
+            generation = PendingGeneration::try_from_slice(&bytes).unwrap();
+            n_calls += 1;
+        };
+        assert_eq!(sudoku, finished);
+        assert!(n_calls > 1);
+
+        // recent puzzles are replaced
+        let replaced = PendingGeneration::new(seed)
+            .resume(|sudoku| *sudoku == finished, || false)
+            .unwrap();
+        assert_ne!(replaced, finished);
+        assert!(replaced.is_uniquely_solvable());
+    }
+}
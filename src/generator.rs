@@ -1,6 +1,6 @@
-use rand::Rng;
+use alloc::vec::Vec;
 use rand::rngs::StdRng;
-use rand::seq::SliceRandom;
+use rand::RngCore;
 
 use crate::bitset::Set;
 use crate::board::Candidate;
@@ -9,6 +9,58 @@ use crate::consts::*;
 use crate::helper::{CellArray, HouseArray, Unsolvable};
 use crate::Sudoku;
 
+// Hands out bounded random numbers drawn from as few 32 bit words of the rng as possible.
+//
+// `value` is uniformly distributed in `0..range`. A number below `n` is split off of it as `value % n`,
+// leaving `value / n` for the next draw. Only when less than `n` values are left, another word is drawn.
+// Rejecting the top of the range that isn't a multiple of `n` keeps the numbers unbiased.
+//
+// Generating a puzzle takes about 450 bits: log2(81!) ≈ 400 for the order in which clues are removed,
+// log2(9!) ≈ 18 for the first row of the solution and 1-3 bits per guess while filling the rest of it.
+// In practice that's 16-17 words per puzzle compared to over 100 with a separate `gen_range` call per number.
+pub(crate) struct EntropyPool<'a> {
+    rng: &'a mut StdRng,
+    value: u64,
+    range: u64,
+}
+
+impl<'a> EntropyPool<'a> {
+    pub fn new(rng: &'a mut StdRng) -> Self {
+        EntropyPool { rng, value: 0, range: 1 }
+    }
+
+    // uniformly distributed number in `0..n`
+    pub fn below(&mut self, n: u32) -> u32 {
+        debug_assert!(n > 0);
+        let n = n as u64;
+        loop {
+            if self.range < n {
+                // range < n <= 2^32, so this can't overflow
+                self.value = self.value << 32 | self.rng.next_u32() as u64;
+                self.range <<= 32;
+            }
+            let limit = self.range - self.range % n;
+            if self.value < limit {
+                let num = self.value % n;
+                self.value /= n;
+                self.range = limit / n;
+                return num as u32;
+            }
+            // the rejected rest is still uniformly distributed in its own, smaller range
+            self.value -= limit;
+            self.range -= limit;
+        }
+    }
+
+    // Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for n_choices in (2..=slice.len()).rev() {
+            let choice = self.below(n_choices as u32) as usize;
+            slice.swap(n_choices - 1, choice);
+        }
+    }
+}
+
 // Sudoku generation is done via randomized solving of empty grids
 // the solver is based on jsolve
 // Helper struct for recursive solving
@@ -209,11 +261,11 @@ impl SudokuGenerator {
     }
 
     #[inline(always)]
-    fn find_good_random_guess(&mut self, rng: &mut StdRng) -> Candidate {
+    fn find_good_random_guess(&mut self, rng: &mut EntropyPool) -> Candidate {
         let best_cell = self.find_cell_min_poss();
         let poss_digits = self.cell_poss_digits[best_cell];
 
-        let choice = rng.gen_range(0, poss_digits.len());
+        let choice = rng.below(poss_digits.len() as u32);
         let digit = poss_digits.into_iter().nth(choice as usize).unwrap();
         Candidate {
             digit,
@@ -238,7 +290,7 @@ impl SudokuGenerator {
     }
 
     // for generation of random, filled sudokus
-    fn randomized_solve_one(mut self, stack: &mut Vec<Candidate>, rng: &mut StdRng) -> Result<Sudoku, Unsolvable> {
+    fn randomized_solve_one(mut self, stack: &mut Vec<Candidate>, rng: &mut EntropyPool) -> Result<Sudoku, Unsolvable> {
         // insert and deduce in a loop
         // do a random guess when no more deductions are found
         // backtrack on error (via recursion)
@@ -268,8 +320,9 @@ impl SudokuGenerator {
         // fill first row with a permutation of 1...9
         // not necessary, but ~15% faster
         let mut stack = Vec::with_capacity(N_CELLS);
+        let mut rng = EntropyPool::new(rng);
         let mut perm = [1, 2, 3, 4, 5, 6, 7, 8, 9];
-        perm.shuffle(rng);
+        rng.shuffle(&mut perm);
 
         stack.extend(
             (0..9)
@@ -277,6 +330,30 @@ impl SudokuGenerator {
                 .map(|(cell, &digit)| Candidate::new(cell, digit)),
         );
 
-        Self::new().randomized_solve_one(&mut stack, rng).unwrap()
+        Self::new().randomized_solve_one(&mut stack, &mut rng).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn entropy_pool() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pool = EntropyPool::new(&mut rng);
+        let mut counts = [0; 7];
+        for _ in 0..70_000 {
+            counts[pool.below(7) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| (9_500..10_500).contains(&count)), "{:?}", counts);
+        assert_eq!(pool.below(1), 0);
+
+        let mut digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        pool.shuffle(&mut digits);
+        assert_ne!(digits, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        digits.sort_unstable();
+        assert_eq!(digits, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
     }
 }
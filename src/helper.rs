@@ -3,7 +3,7 @@
 
 use crate::board::{Cell, Digit, House};
 use crate::consts::N_CELLS;
-use std::ops::{Deref, DerefMut, Index, IndexMut};
+use core::ops::{Deref, DerefMut, Index, IndexMut};
 
 #[derive(Debug)]
 pub(crate) struct Unsolvable;
@@ -0,0 +1,107 @@
+//! Hyper sudokus (windoku), which have 4 additional 3x3 regions that must contain every digit once
+use rand::rngs::StdRng;
+
+use crate::bitset::Set;
+use crate::board::Cell;
+use crate::cages::{Cage, KillerSudoku};
+use crate::consts::*;
+use crate::Sudoku;
+
+/// Top left cells of the 4 windows, each offset by one cell from the corner of a block
+const WINDOW_CORNERS: [u8; 4] = [10, 14, 46, 50];
+
+/// A sudoku with 4 additional houses, the windows spanning rows and columns 1-3 and 5-7
+/// (counted from 0). The windows don't overlap, so they are solved as [`Cage`]s of 9 cells summing to 45.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HyperSudoku {
+    clues: Sudoku,
+}
+
+impl HyperSudoku {
+    /// Constructs a hyper sudoku from its clues
+    pub fn new(clues: Sudoku) -> Self {
+        HyperSudoku { clues }
+    }
+
+    /// The cells of the 4 windows
+    pub fn windows() -> [Set<Cell>; 4] {
+        let window = |corner: u8| {
+            (0..3)
+                .flat_map(|row| (0..3).map(move |col| corner + row * 9 + col))
+                .fold(Set::NONE, |set, cell| set | Cell::new(cell))
+        };
+        [window(WINDOW_CORNERS[0]), window(WINDOW_CORNERS[1]), window(WINDOW_CORNERS[2]), window(WINDOW_CORNERS[3])]
+    }
+
+    /// Generates a random, uniquely solvable hyper sudoku.
+    ///
+    /// Clues are removed from a random solved hyper sudoku as long as the solution stays unique,
+    /// so the puzzles are minimal.
+    pub fn generate(rng: &mut StdRng) -> Self {
+        let empty = HyperSudoku::new(Sudoku([0; N_CELLS])).as_killer();
+        let solution = empty.random_solution(rng).expect("empty hyper sudoku has solutions");
+        let mut killer = HyperSudoku::new(solution).as_killer();
+        killer.remove_clues(rng);
+        HyperSudoku::new(killer.clues())
+    }
+
+    /// The given digits
+    pub fn clues(&self) -> Sudoku {
+        self.clues
+    }
+
+    /// Checks whether `sudoku` is a valid solution, i.e. solved, matching all clues and all windows.
+    pub fn is_solution(&self, sudoku: Sudoku) -> bool {
+        self.as_killer().is_solution(sudoku)
+    }
+
+    /// Counts the number of solutions to the hyper sudoku up to `limit`.
+    pub fn solutions_count_up_to(&self, limit: usize) -> usize {
+        self.as_killer().solutions_count_up_to(limit)
+    }
+
+    /// Checks whether the hyper sudoku has one and only one solution.
+    pub fn is_uniquely_solvable(&self) -> bool {
+        self.as_killer().is_uniquely_solvable()
+    }
+
+    /// Returns the solution, if it is unique.
+    pub fn solution(&self) -> Option<Sudoku> {
+        self.as_killer().solution()
+    }
+
+    fn as_killer(&self) -> KillerSudoku {
+        let cages = HyperSudoku::windows().iter().map(|&window| Cage::new(window, 45).unwrap()).collect();
+        KillerSudoku::new(self.clues, cages).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::Digit;
+    use rand::SeedableRng;
+
+    #[test]
+    fn windows() {
+        let windows = HyperSudoku::windows();
+        let all = windows.iter().fold(Set::NONE, |set, &window| set | window);
+        assert_eq!(all.len(), 36);
+        assert!(windows[0].contains(Cell::new(10)) && windows[0].contains(Cell::new(30)));
+        assert!(windows[3].contains(Cell::new(50)) && windows[3].contains(Cell::new(70)));
+        assert!(!all.contains(Cell::new(0)) && !all.contains(Cell::new(40)));
+    }
+
+    #[test]
+    fn generate() {
+        let hyper = HyperSudoku::generate(&mut StdRng::seed_from_u64(0));
+        let solution = hyper.solution().unwrap();
+        assert!(hyper.is_solution(solution));
+        for window in &HyperSudoku::windows() {
+            let digits = window.into_iter().fold(Set::NONE, |set, cell| set | Digit::new(solution.0[cell.as_index()]));
+            assert!(digits.is_full());
+        }
+        // the clues alone don't fix the solution, the windows are needed
+        assert!(!hyper.clues().is_uniquely_solvable());
+    }
+}
@@ -0,0 +1,100 @@
+// Cross-module consistency checks for the contract state.
+// Only compiled for tests and sandbox builds (`--features invariants`),
+// call `assert_invariants` after every mutating operation to catch corruption early.
+
+use crate::{BoardKind, Contract};
+
+impl Contract {
+    pub(crate) fn assert_invariants(&self) {
+        self.assert_leaderboard_invariants();
+        self.assert_stake_invariants();
+    }
+
+    fn assert_stake_invariants(&self) {
+        let staked: u128 = self.players.values().map(|player| player.stake).sum();
+        assert_eq!(
+            self.total_staked, staked,
+            "total_staked is out of sync with the player stakes"
+        );
+
+        for board in BoardKind::ALL.iter().copied() {
+            for (_, account_id) in self.leaderboard.verified_ranking(board).iter() {
+                assert!(
+                    self.players
+                        .get(&account_id)
+                        .is_some_and(|player| player.verified()),
+                    "{:?} verified ranking references unverified player {}",
+                    board,
+                    account_id
+                );
+            }
+        }
+    }
+
+    fn assert_leaderboard_invariants(&self) {
+        let leaderboard = &self.leaderboard;
+        for (key, account_id) in leaderboard.ranking(BoardKind::Count).iter() {
+            let player = self
+                .players
+                .get(&account_id)
+                .unwrap_or_else(|| panic!("Count ranking references unknown player {}", account_id));
+            assert_eq!(
+                BoardKind::Count.sort_key(player.sloved_sudoku_count),
+                key,
+                "Count ranking entry of {} is out of sync with the player record",
+                account_id
+            );
+        }
+
+        for (key, account_id) in leaderboard.ranking(BoardKind::Time).iter() {
+            let player = self
+                .players
+                .get(&account_id)
+                .unwrap_or_else(|| panic!("Time ranking references unknown player {}", account_id));
+            assert_eq!(
+                player.best_time.map(|time| BoardKind::Time.sort_key(time as u128)),
+                Some(key),
+                "Time ranking entry of {} is out of sync with the player record",
+                account_id
+            );
+        }
+
+        let rankings = BoardKind::ALL.iter().flat_map(|&board| {
+            vec![
+                (board, leaderboard.ranking(board)),
+                (board, leaderboard.verified_ranking(board)),
+            ]
+        });
+        for (board, ranking) in rankings {
+            let mut previous = None;
+            for (position, entry) in ranking.iter().enumerate() {
+                assert_eq!(
+                    ranking.key(&entry.1),
+                    Some(entry.0),
+                    "{:?} ranking key of {} is out of sync",
+                    board,
+                    entry.1
+                );
+                assert!(
+                    self.players.get(&entry.1).is_some(),
+                    "{:?} ranking references unknown player {}",
+                    board,
+                    entry.1
+                );
+                assert!(
+                    !self.flagged.contains(&entry.1),
+                    "{:?} ranking references flagged player {}",
+                    board,
+                    entry.1
+                );
+                assert!(
+                    previous < Some(entry.clone()),
+                    "{:?} ranking is not sorted at position {}",
+                    board,
+                    position
+                );
+                previous = Some(entry);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use near_sdk::serde::Serialize;
+use near_sdk::Timestamp;
+
+use crate::Difficulty;
+
+/// Version of the stored state. Bumped with every change to the borsh layout of
+/// [`crate::Contract`] or of the records it stores.
+pub const STATE_VERSION: u32 = 13;
+
+/// Standards implemented by the contract, as (name, version)
+pub const STANDARDS: [(&str, &str); 1] = [("nep297", "1.0.0")];
+
+/// Ways to play a sudoku, every deployment supports all of them
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// Charged the game fee and counted for the leaderboards
+    Ranked,
+    /// Unranked games, see [`crate::Contract::start_practice`]
+    Practice,
+    /// Replays of past daily puzzles, see [`crate::Contract::start_archived`]
+    Archive,
+    /// Levels of increasing difficulty, see [`crate::Contract::start_level`]
+    Campaign,
+    /// Games shared by several players, see [`crate::Contract::create_coop`]
+    Coop,
+    /// Lobbies racing for a pot, see [`crate::Contract::create_race`]
+    Race,
+    /// 4x4 grids with their own leaderboards, see [`crate::Contract::start_game`]
+    Kids,
+    /// 16x16 grids with their own leaderboards, see [`crate::Contract::start_game`]
+    Marathon,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Ranked,
+        GameMode::Practice,
+        GameMode::Archive,
+        GameMode::Campaign,
+        GameMode::Coop,
+        GameMode::Race,
+        GameMode::Kids,
+        GameMode::Marathon,
+    ];
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StandardRequest {
+    pub standard: String,
+    pub version: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DifficultyRequest {
+    pub difficulty: Difficulty,
+    /// Fastest accepted solve in milliseconds, see [`crate::config::Config::min_solve_times`]
+    pub min_solve_time: Timestamp,
+}
+
+/// Capabilities of the deployment, see [`crate::Contract::contract_metadata`]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractMetadata {
+    /// Version of the contract code
+    pub version: String,
+    /// See [`STATE_VERSION`]
+    pub state_version: u32,
+    pub game_modes: Vec<GameMode>,
+    /// Ranked games have to be started by [`crate::Contract::request_puzzle`]
+    pub require_commitment: bool,
+    pub difficulties: Vec<DifficultyRequest>,
+    pub standards: Vec<StandardRequest>,
+}
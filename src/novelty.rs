@@ -0,0 +1,111 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupSet, Vector};
+use near_sdk::env;
+
+use crate::board::Sudoku;
+
+/// Number of recently issued puzzles a new puzzle has to differ from
+pub const RECENT_PUZZLES: u64 = 256;
+/// Generated puzzles per game before a duplicate is issued anyway
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Hashes of the canonical form of the last [`RECENT_PUZZLES`] issued puzzles,
+/// so a puzzle and all its transformed copies (see [`Sudoku::shuffle`]) share one hash.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RecentPuzzles {
+    hashes: LookupSet<[u8; 32]>,
+    /// Ring buffer in issuing order, the oldest entry is at `next % RECENT_PUZZLES`
+    order: Vector<[u8; 32]>,
+    next: u64,
+}
+
+impl RecentPuzzles {
+    pub fn new(prefix: &[u8]) -> Self {
+        Self {
+            hashes: LookupSet::new([prefix, b"h"].concat()),
+            order: Vector::new([prefix, b"o"].concat()),
+            next: 0,
+        }
+    }
+
+    pub fn contains(&self, sudoku: &Sudoku) -> bool {
+        self.hashes.contains(&canonical_hash(sudoku))
+    }
+
+    /// Records `sudoku` as issued, forgetting the oldest puzzle once full. A duplicate (issued
+    /// after [`MAX_ATTEMPTS`]) keeps its earlier place, the ring holds every hash only once.
+    pub fn insert(&mut self, sudoku: &Sudoku) {
+        let hash = canonical_hash(sudoku);
+        if self.hashes.contains(&hash) {
+            return;
+        }
+        let index = self.next % RECENT_PUZZLES;
+        if index < self.order.len() {
+            let oldest = self.order.replace(index, &hash);
+            self.hashes.remove(&oldest);
+        } else {
+            self.order.push(&hash);
+        }
+        self.hashes.insert(&hash);
+        self.next += 1;
+    }
+}
+
+/// sha256 of the minlex form of `sudoku`. Puzzles without a unique solution have no canonical
+/// form and are hashed as they are.
+pub fn canonical_hash(sudoku: &Sudoku) -> [u8; 32] {
+    let canonical = sudoku
+        .canonicalized()
+        .map_or(*sudoku, |(canonical, _)| canonical);
+    env::sha256_array(&canonical.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn transformed_copies() {
+        let mut rnd = StdRng::seed_from_u64(1);
+        let sudoku = Sudoku::generate(&mut rnd);
+        let mut recent = RecentPuzzles::new(b"t");
+        recent.insert(&sudoku);
+
+        assert!(recent.contains(&sudoku.shuffled(&mut rnd)));
+        assert!(!recent.contains(&Sudoku::generate(&mut rnd)));
+    }
+
+    #[test]
+    fn forgets_oldest() {
+        let mut rnd = StdRng::seed_from_u64(2);
+        let first = Sudoku::generate(&mut rnd);
+        let mut recent = RecentPuzzles::new(b"t");
+        recent.insert(&first);
+        for _ in 0..RECENT_PUZZLES {
+            recent.insert(&Sudoku::generate_solved(&mut rnd));
+        }
+        assert!(!recent.contains(&first));
+        assert_eq!(recent.order.len(), RECENT_PUZZLES);
+    }
+
+    #[test]
+    fn duplicates_stay_until_evicted() {
+        let mut rnd = StdRng::seed_from_u64(3);
+        let sudoku = Sudoku::generate(&mut rnd);
+        let mut recent = RecentPuzzles::new(b"t");
+        recent.insert(&sudoku);
+        recent.insert(&sudoku.shuffled(&mut rnd));
+        assert_eq!(recent.order.len(), 1);
+
+        // evicting the only entry of the hash forgets it, no second copy is left in the ring
+        for _ in 1..RECENT_PUZZLES {
+            recent.insert(&Sudoku::generate_solved(&mut rnd));
+        }
+        assert!(recent.contains(&sudoku));
+        recent.insert(&Sudoku::generate_solved(&mut rnd));
+        assert!(!recent.contains(&sudoku));
+        assert_eq!(recent.order.len(), RECENT_PUZZLES);
+    }
+}
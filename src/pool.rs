@@ -0,0 +1,56 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use std::convert::TryInto;
+
+use crate::{Difficulty, Sudoku};
+
+/// A puzzle uploaded by the owner, see [`Contract::add_puzzles`](crate::Contract::add_puzzles)
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PooledPuzzle {
+    pub sudoku: Sudoku,
+    pub difficulty: Difficulty,
+}
+
+impl PooledPuzzle {
+    /// Parses a line of the `poolgen` tool, `<puzzle in the line format> <difficulty> <score>`.
+    /// The score is only informative and not stored. Puzzles without a unique solution are rejected.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let sudoku = Sudoku::from_str_line(fields.next()?).ok()?;
+        let difficulty = Difficulty::from_name(fields.next()?)?;
+        if !sudoku.is_uniquely_solvable() {
+            return None;
+        }
+        Some(Self { sudoku, difficulty })
+    }
+}
+
+/// A puzzle handed out from the pool by [`Contract::start_game`](crate::Contract::start_game).
+/// Each draw is [`shuffled`](Sudoku::shuffle) with its own seed, so players drawing the same
+/// pooled puzzle get different grids while the pool only stores it once.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy)]
+pub struct PoolDraw {
+    pub index: u64,
+    seed: [u8; 32],
+}
+
+impl PoolDraw {
+    /// Draws from a pool of `pool_size` puzzles with `seed`
+    pub fn new(seed: [u8; 32], pool_size: u64) -> Self {
+        let index = u64::from_le_bytes(seed[..8].try_into().unwrap()) % pool_size;
+        Self {
+            index,
+            seed: env::sha256_array(&seed),
+        }
+    }
+
+    /// Applies the transformation of the draw to `sudoku`. The same transformation is applied
+    /// to any grid, so the pooled puzzle maps to the puzzle handed out and its solution to the
+    /// solution of that puzzle.
+    pub fn apply(&self, sudoku: Sudoku) -> Sudoku {
+        sudoku.shuffled(&mut StdRng::from_seed(self.seed))
+    }
+}
@@ -0,0 +1,88 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{AccountId, Balance, Timestamp};
+
+use crate::snapshot::{Standings, StandingsEntry};
+
+/// Percentage of a board's half of the pool paid to its first, second and third place
+pub const PRIZE_SHARES: [u128; 3] = [50, 30, 20];
+/// Default of [`crate::config::Config::prize_claim_period`]
+pub const PRIZE_CLAIM_PERIOD: Timestamp = 30 * 86_400_000;
+
+/// Prizes won by an account and not claimed yet
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Prize {
+    pub amount: Balance,
+    pub expires_at: Timestamp,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PrizeRequest {
+    pub amount: U128,
+    pub expires_at: Timestamp,
+}
+
+impl Prize {
+    pub fn get(&self) -> PrizeRequest {
+        PrizeRequest {
+            amount: U128(self.amount),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// Splits `pool` between the verified boards of `standings`, half for each board.
+/// Shares of missing places and rounding remainders are not paid out.
+pub fn payouts(pool: Balance, standings: &Standings) -> Vec<(AccountId, Balance)> {
+    let board_pool = pool / 2;
+    let winners = |board: &[StandingsEntry]| {
+        board
+            .iter()
+            .zip(PRIZE_SHARES.iter())
+            .map(|(entry, share)| (entry.account_id.clone(), board_pool * share / 100))
+            .collect::<Vec<_>>()
+    };
+
+    let mut payouts = winners(&standings.verified_by_count);
+    payouts.extend(winners(&standings.verified_by_time));
+    payouts
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+
+    fn board(accounts: &[AccountId]) -> Vec<StandingsEntry> {
+        accounts
+            .iter()
+            .map(|account_id| StandingsEntry {
+                account_id: account_id.clone(),
+                value: U128(1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn payout_shares() {
+        let standings = Standings {
+            period_id: 0,
+            by_count: board(&[accounts(4)]),
+            by_time: vec![],
+            verified_by_count: board(&[accounts(0), accounts(1), accounts(2), accounts(3)]),
+            verified_by_time: board(&[accounts(1)]),
+        };
+
+        assert_eq!(
+            payouts(1000, &standings),
+            vec![
+                (accounts(0), 250),
+                (accounts(1), 150),
+                (accounts(2), 100),
+                (accounts(1), 250),
+            ]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+
+use crate::errors::ContractError;
+
+pub const NICKNAME_MIN_LEN: usize = 3;
+pub const NICKNAME_MAX_LEN: usize = 32;
+pub const AVATAR_URL_MAX_LEN: usize = 256;
+const AVATAR_URL_SCHEMES: [&str; 2] = ["https://", "ipfs://"];
+
+/// Public display data of a player
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Profile {
+    pub nickname: String,
+    pub avatar_url: Option<String>,
+}
+
+impl Profile {
+    /// Validates the nickname and avatar url.
+    ///
+    /// Nicknames are 3 to 32 ascii letters, digits, `_`, `-` or inner spaces.
+    /// Avatar urls are at most 256 bytes, start with `https://` or `ipfs://` and contain no whitespace.
+    pub fn new(nickname: String, avatar_url: Option<String>) -> Result<Profile, ContractError> {
+        let valid_nickname = (NICKNAME_MIN_LEN..=NICKNAME_MAX_LEN).contains(&nickname.len())
+            && nickname
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || ch == ' ')
+            && nickname.trim() == nickname
+            && !nickname.contains("  ");
+        if !valid_nickname {
+            return Err(ContractError::InvalidNickname);
+        }
+
+        if let Some(url) = &avatar_url {
+            let valid_url = url.len() <= AVATAR_URL_MAX_LEN
+                && AVATAR_URL_SCHEMES
+                    .iter()
+                    .any(|scheme| url.starts_with(scheme) && url.len() > scheme.len())
+                && url.chars().all(|ch| ch.is_ascii_graphic());
+            if !valid_url {
+                return Err(ContractError::InvalidAvatarUrl);
+            }
+        }
+
+        Ok(Profile {
+            nickname,
+            avatar_url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nickname_validation() {
+        assert!(Profile::new("bozon".into(), None).is_ok());
+        assert!(Profile::new("sudoku master_2".into(), None).is_ok());
+
+        for nickname in &[
+            "ab",
+            " bozon",
+            "bozon ",
+            "bo  zon",
+            "bozon!",
+            "бозон",
+            &"a".repeat(33),
+        ] {
+            assert_eq!(
+                Profile::new(nickname.to_string(), None),
+                Err(ContractError::InvalidNickname)
+            );
+        }
+    }
+
+    #[test]
+    fn avatar_url_validation() {
+        assert!(Profile::new("bozon".into(), Some("https://example.com/a.png".into())).is_ok());
+        assert!(Profile::new("bozon".into(), Some("ipfs://bafybeigdyrzt".into())).is_ok());
+
+        let too_long = format!("https://{}", "a".repeat(AVATAR_URL_MAX_LEN));
+        for url in &[
+            "http://example.com/a.png",
+            "https://",
+            "https://exa mple.com",
+            &too_long,
+        ] {
+            assert_eq!(
+                Profile::new("bozon".into(), Some(url.to_string())),
+                Err(ContractError::InvalidAvatarUrl)
+            );
+        }
+    }
+}
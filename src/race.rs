@@ -0,0 +1,162 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, Balance, Timestamp};
+
+use crate::{Sudoku, SudokuTwoDimensionalArray};
+
+/// Maximal number of entrants of a race, bounds the refunds of an ended race
+pub const RACE_MAX_PLAYERS: u32 = 50;
+/// Storage of the sudoku revealed when a race starts, paid with the deposit of the creator
+pub const RACE_SUDOKU_SIZE: u128 = 42;
+/// Default of [`crate::config::Config::race_time_limit`]
+pub const RACE_TIME_LIMIT: Timestamp = 3_600_000;
+/// Default percentages of the pot paid to the first, second and third finisher
+pub const DEFAULT_RACE_PAYOUT_SCHEDULE: [u32; 3] = [60, 30, 10];
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum RaceStatus {
+    /// Accepting entrants until the start time
+    Open,
+    /// Started full, the sudoku is revealed
+    Running,
+    /// The pot has been paid out
+    Settled,
+    /// Didn't fill up until the start time, entry fees have been refunded
+    Cancelled,
+}
+
+/// A lobby whose entrants race on the same sudoku for the pot of their entry fees. It is removed
+/// once it is settled or cancelled, and all storage deposits are returned.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Race {
+    pub creator: AccountId,
+    pub entry_fee: Balance,
+    pub max_players: u32,
+    pub start_at: Timestamp,
+    pub status: RaceStatus,
+    pub entrants: Vec<AccountId>,
+    /// Generated when the race starts, so nobody can solve it in advance
+    pub sudoku: Option<Sudoku>,
+    /// Entrants with a correct solution in the order they finished
+    pub finishers: Vec<AccountId>,
+    /// Storage of the race and its sudoku paid by the creator, refunded once it is settled or
+    /// cancelled
+    pub deposit: Balance,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaceRequest {
+    pub creator: AccountId,
+    pub entry_fee: U128,
+    pub max_players: u32,
+    pub start_at: Timestamp,
+    pub status: RaceStatus,
+    pub entrants: Vec<AccountId>,
+    pub sudoku: Option<SudokuTwoDimensionalArray>,
+    pub finishers: Vec<AccountId>,
+}
+
+impl Race {
+    pub fn pot(&self) -> Balance {
+        self.entry_fee * self.entrants.len() as Balance
+    }
+
+    /// Whether every paid place of `schedule` is taken or every entrant finished
+    pub fn places_taken(&self, schedule: &[u32]) -> bool {
+        self.finishers.len() >= schedule.len().min(self.entrants.len())
+    }
+
+    /// Checks that `array` is solved and keeps all clues of the revealed sudoku.
+    pub fn is_solution(&self, array: &SudokuTwoDimensionalArray) -> bool {
+        self.sudoku.is_some_and(|sudoku| {
+            let clues = sudoku.to_two_dimensional_array();
+            array
+                .iter()
+                .flatten()
+                .zip(clues.iter().flatten())
+                .all(|(&num, &clue)| clue == 0 || clue == num)
+                && Sudoku::from_two_dimensional_array(array).is_solved()
+        })
+    }
+
+    pub fn result(&self, race_id: u64) -> RaceResult {
+        RaceResult {
+            race_id,
+            status: self.status,
+            entrants: self.entrants.clone(),
+            finishers: self.finishers.clone(),
+        }
+    }
+
+    pub fn get(&self) -> RaceRequest {
+        RaceRequest {
+            creator: self.creator.clone(),
+            entry_fee: U128(self.entry_fee),
+            max_players: self.max_players,
+            start_at: self.start_at,
+            status: self.status,
+            entrants: self.entrants.clone(),
+            sudoku: self.sudoku.map(|sudoku| sudoku.to_two_dimensional_array()),
+            finishers: self.finishers.clone(),
+        }
+    }
+}
+
+/// Final state of a race, logged when it is removed, see `events::race_ended`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaceResult {
+    pub race_id: u64,
+    pub status: RaceStatus,
+    pub entrants: Vec<AccountId>,
+    pub finishers: Vec<AccountId>,
+}
+
+/// Deposit for the storage of `account_id` in the entrants and, once it finishes, in the
+/// finishers of a race. Paid when joining and refunded when the race ends.
+pub fn entry_deposit(account_id: &AccountId) -> Balance {
+    2 * (4 + account_id.as_str().len()) as Balance * env::STORAGE_PRICE_PER_BYTE
+}
+
+/// Splits `pot` between `finishers` by the percentages of `schedule`.
+/// Shares of places nobody finished on and rounding remainders are not paid out.
+pub fn payouts(
+    pot: Balance,
+    schedule: &[u32],
+    finishers: &[AccountId],
+) -> Vec<(AccountId, Balance)> {
+    finishers
+        .iter()
+        .zip(schedule)
+        .map(|(account_id, &share)| (account_id.clone(), pot * share as Balance / 100))
+        .collect()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn payout_schedule() {
+        assert_eq!(
+            payouts(
+                1000,
+                &DEFAULT_RACE_PAYOUT_SCHEDULE,
+                &[accounts(2), accounts(0)]
+            ),
+            vec![(accounts(2), 600), (accounts(0), 300)]
+        );
+    }
+
+    #[test]
+    fn sudoku_size() {
+        let revealed = Some(Sudoku([0; 81])).try_to_vec().unwrap();
+        let hidden = None::<Sudoku>.try_to_vec().unwrap();
+        assert_eq!((revealed.len() - hidden.len()) as u128, RACE_SUDOKU_SIZE);
+    }
+}
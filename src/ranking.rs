@@ -0,0 +1,371 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::{AccountId, StorageUsage};
+
+use std::cmp::Ordering;
+
+use crate::storage;
+
+/// An entry of a ranking, the key followed by the account id breaking ties
+type Entry = (u128, AccountId);
+
+/// Full ranking of all players on one board, kept sorted in storage.
+///
+/// Entries are ordered by ascending key, ties are broken by account id. They are the nodes of an
+/// AVL tree whose links carry the height and size of the subtree below them, so score changes,
+/// positions and lookups by position read and write O(log n) nodes. Iterating from a position
+/// reads one node per entry.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RankIndex {
+    root: Option<Link>,
+    /// Id of the next inserted node, ids are never reused
+    next_id: u64,
+    nodes: LookupMap<u64, Node>,
+    keys: LookupMap<AccountId, u128>,
+}
+
+/// Reference to the root of a subtree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy)]
+struct Link {
+    id: u64,
+    height: u8,
+    /// Entries in the subtree
+    size: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct Node {
+    entry: Entry,
+    left: Option<Link>,
+    right: Option<Link>,
+}
+
+fn height(link: Option<Link>) -> u8 {
+    link.map_or(0, |link| link.height)
+}
+
+fn size(link: Option<Link>) -> u64 {
+    link.map_or(0, |link| link.size)
+}
+
+impl RankIndex {
+    pub fn new(prefix: &[u8]) -> Self {
+        Self {
+            root: None,
+            next_id: 0,
+            nodes: LookupMap::new([prefix, b"n"].concat()),
+            keys: LookupMap::new([prefix, b"k"].concat()),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        size(self.root)
+    }
+
+    /// Key of `account_id`
+    pub fn key(&self, account_id: &AccountId) -> Option<u128> {
+        self.keys.get(account_id)
+    }
+
+    /// Position of `account_id`, 0 is first place
+    pub fn position(&self, account_id: &AccountId) -> Option<u64> {
+        let entry = (self.keys.get(account_id)?, account_id.clone());
+        let mut position = 0;
+        let mut next = self.root;
+        while let Some(link) = next {
+            let node = self.node(link.id);
+            match entry.cmp(&node.entry) {
+                Ordering::Less => next = node.left,
+                Ordering::Equal => return Some(position + size(node.left)),
+                Ordering::Greater => {
+                    position += size(node.left) + 1;
+                    next = node.right;
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, position: u64) -> Option<Entry> {
+        self.iter_from(position).next()
+    }
+
+    /// Entries from first to last place
+    pub fn iter(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.iter_from(0)
+    }
+
+    /// Entries from `position` to last place
+    pub fn iter_from(&self, mut position: u64) -> impl Iterator<Item = Entry> + '_ {
+        // nodes still to visit, the last one is next
+        let mut stack = vec![];
+        let mut next = self.root;
+        while let Some(link) = next {
+            let node = self.node(link.id);
+            let left = size(node.left);
+            if position < left {
+                next = node.left;
+                stack.push(node);
+            } else if position == left {
+                stack.push(node);
+                break;
+            } else {
+                position -= left + 1;
+                next = node.right;
+            }
+        }
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let mut next = node.right;
+            while let Some(link) = next {
+                let child = self.node(link.id);
+                next = child.left;
+                stack.push(child);
+            }
+            Some(node.entry)
+        })
+    }
+
+    pub fn insert(&mut self, account_id: &AccountId, key: u128) {
+        if let Some(old_key) = self.keys.insert(account_id, &key) {
+            self.root = self.remove_at(self.root, &(old_key, account_id.clone()));
+        }
+        self.root = Some(self.insert_at(self.root, (key, account_id.clone())));
+    }
+
+    /// Storage of the entry of `account_id`, 0 if it isn't ranked
+    pub fn storage_size(&self, account_id: &AccountId) -> StorageUsage {
+        let entry = match self.keys.get(account_id) {
+            Some(key) => (key, account_id.clone()),
+            None => return 0,
+        };
+        let mut next = self.root;
+        while let Some(link) = next {
+            let node = self.node(link.id);
+            next = match entry.cmp(&node.entry) {
+                Ordering::Less => node.left,
+                Ordering::Equal => break,
+                Ordering::Greater => node.right,
+            };
+        }
+        storage::lookup_size(&self.keys, account_id)
+            + next.map_or(0, |link| storage::lookup_size(&self.nodes, &link.id))
+    }
+
+    pub fn remove(&mut self, account_id: &AccountId) {
+        if let Some(key) = self.keys.remove(account_id) {
+            self.root = self.remove_at(self.root, &(key, account_id.clone()));
+        }
+    }
+
+    fn node(&self, id: u64) -> Node {
+        self.nodes.get(&id).expect("linked nodes are stored")
+    }
+
+    /// Inserts `entry` into the subtree of `link`, returns the link to its new root
+    fn insert_at(&mut self, link: Option<Link>, entry: Entry) -> Link {
+        let link = match link {
+            Some(link) => link,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                let node = Node {
+                    entry,
+                    left: None,
+                    right: None,
+                };
+                return self.save(id, node);
+            }
+        };
+        let mut node = self.node(link.id);
+        if entry < node.entry {
+            node.left = Some(self.insert_at(node.left, entry));
+        } else {
+            node.right = Some(self.insert_at(node.right, entry));
+        }
+        self.balance(link.id, node)
+    }
+
+    /// Removes `entry` from the subtree of `link`, returns the link to its new root
+    fn remove_at(&mut self, link: Option<Link>, entry: &Entry) -> Option<Link> {
+        let id = link?.id;
+        let mut node = self.node(id);
+        match entry.cmp(&node.entry) {
+            Ordering::Less => node.left = self.remove_at(node.left, entry),
+            Ordering::Greater => node.right = self.remove_at(node.right, entry),
+            Ordering::Equal => match (node.left, node.right) {
+                (None, child) | (child, None) => {
+                    self.nodes.remove(&id);
+                    return child;
+                }
+                (Some(_), Some(right)) => {
+                    let (right, first) = self.remove_first(right);
+                    node.right = right;
+                    node.entry = first;
+                }
+            },
+        }
+        Some(self.balance(id, node))
+    }
+
+    /// Removes the first entry of the subtree of `link`, returns the link to its new root and the
+    /// entry
+    fn remove_first(&mut self, link: Link) -> (Option<Link>, Entry) {
+        let mut node = self.node(link.id);
+        match node.left {
+            None => {
+                self.nodes.remove(&link.id);
+                (node.right, node.entry)
+            }
+            Some(left) => {
+                let (left, first) = self.remove_first(left);
+                node.left = left;
+                (Some(self.balance(link.id, node)), first)
+            }
+        }
+    }
+
+    /// Stores `node` under `id` after a change below it, rotated if its subtrees differ in height
+    /// by more than one. Returns the link to the root of the subtree.
+    fn balance(&mut self, id: u64, mut node: Node) -> Link {
+        if height(node.left) > height(node.right) + 1 {
+            let left = node.left.unwrap();
+            let left_node = self.node(left.id);
+            if height(left_node.left) < height(left_node.right) {
+                node.left = Some(self.rotate_left(left.id, left_node));
+            }
+            return self.rotate_right(id, node);
+        }
+        if height(node.right) > height(node.left) + 1 {
+            let right = node.right.unwrap();
+            let right_node = self.node(right.id);
+            if height(right_node.right) < height(right_node.left) {
+                node.right = Some(self.rotate_right(right.id, right_node));
+            }
+            return self.rotate_left(id, node);
+        }
+        self.save(id, node)
+    }
+
+    /// Makes the left child of `node` the root of its subtree
+    fn rotate_right(&mut self, id: u64, mut node: Node) -> Link {
+        let left = node.left.unwrap();
+        let mut left_node = self.node(left.id);
+        node.left = left_node.right;
+        left_node.right = Some(self.save(id, node));
+        self.save(left.id, left_node)
+    }
+
+    /// Makes the right child of `node` the root of its subtree
+    fn rotate_left(&mut self, id: u64, mut node: Node) -> Link {
+        let right = node.right.unwrap();
+        let mut right_node = self.node(right.id);
+        node.right = right_node.left;
+        right_node.left = Some(self.save(id, node));
+        self.save(right.id, right_node)
+    }
+
+    fn save(&mut self, id: u64, node: Node) -> Link {
+        let link = Link {
+            id,
+            height: 1 + height(node.left).max(height(node.right)),
+            size: 1 + size(node.left) + size(node.right),
+        };
+        self.nodes.insert(&id, &node);
+        link
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{env, testing_env, Gas};
+
+    fn order(index: &RankIndex) -> Vec<AccountId> {
+        index.iter().map(|(_, account_id)| account_id).collect()
+    }
+
+    #[test]
+    fn keeps_order() {
+        let mut index = RankIndex::new(b"t");
+        index.insert(&accounts(0), 30);
+        index.insert(&accounts(1), 10);
+        index.insert(&accounts(2), 20);
+        index.insert(&accounts(3), 20);
+        assert_eq!(
+            order(&index),
+            vec![accounts(1), accounts(2), accounts(3), accounts(0)]
+        );
+
+        index.insert(&accounts(0), 5);
+        index.insert(&accounts(1), 25);
+        assert_eq!(
+            order(&index),
+            vec![accounts(0), accounts(2), accounts(3), accounts(1)]
+        );
+
+        index.remove(&accounts(2));
+        assert_eq!(order(&index), vec![accounts(0), accounts(3), accounts(1)]);
+        for (position, account_id) in order(&index).iter().enumerate() {
+            assert_eq!(index.position(account_id), Some(position as u64));
+        }
+        assert_eq!(index.position(&accounts(2)), None);
+        assert_eq!(index.get(1), Some((20, accounts(3))));
+        assert_eq!(index.key(&accounts(1)), Some(25));
+        assert_eq!(index.len(), 3);
+    }
+
+    #[test]
+    fn large_index() {
+        let mut index = RankIndex::new(b"t");
+        let account = |i: u64| -> AccountId { format!("player{}.near", i).parse().unwrap() };
+        // keys repeat, so ties are broken by account id
+        let key = |i: u64| (i * 7919 % 1000) as u128;
+        // a fresh context every few updates resets the gas but keeps the storage
+        for i in 0..3000 {
+            if i % 10 == 0 {
+                testing_env!(VMContextBuilder::new().build());
+            }
+            index.insert(&account(i), key(i));
+            if i % 3 == 2 {
+                index.remove(&account(i - 2));
+                index.insert(&account(i - 1), key(i - 1) + 500);
+            }
+        }
+
+        let mut expected: Vec<Entry> = (0..3000)
+            .filter(|i| i % 3 != 0)
+            .map(|i| (key(i) + if i % 3 == 1 { 500 } else { 0 }, account(i)))
+            .collect();
+        expected.sort();
+        assert_eq!(index.len(), 2000);
+        for start in (0..2000).step_by(100) {
+            testing_env!(VMContextBuilder::new().build());
+            let page: Vec<Entry> = index.iter_from(start as u64).take(100).collect();
+            assert_eq!(page, expected[start..start + 100]);
+        }
+
+        // finding the last places takes a few reads, not one per place ahead
+        testing_env!(VMContextBuilder::new().build());
+        let last = &expected[1999];
+        assert_eq!(index.position(&last.1), Some(1999));
+        assert_eq!(index.get(1999).as_ref(), Some(last));
+        assert!(env::used_gas() < Gas::ONE_TERA * 5);
+
+        for (position, entry) in expected.iter().enumerate().step_by(37) {
+            assert_eq!(index.position(&entry.1), Some(position as u64));
+            assert_eq!(index.get(position as u64).as_ref(), Some(entry));
+            assert_eq!(
+                index.iter_from(position as u64).nth(1),
+                expected.get(position + 1).cloned()
+            );
+        }
+        assert_eq!(index.get(2000), None);
+        assert_eq!(index.position(&account(0)), None);
+
+        // an AVL tree of 2000 nodes is at most 1.44 * log2(2000) high
+        assert!(height(index.root) <= 15);
+    }
+}
@@ -0,0 +1,113 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, Timestamp};
+
+use crate::Sudoku;
+
+/// Maximal number of recorded entries per game, later entries are dropped
+pub const REPLAY_MAX_EVENTS: usize = 128;
+
+/// A digit entered into or erased from a cell
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReplayEvent {
+    /// Cell number in 0..=80, row by row
+    pub cell: u8,
+    /// The entered digit, 0 if the cell was cleared
+    pub digit: u8,
+    /// Milliseconds since the start of the game
+    pub time: u32,
+}
+
+/// The entries of one game in the order they were saved, so the solve can be played back
+/// as a ghost or audited, see [`crate::Contract::get_replay`] and
+/// [`crate::Contract::get_move_history`]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Replay {
+    pub game_id: u64,
+    pub events: Vec<ReplayEvent>,
+    /// Entries beyond [`REPLAY_MAX_EVENTS`] were dropped
+    pub truncated: bool,
+}
+
+impl Replay {
+    pub fn new(game_id: u64) -> Self {
+        Self {
+            game_id,
+            events: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Records every cell that differs between `before` and `after` as entered at `time`
+    /// milliseconds into the game. Times beyond `u32::MAX` are clamped.
+    pub fn record(&mut self, before: &Sudoku, after: &Sudoku, time: Timestamp) {
+        let time = time.min(u32::MAX as Timestamp) as u32;
+        for (cell, (&old, &new)) in before.0.iter().zip(after.0.iter()).enumerate() {
+            if old == new {
+                continue;
+            }
+            if self.events.len() == REPLAY_MAX_EVENTS {
+                self.truncated = true;
+                return;
+            }
+            self.events.push(ReplayEvent {
+                cell: cell as u8,
+                digit: new,
+                time,
+            });
+        }
+    }
+
+    /// sha256 of the borsh serialized events
+    pub fn hash(&self) -> [u8; 32] {
+        env::sha256_array(&self.events.try_to_vec().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_changed_cells() {
+        let mut before = Sudoku([0; 81]);
+        before.0[3] = 5;
+        let mut after = before;
+        after.0[3] = 0;
+        after.0[80] = 9;
+
+        let mut replay = Replay::new(7);
+        replay.record(&before, &after, 1500);
+        replay.record(&after, &after, 2000);
+        assert_eq!(
+            replay.events,
+            vec![
+                ReplayEvent {
+                    cell: 3,
+                    digit: 0,
+                    time: 1500
+                },
+                ReplayEvent {
+                    cell: 80,
+                    digit: 9,
+                    time: 1500
+                },
+            ]
+        );
+        assert!(!replay.truncated);
+    }
+
+    #[test]
+    fn bounded_length() {
+        let empty = Sudoku([0; 81]);
+        let full = Sudoku([1; 81]);
+
+        let mut replay = Replay::new(0);
+        replay.record(&empty, &full, 0);
+        replay.record(&full, &empty, 1);
+        assert_eq!(replay.events.len(), REPLAY_MAX_EVENTS);
+        assert!(replay.truncated);
+    }
+}
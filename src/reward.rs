@@ -0,0 +1,37 @@
+use near_sdk::Timestamp;
+
+use crate::config::Config;
+
+/// Length of a reward day in milliseconds. Days are counted in UTC since the unix epoch,
+/// so the claim window is the same for every player regardless of their timezone.
+pub const DAY: Timestamp = 86_400_000;
+/// Default of [`Config::daily_reward_points`]
+pub const DAILY_REWARD_POINTS: u128 = 10;
+/// Default of [`Config::max_streak_multiplier`]
+pub const MAX_STREAK_MULTIPLIER: u32 = 7;
+
+/// UTC day of the timestamp in milliseconds
+pub fn day(timestamp: Timestamp) -> u64 {
+    timestamp / DAY
+}
+
+/// Points credited for a claim on the `streak`th consecutive day
+pub fn reward_points(streak: u32, config: &Config) -> u128 {
+    config.daily_reward_points.0 * streak.clamp(1, config.max_streak_multiplier) as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_multiplier() {
+        let config = Config::default();
+        assert_eq!(reward_points(1, &config), DAILY_REWARD_POINTS);
+        assert_eq!(reward_points(3, &config), 3 * DAILY_REWARD_POINTS);
+        assert_eq!(
+            reward_points(MAX_STREAK_MULTIPLIER + 10, &config),
+            MAX_STREAK_MULTIPLIER as u128 * DAILY_REWARD_POINTS
+        );
+    }
+}
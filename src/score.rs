@@ -0,0 +1,44 @@
+use near_sdk::Timestamp;
+
+use crate::difficulty::Difficulty;
+
+/// Points of an instant solve, indexed by [`Difficulty`]
+pub const BASE_POINTS: [u128; 4] = [100, 200, 400, 800];
+/// Solve time in milliseconds after which a solve is worth half its base points
+pub const HALF_POINTS_TIME: Timestamp = 600_000;
+/// Default of [`crate::config::Config::no_hint_bonus_percent`]
+pub const NO_HINT_BONUS_PERCENT: u32 = 20;
+
+/// Season points of a solve taking `time` milliseconds, wrong submission penalties included.
+/// Points decay hyperbolically, so slow solves of hard sudokus still beat fast easy ones.
+pub fn score(
+    difficulty: Difficulty,
+    time: Timestamp,
+    hints_used: u32,
+    no_hint_bonus_percent: u32,
+) -> u128 {
+    let points = BASE_POINTS[difficulty as usize] * HALF_POINTS_TIME as u128
+        / (HALF_POINTS_TIME + time) as u128;
+    if hints_used == 0 {
+        points * (100 + no_hint_bonus_percent as u128) / 100
+    } else {
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decays_with_time() {
+        let bonus = NO_HINT_BONUS_PERCENT;
+        assert_eq!(score(Difficulty::Easy, 0, 1, bonus), 100);
+        assert_eq!(score(Difficulty::Easy, HALF_POINTS_TIME, 1, bonus), 50);
+        assert_eq!(score(Difficulty::Easy, HALF_POINTS_TIME, 0, bonus), 60);
+        assert!(
+            score(Difficulty::Expert, 3 * HALF_POINTS_TIME, 0, bonus)
+                > score(Difficulty::Easy, 0, 0, bonus)
+        );
+    }
+}
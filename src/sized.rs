@@ -0,0 +1,448 @@
+//! Sudokus of any box order: 4x4 (order 2), 9x9, 16x16 and 25x25 grids
+//!
+//! [`Sudoku`] is the 9x9 board the strategies, the fast solver and the contract's ranked games are
+//! built on, its bit layouts are fixed to 9 digits. [`SizedSudoku`] stores its cells in a `Vec` and
+//! solves by filling in singles and guessing, which is fast enough to generate and check the
+//! smaller and larger grids.
+use alloc::{string::String, vec, vec::Vec};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::errors::SizedSudokuError;
+use crate::Sudoku;
+
+/// A sudoku whose blocks have `N`×`N` cells, so it has `N`² rows, columns, blocks and digits.
+/// Cells are numbered row by row, 0 is an empty cell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SizedSudoku<const N: usize> {
+    cells: Vec<u8>,
+}
+
+/// 4x4 sudoku with the digits 1-4
+pub type Sudoku4 = SizedSudoku<2>;
+/// 9x9 sudoku, convertible from and to [`Sudoku`]
+pub type Sudoku9 = SizedSudoku<3>;
+/// 16x16 sudoku with the digits 1-16
+pub type Sudoku16 = SizedSudoku<4>;
+/// 25x25 sudoku with the digits 1-25
+pub type Sudoku25 = SizedSudoku<5>;
+
+impl<const N: usize> SizedSudoku<N> {
+    /// Number of rows, columns, blocks and digits
+    pub const SIDE: usize = N * N;
+    /// Number of cells
+    pub const CELLS: usize = N * N * N * N;
+
+    /// A sudoku without clues
+    pub fn empty() -> Self {
+        SizedSudoku {
+            cells: vec![0; Self::CELLS],
+        }
+    }
+
+    /// Constructs a sudoku from its cells, numbered row by row with 0 for empty cells
+    pub fn from_cells(cells: Vec<u8>) -> Result<Self, SizedSudokuError> {
+        if cells.len() != Self::CELLS {
+            return Err(SizedSudokuError::WrongCellCount(cells.len()));
+        }
+        if let Some(cell) = cells.iter().position(|&digit| digit as usize > Self::SIDE) {
+            return Err(SizedSudokuError::InvalidDigit {
+                cell,
+                digit: cells[cell],
+            });
+        }
+        Ok(SizedSudoku { cells })
+    }
+
+    /// Parses a line of one char per cell. Digits above 9 are the letters from `A` on, so a
+    /// 16x16 sudoku uses `1`-`9` and `A`-`G`. Empty cells are `.`, `0` or `_`.
+    pub fn from_str_line(line: &str) -> Result<Self, SizedSudokuError> {
+        let cells = line
+            .chars()
+            .enumerate()
+            .map(|(cell, ch)| match ch {
+                '.' | '0' | '_' => Ok(0),
+                _ => char_digit(ch)
+                    .filter(|&digit| digit as usize <= Self::SIDE)
+                    .ok_or(SizedSudokuError::InvalidEntry { cell, ch }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_cells(cells)
+    }
+
+    /// The line format of [`SizedSudoku::from_str_line`] with `.` for empty cells
+    pub fn to_str_line(&self) -> String {
+        self.cells.iter().map(|&digit| digit_char(digit)).collect()
+    }
+
+    /// The cells, numbered row by row with 0 for empty cells
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn n_clues(&self) -> usize {
+        self.cells.iter().filter(|&&digit| digit != 0).count()
+    }
+
+    /// Checks whether all cells are filled without any digit repeating in a row, column or block
+    pub fn is_solved(&self) -> bool {
+        !self.cells.contains(&0) && Grid::<N>::new(&self.cells).is_some()
+    }
+
+    /// Checks whether `grid` is solved and keeps every clue
+    pub fn is_solution(&self, grid: &Self) -> bool {
+        grid.is_solved()
+            && self
+                .cells
+                .iter()
+                .zip(&grid.cells)
+                .all(|(&clue, &digit)| clue == 0 || clue == digit)
+    }
+
+    /// Counts the solutions up to `limit`
+    pub fn solutions_count_up_to(&self, limit: usize) -> usize {
+        let mut search = Search::new(limit);
+        search.run::<N>(self.cells.clone(), None);
+        search.n_solutions
+    }
+
+    /// Checks whether the sudoku has one and only one solution
+    pub fn is_uniquely_solvable(&self) -> bool {
+        self.solutions_count_up_to(2) == 1
+    }
+
+    /// Returns the solution, if it is unique
+    pub fn solution(&self) -> Option<Self> {
+        let mut search = Search::new(2);
+        search.run::<N>(self.cells.clone(), None);
+        match search.n_solutions {
+            1 => search.first.map(|cells| SizedSudoku { cells }),
+            _ => None,
+        }
+    }
+
+    /// Generates a random, uniquely solvable sudoku.
+    ///
+    /// Clues are removed from a random solved grid as long as the solution stays unique, so the
+    /// puzzles are minimal. Minimal 16x16 and 25x25 puzzles take long searches to prove unique, see
+    /// [`SizedSudoku::generate_with_clues`] for a cheaper alternative.
+    pub fn generate(rng: &mut StdRng) -> Self {
+        Self::generate_with_clues(rng, 0)
+    }
+
+    /// Like [`SizedSudoku::generate`], but stops removing clues once only `min_clues` are left.
+    /// The more clues remain, the more of the puzzle is solved by singles alone, which is what
+    /// keeps the uniqueness checks cheap.
+    pub fn generate_with_clues(rng: &mut StdRng, min_clues: usize) -> Self {
+        let mut search = Search::new(1);
+        search.run::<N>(vec![0; Self::CELLS], Some(rng));
+        let mut sudoku = SizedSudoku {
+            cells: search.first.expect("empty sudokus have solutions"),
+        };
+
+        let mut cell_order: Vec<_> = (0..Self::CELLS).collect();
+        cell_order.shuffle(rng);
+        let mut n_clues = Self::CELLS;
+        for cell in cell_order {
+            if n_clues <= min_clues {
+                break;
+            }
+            let digit = sudoku.cells[cell];
+            sudoku.cells[cell] = 0;
+            if sudoku.is_uniquely_solvable() {
+                n_clues -= 1;
+            } else {
+                sudoku.cells[cell] = digit;
+            }
+        }
+        sudoku
+    }
+}
+
+impl From<Sudoku> for Sudoku9 {
+    fn from(sudoku: Sudoku) -> Self {
+        SizedSudoku {
+            cells: sudoku.0.to_vec(),
+        }
+    }
+}
+
+impl From<Sudoku9> for Sudoku {
+    fn from(sudoku: Sudoku9) -> Self {
+        let mut cells = [0; 81];
+        cells.copy_from_slice(&sudoku.cells);
+        Sudoku(cells)
+    }
+}
+
+fn char_digit(ch: char) -> Option<u8> {
+    match ch {
+        '1'..='9' => Some(ch as u8 - b'0'),
+        'A'..='P' => Some(ch as u8 - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn digit_char(digit: u8) -> char {
+    match digit {
+        0 => '.',
+        1..=9 => (b'0' + digit) as char,
+        _ => (b'A' + digit - 10) as char,
+    }
+}
+
+/// Digits entered per row, column and block, as bitmasks with bit `digit - 1`
+struct Grid<const N: usize> {
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+    blocks: Vec<u32>,
+}
+
+impl<const N: usize> Grid<N> {
+    const SIDE: usize = N * N;
+    const ALL: u32 = (1 << (N * N)) - 1;
+
+    /// `None` if a digit repeats in a house
+    fn new(cells: &[u8]) -> Option<Self> {
+        let mut grid = Grid {
+            rows: vec![0; Self::SIDE],
+            cols: vec![0; Self::SIDE],
+            blocks: vec![0; Self::SIDE],
+        };
+        for (cell, &digit) in cells.iter().enumerate() {
+            if digit != 0 && !grid.enter(cell, digit) {
+                return None;
+            }
+        }
+        Some(grid)
+    }
+
+    fn block(cell: usize) -> usize {
+        let (row, col) = (cell / Self::SIDE, cell % Self::SIDE);
+        row / N * N + col / N
+    }
+
+    /// Records `digit` in the houses of `cell`, `false` if one of them has it already
+    fn enter(&mut self, cell: usize, digit: u8) -> bool {
+        let bit = 1 << (digit - 1);
+        let (row, col, block) = (cell / Self::SIDE, cell % Self::SIDE, Self::block(cell));
+        if (self.rows[row] | self.cols[col] | self.blocks[block]) & bit != 0 {
+            return false;
+        }
+        self.rows[row] |= bit;
+        self.cols[col] |= bit;
+        self.blocks[block] |= bit;
+        true
+    }
+
+    fn candidates(&self, cell: usize) -> u32 {
+        let (row, col, block) = (cell / Self::SIDE, cell % Self::SIDE, Self::block(cell));
+        Self::ALL & !(self.rows[row] | self.cols[col] | self.blocks[block])
+    }
+
+    /// Cell `index` of house `house`: the rows, then the columns, then the blocks
+    fn house_cell(house: usize, index: usize) -> usize {
+        let side = Self::SIDE;
+        match house / side {
+            0 => house * side + index,
+            1 => index * side + house % side,
+            _ => {
+                let block = house % side;
+                (block / N * N + index / N) * side + block % N * N + index % N
+            }
+        }
+    }
+}
+
+/// Depth first search over the candidates, filling in naked and hidden singles before every guess
+struct Search {
+    limit: usize,
+    n_solutions: usize,
+    first: Option<Vec<u8>>,
+}
+
+impl Search {
+    fn new(limit: usize) -> Self {
+        Search {
+            limit,
+            n_solutions: 0,
+            first: None,
+        }
+    }
+
+    /// Searches the solutions of `cells`, trying the candidates of a guess in random order if
+    /// `rng` is given
+    fn run<const N: usize>(&mut self, mut cells: Vec<u8>, mut rng: Option<&mut StdRng>) {
+        let grid = match fill_singles::<N>(&mut cells) {
+            Some(grid) => grid,
+            None => return,
+        };
+        let guess = (0..cells.len())
+            .filter(|&cell| cells[cell] == 0)
+            .map(|cell| (cell, grid.candidates(cell)))
+            .min_by_key(|&(_, candidates)| candidates.count_ones());
+        let (cell, mut candidates) = match guess {
+            Some(guess) => guess,
+            None => {
+                self.n_solutions += 1;
+                self.first.get_or_insert(cells);
+                return;
+            }
+        };
+
+        let mut digits = vec![];
+        while candidates != 0 {
+            digits.push(candidates.trailing_zeros() as u8 + 1);
+            candidates &= candidates - 1;
+        }
+        if let Some(rng) = rng.as_deref_mut() {
+            digits.shuffle(rng);
+        }
+        for digit in digits {
+            if self.n_solutions >= self.limit {
+                return;
+            }
+            let mut next = cells.clone();
+            next[cell] = digit;
+            self.run::<N>(next, rng.as_deref_mut());
+        }
+    }
+}
+
+/// Enters singles until there are none left. Returns the digits of the houses afterwards, or
+/// `None` on a contradiction.
+///
+/// Hidden singles are searched on the candidates at the start of a pass. Those only ever shrink, so
+/// a digit with a single place left is placed there or the house has no place for it at all.
+fn fill_singles<const N: usize>(cells: &mut [u8]) -> Option<Grid<N>> {
+    let side = Grid::<N>::SIDE;
+    let mut grid = Grid::<N>::new(cells)?;
+    loop {
+        let mut progress = false;
+        for (cell, entry) in cells.iter_mut().enumerate() {
+            if *entry != 0 {
+                continue;
+            }
+            let candidates = grid.candidates(cell);
+            match candidates.count_ones() {
+                0 => return None,
+                1 => {
+                    let digit = candidates.trailing_zeros() as u8 + 1;
+                    *entry = digit;
+                    grid.enter(cell, digit);
+                    progress = true;
+                }
+                _ => {}
+            }
+        }
+
+        let candidates: Vec<u32> = (0..cells.len())
+            .map(|cell| match cells[cell] {
+                0 => grid.candidates(cell),
+                _ => 0,
+            })
+            .collect();
+        for house in 0..3 * side {
+            let mut entered = 0;
+            let mut once = 0;
+            let mut twice = 0;
+            for index in 0..side {
+                let cell = Grid::<N>::house_cell(house, index);
+                entered |= match cells[cell] {
+                    0 => 0,
+                    digit => 1 << (digit - 1),
+                };
+                twice |= once & candidates[cell];
+                once |= candidates[cell];
+            }
+            if (entered | once) != Grid::<N>::ALL {
+                return None;
+            }
+            let mut singles = once & !twice & !entered;
+            while singles != 0 {
+                let bit = singles & singles.wrapping_neg();
+                singles &= singles - 1;
+                let cell = (0..side)
+                    .map(|index| Grid::<N>::house_cell(house, index))
+                    .find(|&cell| candidates[cell] & bit != 0)
+                    .unwrap();
+                let digit = bit.trailing_zeros() as u8 + 1;
+                if cells[cell] != 0 || !grid.enter(cell, digit) {
+                    return None;
+                }
+                cells[cell] = digit;
+                progress = true;
+            }
+        }
+
+        if !progress {
+            return Some(grid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn line_format() {
+        let line = "1..4..2..1..3..2";
+        let sudoku = Sudoku4::from_str_line(line).unwrap();
+        assert_eq!(sudoku.to_str_line(), line);
+        assert_eq!(sudoku.n_clues(), 6);
+        assert_eq!(
+            Sudoku4::from_str_line("1..5............"),
+            Err(SizedSudokuError::InvalidEntry { cell: 3, ch: '5' })
+        );
+        assert_eq!(Sudoku4::from_str_line("1..4"), Err(SizedSudokuError::WrongCellCount(4)));
+        let marathon = "G".repeat(256);
+        assert_eq!(Sudoku16::from_str_line(&marathon).unwrap().cells()[0], 16);
+    }
+
+    #[test]
+    fn agrees_with_9x9_solver() {
+        let sudokus = [
+            "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..",
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        ];
+        for line in &sudokus {
+            let sudoku = Sudoku::from_str_line(line).unwrap();
+            let sized = Sudoku9::from(sudoku);
+            assert!(sized.is_uniquely_solvable());
+            assert_eq!(sized.solution().map(Sudoku::from), sudoku.solution());
+        }
+        // two solutions
+        let sudoku = Sudoku9::from_str_line(&format!("{}{}", ".".repeat(80), "1")).unwrap();
+        assert_eq!(sudoku.solutions_count_up_to(2), 2);
+        assert_eq!(sudoku.solution(), None);
+    }
+
+    #[test]
+    fn conflicting_clues() {
+        let sudoku = Sudoku4::from_str_line("11..............").unwrap();
+        assert_eq!(sudoku.solutions_count_up_to(2), 0);
+    }
+
+    #[test]
+    fn generate() {
+        let rng = &mut StdRng::seed_from_u64(7);
+        let kids = Sudoku4::generate(rng);
+        assert!(kids.is_uniquely_solvable());
+        assert!(kids.is_solution(&kids.solution().unwrap()));
+        // minimal: every clue is needed
+        for cell in (0..Sudoku4::CELLS).filter(|&cell| kids.cells()[cell] != 0) {
+            let mut cells = kids.cells().to_vec();
+            cells[cell] = 0;
+            assert!(!Sudoku4::from_cells(cells).unwrap().is_uniquely_solvable());
+        }
+
+        let marathon = Sudoku16::generate_with_clues(rng, 128);
+        assert_eq!(marathon.n_clues(), 128);
+        assert!(marathon.is_uniquely_solvable());
+        let solution = marathon.solution().unwrap();
+        assert!(solution.is_solved());
+        assert!(marathon.is_solution(&solution));
+    }
+}
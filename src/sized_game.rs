@@ -0,0 +1,210 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Balance, StorageUsage, Timestamp};
+use rand::rngs::StdRng;
+
+use crate::ranking::RankIndex;
+use crate::sized::SizedSudoku;
+use crate::BoardKind;
+
+/// Storage of the entries of one account in the two rankings of one board size, paid from the
+/// prepaid balance with its first solve of that size. Each ranking stores the account id twice,
+/// this covers ids of the maximal 64 characters.
+pub const SIZED_RANKING_SIZE: u128 = 720;
+
+/// Clues left in marathon puzzles. Proving minimal 16x16 puzzles unique takes more gas than a
+/// transaction has, see [`SizedSudoku::generate_with_clues`].
+const MARATHON_CLUES: usize = 128;
+
+/// Grid sizes besides the classic 9x9 one, see [`Contract::start_game`](crate::Contract::start_game)
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum BoardSize {
+    /// 4x4 grids with the digits 1-4
+    Kids,
+    /// 16x16 grids with the digits 1-9 and A-G
+    Marathon,
+}
+
+impl BoardSize {
+    pub const ALL: [BoardSize; 2] = [BoardSize::Kids, BoardSize::Marathon];
+
+    /// Storage of a running game of this size and an account id of up to 64 characters, held from
+    /// the prepaid balance until it ends
+    pub fn game_size(self) -> u128 {
+        match self {
+            BoardSize::Kids => 200,
+            BoardSize::Marathon => 700,
+        }
+    }
+}
+
+/// A running kids or marathon game. These are separate from the 9x9 games of [`crate::Player`],
+/// a player can have one of each.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SizedGame {
+    pub size: BoardSize,
+    /// Cells row by row, 0 for empty cells
+    puzzle: Vec<u8>,
+    solution: Vec<u8>,
+    pub start_time: Timestamp,
+    /// Taken from the prepaid balance at the start, see [`BoardSize::game_size`] and
+    /// [`SIZED_RANKING_SIZE`]
+    pub deposit: Balance,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SizedGameRequest {
+    pub size: BoardSize,
+    /// In the line format of [`SizedSudoku::from_str_line`]
+    pub sudoku: String,
+    pub start_time: Timestamp,
+}
+
+impl SizedGame {
+    pub fn generate(
+        size: BoardSize,
+        rng: &mut StdRng,
+        start_time: Timestamp,
+        deposit: Balance,
+    ) -> Self {
+        let (puzzle, solution) = match size {
+            BoardSize::Kids => generate::<2>(rng, 0),
+            BoardSize::Marathon => generate::<4>(rng, MARATHON_CLUES),
+        };
+        Self {
+            size,
+            puzzle,
+            solution,
+            start_time,
+            deposit,
+        }
+    }
+
+    /// Checks whether `line` is the solution. The puzzles are uniquely solvable, so any other
+    /// grid breaks a rule or a clue.
+    pub fn is_solution(&self, line: &str) -> bool {
+        let grid = match self.size {
+            BoardSize::Kids => {
+                SizedSudoku::<2>::from_str_line(line).map(|grid| grid.cells().to_vec())
+            }
+            BoardSize::Marathon => {
+                SizedSudoku::<4>::from_str_line(line).map(|grid| grid.cells().to_vec())
+            }
+        };
+        grid.is_ok_and(|grid| grid == self.solution)
+    }
+
+    pub fn get(&self) -> SizedGameRequest {
+        let sudoku =
+            match self.size {
+                BoardSize::Kids => SizedSudoku::<2>::from_cells(self.puzzle.clone())
+                    .map(|sudoku| sudoku.to_str_line()),
+                BoardSize::Marathon => SizedSudoku::<4>::from_cells(self.puzzle.clone())
+                    .map(|sudoku| sudoku.to_str_line()),
+            };
+        SizedGameRequest {
+            size: self.size,
+            sudoku: sudoku.unwrap(),
+            start_time: self.start_time,
+        }
+    }
+}
+
+/// Cells of a generated puzzle and of its solution
+fn generate<const N: usize>(rng: &mut StdRng, min_clues: usize) -> (Vec<u8>, Vec<u8>) {
+    let puzzle = SizedSudoku::<N>::generate_with_clues(rng, min_clues);
+    let solution = puzzle
+        .solution()
+        .expect("generated sudokus are uniquely solvable");
+    (puzzle.cells().to_vec(), solution.cells().to_vec())
+}
+
+/// Solve counts and best times of the kids and marathon games, kept apart from the 9x9
+/// [`crate::Leaderboard`]. Only [`BoardKind::Count`] and [`BoardKind::Time`] are ranked.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SizedBoards {
+    kids_by_count: RankIndex,
+    kids_by_time: RankIndex,
+    marathon_by_count: RankIndex,
+    marathon_by_time: RankIndex,
+}
+
+impl SizedBoards {
+    pub fn new() -> Self {
+        Self {
+            kids_by_count: RankIndex::new(b"ykc"),
+            kids_by_time: RankIndex::new(b"ykt"),
+            marathon_by_count: RankIndex::new(b"ymc"),
+            marathon_by_time: RankIndex::new(b"ymt"),
+        }
+    }
+
+    pub fn ranking(&self, size: BoardSize, board: BoardKind) -> Option<&RankIndex> {
+        match (size, board) {
+            (BoardSize::Kids, BoardKind::Count) => Some(&self.kids_by_count),
+            (BoardSize::Kids, BoardKind::Time) => Some(&self.kids_by_time),
+            (BoardSize::Marathon, BoardKind::Count) => Some(&self.marathon_by_count),
+            (BoardSize::Marathon, BoardKind::Time) => Some(&self.marathon_by_time),
+            _ => None,
+        }
+    }
+
+    fn rankings_mut(&mut self, size: BoardSize) -> (&mut RankIndex, &mut RankIndex) {
+        match size {
+            BoardSize::Kids => (&mut self.kids_by_count, &mut self.kids_by_time),
+            BoardSize::Marathon => (&mut self.marathon_by_count, &mut self.marathon_by_time),
+        }
+    }
+
+    /// Whether `account_id` solved a game of `size`, so its ranking storage is paid
+    pub fn is_ranked(&self, account_id: &AccountId, size: BoardSize) -> bool {
+        self.ranking(size, BoardKind::Count)
+            .is_some_and(|ranking| ranking.key(account_id).is_some())
+    }
+
+    /// Storage of the entries of `account_id` in all rankings
+    pub fn storage_size(&self, account_id: &AccountId) -> StorageUsage {
+        [
+            &self.kids_by_count,
+            &self.kids_by_time,
+            &self.marathon_by_count,
+            &self.marathon_by_time,
+        ]
+        .iter()
+        .map(|ranking| ranking.storage_size(account_id))
+        .sum()
+    }
+
+    /// Counts a solve of `size` taking `time` milliseconds
+    pub fn record_solve(&mut self, account_id: &AccountId, size: BoardSize, time: Timestamp) {
+        let (by_count, by_time) = self.rankings_mut(size);
+        let solves = by_count
+            .key(account_id)
+            .map_or(0, |key| BoardKind::Count.sort_key(key));
+        by_count.insert(account_id, BoardKind::Count.sort_key(solves + 1));
+        let best = by_time.key(account_id).map_or(time as u128, |key| {
+            BoardKind::Time.sort_key(key).min(time as u128)
+        });
+        by_time.insert(account_id, BoardKind::Time.sort_key(best));
+    }
+
+    /// Removes `account_id` from all rankings. Returns the sizes it was ranked in.
+    pub fn remove_player(&mut self, account_id: &AccountId) -> Vec<BoardSize> {
+        let ranked: Vec<BoardSize> = BoardSize::ALL
+            .iter()
+            .copied()
+            .filter(|&size| self.is_ranked(account_id, size))
+            .collect();
+        for &size in &ranked {
+            let (by_count, by_time) = self.rankings_mut(size);
+            by_count.remove(account_id);
+            by_time.remove(account_id);
+        }
+        ranked
+    }
+}
@@ -0,0 +1,99 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, Timestamp};
+
+/// Places of every board kept by compacted snapshots, see [`Snapshot::compact`]
+pub const ARCHIVED_PLACES: usize = 3;
+/// Default of [`crate::config::Config::snapshot_retention`]
+pub const SNAPSHOT_RETENTION: u64 = 52;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StandingsEntry {
+    pub account_id: AccountId,
+    /// Sudokus solved or best time in milliseconds within the period, depending on the board
+    pub value: U128,
+}
+
+/// Final standings of a leaderboard period, each board from first to last place
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Standings {
+    pub period_id: u64,
+    pub by_count: Vec<StandingsEntry>,
+    pub by_time: Vec<StandingsEntry>,
+    pub verified_by_count: Vec<StandingsEntry>,
+    pub verified_by_time: Vec<StandingsEntry>,
+}
+
+impl Standings {
+    fn truncate(&mut self, places: usize) {
+        self.by_count.truncate(places);
+        self.by_time.truncate(places);
+        self.verified_by_count.truncate(places);
+        self.verified_by_time.truncate(places);
+    }
+}
+
+/// Record of [`Standings`] taken when their period ended, it only changes when pruned by
+/// [`crate::Contract::prune_snapshots`]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Snapshot {
+    standings: Standings,
+    taken_at: Timestamp,
+    /// Hash of the full standings, also after compacting
+    hash: [u8; 32],
+    compacted: bool,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SnapshotRequest {
+    pub standings: Standings,
+    pub taken_at: Timestamp,
+    /// Hex encoded sha256 of the borsh serialized `standings`. For compacted snapshots it
+    /// matches the full standings, which were logged when compacting.
+    pub hash: String,
+    /// Only the first [`ARCHIVED_PLACES`] of every board are left
+    pub compacted: bool,
+}
+
+impl Snapshot {
+    pub fn new(standings: Standings) -> Snapshot {
+        let hash = env::sha256_array(&standings.try_to_vec().unwrap());
+        Snapshot {
+            standings,
+            taken_at: env::block_timestamp_ms(),
+            hash,
+            compacted: false,
+        }
+    }
+
+    /// Drops all but the first [`ARCHIVED_PLACES`] of every board, the hash is kept.
+    pub fn compact(&mut self) {
+        self.standings.truncate(ARCHIVED_PLACES);
+        self.compacted = true;
+    }
+
+    pub fn compacted(&self) -> bool {
+        self.compacted
+    }
+
+    pub fn standings(&self) -> &Standings {
+        &self.standings
+    }
+
+    pub fn get(&self) -> SnapshotRequest {
+        SnapshotRequest {
+            standings: self.standings.clone(),
+            taken_at: self.taken_at,
+            hash: self
+                .hash
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            compacted: self.compacted,
+        }
+    }
+}
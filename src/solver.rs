@@ -37,30 +37,111 @@
 //  zhouyundong, champagne and JasonLion have all given permission
 //  for a port under the AGPLv3 license in the forum thread
 //      http://forum.enjoysudoku.com/3-77us-solver-2-8g-cpu-testcase-17sodoku-t30470-270.html#p262718
+//
+//  This is the one and only backtracking backend. Every solution search and count of `Sudoku`,
+//  and with it the uniqueness checks of the generator and the contract, runs through it.
 
+use alloc::{vec, vec::Vec};
+use crate::errors::Exceeded;
 use crate::helper::Unsolvable;
 use crate::Sudoku;
 use crunchy::unroll;
+#[cfg(feature = "std")]
+use near_sdk::serde::{Deserialize, Serialize};
 
 // masks of 27 bits
 const NONE: u32 = 0;
 const ALL: u32 = 0o777_777_777;
 const LOW9: u32 = 0o000_000_777;
 
+/// Counters of the work done by the backtracking solver, see [`Sudoku::solve_with_stats`]
+///
+/// The numbers depend on the internals of the solver and may change between versions.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SolverStats {
+    /// Candidates tried in a cell with more than one candidate left
+    pub guesses: u32,
+    /// Guesses that ran into a contradiction during propagation and were undone
+    pub backtracks: u32,
+    /// Rounds of locked candidates and naked singles until nothing more was found
+    pub propagation_passes: u32,
+}
+
+/// A digit entered by the backtracking solver, see [`Sudoku::solution_trace`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+pub struct Placement {
+    /// Cell number from 0..=80, 0..=8 for the first row, 9..=17 for the second and so on
+    pub cell: u8,
+    /// The entered digit
+    pub digit: u8,
+    /// How the solver found the digit
+    pub kind: PlacementKind,
+}
+
+/// The reason for a [`Placement`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+pub enum PlacementKind {
+    /// The digit was the last candidate of the cell
+    NakedSingle,
+    /// The cell was the last place for the digit, found while propagating locked candidates
+    HiddenSingle,
+    /// The digit was tried out and turned out to be correct
+    Guess,
+}
+
 // When the solver finds a solution it can save it or just count.
 // The latter is marginally faster.
-enum Solutions<'a> {
+enum Sink<'a> {
     Count(usize),
     Vector(&'a mut Vec<Sudoku>),
     Buffer(&'a mut [[u8; 81]], usize),
 }
 
-impl Solutions<'_> {
+struct Solutions<'a> {
+    sink: Sink<'a>,
+    stats: SolverStats,
+    // placements leading to the current state, only recorded if `Some`
+    trace: Option<Vec<Placement>>,
+    // the search is aborted, when another guess would exceed this
+    max_guesses: u32,
+    exceeded: bool,
+}
+
+impl<'a> Solutions<'a> {
+    fn new(sink: Sink<'a>) -> Self {
+        Solutions {
+            sink,
+            stats: SolverStats::default(),
+            trace: None,
+            max_guesses: u32::MAX,
+            exceeded: false,
+        }
+    }
+
+    // Whether the search must stop, either because enough solutions were found or the guess budget is used up
+    fn is_done(&self, limit: usize) -> bool {
+        self.len() == limit || self.exceeded
+    }
+
+    fn record(&mut self, band: usize, cell_mask: u32, digit: usize, kind: PlacementKind) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(Placement {
+                cell: (band * 27 + bit_pos(cell_mask)) as u8,
+                digit: digit as u8 + 1,
+                kind,
+            });
+        }
+    }
+
     fn len(&self) -> usize {
-        match self {
-            Solutions::Vector(v) => v.len(),
-            Solutions::Count(len) => *len,
-            Solutions::Buffer(_, len) => *len,
+        match &self.sink {
+            Sink::Vector(v) => v.len(),
+            Sink::Count(len) => *len,
+            Sink::Buffer(_, len) => *len,
         }
     }
 }
@@ -133,27 +214,61 @@ impl SudokuSolver {
     /// Find and return up to `limit` solutions
     pub fn solutions_up_to(self, limit: usize) -> Vec<Sudoku> {
         let mut solutions = vec![];
-        self._solutions_up_to(limit, &mut Solutions::Vector(&mut solutions));
+        self._solutions_up_to(limit, &mut Solutions::new(Sink::Vector(&mut solutions)));
         solutions
     }
 
     /// Count up to `limit` solutions and save up to buffer.len() of them
     /// in `buffer`. Returns number of solutions.
     pub fn solutions_up_to_buffer(self, buffer: &mut [[u8; 81]], limit: usize) -> usize {
-        let mut solutions = Solutions::Buffer(buffer, 0);
+        let mut solutions = Solutions::new(Sink::Buffer(buffer, 0));
         self._solutions_up_to(limit, &mut solutions);
         solutions.len()
     }
 
+    /// Like [`SudokuSolver::solutions_up_to_buffer`], but also returns the work done by the solver
+    pub fn solutions_up_to_buffer_with_stats(self, buffer: &mut [[u8; 81]], limit: usize) -> (usize, SolverStats) {
+        let mut solutions = Solutions::new(Sink::Buffer(buffer, 0));
+        self._solutions_up_to(limit, &mut solutions);
+        (solutions.len(), solutions.stats)
+    }
+
+    /// Like [`SudokuSolver::solutions_up_to_buffer`], but gives up after `max_guesses` guesses
+    pub fn solutions_up_to_buffer_with_limit(
+        self,
+        buffer: &mut [[u8; 81]],
+        limit: usize,
+        max_guesses: u32,
+    ) -> Result<usize, Exceeded> {
+        let mut solutions = Solutions::new(Sink::Buffer(buffer, 0));
+        solutions.max_guesses = max_guesses;
+        self._solutions_up_to(limit, &mut solutions);
+        match solutions.exceeded {
+            true => Err(Exceeded { max_guesses }),
+            false => Ok(solutions.len()),
+        }
+    }
+
+    /// Find a solution and return the placements that lead to it, without any wrong guesses
+    pub fn solution_trace(self) -> Option<Vec<Placement>> {
+        let mut solutions = Solutions::new(Sink::Count(0));
+        solutions.trace = Some(vec![]);
+        self._solutions_up_to(1, &mut solutions);
+        match solutions.len() {
+            1 => solutions.trace,
+            _ => None,
+        }
+    }
+
     /// Find up to `limit` solutions and return count
     pub fn solutions_count_up_to(self, limit: usize) -> usize {
-        let mut solutions = Solutions::Count(0);
+        let mut solutions = Solutions::new(Sink::Count(0));
         self._solutions_up_to(limit, &mut solutions);
         solutions.len()
     }
 
     fn _solutions_up_to(mut self, limit: usize, solutions: &mut Solutions) {
-        if self.find_naked_singles().is_err() {
+        if self.find_naked_singles(solutions).is_err() {
             return;
         }
 
@@ -173,16 +288,19 @@ impl SudokuSolver {
     // jczsolve equivalent: FullUpdate
     fn _solve(&mut self, limit: usize, solutions: &mut Solutions) -> Result<(), Unsolvable> {
         debug_assert!(solutions.len() <= limit);
-        if solutions.len() == limit {
+        if solutions.is_done(limit) {
             return Err(Unsolvable); // not really, but it forces a recursion stop
         }
         loop {
+            solutions.stats.propagation_passes += 1;
+            let unsolved_cells = self.unsolved_cells;
             self.find_locked_candidates_and_update()?;
+            self.record_hidden_singles(unsolved_cells, solutions);
             if self.is_solved() {
                 return Ok(());
             }
             // if singles found, go again
-            if self.find_naked_singles()? {
+            if self.find_naked_singles(solutions)? {
                 continue;
             }
             return Ok(());
@@ -194,7 +312,7 @@ impl SudokuSolver {
     /// 2 (good guess locations) or >=3 (bad guess locations).
     //
     // jczsolve equivalent: ApplySingleOrEmptyCells
-    fn find_naked_singles(&mut self) -> Result<bool, Unsolvable> {
+    fn find_naked_singles(&mut self, solutions: &mut Solutions) -> Result<bool, Unsolvable> {
         let mut single_applied = false;
 
         for band in 0..3 {
@@ -228,6 +346,7 @@ impl SudokuSolver {
                 for digit in 0..9 {
                     if self.poss_cells[digit * 3 + band] & cell_mask_single != NONE {
                         self.insert_candidate_by_mask(digit * 3 + band, cell_mask_single);
+                        solutions.record(band, cell_mask_single, digit, PlacementKind::NakedSingle);
                         continue 'singles;
                     }
                 }
@@ -239,6 +358,25 @@ impl SudokuSolver {
         Ok(single_applied)
     }
 
+    /// Records the cells solved since `unsolved_cells` that weren't entered as naked singles or guesses.
+    fn record_hidden_singles(&self, unsolved_cells: UncheckedIndexArray<u32, 3>, solutions: &mut Solutions) {
+        if solutions.trace.is_none() {
+            return;
+        }
+        for band in 0..3 {
+            for cell_mask in mask_iter(unsolved_cells[band] & !self.unsolved_cells[band]) {
+                let cell = (band * 27 + bit_pos(cell_mask)) as u8;
+                let trace = solutions.trace.as_ref().unwrap();
+                if trace.iter().any(|placement| placement.cell == cell) {
+                    continue;
+                }
+                if let Some(digit) = (0..9).find(|digit| self.poss_cells[digit * 3 + band] & cell_mask != NONE) {
+                    solutions.record(band, cell_mask, digit, PlacementKind::HiddenSingle);
+                }
+            }
+        }
+    }
+
     /// Searches for minirows that must contain a digit because they are the only minirow
     /// in a row or block that still contains candidates and remove the candidates
     /// from conflicting minirows' cells.
@@ -260,7 +398,7 @@ impl SudokuSolver {
                     // which would in this rare case cause the code to run slower.
                     //
                     // `test::black_box(true)` has the same effect but is unstable
-                    if (self.requirement_for_weird_optimization[0] >> subband / 3) & LOW9 != NONE
+                    if (self.requirement_for_weird_optimization[0] >> (subband / 3)) & LOW9 != NONE
                     && self.poss_cells[subband] != self.prev_poss_cells[subband]
                     {
                         found_nothing = false;
@@ -343,10 +481,10 @@ impl SudokuSolver {
     fn guess(&mut self, limit: usize, solutions: &mut Solutions) {
         if self.is_solved() {
             debug_assert!(solutions.len() < limit);
-            match solutions {
-                Solutions::Count(count) => *count += 1,
-                Solutions::Vector(vec) => vec.push(self.extract_solution()),
-                Solutions::Buffer(buf, len) => {
+            match &mut solutions.sink {
+                Sink::Count(count) => *count += 1,
+                Sink::Vector(vec) => vec.push(self.extract_solution()),
+                Sink::Buffer(buf, len) => {
                     if let Some(sudoku_slot) = buf.get_mut(*len) {
                         *sudoku_slot = self.extract_solution().to_bytes();
                     }
@@ -359,6 +497,33 @@ impl SudokuSolver {
         }
     }
 
+    /// Insert the digit of `subband` in the cell of `cell_mask` as a guess and continue solving.
+    fn try_guess(&mut self, subband: usize, cell_mask: u32, limit: usize, solutions: &mut Solutions) {
+        if solutions.is_done(limit) {
+            return;
+        }
+        if solutions.stats.guesses == solutions.max_guesses {
+            solutions.exceeded = true;
+            return;
+        }
+        self.insert_candidate_by_mask(subband, cell_mask);
+        solutions.stats.guesses += 1;
+        let trace_len = solutions.trace.as_ref().map_or(0, Vec::len);
+        solutions.record(subband % 3, cell_mask, subband / 3, PlacementKind::Guess);
+
+        match self._solve(limit, solutions) {
+            Ok(()) => self.guess(limit, solutions),
+            Err(Unsolvable) if !solutions.is_done(limit) => solutions.stats.backtracks += 1,
+            Err(Unsolvable) => {}
+        }
+        // traces are only recorded with a limit of 1, so a branch that didn't reach it was a dead end
+        if solutions.len() < limit {
+            if let Some(trace) = &mut solutions.trace {
+                trace.truncate(trace_len);
+            }
+        }
+    }
+
     /// Find some cell with only 2 possible values and try both in order.
     //
     // Whenever a guess has to be taken, there is virtually always a cell
@@ -384,16 +549,10 @@ impl SudokuSolver {
                     if first {
                         first = false;
                         let mut solver = *self;
-                        solver.insert_candidate_by_mask(subband, cell_mask);
-                        if solver._solve(limit, solutions).is_ok() {
-                            solver.guess(limit, solutions);
-                        }
+                        solver.try_guess(subband, cell_mask, limit, solutions);
                         self.poss_cells[subband] ^= cell_mask;
                     } else {
-                        self.insert_candidate_by_mask(subband, cell_mask);
-                        if self._solve(limit, solutions).is_ok() {
-                            self.guess(limit, solutions);
-                        }
+                        self.try_guess(subband, cell_mask, limit, solutions);
                         return Err(Unsolvable);
                     }
                 }
@@ -439,11 +598,8 @@ impl SudokuSolver {
         while subband < 27 {
             if self.poss_cells[subband] & unsolved_cell != NONE {
                 let mut solver = *self;
-                solver.insert_candidate_by_mask(subband, unsolved_cell);
-                if solver._solve(limit, solutions).is_ok() {
-                    solver.guess(limit, solutions);
-                }
-                if solutions.len() == limit {
+                solver.try_guess(subband, unsolved_cell, limit, solutions);
+                if solutions.is_done(limit) {
                     return;
                 }
                 self.poss_cells[subband] ^= unsolved_cell;
@@ -550,7 +706,7 @@ fn nonconflicting_cells_same_band(cell: usize) -> u32 {
 fn nonconflicting_cells_neighbor_bands(cell: u8) -> u32 {
     // only 3 cells in a column conflict with a candidate in another band
     // and they are all in one column, each 9 steps apart in the mask
-    ALL ^ (0o_001_001_001 << cell % 9)
+    ALL ^ (0o_001_001_001 << (cell % 9))
 }
 
 // Compress the 9 cell possibilities for a row into 3 bits,
@@ -562,7 +718,7 @@ fn nonconflicting_cells_neighbor_bands(cell: u8) -> u32 {
 //                      TblRowUniq was replaced by shrink_mask here
 #[inline]
 fn shrink_mask(cell_mask: u32) -> u32 {
-    SHRINK_MASK[cell_mask as usize] as u32
+    SHRINK_MASK[cell_mask as usize]
 }
 
 // Returns mask of cells that are compatible with locked candidates
@@ -604,7 +760,7 @@ fn nonconflicting_cells_neighbor_bands_by_locked_candidates(row_shrink: u32) ->
 // jczsolve equivalent: TblColumnSingle
 #[inline]
 fn column_single(row_shrink: u32) -> u32 {
-    COLUMN_SINGLE[row_shrink as usize] as u32
+    COLUMN_SINGLE[row_shrink as usize]
 }
 
 /// Maps a mask of possible minirows to the mask of locked minirows (locked candidates).
@@ -896,7 +1052,7 @@ static COLUMN_SINGLE: UncheckedIndexArray<u32, 512> = UncheckedIndexArray([
 #[derive(Clone, Copy)]
 struct UncheckedIndexArray<T, const N: usize>([T; N]);
 
-impl<T, const N: usize> std::ops::Index<usize> for UncheckedIndexArray<T, N> {
+impl<T, const N: usize> core::ops::Index<usize> for UncheckedIndexArray<T, N> {
     type Output = T;
     fn index(&self, idx: usize) -> &Self::Output {
         if cfg!(feature = "unchecked_indexing") {
@@ -908,7 +1064,7 @@ impl<T, const N: usize> std::ops::Index<usize> for UncheckedIndexArray<T, N> {
     }
 }
 
-impl<T, const N: usize> std::ops::IndexMut<usize> for UncheckedIndexArray<T, N> {
+impl<T, const N: usize> core::ops::IndexMut<usize> for UncheckedIndexArray<T, N> {
     fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
         if cfg!(feature = "unchecked_indexing") {
             debug_assert!(idx < self.0.len());
@@ -922,11 +1078,11 @@ impl<T, const N: usize> std::ops::IndexMut<usize> for UncheckedIndexArray<T, N>
 
 // for each set bit in mask, return a mask with only that bit set
 fn mask_iter(mask: u32) -> impl Iterator<Item = u32> {
-    std::iter::repeat(()).scan(mask, |mask, ()| {
+    core::iter::repeat(()).scan(mask, |mask, ()| {
         if *mask == 0 {
             return None;
         }
-        let lowest_bit = *mask & !*mask + 1;
+        let lowest_bit = *mask & (!*mask + 1);
         *mask ^= lowest_bit;
         Some(lowest_bit)
     })
@@ -0,0 +1,43 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{AccountId, Balance};
+
+/// Funds a sponsor added to the prize pool of a leaderboard period
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Sponsorship {
+    pub sponsor: AccountId,
+    pub amount: Balance,
+    /// Display name, validated like profile nicknames
+    pub name: String,
+    /// Validated like profile avatar urls
+    pub url: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SponsorshipRequest {
+    pub sponsor: AccountId,
+    pub amount: U128,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+impl Sponsorship {
+    pub fn get(&self) -> SponsorshipRequest {
+        SponsorshipRequest {
+            sponsor: self.sponsor.clone(),
+            amount: U128(self.amount),
+            name: self.name.clone(),
+            url: self.url.clone(),
+        }
+    }
+}
+
+/// Sum of all sponsored amounts
+pub fn total(sponsorships: &[Sponsorship]) -> Balance {
+    sponsorships
+        .iter()
+        .map(|sponsorship| sponsorship.amount)
+        .sum()
+}
@@ -0,0 +1,197 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use near_sdk::Timestamp;
+use std::collections::BTreeMap;
+
+use crate::difficulty::Difficulty;
+
+/// Upper bounds in milliseconds of the solve time histogram buckets, the last bucket is open
+pub const HISTOGRAM_BOUNDS: [Timestamp; 7] = [
+    60_000, 120_000, 300_000, 600_000, 1_200_000, 1_800_000, 3_600_000,
+];
+
+/// Solve statistics of a player for a single difficulty
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DifficultyStats {
+    solved_count: u64,
+    best_time: Option<Timestamp>,
+    total_time: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DifficultyStatsRequest {
+    pub solved_count: u64,
+    pub best_time: Option<Timestamp>,
+    pub average_time: Option<Timestamp>,
+}
+
+impl DifficultyStats {
+    pub fn record(&mut self, time: Timestamp) {
+        self.solved_count += 1;
+        self.total_time += time;
+        self.best_time = Some(self.best_time.map_or(time, |best| best.min(time)));
+    }
+
+    pub fn get(&self) -> DifficultyStatsRequest {
+        DifficultyStatsRequest {
+            solved_count: self.solved_count,
+            best_time: self.best_time,
+            average_time: match self.solved_count {
+                0 => None,
+                count => Some(self.total_time / count),
+            },
+        }
+    }
+}
+
+/// Statistics of a player's ranked games over all difficulties
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Solves per bucket of [`HISTOGRAM_BOUNDS`]
+    histogram: [u32; 8],
+    /// Solves that took at least one hint
+    hinted_count: u32,
+    /// Unfinished games replaced by a new game
+    abandoned_count: u32,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HistogramBucket {
+    /// Solve times up to this many milliseconds, `None` for the last bucket
+    pub up_to: Option<Timestamp>,
+    pub count: u32,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StatisticsRequest {
+    pub solved_count: u64,
+    pub abandoned_count: u32,
+    /// Share of finished games among finished and abandoned ones
+    pub solve_rate_percent: Option<u8>,
+    pub average_time: Option<Timestamp>,
+    /// Estimated from the histogram, assuming solve times spread evenly within a bucket
+    pub median_time: Option<Timestamp>,
+    pub histogram: Vec<HistogramBucket>,
+    pub hint_usage_percent: Option<u8>,
+    pub by_difficulty: BTreeMap<Difficulty, DifficultyStatsRequest>,
+}
+
+impl SolveStats {
+    pub fn record(&mut self, time: Timestamp, hints_used: u32) {
+        let bucket = HISTOGRAM_BOUNDS
+            .iter()
+            .position(|&bound| time <= bound)
+            .unwrap_or(HISTOGRAM_BOUNDS.len());
+        self.histogram[bucket] += 1;
+        if hints_used > 0 {
+            self.hinted_count += 1;
+        }
+    }
+
+    pub fn abandon(&mut self) {
+        self.abandoned_count += 1;
+    }
+
+    fn median_time(&self) -> Option<Timestamp> {
+        let solved: u32 = self.histogram.iter().sum();
+        if solved == 0 {
+            return None;
+        }
+
+        let median = (solved - 1) / 2;
+        let mut below = 0;
+        for (bucket, &count) in self.histogram.iter().enumerate() {
+            if median < below + count {
+                let lower = bucket
+                    .checked_sub(1)
+                    .map_or(0, |prev| HISTOGRAM_BOUNDS[prev]);
+                let upper = match HISTOGRAM_BOUNDS.get(bucket) {
+                    Some(&upper) => upper,
+                    None => return Some(lower),
+                };
+                let slot = (2 * (median - below) + 1) as Timestamp;
+                return Some(lower + (upper - lower) * slot / (2 * count) as Timestamp);
+            }
+            below += count;
+        }
+        unreachable!()
+    }
+
+    pub fn get(&self, by_difficulty: &[DifficultyStats; 4]) -> StatisticsRequest {
+        let solved_count: u64 = by_difficulty.iter().map(|stats| stats.solved_count).sum();
+        let total_time: u64 = by_difficulty.iter().map(|stats| stats.total_time).sum();
+        let percent = |part: u64, total: u64| match total {
+            0 => None,
+            total => Some((part * 100 / total) as u8),
+        };
+
+        StatisticsRequest {
+            solved_count,
+            abandoned_count: self.abandoned_count,
+            solve_rate_percent: percent(solved_count, solved_count + self.abandoned_count as u64),
+            average_time: match solved_count {
+                0 => None,
+                count => Some(total_time / count),
+            },
+            median_time: self.median_time(),
+            histogram: self
+                .histogram
+                .iter()
+                .enumerate()
+                .map(|(bucket, &count)| HistogramBucket {
+                    up_to: HISTOGRAM_BOUNDS.get(bucket).copied(),
+                    count,
+                })
+                .collect(),
+            hint_usage_percent: percent(self.hinted_count as u64, solved_count),
+            by_difficulty: Difficulty::ALL
+                .iter()
+                .map(|&difficulty| (difficulty, by_difficulty[difficulty as usize].get()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let mut stats = DifficultyStats::default();
+        assert_eq!(stats.get().average_time, None);
+
+        stats.record(300);
+        stats.record(100);
+        stats.record(500);
+        assert_eq!(
+            stats.get(),
+            DifficultyStatsRequest {
+                solved_count: 3,
+                best_time: Some(100),
+                average_time: Some(300),
+            }
+        );
+    }
+
+    #[test]
+    fn median_from_histogram() {
+        let mut stats = SolveStats::default();
+        assert_eq!(stats.median_time(), None);
+
+        stats.record(30_000, 0);
+        stats.record(90_000, 1);
+        stats.record(100_000, 0);
+        assert_eq!(stats.median_time(), Some(75_000));
+
+        stats.record(5_000_000, 0);
+        stats.record(6_000_000, 0);
+        stats.record(7_000_000, 0);
+        assert_eq!(stats.median_time(), Some(105_000));
+        stats.record(8_000_000, 0);
+        assert_eq!(stats.median_time(), Some(3_600_000));
+    }
+}
@@ -0,0 +1,122 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId, StorageUsage};
+
+/// Bytes every stored key-value record is charged on top of its key and value
+pub const RECORD_OVERHEAD: StorageUsage = 40;
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageReport {
+    /// Bytes of all records kept for the player: its entry in the players map, the side records,
+    /// ranking entries, attestations, archive completions and running games
+    pub player_bytes: StorageUsage,
+    /// Storage deposit taken for the player, including the verified rankings if verified and
+    /// everything paid from the prepaid balance for its records
+    pub player_deposit: U128,
+    pub contract_bytes: StorageUsage,
+    /// Deposit the contract account needs to stake for `contract_bytes`
+    pub contract_deposit: U128,
+}
+
+/// Storage charged for a record with a key of `key_len` and a value of `value_len` bytes
+pub fn record_size(key_len: usize, value_len: usize) -> StorageUsage {
+    (key_len + value_len) as StorageUsage + RECORD_OVERHEAD
+}
+
+/// Storage of the record under `key`, 0 if there is none
+fn stored_size(key: &[u8]) -> StorageUsage {
+    env::storage_read(key).map_or(0, |value| record_size(key.len(), value.len()))
+}
+
+/// Storage of the record of `key` in `collection`, a `LookupMap` or `LookupSet`. Both serialize
+/// to the prefix of their records. Like [`unordered_map_size`] this follows the layouts of the
+/// near-sdk collections, which is why near-sdk is pinned in `Cargo.toml`.
+pub fn lookup_size<K: BorshSerialize>(collection: &impl BorshSerialize, key: &K) -> StorageUsage {
+    let prefix = Vec::<u8>::deserialize(&mut &collection.try_to_vec().unwrap()[..]).unwrap();
+    stored_size(&[prefix, key.try_to_vec().unwrap()].concat())
+}
+
+/// Storage of the entry of `key` in `map`: its index record and its slots in the vectors of
+/// keys and values
+pub fn unordered_map_size<K: BorshSerialize, V>(map: &UnorderedMap<K, V>, key: &K) -> StorageUsage {
+    // the index prefix, then length and prefix of the keys and of the values
+    let (index_prefix, _, keys_prefix, _, values_prefix) =
+        <(Vec<u8>, u64, Vec<u8>, u64, Vec<u8>)>::try_from_slice(&map.try_to_vec().unwrap())
+            .unwrap();
+    let index_key = [index_prefix, key.try_to_vec().unwrap()].concat();
+    match env::storage_read(&index_key) {
+        Some(index) => {
+            stored_size(&index_key)
+                + stored_size(&[keys_prefix, index.clone()].concat())
+                + stored_size(&[values_prefix, index].concat())
+        }
+        None => 0,
+    }
+}
+
+/// A record of a player stored under its own key, so it's only read by the views returning it.
+/// The player keeps the enum tag and the key of the record, or the tag and the inline value.
+/// Players read from older layouts keep the record inline until
+/// [`crate::Contract::migrate_players`] moves it, the legacy layout has no account id to derive
+/// the key from.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum SideRecord<T> {
+    Inline(Option<T>),
+    Keyed(Vec<u8>),
+}
+
+impl<T: BorshSerialize + BorshDeserialize + Clone> SideRecord<T> {
+    /// An empty record of `account_id` under `prefix`
+    pub fn new(prefix: &[u8], account_id: &AccountId) -> Self {
+        Self::Keyed([prefix, account_id.as_str().as_bytes()].concat())
+    }
+
+    /// Storage of the record under its own key, 0 while it's inline or empty
+    pub fn storage_size(&self) -> StorageUsage {
+        match self {
+            Self::Inline(_) => 0,
+            Self::Keyed(key) => stored_size(key),
+        }
+    }
+
+    pub fn get(&self) -> Option<T> {
+        match self {
+            Self::Inline(value) => value.clone(),
+            Self::Keyed(key) => env::storage_read(key)
+                .map(|bytes| T::try_from_slice(&bytes).expect("stored records are valid")),
+        }
+    }
+
+    pub fn set(&mut self, value: &T) {
+        match self {
+            Self::Inline(inline) => *inline = Some(value.clone()),
+            Self::Keyed(key) => {
+                env::storage_write(key, &value.try_to_vec().unwrap());
+            }
+        }
+    }
+
+    pub fn remove(&mut self) {
+        match self {
+            Self::Inline(inline) => *inline = None,
+            Self::Keyed(key) => {
+                env::storage_remove(key);
+            }
+        }
+    }
+
+    /// Moves an inline record under the key of `account_id`, and rewrites a keyed one so it
+    /// gets the current layout
+    pub fn migrate(&mut self, prefix: &[u8], account_id: &AccountId) {
+        let value = self.get();
+        if let Self::Inline(_) = self {
+            *self = Self::new(prefix, account_id);
+        }
+        if let Some(value) = value {
+            self.set(&value);
+        }
+    }
+}
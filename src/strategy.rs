@@ -11,11 +11,17 @@
 //! made to optimize it, expect the solver to be at least an order of magnitude
 //! slower than the fast solver.
 
+mod config;
 pub mod deduction;
+pub(crate) mod grade;
+pub(crate) mod hint;
 mod solver;
 mod strategies;
 pub(crate) mod utils;
 
-pub use self::deduction::Deduction;
+pub use self::config::SolverConfig;
+pub use self::deduction::{Chain, Deduction, Deductions, Link, LinkKind};
+pub use self::grade::{Grade, GradedGeneration, MAX_GRADED_ATTEMPTS, UNSOLVED_SCORE};
+pub use self::hint::Hint;
 pub use self::solver::StrategySolver;
 pub use self::strategies::Strategy;
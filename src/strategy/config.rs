@@ -0,0 +1,35 @@
+/// Settings for the more expensive strategies of the [`StrategySolver`](super::StrategySolver)
+///
+/// Start from [`SolverConfig::default()`] and change the fields as needed.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SolverConfig {
+    /// Maximum number of links in the chains of [`XChain`](super::Strategy::XChain),
+    /// [`XyChain`](super::Strategy::XyChain) and [`Aic`](super::Strategy::Aic).
+    /// Values above [`MAX_CHAIN_LENGTH`](super::deduction::MAX_CHAIN_LENGTH) are treated as `MAX_CHAIN_LENGTH`.
+    pub max_chain_length: usize,
+    /// Whether [`AlsXz`](super::Strategy::AlsXz) searches for eliminations.
+    /// It compares all pairs of almost locked sets, which is slow, so it's disabled by default.
+    pub enable_als_xz: bool,
+    /// Whether strategies that rely on the sudoku having a unique solution may be used,
+    /// i.e. [`UniqueRectangleType1`](super::Strategy::UniqueRectangleType1) to
+    /// [`UniqueRectangleType4`](super::Strategy::UniqueRectangleType4),
+    /// [`AvoidableRectangles`](super::Strategy::AvoidableRectangles) and [`BugPlusOne`](super::Strategy::BugPlusOne).
+    /// Disable this when analysing sudokus that may have multiple solutions, the eliminations would be wrong.
+    pub assume_unique_solution: bool,
+}
+
+impl SolverConfig {
+    /// Default of [`SolverConfig::max_chain_length`]
+    pub const DEFAULT_MAX_CHAIN_LENGTH: usize = 9;
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            max_chain_length: Self::DEFAULT_MAX_CHAIN_LENGTH,
+            enable_als_xz: false,
+            assume_unique_solution: true,
+        }
+    }
+}
@@ -1,11 +1,14 @@
 //! Results of strategy applications
 
+use alloc::vec::Vec;
 use super::Strategy;
 use crate::bitset::Set;
 use crate::board::Candidate;
 use crate::board::*;
+#[cfg(feature = "std")]
+use near_sdk::serde::{Serialize, Serializer};
 
-type DeductionRange = std::ops::Range<usize>;
+type DeductionRange = core::ops::Range<usize>;
 type _Deduction = Deduction<DeductionRange>;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -18,7 +21,7 @@ pub struct Deductions {
 
 /// Borrowing iterator over [`Deductions`]
 pub struct Iter<'a> {
-    deductions: std::slice::Iter<'a, _Deduction>,
+    deductions: core::slice::Iter<'a, _Deduction>,
     eliminated_entries: &'a [Candidate],
 }
 
@@ -59,17 +62,120 @@ impl Deductions {
     }
 }
 
+/// Serializes as the sequence of [`Deduction`]s.
+#[cfg(feature = "std")]
+impl Serialize for Deductions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Maximum number of links in a [`Chain`]
+pub const MAX_CHAIN_LENGTH: usize = 15;
+
+/// An alternating inference chain of candidates
+///
+/// The links between consecutive candidates alternate between strong and weak, starting and ending with a strong link.
+/// Therefore at least one of the first and the last candidate is true.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chain {
+    nodes: [Candidate; MAX_CHAIN_LENGTH + 1],
+    len: u8,
+}
+
+impl Chain {
+    pub(crate) fn new(nodes: &[Candidate]) -> Chain {
+        debug_assert!(nodes.len() <= MAX_CHAIN_LENGTH + 1);
+        let mut chain = Chain {
+            nodes: [nodes[0]; MAX_CHAIN_LENGTH + 1],
+            len: nodes.len() as u8,
+        };
+        chain.nodes[..nodes.len()].copy_from_slice(nodes);
+        chain
+    }
+
+    /// Returns the candidates of the chain in order.
+    pub fn nodes(&self) -> &[Candidate] {
+        &self.nodes[..self.len as usize]
+    }
+
+    /// Returns the links between consecutive candidates of the chain.
+    pub fn links(&self) -> impl Iterator<Item = Link> + '_ {
+        self.nodes().windows(2).enumerate().map(|(i, pair)| Link {
+            from: pair[0],
+            to: pair[1],
+            kind: match i % 2 {
+                0 => LinkKind::Strong,
+                _ => LinkKind::Weak,
+            },
+        })
+    }
+
+    fn strategy(&self) -> Strategy {
+        let nodes = self.nodes();
+        let digit = nodes[0].digit;
+        if nodes.iter().all(|node| node.digit == digit) {
+            return Strategy::XChain;
+        }
+        let is_xy_chain = self.links().all(|link| match link.kind {
+            LinkKind::Strong => link.from.cell == link.to.cell,
+            LinkKind::Weak => link.from.digit == link.to.digit,
+        });
+        match is_xy_chain {
+            true => Strategy::XyChain,
+            false => Strategy::Aic,
+        }
+    }
+}
+
+/// Serializes as the sequence of candidates, see [`Chain::nodes`].
+#[cfg(feature = "std")]
+impl Serialize for Chain {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.nodes())
+    }
+}
+
+/// Link between 2 candidates of a [`Chain`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+#[allow(missing_docs)]
+pub struct Link {
+    pub from: Candidate,
+    pub to: Candidate,
+    pub kind: LinkKind,
+}
+
+/// Type of a [`Link`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+pub enum LinkKind {
+    /// At least one of the candidates is true
+    Strong,
+    /// At most one of the candidates is true
+    Weak,
+}
+
 /// Result of a single, successful strategy application
 ///
 /// This enum contains the data necessary to explain why the step could be taken.
+///
+/// It serializes with the variant name as the key, cells, houses and other positions as their indices and
+/// sets as sequences, e.g. `{"HiddenSingles":[{"cell":40,"digit":5},{"Block":4}]}`.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 #[allow(missing_docs)]
 pub enum Deduction<T> {
     /// Result of [`NakedSingles`](super::Strategy::NakedSingles)
     NakedSingles(Candidate),
     /// Result of [`HiddenSingles`](super::Strategy::HiddenSingles)
     HiddenSingles(Candidate, HouseType),
+    /// Result of [`BugPlusOne`](super::Strategy::BugPlusOne), the candidate outside the bivalue universal grave
+    BugPlusOne(Candidate),
     /// Result of [`LockedCandidates`](super::Strategy::LockedCandidates)
     LockedCandidates {
         digit: Digit,
@@ -103,6 +209,20 @@ pub enum Deduction<T> {
         positions: Set<Position<Line>>,
         conflicts: T,
     },
+    /// Result of [`FinnedXWing`](super::Strategy::FinnedXWing), [`SashimiXWing`](super::Strategy::SashimiXWing),
+    /// [`FinnedSwordfish`](super::Strategy::FinnedSwordfish) or [`SashimiSwordfish`](super::Strategy::SashimiSwordfish)
+    FinnedFish {
+        digit: Digit,
+        /// The base lines of the fish. Can be 2 or 3 lines.
+        lines: Set<Line>,
+        /// The positions of the cover lines. The number of positions is always equal to the number of lines.
+        positions: Set<Position<Line>>,
+        /// The candidates of the base lines outside the cover lines. They always lie in one block.
+        fins: Set<Cell>,
+        /// One of the base lines has only a single candidate in the cover lines
+        is_sashimi: bool,
+        conflicts: T,
+    },
     Fish {
         digit: Digit,
         base: Set<House>,
@@ -119,12 +239,82 @@ pub enum Deduction<T> {
         pincers: Set<Cell>,
         conflicts: T,
     },
+    /// Result of [`Skyscraper`](super::Strategy::Skyscraper)
+    Skyscraper {
+        digit: Digit,
+        /// The 2 parallel lines with only 2 candidates of `digit` each
+        strong_links: [House; 2],
+        /// The ends of the strong links that don't share a line. One of them holds `digit`.
+        ends: Set<Cell>,
+        conflicts: T,
+    },
+    /// Result of [`TwoStringKite`](super::Strategy::TwoStringKite)
+    TwoStringKite {
+        digit: Digit,
+        /// A row and a column with only 2 candidates of `digit` each
+        strong_links: [House; 2],
+        /// The ends of the strong links outside the shared block. One of them holds `digit`.
+        ends: Set<Cell>,
+        conflicts: T,
+    },
+    /// Result of [`TurbotFish`](super::Strategy::TurbotFish)
+    TurbotFish {
+        digit: Digit,
+        /// 2 houses with only 2 candidates of `digit` each
+        strong_links: [House; 2],
+        /// The ends of the strong links that don't see each other. One of them holds `digit`.
+        ends: Set<Cell>,
+        conflicts: T,
+    },
+    /// Result of [`WWing`](super::Strategy::WWing)
+    WWing {
+        /// 2 cells with the same 2 candidates, that don't see each other
+        pincers: Set<Cell>,
+        /// The candidate of the pincers that is linked by `strong_link`
+        link_digit: Digit,
+        /// A house with only 2 candidates of `link_digit`, each seeing one pincer
+        strong_link: House,
+        conflicts: T,
+    },
     AvoidableRectangle {
         /// The 2 rows and 2 columns forming the avoidable rectangle. The cells where they overlap always occupy 2 blocks in one chute.
         lines: Set<Line>,
         conflicts: T,
     },
-    //SinglesChain(T),
+    /// Result of [`SinglesChain`](super::Strategy::SinglesChain)
+    SinglesChain {
+        digit: Digit,
+        /// The cells of the chain, split by color. Exactly one color holds `digit`.
+        colors: [Set<Cell>; 2],
+        /// Either all cells of the color that sees itself or the cells outside the chain that see both colors
+        conflicts: T,
+    },
+    /// Result of [`XChain`](super::Strategy::XChain), [`XyChain`](super::Strategy::XyChain) or
+    /// [`Aic`](super::Strategy::Aic)
+    Aic {
+        chain: Chain,
+        /// The candidates that conflict with both ends of the chain
+        conflicts: T,
+    },
+    /// Result of [`AlsXz`](super::Strategy::AlsXz)
+    AlsXz {
+        /// The cells of the 2 almost locked sets. Each set lies in one house and has one more candidate than cells.
+        sets: [Set<Cell>; 2],
+        /// The digit whose candidates in one set all see its candidates in the other
+        restricted_common: Digit,
+        conflicts: T,
+    },
+    /// Result of [`UniqueRectangleType1`](super::Strategy::UniqueRectangleType1) to
+    /// [`UniqueRectangleType4`](super::Strategy::UniqueRectangleType4)
+    UniqueRectangle {
+        /// The 4 cells in 2 rows, 2 columns and 2 blocks that would form a deadly pattern
+        rectangle: Set<Cell>,
+        /// The 2 digits that could be swapped in the deadly pattern
+        digits: Set<Digit>,
+        /// The type of the unique rectangle, 1-4
+        variant: u8,
+        conflicts: T,
+    },
 }
 
 impl Deduction<&'_ [Candidate]> {
@@ -134,6 +324,7 @@ impl Deduction<&'_ [Candidate]> {
         match *self {
             NakedSingles { .. } => Strategy::NakedSingles,
             HiddenSingles { .. } => Strategy::HiddenSingles,
+            BugPlusOne { .. } => Strategy::BugPlusOne,
             LockedCandidates { .. } => Strategy::LockedCandidates,
             BasicFish { positions, .. } => match positions.len() {
                 2 => Strategy::XWing,
@@ -141,7 +332,25 @@ impl Deduction<&'_ [Candidate]> {
                 4 => Strategy::Jellyfish,
                 _ => unreachable!(),
             },
-            //SinglesChain { .. } => Strategy::SinglesChain,
+            FinnedFish {
+                lines, is_sashimi, ..
+            } => match (is_sashimi, lines.len()) {
+                (false, 2) => Strategy::FinnedXWing,
+                (true, 2) => Strategy::SashimiXWing,
+                (false, 3) => Strategy::FinnedSwordfish,
+                (true, 3) => Strategy::SashimiSwordfish,
+                _ => unreachable!(),
+            },
+            SinglesChain { .. } => Strategy::SinglesChain,
+            Aic { chain, .. } => chain.strategy(),
+            AlsXz { .. } => Strategy::AlsXz,
+            UniqueRectangle { variant, .. } => match variant {
+                1 => Strategy::UniqueRectangleType1,
+                2 => Strategy::UniqueRectangleType2,
+                3 => Strategy::UniqueRectangleType3,
+                4 => Strategy::UniqueRectangleType4,
+                _ => unreachable!(),
+            },
             Subsets {
                 house,
                 positions,
@@ -191,7 +400,11 @@ impl Deduction<&'_ [Candidate]> {
                 3 => Strategy::XyzWing,
                 _ => unreachable!(),
             },
-            AvoidableRectangle { .. } => unimplemented!(),
+            Skyscraper { .. } => Strategy::Skyscraper,
+            TwoStringKite { .. } => Strategy::TwoStringKite,
+            TurbotFish { .. } => Strategy::TurbotFish,
+            WWing { .. } => Strategy::WWing,
+            AvoidableRectangle { .. } => Strategy::AvoidableRectangles,
         }
     }
 }
@@ -205,6 +418,7 @@ impl _Deduction {
         match self {
             NakedSingles(c) => NakedSingles(c),
             HiddenSingles(c, h) => HiddenSingles(c, h),
+            BugPlusOne(c) => BugPlusOne(c),
 
             LockedCandidates {
                 miniline, digit, is_pointing,
@@ -223,6 +437,12 @@ impl _Deduction {
             }
             => BasicFish { lines, positions, digit, conflicts: &eliminated[conflicts]},
 
+            FinnedFish {
+                digit, lines, positions, fins, is_sashimi,
+                conflicts
+            }
+            => FinnedFish { digit, lines, positions, fins, is_sashimi, conflicts: &eliminated[conflicts] },
+
             Fish {
                 digit, base, cover,
                 conflicts,
@@ -235,8 +455,103 @@ impl _Deduction {
             }
             => Wing { hinge, hinge_digits, pincers, conflicts: &eliminated[conflicts] },
 
-            AvoidableRectangle { .. } => unimplemented!(),
-            //SinglesChain(x) => SinglesChain(&eliminated[x]),
+            Skyscraper {
+                digit, strong_links, ends,
+                conflicts
+            }
+            => Skyscraper { digit, strong_links, ends, conflicts: &eliminated[conflicts] },
+
+            TwoStringKite {
+                digit, strong_links, ends,
+                conflicts
+            }
+            => TwoStringKite { digit, strong_links, ends, conflicts: &eliminated[conflicts] },
+
+            TurbotFish {
+                digit, strong_links, ends,
+                conflicts
+            }
+            => TurbotFish { digit, strong_links, ends, conflicts: &eliminated[conflicts] },
+
+            WWing {
+                pincers, link_digit, strong_link,
+                conflicts
+            }
+            => WWing { pincers, link_digit, strong_link, conflicts: &eliminated[conflicts] },
+
+            AvoidableRectangle {
+                lines,
+                conflicts
+            }
+            => AvoidableRectangle { lines, conflicts: &eliminated[conflicts] },
+
+            SinglesChain {
+                digit, colors,
+                conflicts
+            }
+            => SinglesChain { digit, colors, conflicts: &eliminated[conflicts] },
+
+            Aic {
+                chain,
+                conflicts
+            }
+            => Aic { chain, conflicts: &eliminated[conflicts] },
+
+            AlsXz {
+                sets, restricted_common,
+                conflicts
+            }
+            => AlsXz { sets, restricted_common, conflicts: &eliminated[conflicts] },
+
+            UniqueRectangle {
+                rectangle, digits, variant,
+                conflicts
+            }
+            => UniqueRectangle { rectangle, digits, variant, conflicts: &eliminated[conflicts] },
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::StrategySolver;
+    use crate::Sudoku;
+    use near_sdk::serde_json;
+
+    #[test]
+    fn serialize() {
+        assert_eq!(Strategy::NakedSingles as u8, 0);
+        assert_eq!(Strategy::UniqueRectangleType4 as u8, 34);
+
+        let hidden_single: Deduction<&[Candidate]> =
+            Deduction::HiddenSingles(Candidate::new(40, 5), HouseType::Block(Block::new(4)));
+        assert_eq!(
+            serde_json::to_string(&hidden_single).unwrap(),
+            r#"{"HiddenSingles":[{"cell":40,"digit":5},{"Block":4}]}"#
+        );
+
+        let conflicts = [Candidate::new(3, 7), Candidate::new(4, 7)];
+        let subset = Deduction::Subsets {
+            house: House::new(19),
+            positions: Set::from([Position::new(0), Position::new(2)]),
+            digits: Set::from([Digit::new(1), Digit::new(7)]),
+            conflicts: &conflicts[..],
+        };
+        assert_eq!(
+            serde_json::to_string(&subset).unwrap(),
+            concat!(
+                r#"{"Subsets":{"house":19,"positions":[0,2],"digits":[1,7],"#,
+                r#""conflicts":[{"cell":3,"digit":7},{"cell":4,"digit":7}]}}"#,
+            )
+        );
+
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let (_, deductions) = StrategySolver::from_sudoku(sudoku).solve(Strategy::ALL).unwrap();
+        let json = serde_json::to_string(&deductions).unwrap();
+        assert!(json.starts_with("[{\"NakedSingles\":{\"cell\":"));
+        assert_eq!(json.matches("Singles").count(), deductions.len());
+    }
+}
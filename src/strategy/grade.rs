@@ -0,0 +1,181 @@
+//! Difficulty grading based on the strategies needed to solve a sudoku
+
+use super::{Deductions, Strategy};
+use crate::{Difficulty, Sudoku};
+
+/// Score of sudokus that can't be solved with [`Strategy::ALL`], above every strategy rating
+pub const UNSOLVED_SCORE: u32 = 100;
+/// Number of puzzles [`Sudoku::generate_graded`](crate::Sudoku::generate_graded) tries before giving up
+pub const MAX_GRADED_ATTEMPTS: u32 = 100;
+
+/// Difficulty rating of a sudoku, see [`Sudoku::grade_detailed`](crate::Sudoku::grade_detailed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grade {
+    /// Hardest strategy used in the solving path.
+    /// `None` if the strategies got stuck or the sudoku was already solved.
+    pub hardest: Option<Strategy>,
+    /// [`Strategy::rating`] of the hardest strategy or [`UNSOLVED_SCORE`]
+    pub score: u32,
+    pub difficulty: Difficulty,
+}
+
+impl Grade {
+    pub(crate) fn from_deductions(deductions: &Deductions) -> Grade {
+        let hardest = deductions
+            .iter()
+            .map(|deduction| deduction.strategy())
+            .max_by_key(|strategy| strategy.rating());
+        let score = hardest.map_or(0, Strategy::rating);
+        Grade {
+            hardest,
+            score,
+            difficulty: difficulty(score),
+        }
+    }
+
+    pub(crate) fn unsolved() -> Grade {
+        Grade {
+            hardest: None,
+            score: UNSOLVED_SCORE,
+            difficulty: Difficulty::Expert,
+        }
+    }
+}
+
+/// Strategies used for [`Sudoku::difficulty_score`](crate::Sudoku::difficulty_score), in order of application.
+///
+/// This is a frozen copy of [`Strategy::ALL`]. Strategies added later must not change existing scores,
+/// so they aren't appended here.
+#[rustfmt::skip]
+pub(crate) const SCORED_STRATEGIES: &[Strategy] = &[
+    Strategy::NakedSingles,
+    Strategy::HiddenSingles,
+    Strategy::LockedCandidates,
+    Strategy::NakedPairs,
+    Strategy::XWing,
+    Strategy::HiddenPairs,
+    Strategy::FinnedXWing,
+    Strategy::SashimiXWing,
+    Strategy::NakedTriples,
+    Strategy::Swordfish,
+    Strategy::HiddenTriples,
+    Strategy::FinnedSwordfish,
+    Strategy::Skyscraper,
+    Strategy::SashimiSwordfish,
+    Strategy::TwoStringKite,
+    Strategy::TurbotFish,
+    Strategy::XyWing,
+    Strategy::XyzWing,
+    Strategy::WWing,
+    Strategy::SinglesChain,
+    Strategy::UniqueRectangleType1,
+    Strategy::UniqueRectangleType2,
+    Strategy::UniqueRectangleType4,
+    Strategy::UniqueRectangleType3,
+    Strategy::NakedQuads,
+    Strategy::Jellyfish,
+    Strategy::HiddenQuads,
+    Strategy::BugPlusOne,
+    Strategy::XChain,
+    Strategy::XyChain,
+    Strategy::Aic,
+    Strategy::AlsXz,
+];
+
+/// Sum of the [`Strategy::rating`]s of all `deductions` plus [`UNSOLVED_SCORE`] for each of the `n_unsolved` cells
+/// the strategies couldn't solve
+pub(crate) fn difficulty_score(deductions: &Deductions, n_unsolved: u32) -> u32 {
+    let path_score: u32 = deductions.iter().map(|deduction| deduction.strategy().rating()).sum();
+    path_score + n_unsolved * UNSOLVED_SCORE
+}
+
+/// Result of [`Sudoku::generate_graded`](crate::Sudoku::generate_graded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GradedGeneration {
+    /// The first puzzle of the target difficulty or, if none was found, the first one closest to it
+    pub sudoku: Sudoku,
+    pub grade: Grade,
+    /// Number of puzzles generated and graded
+    pub attempts: u32,
+    /// Number of generated puzzles per difficulty, in the order of [`Difficulty::ALL`]
+    pub attempts_by_difficulty: [u32; 4],
+}
+
+/// Singles are easy, the simplest eliminations medium and everything up to the wings hard.
+fn difficulty(score: u32) -> Difficulty {
+    match score {
+        0..=23 => Difficulty::Easy,
+        24..=30 => Difficulty::Medium,
+        31..=44 => Difficulty::Hard,
+        _ => Difficulty::Expert,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Sudoku;
+    use crate::strategy::Strategy;
+    use crate::Difficulty;
+
+    #[test]
+    fn generate_graded() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::from_seed([9; 32]);
+        for &target in &[Difficulty::Easy, Difficulty::Hard] {
+            let generation = Sudoku::generate_graded(&mut rng, target).unwrap();
+            assert_eq!(generation.grade.difficulty, target);
+            assert_eq!(generation.sudoku.grade_detailed(), generation.grade);
+            assert_eq!(generation.attempts_by_difficulty.iter().sum::<u32>(), generation.attempts);
+            assert_eq!(generation.attempts_by_difficulty[target as usize], 1);
+        }
+    }
+
+    #[test]
+    fn grade_singles() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let grade = sudoku.grade_detailed();
+        assert_eq!(grade.difficulty, Difficulty::Easy);
+        assert!(matches!(grade.hardest, Some(Strategy::NakedSingles | Strategy::HiddenSingles)));
+        assert_eq!(sudoku.grade(), Difficulty::Easy);
+    }
+
+    #[test]
+    fn scored_strategies_are_available() {
+        use super::SCORED_STRATEGIES;
+        // removing a strategy would change the scores
+        assert!(SCORED_STRATEGIES.iter().all(|strategy| Strategy::ALL.contains(strategy)));
+    }
+
+    #[test]
+    fn difficulty_score() {
+        let easy =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let xy_wing =
+            Sudoku::from_str_line("9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9")
+                .unwrap();
+        let easy_score = easy.difficulty_score();
+        let n_empty = 81 - easy.n_clues() as u32;
+        // every empty cell is filled by a single
+        assert!(easy_score >= n_empty * Strategy::HiddenSingles.rating());
+        assert!(easy_score <= n_empty * Strategy::NakedSingles.rating());
+        assert_eq!(easy.difficulty_score(), easy_score);
+
+        assert!(xy_wing.difficulty_score() > xy_wing.grade_detailed().score);
+        assert_eq!(easy.solution().unwrap().difficulty_score(), 0);
+    }
+
+    // sudoku taken from http://www.sudokuwiki.org/Y_Wing_Strategy, Example 1 (2019-03-18)
+    #[test]
+    fn grade_xy_wing() {
+        let sudoku =
+            Sudoku::from_str_line("9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9")
+                .unwrap();
+        let grade = sudoku.grade_detailed();
+        assert!(grade.score >= Strategy::XyWing.rating());
+        assert_ne!(grade.difficulty, Difficulty::Easy);
+    }
+}
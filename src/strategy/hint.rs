@@ -0,0 +1,449 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use near_sdk::serde::{Deserialize, Serialize};
+
+use super::{Deduction, Strategy, StrategySolver};
+use crate::bitset::Set;
+use crate::board::positions::{HouseType, LineType, MiniLineType};
+use crate::board::{Candidate, Cell, CellState, Digit, GridState, House};
+use crate::Sudoku;
+
+/// An explained next step, found by [`Sudoku::next_hint`]
+///
+/// Cells are given as indices 0-80 in row-major order and houses as indices 0-26: rows 0-8, columns 9-17
+/// and blocks 18-26. Digits are 1-9.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
+pub struct Hint {
+    /// The strategy that finds the step
+    pub strategy: Strategy,
+    /// The cells that form the pattern of the strategy
+    pub cells: Vec<u8>,
+    /// The houses that form the pattern of the strategy
+    pub houses: Vec<u8>,
+    /// The digit that can be entered, as `(cell, digit)`
+    pub placement: Option<(u8, u8)>,
+    /// The candidates that can be removed, as `(cell, digit)` pairs
+    pub eliminations: Vec<(u8, u8)>,
+    /// Explanation of the step in English
+    pub explanation: String,
+}
+
+impl Hint {
+    // Finds the first deduction of the enabled strategies of `solver`, trying them from easiest to hardest
+    pub(crate) fn find(mut solver: StrategySolver) -> Option<Hint> {
+        for strategy in solver.strategies.clone() {
+            strategy.deduce_one(&mut solver).ok()?;
+            if !solver.deductions.is_empty() {
+                let deductions = solver.into_deductions();
+                return deductions.get(0).map(|deduction| Hint::from_deduction(deduction, strategy));
+            }
+        }
+        None
+    }
+
+    fn from_deduction(deduction: Deduction<&[Candidate]>, strategy: Strategy) -> Hint {
+        use self::Deduction::*;
+        // avoidable rectangles are only identifiable by the strategy that found them
+        let strategy = match deduction {
+            AvoidableRectangle { .. } => strategy,
+            _ => deduction.strategy(),
+        };
+
+        let (cells, houses, placement, conflicts, explanation): (Set<Cell>, Vec<House>, _, &[Candidate], _) =
+            match deduction {
+                NakedSingles(candidate) => (
+                    candidate.cell.as_set(),
+                    vec![],
+                    Some(candidate),
+                    &[],
+                    format!("{} has only one candidate left, {}.", cell_name(candidate.cell), candidate.digit.get()),
+                ),
+                HiddenSingles(candidate, house_type) => {
+                    let house = match house_type {
+                        HouseType::Row(row) => row.into(),
+                        HouseType::Col(col) => col.into(),
+                        HouseType::Block(block) => block.into(),
+                    };
+                    let explanation = format!(
+                        "In {}, {} can only go in {}.",
+                        house_name(house),
+                        candidate.digit.get(),
+                        cell_name(candidate.cell),
+                    );
+                    (candidate.cell.as_set(), vec![house], Some(candidate), &[], explanation)
+                }
+                BugPlusOne(candidate) => {
+                    let explanation = format!(
+                        "All unsolved cells except {0} have exactly 2 candidates. If {0} weren't {1}, \
+                         the sudoku would have multiple solutions.",
+                        cell_name(candidate.cell),
+                        candidate.digit.get(),
+                    );
+                    (candidate.cell.as_set(), vec![], Some(candidate), &[], explanation)
+                }
+                LockedCandidates {
+                    digit,
+                    miniline,
+                    is_pointing,
+                    conflicts,
+                } => {
+                    let first_cell = miniline.cells().into_iter().next().unwrap();
+                    let line: House = match miniline.categorize() {
+                        MiniLineType::MiniRow(_) => first_cell.row().into(),
+                        MiniLineType::MiniCol(_) => first_cell.col().into(),
+                    };
+                    let block: House = first_cell.block().into();
+                    let (confining, confined_to) = match is_pointing {
+                        true => (block, line),
+                        false => (line, block),
+                    };
+                    let explanation = format!(
+                        "In {}, {} can only go in the cells it shares with {}, so it can't be anywhere else in {}.",
+                        house_name(confining),
+                        digit.get(),
+                        house_name(confined_to),
+                        house_name(confined_to),
+                    );
+                    (miniline.cells(), vec![confining, confined_to], None, conflicts, explanation)
+                }
+                Subsets {
+                    house,
+                    positions,
+                    digits,
+                    conflicts,
+                } => {
+                    use crate::board::CellAt;
+                    let cells = house.cells_at(positions);
+                    let explanation = match strategy {
+                        Strategy::NakedPairs | Strategy::NakedTriples | Strategy::NakedQuads => format!(
+                            "The cells {} of {} can only hold the digits {}, so these digits can't be anywhere else \
+                             in {}.",
+                            cells_name(cells),
+                            house_name(house),
+                            digits_name(digits),
+                            house_name(house),
+                        ),
+                        _ => format!(
+                            "In {}, the digits {} can only go in the cells {}, so these cells can't hold any other \
+                             digit.",
+                            house_name(house),
+                            digits_name(digits),
+                            cells_name(cells),
+                        ),
+                    };
+                    (cells, vec![house], None, conflicts, explanation)
+                }
+                BasicFish {
+                    digit,
+                    lines,
+                    positions,
+                    conflicts,
+                }
+                | FinnedFish {
+                    digit,
+                    lines,
+                    positions,
+                    conflicts,
+                    ..
+                } => {
+                    use crate::board::CellAt;
+                    let base: Vec<House> = lines.into_iter().map(House::from).collect();
+                    let mut cells = lines.into_iter().fold(Set::NONE, |cells, line| cells | line.cells_at(positions));
+                    // the cover lines cross the base lines at the positions
+                    let first_line = lines.into_iter().next().unwrap();
+                    let cover: Vec<House> = first_line
+                        .cells_at(positions)
+                        .into_iter()
+                        .map(|cell| match first_line.categorize() {
+                            LineType::Row(_) => cell.col().into(),
+                            LineType::Col(_) => cell.row().into(),
+                        })
+                        .collect();
+                    let mut explanation = fish_explanation(digit, &base, &cover);
+                    if let FinnedFish { fins, .. } = deduction {
+                        cells |= fins;
+                        explanation += &format!(
+                            " The fins {} also hold {} possibly, only cells seeing them are affected.",
+                            cells_name(fins),
+                            digit.get(),
+                        );
+                    }
+                    (cells, [base, cover].concat(), None, conflicts, explanation)
+                }
+                Fish {
+                    digit,
+                    base,
+                    cover,
+                    conflicts,
+                } => {
+                    let cells_of =
+                        |houses: Set<House>| houses.into_iter().fold(Set::NONE, |cells, house| cells | house.cells());
+                    let cells = cells_of(base) & cells_of(cover);
+                    let (base, cover): (Vec<_>, Vec<_>) = (base.into_iter().collect(), cover.into_iter().collect());
+                    let explanation = fish_explanation(digit, &base, &cover);
+                    (cells, [base, cover].concat(), None, conflicts, explanation)
+                }
+                Wing {
+                    hinge,
+                    hinge_digits,
+                    pincers,
+                    conflicts,
+                } => {
+                    let explanation = format!(
+                        "{} with the candidates {} and the pincers {} form a wing, so one of them holds {} and cells \
+                         seeing all of them can't.",
+                        cell_name(hinge),
+                        digits_name(hinge_digits),
+                        cells_name(pincers),
+                        conflicts[0].digit.get(),
+                    );
+                    (hinge.as_set() | pincers, vec![], None, conflicts, explanation)
+                }
+                Skyscraper {
+                    digit,
+                    strong_links,
+                    ends,
+                    conflicts,
+                }
+                | TwoStringKite {
+                    digit,
+                    strong_links,
+                    ends,
+                    conflicts,
+                }
+                | TurbotFish {
+                    digit,
+                    strong_links,
+                    ends,
+                    conflicts,
+                } => {
+                    let explanation = format!(
+                        "{} can only go in 2 cells in each of {} and {}, which are connected, so one of the ends {} \
+                         holds {} and cells seeing both can't.",
+                        digit.get(),
+                        house_name(strong_links[0]),
+                        house_name(strong_links[1]),
+                        cells_name(ends),
+                        digit.get(),
+                    );
+                    (ends, strong_links.to_vec(), None, conflicts, explanation)
+                }
+                WWing {
+                    pincers,
+                    link_digit,
+                    strong_link,
+                    conflicts,
+                } => {
+                    let explanation = format!(
+                        "The cells {} have the same 2 candidates and {} connects them by {}, so one of them holds {} \
+                         and cells seeing both can't.",
+                        cells_name(pincers),
+                        house_name(strong_link),
+                        link_digit.get(),
+                        conflicts[0].digit.get(),
+                    );
+                    (pincers, vec![strong_link], None, conflicts, explanation)
+                }
+                AvoidableRectangle { lines, conflicts } => {
+                    let houses: Vec<House> = lines.into_iter().map(House::from).collect();
+                    let cells = houses[..2]
+                        .iter()
+                        .fold(Set::NONE, |cells, &house| cells | house.cells())
+                        & houses[2..].iter().fold(Set::NONE, |cells, &house| cells | house.cells());
+                    let explanation = format!(
+                        "The cells {} would form a deadly pattern with 2 solutions, so {} can't be there.",
+                        cells_name(cells),
+                        candidates_name(conflicts),
+                    );
+                    (cells, houses, None, conflicts, explanation)
+                }
+                SinglesChain {
+                    digit,
+                    colors,
+                    conflicts,
+                } => {
+                    let explanation = format!(
+                        "The cells {} and {} are linked by the only 2 candidates of {} in their houses. \
+                         One color holds {}, so {} can't.",
+                        cells_name(colors[0]),
+                        cells_name(colors[1]),
+                        digit.get(),
+                        digit.get(),
+                        candidates_name(conflicts),
+                    );
+                    (colors[0] | colors[1], vec![], None, conflicts, explanation)
+                }
+                Aic { chain, conflicts } => {
+                    let nodes = chain.nodes();
+                    let explanation = format!(
+                        "The chain {} ensures that {} or {} is true, so {} can't be.",
+                        candidates_name(nodes),
+                        candidate_name(nodes[0]),
+                        candidate_name(nodes[nodes.len() - 1]),
+                        candidates_name(conflicts),
+                    );
+                    let cells = nodes.iter().fold(Set::NONE, |cells, node| cells | node.cell);
+                    (cells, vec![], None, conflicts, explanation)
+                }
+                AlsXz {
+                    sets,
+                    restricted_common,
+                    conflicts,
+                } => {
+                    let explanation = format!(
+                        "The almost locked sets {} and {} share the restricted common digit {}, \
+                         so one of them holds {} and cells seeing all its candidates can't.",
+                        cells_name(sets[0]),
+                        cells_name(sets[1]),
+                        restricted_common.get(),
+                        conflicts[0].digit.get(),
+                    );
+                    (sets[0] | sets[1], vec![], None, conflicts, explanation)
+                }
+                UniqueRectangle {
+                    rectangle,
+                    digits,
+                    conflicts,
+                    ..
+                } => {
+                    let explanation = format!(
+                        "If the cells {} only held {}, the sudoku would have 2 solutions, so {} can't be true.",
+                        cells_name(rectangle),
+                        digits_name(digits),
+                        candidates_name(conflicts),
+                    );
+                    (rectangle, vec![], None, conflicts, explanation)
+                }
+            };
+
+        Hint {
+            strategy,
+            cells: cells.into_iter().map(Cell::get).collect(),
+            houses: houses.into_iter().map(House::get).collect(),
+            placement: placement.map(|Candidate { cell, digit }| (cell.get(), digit.get())),
+            eliminations: conflicts
+                .iter()
+                .map(|&Candidate { cell, digit }| (cell.get(), digit.get()))
+                .collect(),
+            explanation,
+        }
+    }
+}
+
+fn fish_explanation(digit: Digit, base: &[House], cover: &[House]) -> String {
+    let houses_name = |houses: &[House]| houses.iter().map(|&house| house_name(house)).collect::<Vec<_>>().join(", ");
+    format!(
+        "In {}, {} can only go in cells of {}, so it can't be anywhere else in those.",
+        houses_name(base),
+        digit.get(),
+        houses_name(cover),
+    )
+}
+
+fn cell_name(cell: Cell) -> String {
+    format!("r{}c{}", cell.row().get() + 1, cell.col().get() + 1)
+}
+
+fn cells_name(cells: Set<Cell>) -> String {
+    cells.into_iter().map(cell_name).collect::<Vec<_>>().join(", ")
+}
+
+fn house_name(house: House) -> String {
+    match house.categorize() {
+        HouseType::Row(row) => format!("row {}", row.get() + 1),
+        HouseType::Col(col) => format!("column {}", col.get() + 1),
+        HouseType::Block(block) => format!("block {}", block.get() + 1),
+    }
+}
+
+fn digits_name(digits: Set<Digit>) -> String {
+    digits.into_iter().map(|digit| digit.get().to_string()).collect::<Vec<_>>().join("")
+}
+
+fn candidate_name(candidate: Candidate) -> String {
+    format!("{}{}", candidate.digit.get(), cell_name(candidate.cell))
+}
+
+fn candidates_name(candidates: &[Candidate]) -> String {
+    candidates.iter().map(|&candidate| candidate_name(candidate)).collect::<Vec<_>>().join(", ")
+}
+
+// Whether `state` agrees with `solution`, i.e. all entered digits are right and no right digit was eliminated
+pub(crate) fn is_consistent(state: &GridState, solution: Sudoku) -> bool {
+    state.cell_states().iter().zip(solution.iter()).all(|(&cell_state, solution_digit)| {
+        let solution_digit = Digit::new(solution_digit.unwrap());
+        match cell_state {
+            CellState::Digit(digit) => digit == solution_digit,
+            CellState::Candidates(candidates) => candidates.contains(solution_digit),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use near_sdk::serde_json;
+
+    #[test]
+    fn next_hint() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let solution = sudoku.solution().unwrap();
+        let hint = sudoku.next_hint(&GridState::from(sudoku.candidates())).unwrap();
+
+        assert_eq!(hint.strategy, Strategy::NakedSingles);
+        let (cell, digit) = hint.placement.unwrap();
+        assert_eq!(solution.0[cell as usize], digit);
+        assert_eq!(hint.cells, [cell]);
+        assert!(hint.eliminations.is_empty());
+        assert!(hint.explanation.contains(&format!("only one candidate left, {}", digit)));
+
+        let json = serde_json::to_string(&hint).unwrap();
+        assert!(json.contains("\"strategy\":\"NakedSingles\""));
+    }
+
+    #[test]
+    fn next_hint_eliminations() {
+        // singles get stuck on this sudoku
+        let sudoku =
+            Sudoku::from_str_line("9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9")
+                .unwrap();
+        let solution = sudoku.solution().unwrap();
+        let state = GridState::from(sudoku.candidates());
+        let hint = sudoku.next_hint(&state).unwrap();
+
+        assert!(hint.placement.is_none());
+        assert!(!hint.eliminations.is_empty());
+        for &(cell, digit) in &hint.eliminations {
+            assert_ne!(solution.0[cell as usize], digit);
+        }
+        assert_eq!(hint.strategy, Strategy::LockedCandidates);
+        assert_eq!(hint.cells, [9, 10, 11]);
+        assert_eq!(hint.houses, [1, 18]);
+        assert_eq!(
+            hint.explanation,
+            "In row 2, 4 can only go in the cells it shares with block 1, so it can't be anywhere else in block 1."
+        );
+    }
+
+    #[test]
+    fn next_hint_wrong_state() {
+        let sudoku =
+            Sudoku::from_str_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79")
+                .unwrap();
+        let mut cell_states = GridState::from(sudoku.candidates()).cell_states();
+        let solution = sudoku.solution().unwrap();
+        // enter a wrong digit into the first empty cell
+        let wrong_digit = solution.0[2] % 9 + 1;
+        cell_states[2] = CellState::Digit(Digit::new(wrong_digit));
+        assert_eq!(sudoku.next_hint(&GridState::from(cell_states)), None);
+    }
+}
@@ -1,14 +1,17 @@
+use alloc::{vec, vec::Vec};
 use crate::bitset::Set;
 use crate::board::Candidate;
 use crate::board::*;
+use crate::errors::GridStateParseError;
 use crate::helper::{CellArray, DigitArray, HouseArray, Unsolvable};
 use crate::strategy::{
-    deduction::{Deduction, Deductions},
+    deduction::{Chain, Deduction, Deductions},
     strategies::*,
+    SolverConfig,
 };
 use crate::Sudoku;
 
-type EliminationsRange = std::ops::Range<usize>;
+type EliminationsRange = core::ops::Range<usize>;
 type _Deduction = Deduction<EliminationsRange>;
 
 /// The `StrategySolver` is the struct for solving sudokus with
@@ -44,6 +47,10 @@ pub struct StrategySolver {
     // optimization hints for strategies
     pub(crate) hidden_singles_last_house: u8,
 
+    pub(crate) config: SolverConfig,
+    // strategies used by `solve_enabled`, in order of application
+    pub(crate) strategies: Vec<Strategy>,
+
     // The initial state of a sudoku given as a puzzle.
     // If the solution is unique, this can be used for the strategy of
     // AvoidableRectangles
@@ -71,6 +78,8 @@ impl StrategySolver {
             eliminated_entries: vec![],
             n_solved: 0,
             hidden_singles_last_house: 0,
+            config: SolverConfig::default(),
+            strategies: Strategy::ALL.to_vec(),
             clues: None,
             grid: State::from(Sudoku([0; 81])),
             cell_poss_digits: State::from(CellArray([Set::ALL; 81])),
@@ -102,6 +111,30 @@ impl StrategySolver {
         }
     }
 
+    /// Replace the settings of the more expensive strategies.
+    pub fn with_config(self, config: SolverConfig) -> StrategySolver {
+        StrategySolver { config, ..self }
+    }
+
+    /// Returns the settings of the more expensive strategies.
+    pub fn config(&self) -> SolverConfig {
+        self.config
+    }
+
+    /// Replace the strategies used by [`solve_enabled`](Self::solve_enabled). They are applied in the given order,
+    /// the solver always returns to the first one after any progress. By default, [`Strategy::ALL`] is used.
+    pub fn with_strategies(self, strategies: &[Strategy]) -> StrategySolver {
+        StrategySolver {
+            strategies: strategies.to_vec(),
+            ..self
+        }
+    }
+
+    /// Returns the strategies used by [`solve_enabled`](Self::solve_enabled) in order of application.
+    pub fn strategies(&self) -> &[Strategy] {
+        &self.strategies
+    }
+
     /// Construct a new StrategySolver from an array of [`CellState`s](crate::board::CellState).
     /// This allows communicating the impossibility of some candidates, that aren't already
     /// trivially conflicting with entries. The cell order in the array is the same as for
@@ -128,33 +161,12 @@ impl StrategySolver {
         }
     }
 
-    /// Construct a new StrategySolver from a printout of cell candidates.
+    /// Construct a new StrategySolver from a printout of cell candidates in the pencil-marked block format,
+    /// see [`GridState::from_str_block`](crate::board::GridState::from_str_block).
     /// This allows communicating the impossibility of some candidates, that aren't already
-    /// trivially conflicting with entries.
-    #[allow(unused)] // it is used, but only in conditionally compiled tests
-    pub(crate) fn from_grid_state_str(grid_state: &str) -> StrategySolver {
-        let mut _grid_state = [CellState::Candidates(Set::NONE); 81];
-        let entries = grid_state
-            .lines()
-            .flat_map(str::split_whitespace)
-            .filter(|&entry| entry == "_" || entry.parse::<u32>().is_ok());
-
-        for (cell_state, entry) in _grid_state.iter_mut().zip(entries) {
-            let candidates = entry
-                .as_bytes()
-                .iter()
-                .map(|byte| byte - b'0')
-                // only ascii bytes 1-9 will pass Digit::new_checked
-                .filter_map(Digit::new_checked)
-                .fold(Set::NONE, std::ops::BitOr::bitor);
-            let state = match candidates.unique().unwrap_or(None) {
-                Some(digit) => CellState::Digit(digit),
-                None => CellState::Candidates(candidates),
-            };
-            *cell_state = state;
-        }
-
-        Self::from_grid_state(_grid_state)
+    /// trivially conflicting with entries. The `Display` output of the solver can be read back by this.
+    pub fn from_grid_state_str(grid_state: &str) -> Result<StrategySolver, GridStateParseError> {
+        GridState::from_str_block(grid_state).map(|grid_state| Self::from_grid_state(grid_state.cell_states()))
     }
 
     /// Returns the current state of the Sudoku
@@ -204,6 +216,7 @@ impl StrategySolver {
     }
 
     /// Try to insert the given candidate. Fails, if the cell already contains a digit.
+    #[allow(clippy::result_unit_err)]
     pub fn insert_candidate(&mut self, candidate: Candidate) -> Result<(), ()> {
         self.update_grid();
         Self::push_new_candidate(
@@ -221,7 +234,7 @@ impl StrategySolver {
     }
 
     #[rustfmt::skip]
-    fn into_deductions(self) -> Deductions {
+    pub(crate) fn into_deductions(self) -> Deductions {
         let Self { deductions, deduced_entries, eliminated_entries, .. } = self;
         Deductions { deductions, deduced_entries, eliminated_entries }
     }
@@ -234,6 +247,7 @@ impl StrategySolver {
 
     /// Try to solve the sudoku using the given `strategies`. Returns a `Result` of the sudoku and a struct containing the series of deductions.
     /// If a solution was found, `Ok(..)` is returned, otherwise `Err(..)`.
+    #[allow(clippy::result_large_err)]
     pub fn solve(mut self, strategies: &[Strategy]) -> Result<(Sudoku, Deductions), (Sudoku, Deductions)> {
         self.try_solve(strategies);
         self.update_grid();
@@ -243,6 +257,14 @@ impl StrategySolver {
         }
     }
 
+    /// Try to solve the sudoku using the strategies set by [`with_strategies`](Self::with_strategies).
+    /// Returns the same as [`solve`](Self::solve).
+    #[allow(clippy::result_large_err)]
+    pub fn solve_enabled(mut self) -> Result<(Sudoku, Deductions), (Sudoku, Deductions)> {
+        let strategies = core::mem::take(&mut self.strategies);
+        self.solve(&strategies)
+    }
+
     // FIXME: change name
     /// Try to solve the sudoku using the given `strategies`. Returns `true` if new deductions were made.
     fn try_solve(&mut self, strategies: &[Strategy]) -> bool {
@@ -602,13 +624,13 @@ impl StrategySolver {
         deduced_entries: &mut Vec<Candidate>,
         candidate: Candidate,
         deductions: &mut Vec<_Deduction>,
-        strategy: _Deduction, // either a user-given or naked or hidden singles
+        strategy: _Deduction, // either a user-given, naked or hidden single or BUG+1
     ) -> Result<(), Unsolvable> {
         #[cfg(debug_assertions)]
         {
             use self::Deduction::*;
             match strategy {
-                NakedSingles(..) | HiddenSingles(..) => (),
+                NakedSingles(..) | HiddenSingles(..) | BugPlusOne(..) => (),
                 _ => panic!("Internal error: Called push_new_candidate with wrong strategy type"),
             };
         }
@@ -703,6 +725,28 @@ impl StrategySolver {
         self.update_cell_poss_house_solved()
     }
 
+    pub(crate) fn find_bug_plus_one(&mut self) -> Result<(), Unsolvable> {
+        if !self.config.assume_unique_solution {
+            return Ok(());
+        }
+        self.update_cell_poss_house_solved()?;
+
+        {
+            let cell_poss_digits = &self.cell_poss_digits.state;
+            let grid = &mut self.grid.state;
+            let deduced_entries = &mut self.deduced_entries;
+            let deductions = &mut self.deductions;
+
+            bug_plus_one::find_bug_plus_one(cell_poss_digits, |candidate| {
+                let deduction = Deduction::BugPlusOne(candidate);
+                Self::push_new_candidate(grid, deduced_entries, candidate, deductions, deduction)
+            })?;
+        }
+
+        // call update again so newly found entries are inserted
+        self.update_cell_poss_house_solved()
+    }
+
     // stop after first will only eliminate line OR field neighbors for ONE number
     // even if multiple are found at the same time
     pub(crate) fn find_locked_candidates(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
@@ -712,7 +756,7 @@ impl StrategySolver {
         let deductions = &mut self.deductions;
 
         locked_candidates::find_locked_candidates(
-            &cell_poss_digits,
+            cell_poss_digits,
             stop_after_first,
             |miniline, digit, _miniline_cands, neighbors, is_pointing| {
                 let conflicts = neighbors
@@ -858,6 +902,49 @@ impl StrategySolver {
         )
     }
 
+    pub(crate) fn find_finned_fish(
+        &mut self,
+        target_size: u8,
+        sashimi: bool,
+        stop_after_first: bool,
+    ) -> Result<(), Unsolvable> {
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
+
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
+
+        finned_fish::find_finned_fish(
+            house_poss_positions,
+            target_size,
+            sashimi,
+            stop_after_first,
+            |all_lines, digit, lines, positions_in_line, fins| {
+                // the fins are all in one block, the eliminations have to see it
+                let fin_block = fins.one_possibility().block();
+                let conflicts = all_lines
+                    .without(lines)
+                    .into_iter()
+                    .flat_map(|line| positions_in_line.into_iter().map(move |pos| line.cell_at(pos)))
+                    .filter(|&cell| cell.block() == fin_block && cell_poss_digits[cell].contains(digit))
+                    .map(|cell| Candidate { cell, digit });
+
+                let on_conflict = |conflicts| Deduction::FinnedFish {
+                    digit,
+                    lines,
+                    positions: positions_in_line,
+                    fins,
+                    is_sashimi: sashimi,
+                    conflicts,
+                };
+
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
+    }
+
     pub(crate) fn find_mutant_fish(
         &mut self,
         target_size: u8,
@@ -879,7 +966,7 @@ impl StrategySolver {
                 let cover_cells = cover
                     .into_iter()
                     .map(House::cells)
-                    .fold(Set::NONE, std::ops::BitOr::bitor);
+                    .fold(Set::NONE, core::ops::BitOr::bitor);
 
                 let impossible_cells: Set<Cell> = cover_cells ^ candidate_cells;
 
@@ -971,146 +1058,236 @@ impl StrategySolver {
         )
     }
 
-    /*
-    pub(crate) fn find_singles_chain(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
-        #[derive(Copy, Clone, PartialEq, Eq)]
-        enum Color {
-            A,
-            B,
-        }
+    pub(crate) fn find_single_digit_pattern(
+        &mut self,
+        strategy: &Strategy,
+        stop_after_first: bool,
+    ) -> Result<(), Unsolvable> {
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
 
-        /// Recursively visit all cells connected by being the only 2 possible candidates in a house.
-        /// mark all visited cells
-        fn follow_links(digit: Digit, cell: Cell, is_a: bool, sudoku: &StrategySolver, cell_color: &mut CellArray<Option<Color>>, link_nr: u8, cell_linked: &mut CellArray<u8>) {
-            if cell_linked[cell] <= link_nr { return }
-
-            for &(con_house, current_pos) in &[
-                (cell.row().house(), cell.row_pos()),
-                (cell.col().house(), cell.col_pos()),
-                (cell.block().house(), cell.block_pos()),
-            ] {
-                let house_poss_positions = sudoku.house_poss_positions.state[con_house][digit];
-                if house_poss_positions.len() == 2 {
-                    let other_pos = house_poss_positions.without(current_pos.as_set()).one_possibility();
-                    let other_cell = con_house.cell_at(other_pos);
-
-                    match cell_linked[other_cell] <= link_nr {
-                        true => continue,
-                        false => cell_linked[other_cell] = link_nr,
-                    };
-
-                    cell_color[other_cell] = if is_a { Some(Color::A) } else { Some(Color::B) };
-
-                    follow_links(digit, other_cell, !is_a, sudoku, cell_color, link_nr, cell_linked);
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
+
+        single_digit_patterns::find_single_digit_patterns(
+            house_poss_positions,
+            stop_after_first,
+            |digit, strong_links, connected, [free1, free2]| {
+                let pattern = single_digit_patterns::classify(strong_links, connected);
+                if pattern != *strategy {
+                    return false;
                 }
-            }
-        }
 
-        for digit in Set::<Digit>::ALL {
-            let mut link_nr = 0;
+                let ends = free1.as_set() | free2;
+                let conflicts = (free1.neighbors_set() & free2.neighbors_set())
+                    .into_iter()
+                    .filter(|&cell| cell_poss_digits[cell].contains(digit))
+                    .map(|cell| Candidate { cell, digit });
 
-            let mut cell_linked = CellArray([0; 81]);
-            let mut cell_color = CellArray([None; 81]);
+                let on_conflict = |conflicts| match pattern {
+                    Strategy::Skyscraper => Deduction::Skyscraper {
+                        digit,
+                        strong_links,
+                        ends,
+                        conflicts,
+                    },
+                    Strategy::TwoStringKite => Deduction::TwoStringKite {
+                        digit,
+                        strong_links,
+                        ends,
+                        conflicts,
+                    },
+                    _ => Deduction::TurbotFish {
+                        digit,
+                        strong_links,
+                        ends,
+                        conflicts,
+                    },
+                };
 
-            for house in House::all() {
-                let house_poss_positions = self.house_poss_positions.state[house][digit];
-                if house_poss_positions.len() == 2 {
-                    let first = house_poss_positions.one_possibility();
-                    let cell = house.cell_at(first);
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
+    }
 
-                    if cell_color[cell].is_none() {
-                        follow_links(digit, cell, true, self, &mut cell_color, link_nr, &mut cell_linked);
-                        link_nr += 1;
-                    };
-                }
-            }
+    pub(crate) fn find_w_wing(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
 
-            for link_nr in 0..link_nr {
-                // Rule 1:
-                // if two cells in the same row, part of the same chain
-                // have the same color, those cells must not contain the number
-                // Rule 2:
-                // if one cell is neighbor to two cells with opposite colors
-                // it can not contain the number
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
 
+        w_wing::find_w_wing(
+            cell_poss_digits,
+            house_poss_positions,
+            stop_after_first,
+            |[pincer1, pincer2], link_digit, digit, strong_link| {
+                let conflicts = (pincer1.neighbors_set() & pincer2.neighbors_set())
+                    .into_iter()
+                    .filter(|&cell| cell_poss_digits[cell].contains(digit))
+                    .map(|cell| Candidate { cell, digit });
 
-                // ===== Rule 1 ======
-                for house in House::all() {
-                    // Collect colors in this link chain and this house
-                    let mut house_colors = [None; 9];
-                    for (pos, cell) in house.cells()
-                        .into_iter()
-                        .enumerate()
-                        // TODO: Double check the logic here
-                        // this used to take the pos for indexing
-                        .filter(|&(_, cell)| cell_linked[cell] == link_nr)
-                    {
-                        house_colors[pos] = cell_color[cell];
-                    }
+                let on_conflict = |conflicts| Deduction::WWing {
+                    pincers: pincer1.as_set() | pincer2,
+                    link_digit,
+                    strong_link,
+                    conflicts,
+                };
 
-                    let (n_a, n_b) = house_colors.iter()
-                        .fold((0, 0), |(n_a, n_b), &color| {
-                            match color {
-                                Some(Color::A) => (n_a+1, n_b),
-                                Some(Color::B) => (n_a, n_b+1),
-                                None => (n_a, n_b),
-                            }
-                        });
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
+    }
 
-                    fn mark_impossible(digit: Digit, link_nr: u8, color: Color, cell_color: CellArray<Option<Color>>, cell_linked: CellArray<u8>, impossible_entries: &mut Vec<Candidate>) {
-                        Cell::all().zip(cell_color.iter()).zip(cell_linked.iter())
-                            .filter(|&((_, &cell_color), &cell_link_nr)| link_nr == cell_link_nr && Some(color) == cell_color)
-                            .for_each(|((cell, _), _)| impossible_entries.push( Candidate { cell, digit }));
-                    }
+    pub(crate) fn find_singles_chain(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
 
-                    let impossible_color;
-                    match (n_a >= 2, n_b >= 2) {
-                        (true, true) => return Err(Unsolvable),
-                        (true, false) => impossible_color = Color::A,
-                        (false, true) => impossible_color = Color::B,
-                        (false, false) => continue,
-                    };
-                    mark_impossible(digit, link_nr, impossible_color, cell_color, cell_linked, &mut self.eliminated_entries);
-                    // chain handled, go to next
-                    // note: as this eagerly marks a color impossible as soon as a double in any color is found
-                    //       a case of two doubles in some later house will not always be found
-                    //       impossibility is then detected further down the strategy chain
-                    break
-                }
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
 
-                // ===== Rule 2 =====
-                let mut cell_sees_color = CellArray([(false, false); 81]);
-                for ((cell, &cell_color), _) in Cell::all()
-                    .zip(cell_color.iter())
-                    .zip(cell_linked.iter())
-                    .filter(|&((_, &cell_color), &cell_link_nr)| link_nr == cell_link_nr && cell_color.is_some())
-                {
-                    for &house in &cell.houses() {
-                        for neighbor_cell in house.cells().into_iter().filter(|&c| cell != c) {
-                            let (sees_a, sees_b) = cell_sees_color[neighbor_cell];
-                            if cell_color == Some(Color::A) && !sees_a {
-                                cell_sees_color[neighbor_cell].0 = true;
-                                if sees_b {
-                                    self.eliminated_entries.push( Candidate{ cell: neighbor_cell, digit })
-                                }
-                            } else if cell_color == Some(Color::B) && !sees_b {
-                                cell_sees_color[neighbor_cell].1 = true;
-                                if sees_a {
-                                    self.eliminated_entries.push( Candidate{ cell: neighbor_cell, digit })
-                                }
-                            }
-                        }
+        singles_chain::find_singles_chain(
+            cell_poss_digits,
+            house_poss_positions,
+            stop_after_first,
+            |digit, colors| {
+                let sees_itself = |color: Set<Cell>| color.into_iter().any(|cell| cell.neighbors_set().overlaps(color));
+                let impossible_cells = match colors.iter().copied().find(|&color| sees_itself(color)) {
+                    Some(false_color) => false_color,
+                    None => {
+                        (singles_chain::seen_cells(colors[0]) & singles_chain::seen_cells(colors[1]))
+                            .without(colors[0] | colors[1])
                     }
-                }
-            }
+                };
+
+                let conflicts = impossible_cells
+                    .into_iter()
+                    .filter(|&cell| cell_poss_digits[cell].contains(digit))
+                    .map(|cell| Candidate { cell, digit });
+
+                let on_conflict = |conflicts| Deduction::SinglesChain {
+                    digit,
+                    colors,
+                    conflicts,
+                };
+
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
+    }
+
+    pub(crate) fn find_chains(&mut self, strategy: Strategy, stop_after_first: bool) -> Result<(), Unsolvable> {
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
+
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
+
+        chains::find_chains(
+            cell_poss_digits,
+            house_poss_positions,
+            strategy,
+            self.config.max_chain_length,
+            stop_after_first,
+            |nodes| {
+                let conflicts = chains::eliminations(nodes[0], nodes[nodes.len() - 1], cell_poss_digits);
+                let on_conflict = |conflicts| Deduction::Aic {
+                    chain: Chain::new(nodes),
+                    conflicts,
+                };
+
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
+    }
+
+    pub(crate) fn find_als_xz(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
+        if !self.config.enable_als_xz {
+            return Ok(());
         }
-        Ok(())
+        self.update_cell_poss_house_solved()?;
+
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+
+        almost_locked_sets::find_als_xz(cell_poss_digits, stop_after_first, |sets, restricted_common| {
+            let conflicts = almost_locked_sets::eliminations(cell_poss_digits, sets, restricted_common);
+            let on_conflict = |conflicts| Deduction::AlsXz {
+                sets,
+                restricted_common,
+                conflicts,
+            };
+
+            Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+        })
+    }
+
+    pub(crate) fn find_avoidable_rectangles(&mut self, stop_after_first: bool) -> Result<(), Unsolvable> {
+        let clues = match self.clues {
+            Some(clues) if self.config.assume_unique_solution => clues,
+            _ => return Ok(()),
+        };
+        self.update_cell_poss_house_solved()?;
+        self.update_grid();
+
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+
+        avoidable_rectangles::find_avoidable_rectangles(
+            self.grid.state,
+            clues,
+            cell_poss_digits,
+            stop_after_first,
+            |lines, conflict| {
+                let on_conflict = |conflicts| Deduction::AvoidableRectangle { lines, conflicts };
+                Self::enter_conflicts(eliminated_entries, deductions, [conflict], on_conflict)
+            },
+        )
+    }
+
+    pub(crate) fn find_unique_rectangles(&mut self, variant: u8, stop_after_first: bool) -> Result<(), Unsolvable> {
+        if !self.config.assume_unique_solution {
+            return Ok(());
+        }
+        self.update_house_poss_positions()?;
+        self.update_cell_poss_house_solved()?;
+
+        let cell_poss_digits = &self.cell_poss_digits.state;
+        let eliminated_entries = &mut self.eliminated_entries;
+        let deductions = &mut self.deductions;
+        let house_poss_positions = &self.house_poss_positions.state;
+
+        unique_rectangles::find_unique_rectangles(
+            cell_poss_digits,
+            house_poss_positions,
+            variant,
+            stop_after_first,
+            |rectangle, digits, conflicts| {
+                let on_conflict = |conflicts| Deduction::UniqueRectangle {
+                    rectangle,
+                    digits,
+                    variant,
+                    conflicts,
+                };
+
+                Self::enter_conflicts(eliminated_entries, deductions, conflicts, on_conflict)
+            },
+        )
     }
-    */
 }
 
-impl std::fmt::Display for StrategySolver {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl core::fmt::Display for StrategySolver {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         let mut solver = self.clone();
 
         solver.update_for_grid_state_str();
@@ -1291,8 +1468,9 @@ impl<T> State<T> {
 //     }
 // }
 
+#[allow(clippy::too_many_arguments)]
 fn print_grid_state(
-    f: &mut std::fmt::Formatter,
+    f: &mut core::fmt::Formatter,
     grid_state: [CellState; 81],
     upper_left_corner: &str,
     upper_right_corner: &str,
@@ -1307,7 +1485,7 @@ fn print_grid_state(
 
     horizontal_bar: &str,
     vertical_bar: &str,
-) -> Result<(), std::fmt::Error> {
+) -> Result<(), core::fmt::Error> {
     // TODO: Decide what to print if a cell has no candidates anymore
     //       leaving empty is possible, but more difficult to parse correctly
     //       '_' is another good alternative
@@ -1317,7 +1495,7 @@ fn print_grid_state(
             .map(|row| row * 9 + col)
             .map(|cell| match grid_state[cell] {
                 CellState::Digit(_) => 1,
-                CellState::Candidates(digits) => std::cmp::max(digits.len(), 1),
+                CellState::Candidates(digits) => core::cmp::max(digits.len(), 1),
             })
             .max()
             .unwrap();
@@ -1362,7 +1540,7 @@ fn print_grid_state(
                                 f,
                                 "{:1$}",
                                 ' ',
-                                (1 + column_widths[full_col] - std::cmp::max(cands.len(), 1)) as usize
+                                (1 + column_widths[full_col] - core::cmp::max(cands.len(), 1)) as usize
                             )?;
                         }
                     }
@@ -1393,13 +1571,13 @@ fn print_grid_state(
 }
 
 fn _print_separator(
-    f: &mut std::fmt::Formatter,
+    f: &mut core::fmt::Formatter,
     left_junction: &str,
     middle_junction: &str,
     right_junction: &str,
     horizontal_bar: &str,
     lengths: [usize; 3],
-) -> Result<(), std::fmt::Error> {
+) -> Result<(), core::fmt::Error> {
     writeln!(
         f,
         "{left}{line0}{middle}{line1}{middle}{line2}{right}",
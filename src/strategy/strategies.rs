@@ -3,70 +3,205 @@ pub(crate) mod prelude;
 pub(crate) mod almost_locked_sets;
 pub(crate) mod avoidable_rectangles;
 pub(crate) mod basic_fish;
+pub(crate) mod bug_plus_one;
+pub(crate) mod chains;
+pub(crate) mod finned_fish;
 pub(crate) mod hidden_singles;
 pub(crate) mod hidden_subsets;
 pub(crate) mod locked_candidates;
 pub(crate) mod mutant_fish;
 pub(crate) mod naked_singles;
 pub(crate) mod naked_subsets;
+pub(crate) mod single_digit_patterns;
+pub(crate) mod singles_chain;
+#[cfg(test)]
+pub(crate) mod testing;
+pub(crate) mod unique_rectangles;
+pub(crate) mod w_wing;
 pub(crate) mod xy_wing;
 pub(crate) mod xyz_wing;
 
 use super::StrategySolver;
+#[cfg(feature = "std")]
+use near_sdk::serde::{Deserialize, Serialize};
 use crate::helper::Unsolvable;
 
 /// The strategies that can be used to find hints, solve or grade a sudoku.
 ///
 /// This can be used with [`StrategySolver::solve`].
 /// May be expanded in the future.
+///
+/// The discriminants are stable: new strategies are appended with new numbers and existing numbers
+/// are never reused, so `strategy as u8` can be stored or sent to clients. The serialized form is the variant name.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "near_sdk::serde"))]
 #[allow(missing_docs)]
 pub enum Strategy {
-    NakedSingles,
-    HiddenSingles,
-    LockedCandidates,
-    NakedPairs,
-    NakedTriples,
-    NakedQuads,
-    HiddenPairs,
-    HiddenTriples,
-    HiddenQuads,
-    XWing,
-    Swordfish,
-    Jellyfish,
-    XyWing,
-    XyzWing,
-    MutantSwordfish,
-    MutantJellyfish,
-    AvoidableRectangles,
-    //SinglesChain,
+    NakedSingles = 0,
+    HiddenSingles = 1,
+    LockedCandidates = 2,
+    NakedPairs = 3,
+    NakedTriples = 4,
+    NakedQuads = 5,
+    HiddenPairs = 6,
+    HiddenTriples = 7,
+    HiddenQuads = 8,
+    XWing = 9,
+    Swordfish = 10,
+    Jellyfish = 11,
+    FinnedXWing = 12,
+    SashimiXWing = 13,
+    FinnedSwordfish = 14,
+    SashimiSwordfish = 15,
+    Skyscraper = 16,
+    TwoStringKite = 17,
+    TurbotFish = 18,
+    XyWing = 19,
+    XyzWing = 20,
+    WWing = 21,
+    MutantSwordfish = 22,
+    MutantJellyfish = 23,
+    AvoidableRectangles = 24,
+    BugPlusOne = 25,
+    SinglesChain = 26,
+    XChain = 27,
+    XyChain = 28,
+    Aic = 29,
+    AlsXz = 30,
+    UniqueRectangleType1 = 31,
+    UniqueRectangleType2 = 32,
+    UniqueRectangleType3 = 33,
+    UniqueRectangleType4 = 34,
 }
 
 impl Strategy {
-    /// Set of all available strategies, for test purposes
-    #[allow(unused)]
+    /// Set of all available strategies, ordered from easiest to hardest
     #[rustfmt::skip]
-    pub(crate) const ALL: &'static [Strategy] = &[
-                                    // difficulty as assigned by
-                                    // SudokuExplainer
-        Strategy::NakedSingles,     // 23
-        Strategy::HiddenSingles,    // 15
-        Strategy::LockedCandidates, // 28
-        Strategy::NakedPairs,       // 30
-        Strategy::XWing,            // 32
-        Strategy::HiddenPairs,      // 34
-        Strategy::NakedTriples,     // 36
-        Strategy::Swordfish,        // 38
-        Strategy::HiddenTriples,    // 40
-        Strategy::XyWing,           // 42
-        Strategy::XyzWing,          // 44
-        Strategy::NakedQuads,       // 50
-        Strategy::Jellyfish,        // 52
-        Strategy::HiddenQuads,      // 54
-        //Strategy::SinglesChain,
+    pub const ALL: &'static [Strategy] = &[
+                                        // difficulty as assigned by
+                                        // SudokuExplainer
+        Strategy::NakedSingles,         // 23
+        Strategy::HiddenSingles,        // 15
+        Strategy::LockedCandidates,     // 28
+        Strategy::NakedPairs,           // 30
+        Strategy::XWing,                // 32
+        Strategy::HiddenPairs,          // 34
+        Strategy::FinnedXWing,          // 34
+        Strategy::SashimiXWing,         // 35
+        Strategy::NakedTriples,         // 36
+        Strategy::Swordfish,            // 38
+        Strategy::HiddenTriples,        // 40
+        Strategy::FinnedSwordfish,      // 40
+        Strategy::Skyscraper,           // 40
+        Strategy::SashimiSwordfish,     // 41
+        Strategy::TwoStringKite,        // 41
+        Strategy::TurbotFish,           // 42
+        Strategy::XyWing,               // 42
+        Strategy::XyzWing,              // 44
+        Strategy::WWing,                // 44
+        Strategy::SinglesChain,         // 45
+        Strategy::UniqueRectangleType1, // 45
+        Strategy::UniqueRectangleType2, // 45
+        Strategy::UniqueRectangleType4, // 45
+        Strategy::UniqueRectangleType3, // 46
+        Strategy::NakedQuads,           // 50
+        Strategy::Jellyfish,            // 52
+        Strategy::HiddenQuads,          // 54
+        Strategy::BugPlusOne,           // 56
+        Strategy::XChain,               // 65
+        Strategy::XyChain,              // 66
+        Strategy::Aic,                  // 70
+        Strategy::AlsXz,                // 71
     ];
 
+    /// Difficulty rating of the strategy, as assigned by SudokuExplainer times 10.
+    /// Strategies SudokuExplainer doesn't know are rated above their closest relative.
+    pub fn rating(self) -> u32 {
+        use self::Strategy::*;
+        match self {
+            HiddenSingles => 15,
+            NakedSingles => 23,
+            LockedCandidates => 28,
+            NakedPairs => 30,
+            XWing => 32,
+            HiddenPairs => 34,
+            FinnedXWing => 34,
+            SashimiXWing => 35,
+            NakedTriples => 36,
+            Swordfish => 38,
+            HiddenTriples => 40,
+            FinnedSwordfish => 40,
+            SashimiSwordfish => 41,
+            Skyscraper => 40,
+            TwoStringKite => 41,
+            TurbotFish => 42,
+            XyWing => 42,
+            XyzWing => 44,
+            WWing => 44,
+            SinglesChain => 45,
+            UniqueRectangleType1 => 45,
+            UniqueRectangleType2 => 45,
+            UniqueRectangleType3 => 46,
+            UniqueRectangleType4 => 45,
+            AvoidableRectangles => 47,
+            NakedQuads => 50,
+            Jellyfish => 52,
+            HiddenQuads => 54,
+            MutantSwordfish => 56,
+            MutantJellyfish => 58,
+            BugPlusOne => 56,
+            XChain => 65,
+            XyChain => 66,
+            Aic => 70,
+            AlsXz => 71,
+        }
+    }
+
+    /// Common name of the strategy, e.g. "X-Wing"
+    pub fn name(self) -> &'static str {
+        use self::Strategy::*;
+        match self {
+            NakedSingles => "Naked Single",
+            HiddenSingles => "Hidden Single",
+            LockedCandidates => "Locked Candidates",
+            NakedPairs => "Naked Pair",
+            NakedTriples => "Naked Triple",
+            NakedQuads => "Naked Quad",
+            HiddenPairs => "Hidden Pair",
+            HiddenTriples => "Hidden Triple",
+            HiddenQuads => "Hidden Quad",
+            XWing => "X-Wing",
+            Swordfish => "Swordfish",
+            Jellyfish => "Jellyfish",
+            FinnedXWing => "Finned X-Wing",
+            SashimiXWing => "Sashimi X-Wing",
+            FinnedSwordfish => "Finned Swordfish",
+            SashimiSwordfish => "Sashimi Swordfish",
+            Skyscraper => "Skyscraper",
+            TwoStringKite => "2-String Kite",
+            TurbotFish => "Turbot Fish",
+            XyWing => "XY-Wing",
+            XyzWing => "XYZ-Wing",
+            WWing => "W-Wing",
+            MutantSwordfish => "Mutant Swordfish",
+            MutantJellyfish => "Mutant Jellyfish",
+            AvoidableRectangles => "Avoidable Rectangle",
+            BugPlusOne => "BUG+1",
+            SinglesChain => "Simple Coloring",
+            XChain => "X-Chain",
+            XyChain => "XY-Chain",
+            Aic => "Alternating Inference Chain",
+            AlsXz => "ALS-XZ",
+            UniqueRectangleType1 => "Unique Rectangle Type 1",
+            UniqueRectangleType2 => "Unique Rectangle Type 2",
+            UniqueRectangleType3 => "Unique Rectangle Type 3",
+            UniqueRectangleType4 => "Unique Rectangle Type 4",
+        }
+    }
+
     // is_first_strategy is an optimization hint
     // it doesn't need to be used
     pub(crate) fn deduce(
@@ -92,12 +227,25 @@ impl Strategy {
             XWing => state.find_xwings(stop_after_first),
             Swordfish => state.find_swordfish(stop_after_first),
             Jellyfish => state.find_jellyfish(stop_after_first),
+            FinnedXWing => state.find_finned_fish(2, false, stop_after_first),
+            SashimiXWing => state.find_finned_fish(2, true, stop_after_first),
+            FinnedSwordfish => state.find_finned_fish(3, false, stop_after_first),
+            SashimiSwordfish => state.find_finned_fish(3, true, stop_after_first),
             XyWing => state.find_xy_wing(stop_after_first),
             XyzWing => state.find_xyz_wing(stop_after_first),
+            Skyscraper | TwoStringKite | TurbotFish => state.find_single_digit_pattern(self, stop_after_first),
+            WWing => state.find_w_wing(stop_after_first),
+            BugPlusOne => state.find_bug_plus_one(),
             MutantSwordfish => state.find_mutant_fish(3, stop_after_first),
             MutantJellyfish => state.find_mutant_fish(4, stop_after_first),
-            //SinglesChain => state.find_singles_chain(stop_after_first), // TODO: Implement non-eager SinglesChain
-            _ => unimplemented!(),
+            SinglesChain => state.find_singles_chain(stop_after_first),
+            XChain | XyChain | Aic => state.find_chains(*self, stop_after_first),
+            AlsXz => state.find_als_xz(stop_after_first),
+            UniqueRectangleType1 => state.find_unique_rectangles(1, stop_after_first),
+            UniqueRectangleType2 => state.find_unique_rectangles(2, stop_after_first),
+            UniqueRectangleType3 => state.find_unique_rectangles(3, stop_after_first),
+            UniqueRectangleType4 => state.find_unique_rectangles(4, stop_after_first),
+            AvoidableRectangles => state.find_avoidable_rectangles(stop_after_first),
         }
     }
 
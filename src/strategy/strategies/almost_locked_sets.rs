@@ -1,65 +1,39 @@
-// WIP
-#![allow(unused)]
 use super::prelude::*;
 
-pub(crate) fn find_almost_locked_sets(
-    cells_poss_digits: &CellArray<Set<Digit>>,
-    //house_solved_digits: &HouseArray<Set<Digit>>,
-    //subset_size: u8,
+// An almost locked set (ALS) is a set of N cells in one house with N+1 candidates.
+// A digit common to 2 ALS, whose candidates in the first ALS all see its candidates in the second one,
+// is a restricted common candidate (RCC). It can be true in at most one of them, so the other one
+// is a locked set. Therefore any other common digit is true in at least one of the ALS and can be
+// eliminated from all cells seeing every candidate of it in both.
+pub(crate) fn find_als_xz(
+    cell_poss_digits: &CellArray<Set<Digit>>,
     stop_after_first: bool,
-    mut on_subset: impl FnMut(
-        //House,
-        //Set<Position<House>>,
-        //Set<Digit>,
-        (), // rustfmt bug: deletes comments unless something is here
+    mut on_als_xz: impl FnMut(
+        [Set<Cell>; 2], // cells of both ALS
+        Digit,          // restricted common candidate
     ) -> bool,
 ) -> Result<(), Unsolvable> {
-    let als = _find_almost_locked_sets(cells_poss_digits);
-    for first_set_size in 2..=8 {
-        for second_set_size in 1..=first_set_size {
-            let sets1 = &als[first_set_size];
-            let sets2 = &als[second_set_size];
-            // TODO: special case equal sized sets
-            //       so there's no repetition
-
-            // iterate over all house combinations
-            // except combinations of the same house kind
-            // because they have no overlap
-            for (house1, set1) in sets1.iter().enumerate() {
-                for &(cells1, digits1) in set1 {
-                    for (house2, set2) in sets2.iter().enumerate().filter(|&(h2, _)| h2 / 9 != house1 / 9) {
-                        for &(cells2, digits2) in set2 {
-                            let common_digits = digits1 & digits2;
-                            if common_digits.is_empty() {
-                                continue;
-                            }
-
-                            //let mut restricted_common_digits = Set::NONE;
-
-                            for digit in common_digits {
-                                let cells_of_digit = |digit: Digit, cells| {
-                                    let mut cells_of_digit = Set::NONE;
-                                    let digit_set = digit.as_set();
-                                    for cell in cells {
-                                        if cells_poss_digits[cell].overlaps(digit_set) {
-                                            cells_of_digit |= cell;
-                                        }
-                                    }
-                                    cells_of_digit
-                                };
-                                let cells_of_digit1 = cells_of_digit(digit, cells1);
-                                let cells_of_digit2 = cells_of_digit(digit, cells2);
-
-                                // ALS with overlapping cells are possible
-                                // but not yet supported
-                                // also, the restricted common digit must not be in an
-                                // overlapping cell
-                                if cells_of_digit1.overlaps(cells_of_digit2) {
-                                    continue;
-                                }
-                            }
-                        }
-                    }
+    let almost_locked_sets = find_almost_locked_sets(cell_poss_digits);
+
+    for (i, &(cells1, digits1)) in almost_locked_sets.iter().enumerate() {
+        for &(cells2, digits2) in &almost_locked_sets[i + 1..] {
+            if cells1.overlaps(cells2) {
+                continue;
+            }
+            let common_digits = digits1 & digits2;
+            if common_digits.len() < 2 {
+                continue;
+            }
+
+            for digit in common_digits {
+                let digit_cells1 = cells_with_digit(cell_poss_digits, cells1, digit);
+                let digit_cells2 = cells_with_digit(cell_poss_digits, cells2, digit);
+                let is_restricted = digit_cells1
+                    .into_iter()
+                    .all(|cell| cell.neighbors_set() & digit_cells2 == digit_cells2);
+
+                if is_restricted && on_als_xz([cells1, cells2], digit) && stop_after_first {
+                    return Ok(());
                 }
             }
         }
@@ -67,60 +41,133 @@ pub(crate) fn find_almost_locked_sets(
     Ok(())
 }
 
-type AlmostLockedSets = [[Vec<(Set<Cell>, Set<Digit>)>; 27]; 8];
+// Candidates of the common digits other than `restricted_common` that see all candidates of the same digit in both ALS
+pub(crate) fn eliminations(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    [cells1, cells2]: [Set<Cell>; 2],
+    restricted_common: Digit,
+) -> Vec<Candidate> {
+    let digits = |cells: Set<Cell>| cells.into_iter().fold(Set::NONE, |digits, cell| digits | cell_poss_digits[cell]);
+    let other_digits = (digits(cells1) & digits(cells2)).without(restricted_common.as_set());
 
-// 27 houses
-// 1-8 cells
-// 2-9 candidates
-// [[Vec<(Set<Cell>, Set<Digit>)>; 9]; 27]
-pub(crate) fn _find_almost_locked_sets(cells_poss_digits: &CellArray<Set<Digit>>) -> AlmostLockedSets {
-    let mut sets = AlmostLockedSets::default(); //[[vec![]; 9]; 27];
-    for house in House::all() {
-        let cells = house.cells();
-        _walk_combinations(
-            cells_poss_digits,
-            cells.into_iter(),
-            Set::NONE,
-            Set::NONE,
-            &mut sets,
-            house.as_index(),
-            0,
+    let mut conflicts = vec![];
+    for digit in other_digits {
+        let digit_cells = cells_with_digit(cell_poss_digits, cells1 | cells2, digit);
+        let seeing_all = digit_cells
+            .into_iter()
+            .fold(Set::ALL, |seeing, cell| seeing & cell.neighbors_set())
+            .without(cells1 | cells2);
+        conflicts.extend(
+            seeing_all
+                .into_iter()
+                .filter(|&cell| cell_poss_digits[cell].contains(digit))
+                .map(|cell| Candidate { cell, digit }),
         );
     }
+    conflicts.sort();
+    conflicts
+}
+
+fn cells_with_digit(cell_poss_digits: &CellArray<Set<Digit>>, cells: Set<Cell>, digit: Digit) -> Set<Cell> {
+    cells
+        .into_iter()
+        .filter(|&cell| cell_poss_digits[cell].contains(digit))
+        .fold(Set::NONE, |set, cell| set | cell)
+}
+
+// All almost locked sets of all houses. Sets lying in the intersection of a line and a block are only listed once.
+fn find_almost_locked_sets(cell_poss_digits: &CellArray<Set<Digit>>) -> Vec<(Set<Cell>, Set<Digit>)> {
+    let mut sets = vec![];
+    for house in House::all() {
+        let unsolved_cells = house
+            .cells()
+            .into_iter()
+            .filter(|&cell| !cell_poss_digits[cell].is_empty())
+            .fold(Set::NONE, |set, cell| set | cell);
+        walk_combinations(cell_poss_digits, unsolved_cells.into_iter(), Set::NONE, Set::NONE, &mut sets);
+    }
+    sets.sort();
+    sets.dedup();
     sets
 }
 
-pub(crate) fn _walk_combinations(
-    cells_poss_digits: &CellArray<Set<Digit>>,
+fn walk_combinations(
+    cell_poss_digits: &CellArray<Set<Digit>>,
     mut cells: SetIter<Cell>,
     cell_set: Set<Cell>,
     digits: Set<Digit>,
-    almost_locked_sets: &mut AlmostLockedSets,
-    house: usize,
-    depth: u8,
+    almost_locked_sets: &mut Vec<(Set<Cell>, Set<Digit>)>,
 ) {
     while let Some(cell) = cells.next() {
-        let new_cell_set = cell_set | cell.as_set();
-        let candidates = cells_poss_digits[cell];
-        if candidates.len() <= 1 {
-            continue;
+        let new_cell_set = cell_set | cell;
+        let new_digits = digits | cell_poss_digits[cell];
+
+        if new_digits.len() == new_cell_set.len() + 1 {
+            almost_locked_sets.push((new_cell_set, new_digits));
+        }
+
+        walk_combinations(cell_poss_digits, cells.clone(), new_cell_set, new_digits, almost_locked_sets);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::{SolverConfig, Strategy, StrategySolver};
+
+    fn digits(digits: &[u8]) -> Set<Digit> {
+        digits.iter().fold(Set::NONE, |set, &digit| set | Digit::new(digit))
+    }
+
+    // ALS (0,0) {1,2} and ALS (4,0) {1,3}, (4,1) {2,3} with the restricted common candidate 1
+    fn als_xz_cells() -> [(u8, Set<Digit>); 3] {
+        [(0, digits(&[1, 2])), (36, digits(&[1, 3])), (37, digits(&[2, 3]))]
+    }
+
+    #[test]
+    fn als_xz() {
+        let mut cell_poss_digits = CellArray([Set::NONE; 81]);
+        for &(cell, digits) in &als_xz_cells() {
+            cell_poss_digits[Cell::new(cell)] = digits;
+        }
+        // cells seeing both (0,0) and (4,1) plus one that only sees (0,0)
+        for &cell in &[1, 10, 19, 27, 45, 2] {
+            cell_poss_digits[Cell::new(cell)] = digits(&[2, 5, 6, 7]);
         }
-        let new_digits = digits | candidates;
 
-        assert!(new_cell_set.len() == depth + 1);
+        let als1 = Cell::new(0).as_set();
+        let als2 = Cell::new(36).as_set() | Cell::new(37);
+        let mut found = vec![];
+        find_als_xz(&cell_poss_digits, false, |sets, digit| {
+            found.push((sets, digit));
+            false
+        })
+        .unwrap();
+        assert!(found.contains(&([als1, als2], Digit::new(1))));
 
-        if new_digits.len() == new_cell_set.len() + 1 {
-            almost_locked_sets[new_cell_set.len() as usize - 1][house].push((new_cell_set, new_digits));
+        let expected: Vec<_> = [1, 10, 19, 27, 45].iter().map(|&cell| Candidate::new(cell, 2)).collect();
+        assert_eq!(eliminations(&cell_poss_digits, [als1, als2], Digit::new(1)), expected);
+    }
+
+    #[test]
+    fn als_xz_disabled_by_default() {
+        let mut grid_state = [CellState::Candidates(Set::ALL); 81];
+        for &(cell, digits) in &als_xz_cells() {
+            grid_state[cell as usize] = CellState::Candidates(digits);
         }
 
-        _walk_combinations(
-            cells_poss_digits,
-            cells.clone(),
-            new_cell_set,
-            new_digits,
-            almost_locked_sets,
-            house,
-            depth + 1,
-        );
+        let solver = StrategySolver::from_grid_state(grid_state);
+        assert!(!solver.config().enable_als_xz);
+        let (_, deductions) = solver.clone().solve(&[Strategy::AlsXz]).unwrap_err();
+        assert!(deductions.is_empty());
+
+        let config = SolverConfig {
+            enable_als_xz: true,
+            ..SolverConfig::default()
+        };
+        let (_, deductions) = solver.with_config(config).solve(&[Strategy::AlsXz]).unwrap_err();
+        assert!(!deductions.is_empty());
+        assert!(deductions.iter().all(|deduction| deduction.strategy() == Strategy::AlsXz));
     }
 }
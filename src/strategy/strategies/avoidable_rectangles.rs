@@ -1,8 +1,10 @@
-// WIP
 use super::prelude::*;
 
-#[allow(unused)]
-fn find_avoidable_rectangles(
+// An avoidable rectangle is a rectangle in 2 blocks whose cells were all solved, not given as clues. If its 4 cells held
+// only 2 digits, they could be swapped and the sudoku would have a second solution. So when 3 of the cells are solved
+// with 2 digits, the 4th cell can't hold the digit of its diagonal opposite.
+// Only valid for sudokus with a unique solution, see `SolverConfig::assume_unique_solution`.
+pub(crate) fn find_avoidable_rectangles(
     filled_cells: Sudoku,
     clues: Sudoku,
     cell_poss_digits: &CellArray<Set<Digit>>,
@@ -12,7 +14,7 @@ fn find_avoidable_rectangles(
         Set<Line>,
         // impossible candidate
         Candidate,
-    ),
+    ) -> bool,
 ) -> Result<(), Unsolvable> {
     let cell = |row: u8, col: u8| row * 9 + col;
     for row1 in 0..8 {
@@ -59,7 +61,7 @@ fn find_avoidable_rectangles(
                             let col1 = Col::new(col1);
                             let col2 = Col::new(col2);
                             if let Some(digit) = (candidates_remaining_cell & set_digits).unique()? {
-                                on_avoidable_rectangle(
+                                let found_conflicts = on_avoidable_rectangle(
                                     Line::from(row1).as_set()
                                         | Line::from(row2)
                                         | Line::from(col1)
@@ -68,7 +70,10 @@ fn find_avoidable_rectangles(
                                         cell: Cell::new(free_cell),
                                         digit,
                                     },
-                                )
+                                );
+                                if found_conflicts && stop_after_first {
+                                    return Ok(());
+                                }
                             }
                         }
                     }
@@ -78,3 +83,63 @@ fn find_avoidable_rectangles(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::{Deduction, SolverConfig, Strategy, StrategySolver};
+
+    /// 1 and 2 solved in 3 corners of the rectangle of rows 1-2 and columns 1 and 4
+    fn solved_corners() -> Sudoku {
+        let mut sudoku = Sudoku([0; 81]);
+        sudoku.0[0] = 1;
+        sudoku.0[3] = 2;
+        sudoku.0[9] = 2;
+        sudoku
+    }
+
+    fn deductions(solver: StrategySolver) -> usize {
+        let (_, deductions) = solver.solve(&[Strategy::AvoidableRectangles]).unwrap_err();
+        deductions.len()
+    }
+
+    #[test]
+    fn avoidable_rectangle() {
+        let solver = StrategySolver::from_sudoku_and_clues(solved_corners(), Sudoku([0; 81]));
+        let (_, deductions) = solver.solve(&[Strategy::AvoidableRectangles]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+        assert_eq!(
+            deductions.get(0).unwrap(),
+            Deduction::AvoidableRectangle {
+                lines: Line::from(Row::new(0)).as_set()
+                    | Line::from(Row::new(1))
+                    | Line::from(Col::new(0))
+                    | Line::from(Col::new(3)),
+                conflicts: &[Candidate {
+                    cell: Cell::new(12),
+                    digit: Digit::new(1),
+                }][..],
+            }
+        );
+    }
+
+    #[test]
+    fn avoidable_rectangle_needs_solved_cells() {
+        // without the clues, or with a corner given as clue, swapping the digits isn't possible
+        assert_eq!(deductions(StrategySolver::from_sudoku(solved_corners())), 0);
+        let mut clues = Sudoku([0; 81]);
+        clues.0[3] = 2;
+        let solver = StrategySolver::from_sudoku_and_clues(solved_corners(), clues);
+        assert_eq!(deductions(solver), 0);
+    }
+
+    #[test]
+    fn avoidable_rectangle_assumes_unique_solution() {
+        let config = SolverConfig {
+            assume_unique_solution: false,
+            ..SolverConfig::default()
+        };
+        let solver = StrategySolver::from_sudoku_and_clues(solved_corners(), Sudoku([0; 81])).with_config(config);
+        assert_eq!(deductions(solver), 0);
+    }
+}
@@ -36,6 +36,7 @@ pub(crate) fn find_fish(
 // x-wing          2
 // swordfish       3
 // jellyfish       4
+#[allow(clippy::too_many_arguments, clippy::only_used_in_recursion)]
 fn basic_fish_walk_combinations(
     house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
     digit: Digit,
@@ -67,7 +68,7 @@ fn basic_fish_walk_combinations(
 
         // n_poss == 0 => solved row (or impossible)
         // n_poss == 1 => hidden single
-        if n_poss < 2 || new_union_poss_pos.len() > goal_depth as u8 {
+        if n_poss < 2 || new_union_poss_pos.len() > goal_depth {
             continue;
         }
 
@@ -88,3 +89,80 @@ fn basic_fish_walk_combinations(
     }
     false
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::strategies::testing::{assert_needed, grid_without_1};
+    use crate::strategy::{Deduction, Strategy, StrategySolver};
+
+    // digit 1 is limited to `fish_cols` in all `fish_rows`, all other candidates are open
+    fn fish_grid(fish_rows: &[u8], fish_cols: &[u8]) -> StrategySolver {
+        grid_without_1(|row, col| fish_rows.contains(&row) && !fish_cols.contains(&col))
+    }
+
+    fn assert_fish(strategy: Strategy, fish_rows: &[u8], fish_cols: &[u8]) {
+        let (_, deductions) = fish_grid(fish_rows, fish_cols).solve(&[strategy]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+
+        let deduction = deductions.get(0).unwrap();
+        assert_eq!(deduction.strategy(), strategy);
+        if let Deduction::BasicFish { digit, conflicts, .. } = deduction {
+            assert_eq!(digit, Digit::new(1));
+            // digit 1 is eliminated from the fish columns in every other row
+            assert_eq!(conflicts.len(), (9 - fish_rows.len()) * fish_cols.len());
+            for conflict in conflicts {
+                assert!(fish_cols.contains(&conflict.cell.col().get()));
+                assert!(!fish_rows.contains(&conflict.cell.row().get()));
+            }
+        } else {
+            panic!("No fish found.");
+        }
+    }
+
+    #[test]
+    fn xwing() {
+        assert_fish(Strategy::XWing, &[1, 5], &[2, 7]);
+    }
+
+    #[test]
+    fn swordfish() {
+        assert_fish(Strategy::Swordfish, &[0, 3, 6], &[0, 4, 8]);
+    }
+
+    #[test]
+    fn jellyfish() {
+        assert_fish(Strategy::Jellyfish, &[0, 2, 4, 7], &[1, 3, 5, 6]);
+    }
+
+    // a swordfish whose rows don't all contain each of its columns
+    #[test]
+    fn swordfish_incomplete_lines() {
+        let mut grid_state = fish_grid(&[0, 3, 6], &[0, 4, 8]).grid_state();
+        for &cell in &[0, 3 * 9 + 4, 6 * 9 + 8] {
+            grid_state[cell] = CellState::Candidates(Set::ALL.without(Digit::new(1).as_set()));
+        }
+        let solver = StrategySolver::from_grid_state(grid_state);
+        let (_, deductions) = solver.solve(&[Strategy::Swordfish]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+        assert_eq!(deductions.get(0).unwrap().strategy(), Strategy::Swordfish);
+    }
+
+    // generated puzzles, each fish is the only step beyond subsets
+    #[test]
+    fn fish_needed() {
+        assert_needed(
+            Strategy::XWing,
+            "91...5.3...8.6.17..3.8....6.9...........3.92.7..5..3...5.4..68.3...87............",
+        );
+        assert_needed(
+            Strategy::Swordfish,
+            "......5......3......94..678.8...2..19...71..4..5...8.......4.35..89..7...342.....",
+        );
+        assert_needed(
+            Strategy::Jellyfish,
+            "4..3.7.8...7..51.3...4......2...4...8..7.1..6...2..9...6..48.5.3........74.9...1.",
+        );
+    }
+}
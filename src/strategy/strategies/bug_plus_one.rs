@@ -0,0 +1,106 @@
+use super::prelude::*;
+
+// In a Bivalue Universal Grave (BUG) every unsolved cell has 2 candidates and every candidate appears exactly twice in
+// each of its houses. Such a grid has either no solution or at least 2, so a uniquely solvable sudoku can't reach it.
+// If only one cell has a third candidate (BUG+1), that candidate appears 3 times in each house of the cell and has to
+// be the digit of the cell, because removing it would leave a BUG.
+// Only valid for sudokus with a unique solution, see `SolverConfig::assume_unique_solution`.
+pub(crate) fn find_bug_plus_one(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    mut on_new_entry: impl FnMut(Candidate) -> Result<(), Unsolvable>,
+) -> Result<(), Unsolvable> {
+    let mut trivalue_cell = None;
+    for cell in Cell::all() {
+        match cell_poss_digits[cell].len() {
+            0 | 2 => (),
+            3 if trivalue_cell.is_none() => trivalue_cell = Some(cell),
+            _ => return Ok(()),
+        }
+    }
+    let cell = match trivalue_cell {
+        Some(cell) => cell,
+        None => return Ok(()),
+    };
+
+    let count = |house: House, digit: Digit| {
+        house
+            .cells()
+            .into_iter()
+            .filter(|&other| cell_poss_digits[other].contains(digit))
+            .count()
+    };
+    let extra_digit = cell_poss_digits[cell]
+        .into_iter()
+        .find(|&digit| cell.houses().iter().all(|&house| count(house, digit) == 3));
+    let digit = match extra_digit {
+        Some(digit) => digit,
+        None => return Ok(()),
+    };
+
+    // removing the extra candidate has to leave a BUG
+    let houses_of_cell = cell.houses();
+    for house in House::all() {
+        for other_digit in Digit::all() {
+            let is_extra = other_digit == digit && houses_of_cell.contains(&house);
+            match count(house, other_digit) {
+                3 if is_extra => (),
+                0 | 2 if !is_extra => (),
+                _ => return Ok(()),
+            }
+        }
+    }
+    on_new_entry(Candidate { cell, digit })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::{Deduction, SolverConfig, Strategy, StrategySolver};
+
+    /// Every cell holds its digit in a solution and the next higher one, a BUG, plus 9 in the first cell
+    fn bug_plus_one_grid() -> [CellState; 81] {
+        let solution =
+            Sudoku::from_str_line("534678912672195348198342567859761423426853791713924856961537284287419635345286179")
+                .unwrap();
+        let mut grid_state = [CellState::Candidates(Set::NONE); 81];
+        for (cell, digit) in solution.iter().enumerate() {
+            let digit = digit.unwrap();
+            grid_state[cell] = CellState::Candidates(Digit::new(digit).as_set() | Digit::new(digit % 9 + 1));
+        }
+        grid_state[0] = CellState::Candidates(Digit::new(5).as_set() | Digit::new(6) | Digit::new(9));
+        grid_state
+    }
+
+    #[test]
+    fn bug_plus_one() {
+        let solver = StrategySolver::from_grid_state(bug_plus_one_grid());
+        let (_, deductions) = solver.solve(&[Strategy::BugPlusOne]).unwrap_err();
+        let expected = Candidate {
+            cell: Cell::new(0),
+            digit: Digit::new(9),
+        };
+        assert_eq!(deductions.get(0), Some(Deduction::BugPlusOne(expected)));
+    }
+
+    #[test]
+    fn bug_plus_one_needs_a_bug() {
+        // the last cell holds 9 and 2 instead of 9 and 1, so 2 appears 3 times in its houses
+        let mut grid_state = bug_plus_one_grid();
+        grid_state[80] = CellState::Candidates(Digit::new(9).as_set() | Digit::new(2));
+        let solver = StrategySolver::from_grid_state(grid_state);
+        let (_, deductions) = solver.solve(&[Strategy::BugPlusOne]).unwrap_err();
+        assert_eq!(deductions.len(), 0);
+    }
+
+    #[test]
+    fn bug_plus_one_assumes_unique_solution() {
+        let config = SolverConfig {
+            assume_unique_solution: false,
+            ..SolverConfig::default()
+        };
+        let solver = StrategySolver::from_grid_state(bug_plus_one_grid()).with_config(config);
+        let (_, deductions) = solver.solve(&[Strategy::BugPlusOne]).unwrap_err();
+        assert_eq!(deductions.len(), 0);
+    }
+}
@@ -0,0 +1,280 @@
+use super::prelude::*;
+use super::Strategy;
+use crate::strategy::deduction::MAX_CHAIN_LENGTH;
+
+// An alternating inference chain (AIC) links candidates alternately by strong links (at least one is true)
+// and weak links (at most one is true), starting and ending with a strong link. If the first candidate is false,
+// the last one is true, so at least one of the ends is true and all candidates conflicting with both are impossible.
+//
+// X-Chains only link candidates of a single digit, strong links are conjugate pairs in a house.
+// XY-Chains only consist of bivalue cells, strong links are the 2 candidates of a cell and weak links
+// connect cells seeing each other by a common digit.
+// General AICs use all of these links.
+//
+// Chains are searched in order of increasing length so the simplest explanation is found first. For every start and
+// length a breadth first search visits each candidate at most once per link kind, so only the shortest chains between
+// two candidates are considered and the search is bounded by the number of links instead of the number of paths.
+pub(crate) fn find_chains(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    strategy: Strategy,
+    max_length: usize,
+    stop_after_first: bool,
+    mut on_chain: impl FnMut(&[Candidate]) -> bool,
+) -> Result<(), Unsolvable> {
+    let links = Links {
+        cell_poss_digits,
+        house_poss_positions,
+        strategy,
+    };
+    let candidates: Vec<_> = Cell::all()
+        .flat_map(|cell| cell_poss_digits[cell].into_iter().map(move |digit| Candidate { cell, digit }))
+        .collect();
+
+    // a single strong link never yields anything the simpler strategies don't find
+    for length in (3..=max_length.min(MAX_CHAIN_LENGTH)).step_by(2) {
+        for &start in &candidates {
+            for chain in shortest_chains(&links, start, length) {
+                if on_chain(&chain) && stop_after_first {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Breadth first search for the chains of exactly `length` links from `start` that are the shortest ones reaching
+// their end by a strong link. Every chain is found from both ends, it's only returned from the smaller one.
+fn shortest_chains(links: &Links<'_>, start: Candidate, length: usize) -> Vec<Vec<Candidate>> {
+    let index = |Candidate { cell, digit }: Candidate| cell.as_index() * 9 + digit.as_index();
+    // the previous node of every visited candidate, by whether it was reached by a strong link
+    let mut previous: [Vec<Option<Candidate>>; 2] = [vec![None; 729], vec![None; 729]];
+    previous[0][index(start)] = Some(start);
+
+    let mut frontier = vec![start];
+    for n_links in 1..=length {
+        let is_strong = n_links % 2;
+        let mut next_frontier = vec![];
+        for &node in &frontier {
+            let next_nodes = match is_strong {
+                1 => links.strong(node),
+                _ => links.weak(node),
+            };
+            for next in next_nodes {
+                if previous[is_strong][index(next)].is_none() {
+                    previous[is_strong][index(next)] = Some(node);
+                    next_frontier.push(next);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    let mut chains = vec![];
+    for end in frontier.into_iter().filter(|&end| start < end) {
+        let mut chain = vec![end];
+        for n_links in (1..=length).rev() {
+            let node = chain[chain.len() - 1];
+            chain.push(previous[n_links % 2][index(node)].unwrap());
+        }
+        chain.reverse();
+        // a candidate reached by both link kinds could be repeated
+        let is_simple = chain.iter().enumerate().all(|(i, node)| !chain[..i].contains(node));
+        if is_simple {
+            chains.push(chain);
+        }
+    }
+    chains
+}
+
+struct Links<'a> {
+    cell_poss_digits: &'a CellArray<Set<Digit>>,
+    house_poss_positions: &'a HouseArray<DigitArray<Set<Position<House>>>>,
+    strategy: Strategy,
+}
+
+impl Links<'_> {
+    fn uses_digit_links(&self) -> bool {
+        self.strategy != Strategy::XyChain
+    }
+
+    fn uses_cell_links(&self) -> bool {
+        self.strategy != Strategy::XChain
+    }
+
+    fn strong(&self, Candidate { cell, digit }: Candidate) -> Vec<Candidate> {
+        let mut nodes = vec![];
+        if self.uses_digit_links() {
+            let mut cells = Set::NONE;
+            for &house in &cell.houses() {
+                let positions = self.house_poss_positions[house][digit];
+                if positions.len() == 2 {
+                    cells |= house.cells_at(positions).without(cell.as_set());
+                }
+            }
+            nodes.extend(cells.into_iter().map(|cell| Candidate { cell, digit }));
+        }
+
+        let cell_digits = self.cell_poss_digits[cell];
+        if self.uses_cell_links() && cell_digits.len() == 2 {
+            nodes.extend(cell_digits.without(digit.as_set()).into_iter().map(|digit| Candidate { cell, digit }));
+        }
+        nodes
+    }
+
+    fn weak(&self, Candidate { cell, digit }: Candidate) -> Vec<Candidate> {
+        let mut nodes: Vec<_> = cell
+            .neighbors_set()
+            .into_iter()
+            .filter(|&neighbor| self.cell_poss_digits[neighbor].contains(digit))
+            // XY-Chains need bivalue cells to continue
+            .filter(|&neighbor| self.uses_digit_links() || self.cell_poss_digits[neighbor].len() == 2)
+            .map(|cell| Candidate { cell, digit })
+            .collect();
+
+        if self.strategy == Strategy::Aic {
+            let other_digits = self.cell_poss_digits[cell].without(digit.as_set());
+            nodes.extend(other_digits.into_iter().map(|digit| Candidate { cell, digit }));
+        }
+        nodes
+    }
+}
+
+// Candidates that conflict with both `start` and `end`, one of which is true
+pub(crate) fn eliminations(
+    start: Candidate,
+    end: Candidate,
+    cell_poss_digits: &CellArray<Set<Digit>>,
+) -> Vec<Candidate> {
+    if start.digit == end.digit {
+        let digit = start.digit;
+        return (start.cell.neighbors_set() & end.cell.neighbors_set())
+            .into_iter()
+            .filter(|&cell| cell_poss_digits[cell].contains(digit))
+            .map(|cell| Candidate { cell, digit })
+            .collect();
+    }
+
+    if start.cell == end.cell {
+        let cell = start.cell;
+        return cell_poss_digits[cell]
+            .without(start.digit_set() | end.digit_set())
+            .into_iter()
+            .map(|digit| Candidate { cell, digit })
+            .collect();
+    }
+
+    // different digits in different cells, seeing each other
+    let mut conflicts = vec![];
+    if start.cell.neighbors_set().contains(end.cell) {
+        for &(cell, digit) in &[(start.cell, end.digit), (end.cell, start.digit)] {
+            if cell_poss_digits[cell].contains(digit) {
+                conflicts.push(Candidate { cell, digit });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::strategies::testing::grid_without_1;
+    use crate::strategy::{Deduction, LinkKind, SolverConfig, StrategySolver};
+
+    fn candidates(cells: &[u8], digit: u8) -> Vec<Candidate> {
+        cells.iter().map(|&cell| Candidate::new(cell, digit)).collect()
+    }
+
+    #[test]
+    fn x_chain() {
+        // digit 1 is restricted to 2 cells in rows 0, 3 and 6, so the chain
+        // (0,0) = (0,4) - (3,4) = (3,8) - (6,8) = (6,1) links them
+        let solver = grid_without_1(|row, col| match row {
+            0 => col != 0 && col != 4,
+            3 => col != 4 && col != 8,
+            6 => col != 8 && col != 1,
+            _ => false,
+        });
+        let (_, deductions) = solver.solve(&[Strategy::XChain]).unwrap_err();
+
+        let deduction = deductions.get(0).unwrap();
+        assert_eq!(deduction.strategy(), Strategy::XChain);
+        if let Deduction::Aic { chain, conflicts } = deduction {
+            assert_eq!(chain.nodes(), &candidates(&[0, 4, 31, 35, 62, 55], 1)[..]);
+            let kinds: Vec<_> = chain.links().map(|link| link.kind).collect();
+            assert_eq!(
+                kinds,
+                [LinkKind::Strong, LinkKind::Weak, LinkKind::Strong, LinkKind::Weak, LinkKind::Strong]
+            );
+            // the cells seeing both (0,0) and (6,1)
+            assert_eq!(conflicts, &candidates(&[10, 19, 63, 72], 1)[..]);
+        } else {
+            panic!("No X-Chain found.");
+        }
+    }
+
+    fn xy_chain_solver() -> StrategySolver {
+        // bivalue cells (0,0) {1,2}, (0,4) {2,3}, (4,4) {3,4} and (4,8) {4,1}
+        let mut grid_state = [CellState::Candidates(Set::ALL); 81];
+        for &(cell, digits) in &[(0, [1, 2]), (4, [2, 3]), (40, [3, 4]), (44, [4, 1])] {
+            grid_state[cell] = CellState::Candidates(Digit::new(digits[0]).as_set() | Digit::new(digits[1]));
+        }
+        StrategySolver::from_grid_state(grid_state)
+    }
+
+    #[test]
+    fn xy_chain() {
+        let (_, deductions) = xy_chain_solver().solve(&[Strategy::XyChain]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+
+        let deduction = deductions.get(0).unwrap();
+        assert_eq!(deduction.strategy(), Strategy::XyChain);
+        if let Deduction::Aic { chain, conflicts } = deduction {
+            let nodes: Vec<_> = [(0, 1), (0, 2), (4, 2), (4, 3), (40, 3), (40, 4), (44, 4), (44, 1)]
+                .iter()
+                .map(|&(cell, digit)| Candidate::new(cell, digit))
+                .collect();
+            assert_eq!(chain.nodes(), &nodes[..]);
+            // the cells seeing both (0,0) and (4,8)
+            assert_eq!(conflicts, &candidates(&[8, 36], 1)[..]);
+        } else {
+            panic!("No XY-Chain found.");
+        }
+    }
+
+    #[test]
+    fn chains_in_bivalue_grid() {
+        // every cell holds its digit in a solution and the next higher one, so every candidate has 3 strong links.
+        // The number of paths grows exponentially with the length, the search has to stay bounded.
+        let solution =
+            Sudoku::from_str_line("534678912672195348198342567859761423426853791713924856961537284287419635345286179")
+                .unwrap();
+        let mut grid_state = [CellState::Candidates(Set::NONE); 81];
+        for (cell, digit) in solution.iter().enumerate() {
+            let digit = digit.unwrap();
+            grid_state[cell] = CellState::Candidates(Digit::new(digit).as_set() | Digit::new(digit % 9 + 1));
+        }
+        let config = SolverConfig {
+            max_chain_length: MAX_CHAIN_LENGTH,
+            ..SolverConfig::default()
+        };
+        let solver = StrategySolver::from_grid_state(grid_state).with_config(config);
+        // a BUG has at least 2 solutions, no chain can eliminate anything
+        let (_, deductions) = solver.solve(&[Strategy::Aic]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+
+    #[test]
+    fn max_chain_length() {
+        let config = SolverConfig {
+            max_chain_length: 5,
+            ..SolverConfig::default()
+        };
+        let solver = xy_chain_solver().with_config(config);
+        let (_, deductions) = solver.solve(&[Strategy::XyChain]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+}
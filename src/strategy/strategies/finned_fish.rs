@@ -0,0 +1,177 @@
+use super::prelude::*;
+
+// A finned fish is a basic fish with extra candidates in its base lines, the fins, that all lie in one block.
+// Either the fish holds or one of the fins is the digit, so the digit can be eliminated from the cells of the
+// cover lines that see all fins. A sashimi fish is a finned fish that would be degenerate without its fins,
+// because one of its base lines has only a single candidate inside the cover lines.
+pub(crate) fn find_finned_fish(
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    target_size: u8,
+    sashimi: bool,
+    stop_after_first: bool,
+    mut on_fish: impl FnMut(
+        Set<Line>,           // all rows or all cols
+        Digit,               //
+        Set<Line>,           // base
+        Set<Position<Line>>, // cover
+        Set<Cell>,           // fins
+    ) -> bool,
+) -> Result<(), Unsolvable> {
+    for digit in (1..10).map(Digit::new) {
+        for &all_lines in &[Line::ALL_ROWS, Line::ALL_COLS] {
+            // n_poss < 2 => solved line or hidden single
+            let lines: Vec<Line> = all_lines
+                .into_iter()
+                .filter(|&line| house_poss_positions[line][digit].len() >= 2)
+                .collect();
+
+            for base in combinations(&lines, target_size) {
+                let base = base.into_iter().fold(Set::NONE, |set, line| set | line);
+                let union_poss_pos = base
+                    .into_iter()
+                    .map(|line| house_poss_positions[line][digit].as_line_set())
+                    .fold(Set::NONE, |union, poss_pos| union | poss_pos);
+                // without fins, it's a basic fish or nothing
+                if union_poss_pos.len() <= target_size {
+                    continue;
+                }
+
+                let positions: Vec<Position<Line>> = union_poss_pos.into_iter().collect();
+                for cover in combinations(&positions, target_size) {
+                    let cover = cover.into_iter().fold(Set::NONE, |set, pos| set | pos);
+                    let mut fins = Set::NONE;
+                    let mut is_sashimi = false;
+                    let mut is_degenerate = false;
+                    for line in base {
+                        let poss_pos = house_poss_positions[line][digit].as_line_set();
+                        match (poss_pos & cover).len() {
+                            0 => is_degenerate = true,
+                            1 => is_sashimi = true,
+                            _ => (),
+                        }
+                        fins |= line.cells_at(poss_pos.without(cover));
+                    }
+                    if is_degenerate || is_sashimi != sashimi || !in_one_block(fins) {
+                        continue;
+                    }
+
+                    if on_fish(all_lines, digit, base, cover, fins) && stop_after_first {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// all combinations of `size` elements
+pub(crate) fn combinations<T: Copy>(elements: &[T], size: u8) -> impl Iterator<Item = Vec<T>> + '_ {
+    (0u32..1 << elements.len())
+        .filter(move |mask| mask.count_ones() == size as u32)
+        .map(move |mask| {
+            elements
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| mask & 1 << i != 0)
+                .map(|(_, &element)| element)
+                .collect()
+        })
+}
+
+fn in_one_block(cells: Set<Cell>) -> bool {
+    let mut blocks = cells.into_iter().map(Cell::block);
+    match blocks.next() {
+        Some(block) => blocks.all(|other| other == block),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::strategies::testing::{assert_needed, grid_without_1};
+    use crate::strategy::{Deduction, Strategy, StrategySolver};
+
+    // digit 1 is limited to the given columns in rows 1 and 5, all other candidates are open
+    fn fish_grid(row1_cols: &[u8], row5_cols: &[u8]) -> StrategySolver {
+        grid_without_1(|row, col| match row {
+            1 => !row1_cols.contains(&col),
+            5 => !row5_cols.contains(&col),
+            _ => false,
+        })
+    }
+
+    fn assert_finned_fish(solver: StrategySolver, strategy: Strategy, fins: Set<Cell>) {
+        let (_, deductions) = solver.solve(&[strategy]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+
+        let deduction = deductions.get(0).unwrap();
+        assert_eq!(deduction.strategy(), strategy);
+        if let Deduction::FinnedFish {
+            digit,
+            fins: found_fins,
+            conflicts,
+            ..
+        } = deduction
+        {
+            assert_eq!(digit, Digit::new(1));
+            assert_eq!(found_fins, fins);
+            // column 7 in the block of the fins
+            let expected = [3 * 9 + 7, 4 * 9 + 7].map(|cell| Candidate {
+                cell: Cell::new(cell),
+                digit: Digit::new(1),
+            });
+            assert_eq!(conflicts, &expected[..]);
+        } else {
+            panic!("No finned fish found.");
+        }
+    }
+
+    #[test]
+    fn finned_xwing() {
+        let solver = fish_grid(&[2, 7], &[2, 7, 8]);
+        assert_finned_fish(solver, Strategy::FinnedXWing, Cell::new(5 * 9 + 8).as_set());
+
+        let (_, deductions) = fish_grid(&[2, 7], &[2, 7, 8]).solve(&[Strategy::SashimiXWing]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+
+    #[test]
+    fn sashimi_xwing() {
+        let solver = fish_grid(&[2, 7], &[2, 6, 8]);
+        let fins = Cell::new(5 * 9 + 6).as_set() | Cell::new(5 * 9 + 8);
+        assert_finned_fish(solver, Strategy::SashimiXWing, fins);
+
+        let (_, deductions) = fish_grid(&[2, 7], &[2, 6, 8]).solve(&[Strategy::FinnedXWing]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+
+    // fins in two blocks justify nothing
+    #[test]
+    fn fins_in_different_blocks() {
+        let (_, deductions) = fish_grid(&[2, 7], &[0, 2, 7, 8]).solve(&[Strategy::FinnedXWing]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+
+    // generated puzzles, each finned fish is the only step beyond subsets
+    #[test]
+    fn finned_fish_needed() {
+        assert_needed(
+            Strategy::FinnedXWing,
+            "86.3.2.....27....897.....6.....978....1.....94.9............4.....2.3....3..18.5.",
+        );
+        assert_needed(
+            Strategy::SashimiXWing,
+            "..3674..9...8......17..56.....4.3.....9.1..638.....79..7...2.4........18.........",
+        );
+        assert_needed(
+            Strategy::FinnedSwordfish,
+            "2.18...9..87...6...5..63.......94.3...5.8...7..65............42.....7....2.6....8",
+        );
+        assert_needed(
+            Strategy::SashimiSwordfish,
+            ".2..5..38...4...2..3....5.73.6.27.855.1....4...........9..8.16..1..4..5...3......",
+        );
+    }
+}
@@ -7,6 +7,7 @@ pub(crate) fn find_hidden_subsets(
     stop_after_first: bool,
     mut on_subset: impl FnMut(House, Set<Digit>, Set<Position<House>>) -> bool,
 ) -> Result<(), Unsolvable> {
+    #[allow(clippy::too_many_arguments)]
     fn walk_combinations(
         house_poss_positions: &DigitArray<Set<Position<House>>>,
         total_poss_pos: Set<Position<House>>,
@@ -30,6 +30,7 @@ pub(crate) fn find_mutant_fish(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn find_base(
     house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
     digit: Digit,
@@ -103,6 +104,7 @@ fn find_base(
     false
 }
 
+#[allow(clippy::too_many_arguments)]
 fn find_cover(
     digit: Digit,
     base_houses: Set<House>,
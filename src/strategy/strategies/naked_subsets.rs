@@ -7,6 +7,7 @@ pub(crate) fn find_naked_subsets(
     stop_after_first: bool,
     mut on_subset: impl FnMut(House, Set<Position<House>>, Set<Digit>) -> bool,
 ) -> Result<(), Unsolvable> {
+    #[allow(clippy::too_many_arguments)]
     fn walk_combinations(
         cells_poss_digits: &CellArray<Set<Digit>>,
         total_poss_digs: Set<Digit>,
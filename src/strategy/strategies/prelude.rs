@@ -1,3 +1,4 @@
+pub(crate) use alloc::{vec, vec::Vec};
 pub(crate) use crate::bitset::{Iter as SetIter, Set};
 pub(crate) use crate::helper::{CellArray, DigitArray, HouseArray, Unsolvable};
 pub(crate) use crate::Sudoku;
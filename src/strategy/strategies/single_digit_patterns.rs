@@ -0,0 +1,153 @@
+use super::prelude::*;
+use crate::board::positions::HouseType;
+use crate::strategy::Strategy;
+
+// Skyscraper, 2-String Kite and Turbot Fish are all made of two strong links of one digit, i.e. houses with exactly
+// 2 candidates of it, whose ends are connected by a weak link (the cells see each other). At most one of the connected
+// ends holds the digit, so at least one of the free ends does and the digit is eliminated from all cells seeing both.
+pub(crate) fn find_single_digit_patterns(
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    stop_after_first: bool,
+    mut on_pattern: impl FnMut(
+        Digit,      //
+        [House; 2], // strong links
+        [Cell; 2],  // connected ends
+        [Cell; 2],  // free ends
+    ) -> bool,
+) -> Result<(), Unsolvable> {
+    for digit in (1..10).map(Digit::new) {
+        let links = strong_links(house_poss_positions, digit);
+
+        for (i, &(house1, cells1)) in links.iter().enumerate() {
+            for &(house2, cells2) in &links[i + 1..] {
+                // links sharing a cell form a chain of 3 cells, that's a different pattern
+                if cells1.iter().any(|cell| cells2.contains(cell)) {
+                    continue;
+                }
+
+                for &(connected1, free1) in &[(cells1[0], cells1[1]), (cells1[1], cells1[0])] {
+                    for &(connected2, free2) in &[(cells2[0], cells2[1]), (cells2[1], cells2[0])] {
+                        if !connected1.neighbors_set().contains(connected2) {
+                            continue;
+                        }
+                        if on_pattern(digit, [house1, house2], [connected1, connected2], [free1, free2])
+                            && stop_after_first
+                        {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// all houses with exactly 2 candidates of `digit`, each cell pair only once
+fn strong_links(
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    digit: Digit,
+) -> Vec<(House, [Cell; 2])> {
+    let mut links: Vec<(House, [Cell; 2])> = vec![];
+    for house in House::all() {
+        let positions = house_poss_positions[house][digit];
+        if positions.len() != 2 {
+            continue;
+        }
+        let mut cells = positions.into_iter().map(|pos| house.cell_at(pos));
+        let cells = [cells.next().unwrap(), cells.next().unwrap()];
+        if links.iter().all(|&(_, other_cells)| other_cells != cells) {
+            links.push((house, cells));
+        }
+    }
+    links
+}
+
+// Skyscrapers are two parallel lines whose connected ends share a perpendicular line,
+// 2-String Kites a row and a column whose connected ends share a block
+// and every other combination is a Turbot Fish.
+pub(crate) fn classify(strong_links: [House; 2], connected: [Cell; 2]) -> Strategy {
+    use self::HouseType::*;
+    match (strong_links[0].categorize(), strong_links[1].categorize()) {
+        (Row(_), Row(_)) if connected[0].col() == connected[1].col() => Strategy::Skyscraper,
+        (Col(_), Col(_)) if connected[0].row() == connected[1].row() => Strategy::Skyscraper,
+        (Row(_), Col(_)) | (Col(_), Row(_)) if connected[0].block() == connected[1].block() => {
+            Strategy::TwoStringKite
+        }
+        _ => Strategy::TurbotFish,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::strategies::testing::{assert_needed, grid_without_1};
+    use crate::strategy::{Deduction, StrategySolver};
+
+    fn assert_pattern(solver: StrategySolver, strategy: Strategy, conflict_cells: &[u8]) {
+        let (_, deductions) = solver.solve(&[strategy]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+
+        let deduction = deductions.get(0).unwrap();
+        assert_eq!(deduction.strategy(), strategy);
+        let conflicts = match deduction {
+            Deduction::Skyscraper { conflicts, .. }
+            | Deduction::TwoStringKite { conflicts, .. }
+            | Deduction::TurbotFish { conflicts, .. } => conflicts,
+            _ => unreachable!(),
+        };
+        let expected: Vec<_> = conflict_cells
+            .iter()
+            .map(|&cell| Candidate {
+                cell: Cell::new(cell),
+                digit: Digit::new(1),
+            })
+            .collect();
+        assert_eq!(conflicts, &expected[..]);
+    }
+
+    #[test]
+    fn skyscraper() {
+        // rows 1 and 5 are linked through column 2
+        let solver = grid_without_1(|row, col| row == 1 && col != 2 && col != 4 || row == 5 && col != 2 && col != 5);
+        assert_pattern(solver, Strategy::Skyscraper, &[5, 2 * 9 + 5, 3 * 9 + 4, 4 * 9 + 4]);
+    }
+
+    #[test]
+    fn two_string_kite() {
+        // row 0 and column 0 are linked through block 0
+        let solver = grid_without_1(|row, col| row == 0 && col != 1 && col != 6 || col == 0 && row != 2 && row != 7);
+        assert_pattern(solver, Strategy::TwoStringKite, &[7 * 9 + 6]);
+    }
+
+    #[test]
+    fn turbot_fish() {
+        // block 4 and row 8 are linked through column 3
+        let in_block_4 = |row: u8, col: u8| row / 3 == 1 && col / 3 == 1;
+        let solver = grid_without_1(|row, col| {
+            in_block_4(row, col) && (row, col) != (3, 3) && (row, col) != (5, 5) || row == 8 && col != 3 && col != 7
+        });
+        assert_pattern(solver.clone(), Strategy::TurbotFish, &[5 * 9 + 7]);
+
+        // the turbot fish is no skyscraper
+        let (_, deductions) = solver.solve(&[Strategy::Skyscraper]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+
+    // generated puzzles, each pattern is the only step beyond subsets
+    #[test]
+    fn patterns_needed() {
+        assert_needed(
+            Strategy::Skyscraper,
+            ".6...........9....4.1...6.2715..2..6.....7.2....51..48.5.8....1.2.......8...7..9.",
+        );
+        assert_needed(
+            Strategy::TwoStringKite,
+            "2......5.4...1.6....16.72...5......61..7......2...4..75...963........7....6..3..8",
+        );
+        assert_needed(
+            Strategy::TurbotFish,
+            "....6.2..7..2....59..........25...7..8..7...1..4893.....89..6.......1....1.....37",
+        );
+    }
+}
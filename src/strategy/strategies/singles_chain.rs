@@ -0,0 +1,134 @@
+use super::prelude::*;
+
+// A singles chain connects the candidates of one digit through conjugate pairs, i.e. houses with exactly 2 candidates
+// of it. Alternating cells of the chain get opposite colors, exactly one of the colors holds the digit.
+// If two cells of one color see each other, that color is false (color wrap). Cells outside the chain seeing both
+// colors can't hold the digit (color trap).
+pub(crate) fn find_singles_chain(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    stop_after_first: bool,
+    mut on_chain: impl FnMut(
+        Digit,          //
+        [Set<Cell>; 2], // cells of either color
+    ) -> bool,
+) -> Result<(), Unsolvable> {
+    for digit in (1..10).map(Digit::new) {
+        let mut colored = Set::NONE;
+        for start in Cell::all() {
+            if colored.contains(start) || !cell_poss_digits[start].contains(digit) {
+                continue;
+            }
+
+            let mut colors = [start.as_set(), Set::NONE];
+            colored |= start;
+            let mut stack = vec![(start, 0)];
+            while let Some((cell, color)) = stack.pop() {
+                for &house in &cell.houses() {
+                    let positions = house_poss_positions[house][digit];
+                    if positions.len() != 2 {
+                        continue;
+                    }
+                    for other in house.cells_at(positions).without(colored) {
+                        colored |= other;
+                        colors[1 - color] |= other;
+                        stack.push((other, 1 - color));
+                    }
+                }
+            }
+
+            // no conjugate pair
+            if colors[1].is_empty() {
+                continue;
+            }
+            if on_chain(digit, colors) && stop_after_first {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+// all cells seeing at least one cell of `cells`
+pub(crate) fn seen_cells(cells: Set<Cell>) -> Set<Cell> {
+    cells
+        .into_iter()
+        .fold(Set::NONE, |seen, cell| seen | cell.neighbors_set())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::strategies::testing::{assert_needed, grid_without_1};
+    use crate::strategy::{Deduction, Strategy};
+
+    fn cells(cells: &[u8]) -> Set<Cell> {
+        cells.iter().fold(Set::NONE, |set, &cell| set | Cell::new(cell))
+    }
+
+    #[test]
+    fn color_trap() {
+        // chain (0,0) - (0,4) - (4,4) - (4,1) through row 0, column 4 and row 4
+        let solver = grid_without_1(|row, col| {
+            row == 0 && col != 0 && col != 4 || col == 4 && row != 0 && row != 4 || row == 4 && col != 4 && col != 1
+        });
+        let (_, deductions) = solver.solve(&[Strategy::SinglesChain]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+
+        if let Deduction::SinglesChain {
+            digit,
+            colors,
+            conflicts,
+        } = deductions.get(0).unwrap()
+        {
+            assert_eq!(digit, Digit::new(1));
+            assert_eq!(colors, [cells(&[0, 4 * 9 + 4]), cells(&[4, 4 * 9 + 1])]);
+            // the cells seeing both (0,0) and (4,1)
+            let expected: Vec<_> = [9 + 1, 2 * 9 + 1, 3 * 9, 5 * 9]
+                .iter()
+                .map(|&cell| Candidate {
+                    cell: Cell::new(cell),
+                    digit: Digit::new(1),
+                })
+                .collect();
+            assert_eq!(conflicts, &expected[..]);
+        } else {
+            panic!("No singles chain found.");
+        }
+    }
+
+    #[test]
+    fn color_wrap() {
+        // chain (0,0) - (0,4) - (4,4) - (4,1) - (1,1), (0,0) and (1,1) share block 0 and have the same color
+        let solver = grid_without_1(|row, col| {
+            row == 0 && col != 0 && col != 4
+                || col == 4 && row != 0 && row != 4
+                || row == 4 && col != 4 && col != 1
+                || col == 1 && row != 4 && row != 1
+        });
+        let (_, deductions) = solver.solve(&[Strategy::SinglesChain]).unwrap_err();
+
+        if let Deduction::SinglesChain { colors, conflicts, .. } = deductions.get(0).unwrap() {
+            let false_color = colors.iter().copied().find(|color| color.contains(Cell::new(0))).unwrap();
+            let expected: Vec<_> = false_color
+                .into_iter()
+                .map(|cell| Candidate {
+                    cell,
+                    digit: Digit::new(1),
+                })
+                .collect();
+            assert_eq!(conflicts, &expected[..]);
+        } else {
+            panic!("No singles chain found.");
+        }
+    }
+
+    // a generated puzzle, the singles chain is the only step beyond subsets
+    #[test]
+    fn singles_chain_needed() {
+        assert_needed(
+            Strategy::SinglesChain,
+            "18...56.3.....842.....1....5..8.3..2.64..28.5......1....9.4.5...7.5..2...........",
+        );
+    }
+}
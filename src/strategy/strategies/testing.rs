@@ -0,0 +1,42 @@
+//! Grids shared by the tests of the strategies
+
+use super::prelude::*;
+use crate::board::CellState;
+use crate::strategy::{Strategy, StrategySolver};
+
+/// Digit 1 is removed from all cells for whose row and column `remove` returns true, all other
+/// candidates are open.
+pub(crate) fn grid_without_1(remove: impl Fn(u8, u8) -> bool) -> StrategySolver {
+    let mut grid_state = [CellState::Candidates(Set::ALL); 81];
+    for cell in Cell::all() {
+        if remove(cell.row().get(), cell.col().get()) {
+            grid_state[cell.as_index()] = CellState::Candidates(Set::ALL.without(Digit::new(1).as_set()));
+        }
+    }
+    StrategySolver::from_grid_state(grid_state)
+}
+
+/// The strategies that look at a single house or a line and a block
+const BASIC: &[Strategy] = &[
+    Strategy::NakedSingles,
+    Strategy::HiddenSingles,
+    Strategy::LockedCandidates,
+    Strategy::NakedPairs,
+    Strategy::HiddenPairs,
+    Strategy::NakedTriples,
+    Strategy::HiddenTriples,
+    Strategy::NakedQuads,
+    Strategy::HiddenQuads,
+];
+
+/// Asserts that the [`BASIC`] strategies get stuck on `puzzle`, given in the line format, and that adding `strategy`
+/// solves it. Reaching the solution also means none of the eliminations was wrong.
+pub(crate) fn assert_needed(strategy: Strategy, puzzle: &str) {
+    let sudoku = Sudoku::from_str_line(puzzle).unwrap();
+    assert!(sudoku.solve_with_strategies(BASIC).is_err());
+
+    let strategies: Vec<Strategy> = BASIC.iter().copied().chain(Some(strategy)).collect();
+    let (solution, deductions) = sudoku.solve_with_strategies(&strategies).unwrap();
+    assert_eq!(Some(solution), sudoku.solution());
+    assert!(deductions.iter().any(|deduction| deduction.strategy() == strategy));
+}
@@ -0,0 +1,292 @@
+use super::finned_fish::combinations;
+use super::prelude::*;
+
+// A unique rectangle consists of 4 cells in 2 rows, 2 columns and 2 blocks that all contain the same 2 candidates.
+// If only these 2 candidates were left, they could be swapped and the sudoku would have multiple solutions
+// (deadly pattern). Assuming the solution is unique, one of the cells must hold another digit.
+//
+// Type 1: 3 cells are bivalue, the extra candidates of the 4th cell must hold
+// Type 2: the 2 other cells (roof) have the same single extra candidate, one of them holds it
+// Type 3: the extra candidates of the roof form a locked set with other cells of a house shared by the roof
+// Type 4: one of the rectangle digits is locked to the roof in a house, so the other one can't be in the roof
+pub(crate) fn find_unique_rectangles(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    variant: u8,
+    stop_after_first: bool,
+    mut on_unique_rectangle: impl FnMut(
+        Set<Cell>,  // rectangle
+        Set<Digit>, // digits of the deadly pattern
+        Vec<Candidate>,
+    ) -> bool,
+) -> Result<(), Unsolvable> {
+    let cell = |row: u8, col: u8| Cell::new(row * 9 + col);
+    for row1 in 0..8 {
+        for row2 in row1 + 1..9 {
+            for col1 in 0..8 {
+                for col2 in col1 + 1..9 {
+                    // the rectangle must occupy exactly 2 blocks
+                    if (row1 / 3 == row2 / 3) == (col1 / 3 == col2 / 3) {
+                        continue;
+                    }
+                    let cells = [cell(row1, col1), cell(row1, col2), cell(row2, col1), cell(row2, col2)];
+                    let common_digits = cells.iter().fold(Set::ALL, |digits, &cell| digits & cell_poss_digits[cell]);
+                    if common_digits.len() < 2 {
+                        continue;
+                    }
+                    let rectangle = cells.iter().fold(Set::NONE, |set, &cell| set | cell);
+
+                    for pair in combinations(&common_digits.into_iter().collect::<Vec<_>>(), 2) {
+                        let digits = pair[0].as_set() | pair[1];
+                        let floor = cells
+                            .iter()
+                            .filter(|&&cell| cell_poss_digits[cell] == digits)
+                            .fold(Set::NONE, |set, &cell| set | cell);
+                        let roof = rectangle.without(floor);
+
+                        let conflicts = match variant {
+                            1 => type_1(cell_poss_digits, floor, roof, digits),
+                            2 => type_2(cell_poss_digits, floor, roof, digits),
+                            3 => type_3(cell_poss_digits, floor, roof, digits),
+                            4 => type_4(cell_poss_digits, house_poss_positions, floor, roof, digits),
+                            _ => unreachable!(),
+                        };
+                        if conflicts.is_empty() {
+                            continue;
+                        }
+                        if on_unique_rectangle(rectangle, digits, conflicts) && stop_after_first {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn type_1(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    floor: Set<Cell>,
+    roof: Set<Cell>,
+    digits: Set<Digit>,
+) -> Vec<Candidate> {
+    if floor.len() != 3 {
+        return vec![];
+    }
+    let cell = roof.one_possibility();
+    (cell_poss_digits[cell] & digits)
+        .into_iter()
+        .map(|digit| Candidate { cell, digit })
+        .collect()
+}
+
+fn type_2(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    floor: Set<Cell>,
+    roof: Set<Cell>,
+    digits: Set<Digit>,
+) -> Vec<Candidate> {
+    if !is_floor_and_roof(floor, roof) {
+        return vec![];
+    }
+    let [roof1, roof2] = two_cells(roof);
+    let extra_digits = cell_poss_digits[roof1].without(digits);
+    if extra_digits.len() != 1 || cell_poss_digits[roof2].without(digits) != extra_digits {
+        return vec![];
+    }
+    let digit = extra_digits.one_possibility();
+    (roof1.neighbors_set() & roof2.neighbors_set())
+        .into_iter()
+        .filter(|&cell| cell_poss_digits[cell].contains(digit))
+        .map(|cell| Candidate { cell, digit })
+        .collect()
+}
+
+fn type_3(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    floor: Set<Cell>,
+    roof: Set<Cell>,
+    digits: Set<Digit>,
+) -> Vec<Candidate> {
+    if !is_floor_and_roof(floor, roof) {
+        return vec![];
+    }
+    // the roof acts as a single cell with the extra candidates
+    let extra_digits = roof.into_iter().fold(Set::NONE, |extra, cell| extra | cell_poss_digits[cell]).without(digits);
+
+    let mut conflicts = vec![];
+    for house in shared_houses(roof) {
+        let other_cells: Vec<_> = house
+            .cells()
+            .without(roof)
+            .into_iter()
+            .filter(|&cell| !cell_poss_digits[cell].is_empty())
+            .collect();
+
+        for size in 1..=3 {
+            for subset in combinations(&other_cells, size) {
+                let locked_digits = subset.iter().fold(extra_digits, |locked, &cell| locked | cell_poss_digits[cell]);
+                if locked_digits.len() != size + 1 {
+                    continue;
+                }
+                let locked_cells = subset.iter().fold(roof, |locked, &cell| locked | cell);
+                for cell in house.cells().without(locked_cells) {
+                    for digit in cell_poss_digits[cell] & locked_digits {
+                        conflicts.push(Candidate { cell, digit });
+                    }
+                }
+            }
+        }
+    }
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+}
+
+fn type_4(
+    cell_poss_digits: &CellArray<Set<Digit>>,
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    floor: Set<Cell>,
+    roof: Set<Cell>,
+    digits: Set<Digit>,
+) -> Vec<Candidate> {
+    if !is_floor_and_roof(floor, roof) {
+        return vec![];
+    }
+    let mut conflicts = vec![];
+    for house in shared_houses(roof) {
+        for locked_digit in digits {
+            if house.cells_at(house_poss_positions[house][locked_digit]) != roof {
+                continue;
+            }
+            let digit = digits.without(locked_digit.as_set()).one_possibility();
+            conflicts.extend(
+                roof.into_iter()
+                    .filter(|&cell| cell_poss_digits[cell].contains(digit))
+                    .map(|cell| Candidate { cell, digit }),
+            );
+        }
+    }
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+}
+
+// Types 2-4 need 2 bivalue cells in one line, the floor, and the other 2 cells with extra candidates, the roof.
+fn is_floor_and_roof(floor: Set<Cell>, roof: Set<Cell>) -> bool {
+    floor.len() == 2 && !shared_houses(floor).is_empty() && !shared_houses(roof).is_empty()
+}
+
+fn two_cells(cells: Set<Cell>) -> [Cell; 2] {
+    let mut cells = cells.into_iter();
+    [cells.next().unwrap(), cells.next().unwrap()]
+}
+
+fn shared_houses(cells: Set<Cell>) -> Vec<House> {
+    let [cell1, cell2] = two_cells(cells);
+    cell1
+        .houses()
+        .iter()
+        .copied()
+        .filter(|house| house.cells().contains(cell2))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::{Deduction, SolverConfig, Strategy, StrategySolver};
+
+    fn digits(digits: &[u8]) -> Set<Digit> {
+        digits.iter().fold(Set::NONE, |set, &digit| set | Digit::new(digit))
+    }
+
+    // rectangle in rows 0, 4 and columns 0, 1, the cells are given in the order (0,0), (0,1), (4,0), (4,1)
+    fn rectangle_grid(rectangle_digits: [&[u8]; 4]) -> StrategySolver {
+        let mut grid_state = [CellState::Candidates(Set::ALL); 81];
+        for (&cell, digits_) in [0, 1, 36, 37].iter().zip(rectangle_digits.iter()) {
+            grid_state[cell] = CellState::Candidates(digits(digits_));
+        }
+        StrategySolver::from_grid_state(grid_state)
+    }
+
+    fn rectangle() -> Set<Cell> {
+        [0, 1, 36, 37].iter().fold(Set::NONE, |set, &cell| set | Cell::new(cell))
+    }
+
+    fn first_unique_rectangle(solver: StrategySolver, strategy: Strategy) -> Vec<Candidate> {
+        let (_, deductions) = solver.solve(&[strategy]).unwrap_err();
+        match deductions.get(0) {
+            Some(Deduction::UniqueRectangle {
+                rectangle: rect,
+                digits: digits_,
+                conflicts,
+                ..
+            }) => {
+                assert_eq!(rect, rectangle());
+                assert_eq!(digits_, digits(&[1, 2]));
+                assert_eq!(deductions.get(0).unwrap().strategy(), strategy);
+                conflicts.to_vec()
+            }
+            _ => panic!("No unique rectangle found."),
+        }
+    }
+
+    #[test]
+    fn type_1() {
+        let solver = rectangle_grid([&[1, 2], &[1, 2], &[1, 2], &[1, 2, 3, 4]]);
+        let conflicts = first_unique_rectangle(solver, Strategy::UniqueRectangleType1);
+        assert_eq!(conflicts, [Candidate::new(37, 1), Candidate::new(37, 2)]);
+    }
+
+    #[test]
+    fn type_2() {
+        let solver = rectangle_grid([&[1, 2], &[1, 2], &[1, 2, 3], &[1, 2, 3]]);
+        let conflicts = first_unique_rectangle(solver, Strategy::UniqueRectangleType2);
+        // all other cells of row 4 and block 3
+        let expected: Vec<_> = [27, 28, 29, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47]
+            .iter()
+            .map(|&cell| Candidate::new(cell, 3))
+            .collect();
+        assert_eq!(conflicts, expected);
+    }
+
+    #[test]
+    fn type_3() {
+        // the roof's extra digits 3 and 4 form a naked pair with (4,5) {3,4} in row 4
+        let mut solver = rectangle_grid([&[1, 2], &[1, 2], &[1, 2, 3], &[1, 2, 4]]);
+        solver
+            .eliminated_entries
+            .extend((1..10).filter(|&digit| digit != 3 && digit != 4).map(|digit| Candidate::new(41, digit)));
+        let conflicts = first_unique_rectangle(solver, Strategy::UniqueRectangleType3);
+        let expected: Vec<_> = [38, 39, 40, 42, 43, 44]
+            .iter()
+            .flat_map(|&cell| vec![Candidate::new(cell, 3), Candidate::new(cell, 4)])
+            .collect();
+        assert_eq!(conflicts, expected);
+    }
+
+    #[test]
+    fn type_4() {
+        // digit 1 is only possible in the roof in row 4
+        let mut solver = rectangle_grid([&[1, 2], &[1, 2], &[1, 2, 3], &[1, 2, 4]]);
+        solver
+            .eliminated_entries
+            .extend((38..45).map(|cell| Candidate::new(cell, 1)));
+        let conflicts = first_unique_rectangle(solver, Strategy::UniqueRectangleType4);
+        assert_eq!(conflicts, [Candidate::new(36, 2), Candidate::new(37, 2)]);
+    }
+
+    #[test]
+    fn uniqueness_assumption_disabled() {
+        let config = SolverConfig {
+            assume_unique_solution: false,
+            ..SolverConfig::default()
+        };
+        let solver = rectangle_grid([&[1, 2], &[1, 2], &[1, 2], &[1, 2, 3, 4]]).with_config(config);
+        let (_, deductions) = solver.solve(&[Strategy::UniqueRectangleType1]).unwrap_err();
+        assert!(deductions.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use super::prelude::*;
+
+// Two cells with the same 2 candidates x and y, that don't see each other, are pincers. If a strong link on x connects
+// cells seeing one pincer each, at least one pincer is y: otherwise both would be x and neither end of the link could be.
+// y is eliminated from all cells seeing both pincers.
+pub(crate) fn find_w_wing(
+    cells_poss_digits: &CellArray<Set<Digit>>,
+    house_poss_positions: &HouseArray<DigitArray<Set<Position<House>>>>,
+    stop_after_first: bool,
+    mut on_w_wing: impl FnMut(
+        [Cell; 2], // pincers
+        Digit,     // digit of the strong link
+        Digit,     // eliminated digit
+        House,     // strong link
+    ) -> bool,
+) -> Result<(), Unsolvable> {
+    let bivalue_cells: Vec<Cell> = Cell::all().filter(|&cell| cells_poss_digits[cell].len() == 2).collect();
+
+    for (i, &pincer1) in bivalue_cells.iter().enumerate() {
+        let digits = cells_poss_digits[pincer1];
+        for &pincer2 in &bivalue_cells[i + 1..] {
+            if cells_poss_digits[pincer2] != digits || pincer1.neighbors_set().contains(pincer2) {
+                continue;
+            }
+
+            for link_digit in digits {
+                let eliminated_digit = digits.without(link_digit.as_set()).one_possibility();
+                for house in House::all() {
+                    let positions = house_poss_positions[house][link_digit];
+                    if positions.len() != 2 {
+                        continue;
+                    }
+                    let ends = house.cells_at(positions);
+                    if ends.overlaps(pincer1.as_set() | pincer2) {
+                        continue;
+                    }
+
+                    let mut ends = ends.into_iter();
+                    let (end1, end2) = (ends.next().unwrap(), ends.next().unwrap());
+                    let sees = |end: Cell, pincer: Cell| end.neighbors_set().contains(pincer);
+                    let is_w_wing =
+                        sees(end1, pincer1) && sees(end2, pincer2) || sees(end1, pincer2) && sees(end2, pincer1);
+                    if !is_w_wing {
+                        continue;
+                    }
+
+                    if on_w_wing([pincer1, pincer2], link_digit, eliminated_digit, house) && stop_after_first {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::CellState;
+    use crate::strategy::strategies::testing::{assert_needed, grid_without_1};
+    use crate::strategy::{Deduction, Strategy, StrategySolver};
+
+    #[test]
+    fn w_wing() {
+        let digits_12 = Digit::new(1).as_set() | Digit::new(2);
+        // strong link on 1 in row 8
+        let mut grid_state = grid_without_1(|row, col| row == 8 && (1..8).contains(&col)).grid_state();
+        // pincers
+        grid_state[0] = CellState::Candidates(digits_12);
+        grid_state[4 * 9 + 8] = CellState::Candidates(digits_12);
+
+        let solver = StrategySolver::from_grid_state(grid_state);
+        let (_, deductions) = solver.solve(&[Strategy::WWing]).unwrap_err();
+        assert_eq!(deductions.len(), 1);
+        if let Deduction::WWing {
+            pincers,
+            link_digit,
+            strong_link,
+            conflicts,
+        } = deductions.get(0).unwrap()
+        {
+            assert_eq!(pincers, Cell::new(0).as_set() | Cell::new(4 * 9 + 8));
+            assert_eq!(link_digit, Digit::new(1));
+            assert_eq!(strong_link, Cell::new(8 * 9).houses()[0]);
+            let expected = [8, 4 * 9].map(|cell| Candidate {
+                cell: Cell::new(cell),
+                digit: Digit::new(2),
+            });
+            assert_eq!(conflicts, &expected[..]);
+        } else {
+            panic!("No W-Wing found.");
+        }
+    }
+
+    // a generated puzzle, the w-wing is the only step beyond subsets
+    #[test]
+    fn w_wing_needed() {
+        assert_needed(
+            Strategy::WWing,
+            "...7..9.....8.9.24.7.....68....9....26...5.......81..3913.7.4....2.....1.....4..7",
+        );
+    }
+}
@@ -59,6 +59,7 @@ mod test {
 
     // sudoku taken from http://www.sudokuwiki.org/Y_Wing_Strategy, Example 1 (2019-03-18)
     #[test]
+    #[allow(clippy::identity_op)]
     fn xy_wing() {
         let sudoku = Sudoku::from_str_line(
             "9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9",
@@ -66,8 +67,7 @@ mod test {
         .unwrap();
         let solver = crate::strategy::StrategySolver::from_sudoku(sudoku);
         let (_, deductions) = solver.solve(&[crate::strategy::Strategy::XyWing]).unwrap_err();
-
-        // TODO: match both xy-wings
+        assert_eq!(deductions.len(), 2);
 
         if let crate::strategy::Deduction::Wing {
             hinge,
@@ -87,5 +87,53 @@ mod test {
         } else {
             panic!("No XyWing found.");
         }
+
+        if let crate::strategy::Deduction::Wing {
+            hinge,
+            pincers,
+            conflicts,
+            ..
+        } = deductions.get(1).unwrap()
+        {
+            assert_eq!(hinge.get(), 73);
+            assert_eq!(pincers, Cell::new(1).as_set() | Cell::new(54));
+
+            let conflicts_expected = [
+                Candidate {
+                    cell: Cell::new(9),
+                    digit: Digit::new(8),
+                },
+                Candidate {
+                    cell: Cell::new(18),
+                    digit: Digit::new(8),
+                },
+            ];
+            assert_eq!(conflicts, &conflicts_expected[..]);
+        } else {
+            panic!("Second XyWing not found.");
+        }
+    }
+
+    // same sudoku, the other strategies get stuck before the xy-wings
+    // XY-Chains and AICs are generalizations of the wings and left out as well
+    #[test]
+    fn xy_wing_required() {
+        use crate::strategy::Strategy;
+
+        let sudoku = Sudoku::from_str_line(
+            "9..24.....5.69.231.2..5..9..9.7..32...29356.7.7...29...69.2..7351..79.622.7.86..9",
+        )
+        .unwrap();
+        let without_wings: Vec<Strategy> = Strategy::ALL
+            .iter()
+            .copied()
+            .filter(|strategy| {
+                ![Strategy::XyWing, Strategy::XyzWing, Strategy::XyChain, Strategy::Aic].contains(strategy)
+            })
+            .collect();
+
+        assert!(sudoku.solve_with_strategies(&without_wings).is_err());
+        let (solution, _) = sudoku.solve_with_strategies(Strategy::ALL).unwrap();
+        assert_eq!(Some(solution), sudoku.solution());
     }
 }
@@ -1,4 +1,3 @@
-#![allow(unused)]
 use super::prelude::*;
 
 // TODO: unify with xy-wing and possibly more wings (wxyz-wing?)
@@ -42,7 +41,7 @@ pub(crate) fn find_xyz_wing(
                         continue;
                     }
 
-                    // found xy-wing
+                    // found xyz-wing
                     let found_conflicts =
                         on_xyz_wing((cell, poss_digits), [(cell1, poss_digs1), (cell2, poss_digs2)]);
                     if found_conflicts && stop_after_first {
@@ -59,8 +58,9 @@ pub(crate) fn find_xyz_wing(
 mod test {
     use super::*;
 
-    // sudoku taken from http://www.sudokuwiki.org/Y_Wing_Strategy, Example 1 (2019-03-18)
+    // hinge {1,2,4} in r6c9 with the pincers {1,2} in r4c9 and {1,4} in r6c1, r6c7 sees all three and loses the 1
     #[test]
+    #[allow(clippy::identity_op)]
     fn xyz_wing() {
         let solver = crate::strategy::StrategySolver::from_grid_state_str(
             "
@@ -77,7 +77,8 @@ mod test {
             │ 7    1245  146  │ 1456  1245  3     │ 156   8   9    │
             │ 9    125   3    │ 8     1257  2567  │ 156   4   167  │
             └─────────────────┴───────────────────┴────────────────┘",
-        );
+        )
+        .unwrap();
 
         let (_, deductions) = solver.solve(&[crate::strategy::Strategy::XyzWing]).unwrap_err();
         assert_eq!(deductions.len(), 1);
@@ -94,4 +95,12 @@ mod test {
             }
         );
     }
+    // a generated puzzle, the xyz-wing is the only step beyond subsets
+    #[test]
+    fn xyz_wing_needed() {
+        crate::strategy::strategies::testing::assert_needed(
+            crate::strategy::Strategy::XyzWing,
+            ".27.....9.9.23.1.6............3.7.......826..5.1.......1....4.....89..612...639..",
+        );
+    }
 }
@@ -1,7 +1,7 @@
 // WIP
 #![allow(unused)]
 use super::strategies::prelude::*;
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
 
 enum Link {
     Strong,
@@ -9,7 +9,7 @@ enum Link {
 }
 
 // sparse matrix in key-value representation
-type LinkMatrix = HashMap<Candidate, Vec<Candidate>>;
+type LinkMatrix = BTreeMap<Candidate, Vec<Candidate>>;
 
 struct Links {
     weak: LinkMatrix,
@@ -91,6 +91,6 @@ fn conflicting_house_positions(
     let houses = cell.houses().to_vec();
     houses
         .into_iter()
-        .zip(positions.into_iter())
+        .zip(positions)
         .map(move |(house, pos)| (house, house_poss_positions[house][digit] ^ pos))
 }
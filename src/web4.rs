@@ -0,0 +1,90 @@
+//! Serves the embedded client through the [web4](https://github.com/vgrichina/web4) gateway,
+//! see [`crate::Contract::web4_get`]. The client reads and plays through the gateway's
+//! `/web4/contract` endpoints, so the contract needs no other hosting.
+
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+const INDEX_HTML: &str = include_str!("web4/index.html");
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Web4Request {
+    /// The account logged in to the gateway
+    #[serde(rename = "accountId")]
+    pub account_id: Option<AccountId>,
+    pub path: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Web4Response {
+    #[serde(rename = "contentType")]
+    pub content_type: String,
+    pub body: Base64VecU8,
+    pub status: u16,
+}
+
+impl Web4Response {
+    fn html(body: String) -> Self {
+        Self {
+            content_type: "text/html; charset=UTF-8".to_string(),
+            body: body.into_bytes().into(),
+            status: 200,
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            content_type: "text/plain; charset=UTF-8".to_string(),
+            body: b"Not found".to_vec().into(),
+            status: 404,
+        }
+    }
+}
+
+/// The client for `/` and `/index.html` with the account ids filled in, 404 for other paths.
+pub fn get(request: &Web4Request, contract_id: &AccountId) -> Web4Response {
+    match request.path.as_str() {
+        "/" | "/index.html" => Web4Response::html(
+            INDEX_HTML
+                .replace("{{CONTRACT_ID}}", contract_id.as_str())
+                .replace(
+                    "{{ACCOUNT_ID}}",
+                    request.account_id.as_ref().map_or("", |id| id.as_str()),
+                ),
+        ),
+        _ => Web4Response::not_found(),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::accounts;
+
+    fn request(path: &str, account_id: Option<AccountId>) -> Web4Request {
+        Web4Request {
+            account_id,
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn index() {
+        let response = get(&request("/", Some(accounts(1))), &accounts(0));
+        assert_eq!(response.status, 200);
+        let body = String::from_utf8(response.body.into()).unwrap();
+        assert!(body.contains(r#"const CONTRACT = "alice";"#));
+        assert!(body.contains(r#"const ACCOUNT = "bob";"#));
+
+        let body = String::from_utf8(get(&request("/", None), &accounts(0)).body.into()).unwrap();
+        assert!(body.contains(r#"const ACCOUNT = "";"#));
+    }
+
+    #[test]
+    fn not_found() {
+        assert_eq!(get(&request("/missing", None), &accounts(0)).status, 404);
+    }
+}